@@ -0,0 +1,53 @@
+//! 不依赖 Tauri 使用日志管理和摘要功能的示例
+//!
+//! ```bash
+//! cargo run --no-default-features --example library
+//! ```
+//!
+//! 日志和摘要写入系统临时目录，不会修改已有的日志数据
+
+use chrono::Local;
+use work_record::errors::AppError;
+use work_record::log_manager::{LogEntry, LogManager};
+use work_record::settings::Settings;
+use work_record::summary::{GroupBy, SummaryConfig, SummaryFormat, SummaryGenerator, SummaryType};
+
+#[tokio::main]
+async fn main() -> Result<(), AppError> {
+    let dir = std::env::temp_dir().join("work-record-example");
+    let settings = Settings {
+        log_storage_dir: dir.join("logs").to_string_lossy().to_string(),
+        log_output_dir: dir.join("output").to_string_lossy().to_string(),
+        ..Settings::default()
+    };
+
+    let log_manager = LogManager::new(settings.clone());
+    log_manager.add_entry(LogEntry::new(
+        "整理工作日志库的对外接口".to_string(),
+        "example".to_string(),
+        vec!["示例".to_string()],
+    ))?;
+
+    let today = Local::now().date_naive();
+    let stats = log_manager.compute_stats(&today, &today)?;
+    println!("今天共 {} 条日志，{} 字", stats.total_entries, stats.total_words);
+
+    let logs = log_manager.get_entries_in_date_range(&today, &today)?;
+    let config = SummaryConfig {
+        summary_type: SummaryType::Daily,
+        start_date: Some(today),
+        end_date: Some(today),
+        title: "示例日报".to_string(),
+        include_git: false,
+        git_repo_paths: Vec::new(),
+        format: SummaryFormat::Markdown,
+        group_by: GroupBy::Date,
+    };
+    let summary = SummaryGenerator::new(settings)
+        .with_offline(true)
+        .generate_summary(logs, config)
+        .await?;
+    println!("{}", summary);
+
+    Ok(())
+}
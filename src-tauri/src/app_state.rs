@@ -1,27 +1,65 @@
+use crate::errors::AppError;
+use crate::log_manager::LogManager;
+use crate::messages::{self, Language};
 use crate::settings::Settings;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 use tauri::AppHandle;
 
+/// 名称及使用次数的缓存，为 None 时需要重新扫描日志目录
+type CountCache = Arc<Mutex<Option<Vec<(String, usize)>>>>;
+
 /// 应用的全局状态，包含设置和其他共享资源
 #[derive(Debug, Default, Clone)]
 pub struct AppState {
     /// 应用设置
     pub settings: Arc<Mutex<Settings>>,
+    /// 当前使用的设置档案，None 为默认档案
+    pub profile: Option<String>,
     /// 应用句柄，用于跨线程访问 Tauri 功能
     pub app_handle: Arc<Mutex<Option<AppHandle>>>,
+    /// 正在进行的摘要生成任务，按请求 ID 保存取消标记
+    pub summary_tasks: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    /// 缓存的标签及使用次数，日志变更后清空
+    pub tag_cache: CountCache,
+    /// 缓存的来源及使用次数，日志变更后清空
+    pub source_cache: CountCache,
 }
 
 impl AppState {
-    /// 创建新的应用状态
+    /// 创建新的应用状态，档案由环境变量 `WORK_RECORD_PROFILE` 指定
     pub fn new() -> Self {
-        let settings = Settings::load_or_default().unwrap_or_else(|_| {
-            log::warn!("无法加载设置，使用默认值");
-            Settings::default()
+        // 档案名称无效时退回默认档案，避免写入其他路径
+        let profile = Settings::active_profile().filter(|profile| {
+            match Settings::validate_profile_name(profile) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("{}，使用默认档案", e);
+                    false
+                }
+            }
         });
+        if let Some(profile) = &profile {
+            log::info!("使用设置档案: {}", profile);
+        }
+
+        let settings = Settings::load_profile(profile.as_deref()).unwrap_or_else(|e| {
+            log::warn!("无法加载设置: {}，使用默认值", e);
+            Settings {
+                profile: profile.clone(),
+                ..Settings::default()
+            }
+        });
+        messages::set_language(Language::from_code(&settings.language));
 
         Self {
             settings: Arc::new(Mutex::new(settings)),
+            profile,
             app_handle: Arc::new(Mutex::new(None)),
+            summary_tasks: Arc::new(Mutex::new(HashMap::new())),
+            tag_cache: Arc::new(Mutex::new(None)),
+            source_cache: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -37,11 +75,140 @@ impl AppState {
         self.settings.lock().unwrap().clone()
     }
 
-    /// 更新设置
-    pub fn update_settings(&self, settings: Settings) -> Result<(), String> {
-        let mut current_settings = self.settings.lock().map_err(|e| e.to_string())?;
+    /// 更新设置，保存到当前档案的设置文件
+    ///
+    /// 前端提交的设置不包含环境变量覆盖信息，沿用当前设置的记录，避免覆盖值被写入文件
+    pub fn update_settings(&self, mut settings: Settings) -> Result<(), AppError> {
+        settings.profile = self.profile.clone();
+        let mut current_settings = Self::lock(&self.settings)?;
+        settings.env_overrides = current_settings.env_overrides.clone();
         *current_settings = settings.clone();
-        settings.save().map_err(|e| e.to_string())?;
+        settings.save()?;
+        messages::set_language(Language::from_code(&settings.language));
+        // 日志目录可能已变化
+        self.invalidate_vocabulary_cache()
+    }
+
+    /// 获取锁，锁已失效（持有锁的线程崩溃）时返回错误
+    fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, AppError> {
+        mutex
+            .lock()
+            .map_err(|e| AppError::GeneralError(e.to_string()))
+    }
+
+    /// 获取所有标签及使用次数，缓存为空时扫描日志目录
+    pub fn get_tags(&self) -> Result<Vec<(String, usize)>, AppError> {
+        Self::cached(&self.tag_cache, || {
+            LogManager::new(self.get_settings()).list_tags()
+        })
+    }
+
+    /// 获取所有来源及使用次数，缓存为空时扫描日志目录
+    pub fn get_sources(&self) -> Result<Vec<(String, usize)>, AppError> {
+        Self::cached(&self.source_cache, || {
+            LogManager::new(self.get_settings()).list_sources()
+        })
+    }
+
+    /// 清空标签和来源缓存，在日志记录变更后调用
+    pub fn invalidate_vocabulary_cache(&self) -> Result<(), AppError> {
+        *Self::lock(&self.tag_cache)? = None;
+        *Self::lock(&self.source_cache)? = None;
+        Ok(())
+    }
+
+    /// 读取缓存，未命中时调用 `load` 并写入缓存
+    fn cached(
+        cache: &Mutex<Option<Vec<(String, usize)>>>,
+        load: impl FnOnce() -> Result<Vec<(String, usize)>, AppError>,
+    ) -> Result<Vec<(String, usize)>, AppError> {
+        if let Some(values) = Self::lock(cache)?.as_ref() {
+            return Ok(values.clone());
+        }
+
+        let values = load()?;
+        *Self::lock(cache)? = Some(values.clone());
+        Ok(values)
+    }
+
+    /// 登记摘要生成任务，返回该任务的取消标记
+    pub fn register_summary_task(&self, request_id: &str) -> Result<Arc<AtomicBool>, AppError> {
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        Self::lock(&self.summary_tasks)?.insert(request_id.to_string(), cancel_flag.clone());
+        Ok(cancel_flag)
+    }
+
+    /// 请求取消摘要生成任务，未指定 ID 时取消全部任务
+    ///
+    /// 返回被标记取消的任务数量
+    pub fn cancel_summary_task(&self, request_id: Option<&str>) -> Result<usize, AppError> {
+        let tasks = Self::lock(&self.summary_tasks)?;
+        let mut cancelled = 0;
+        for (id, cancel_flag) in tasks.iter() {
+            if request_id.is_none_or(|request_id| request_id == id) {
+                cancel_flag.store(true, Ordering::Relaxed);
+                cancelled += 1;
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// 摘要生成结束后移除任务
+    pub fn remove_summary_task(&self, request_id: &str) -> Result<(), AppError> {
+        Self::lock(&self.summary_tasks)?.remove(request_id);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poisoned_cache_is_reported_as_error() {
+        let state = AppState::default();
+        *state.tag_cache.lock().unwrap() = Some(vec![("rust".to_string(), 2)]);
+        assert_eq!(state.get_tags().unwrap(), [("rust".to_string(), 2)]);
+
+        // 持有锁的线程崩溃后锁失效
+        let tag_cache = state.tag_cache.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = tag_cache.lock().unwrap();
+            panic!("持有缓存锁时崩溃");
+        })
+        .join();
+
+        assert!(matches!(state.get_tags(), Err(AppError::GeneralError(_))));
+        assert!(matches!(
+            state.invalidate_vocabulary_cache(),
+            Err(AppError::GeneralError(_))
+        ));
+    }
+
+    #[test]
+    fn summary_tasks_are_registered_and_cancelled() {
+        let state = AppState::default();
+        let first = state.register_summary_task("first").unwrap();
+        let second = state.register_summary_task("second").unwrap();
+
+        assert_eq!(state.cancel_summary_task(Some("first")).unwrap(), 1);
+        assert!(first.load(Ordering::Relaxed));
+        assert!(!second.load(Ordering::Relaxed));
+
+        state.remove_summary_task("first").unwrap();
+        assert_eq!(state.cancel_summary_task(None).unwrap(), 1);
+        assert!(second.load(Ordering::Relaxed));
+
+        // 持有锁的线程崩溃后，登记、取消和移除任务都返回错误
+        let summary_tasks = state.summary_tasks.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = summary_tasks.lock().unwrap();
+            panic!("持有任务锁时崩溃");
+        })
+        .join();
+
+        assert!(state.register_summary_task("third").is_err());
+        assert!(state.cancel_summary_task(None).is_err());
+        assert!(state.remove_summary_task("second").is_err());
+    }
+}
@@ -3,7 +3,7 @@ use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 use std::process;
 use work_record::errors::AppError;
-use work_record::log_summary_cli::LogSummaryCliHandler;
+use work_record::log_summary_cli::{ColorChoice, LogSummaryCliHandler};
 use work_record::settings::Settings;
 use work_record::summary::SummaryType;
 
@@ -15,7 +15,7 @@ struct Cli {
     command: Option<Commands>,
 
     /// 摘要类型
-    #[arg(short, long, value_enum, default_value_t = SummaryTypeArg::Weekly)]
+    #[arg(short = 't', long, value_enum, default_value_t = SummaryTypeArg::Weekly)]
     summary_type: SummaryTypeArg,
 
     /// 开始日期（格式：YYYY-MM-DD）
@@ -33,6 +33,10 @@ struct Cli {
     /// 输出目录
     #[arg(short, long)]
     output_dir: Option<PathBuf>,
+
+    /// 是否输出颜色：auto 在终端中且未设置 NO_COLOR 时使用颜色，always 始终使用，never 不使用
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
 }
 
 #[derive(Debug, Subcommand)]
@@ -45,6 +49,8 @@ enum Commands {
 
 #[derive(Clone, Debug, ValueEnum)]
 enum SummaryTypeArg {
+    /// 日摘要
+    Daily,
     /// 周摘要
     Weekly,
     /// 月摘要
@@ -58,6 +64,7 @@ enum SummaryTypeArg {
 impl From<SummaryTypeArg> for SummaryType {
     fn from(arg: SummaryTypeArg) -> Self {
         match arg {
+            SummaryTypeArg::Daily => SummaryType::Daily,
             SummaryTypeArg::Weekly => SummaryType::Weekly,
             SummaryTypeArg::Monthly => SummaryType::Monthly,
             SummaryTypeArg::Quarterly => SummaryType::Quarterly,
@@ -80,6 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 解析命令行参数
     let cli = Cli::parse();
+    cli.color.apply();
 
     // 加载设置
     let mut settings = match Settings::load_or_default() {
@@ -166,4 +174,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     Ok(())
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_arguments_are_valid() {
+        Cli::command().debug_assert();
+    }
+}
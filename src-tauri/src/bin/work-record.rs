@@ -1,4 +1,5 @@
 use work_record::cli;
+use work_record::messages::Message;
 
 #[tokio::main]
 async fn main() {
@@ -7,7 +8,7 @@ async fn main() {
 
     // 运行 CLI 程序
     if let Err(err) = cli::run_cli().await {
-        eprintln!("错误: {}", err);
+        eprintln!("{}", Message::CliError(&err));
         std::process::exit(1);
     }
 }
@@ -1,17 +1,38 @@
 use crate::errors::AppError;
-use crate::log_manager::{LogEntry, LogManager};
-use crate::settings::Settings;
-use crate::summary::{SummaryConfig, SummaryGenerator, SummaryType};
-use chrono::{Days, Local, NaiveDate, Utc};
+use crate::git_utils::{
+    get_commits_since, get_head_commit_id, get_repo_name, get_working_directory, AuthorFilter,
+};
+use crate::log_manager::{
+    count_chars, count_words, parse_entries_csv, parse_entries_json, priority_label,
+    sort_entries_by_priority, AddOutcome, ChangePlan, EntryFilter, EntryStatus, ExportFormat,
+    LogEntry, LogManager, SearchOptions, SortOrder,
+};
+use crate::messages::{self, Message};
+use crate::redact::mask_secret;
+use crate::settings::{
+    self, summary_language_name, Settings, API_PROVIDER_ANTHROPIC, CONFIG_ENV_VAR, PROFILE_ENV_VAR,
+    SETTINGS_ENV_VARS, SUMMARY_FILE_CONFLICT_POLICIES, SUMMARY_LANGUAGES,
+};
+use crate::summary::{
+    GroupBy, SummaryConfig, SummaryFileConflict, SummaryFormat, SummaryGenerator, SummaryLogs, SummaryPeriod,
+    SummaryType,
+};
+use chrono::{Datelike, Local, NaiveDate};
 use clap::{CommandFactory, Parser, Subcommand};
 use log::{error, info};
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::process::Command;
+use std::collections::{btree_map, BTreeMap};
+use std::time::Duration;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
 
+/// 监视模式的进度文件名，位于日志存储目录下，记录各仓库最后处理的提交
+const WATCH_STATE_FILE_NAME: &str = ".watch-state.json";
+
 /// 工作日志记录 - 一个简单高效的工作日志管理工具
 #[derive(Parser)]
 #[command(name = "work-record")]
@@ -24,6 +45,18 @@ pub struct Cli {
     #[arg(short, long)]
     verbose: bool,
 
+    /// 使用指定的设置档案 (settings.<档案名>.json)，默认读取环境变量 WORK_RECORD_PROFILE
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// 使用指定路径的设置文件，优先于档案，默认读取环境变量 WORK_RECORD_CONFIG
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// 预演 import、archive、migrate-to-sqlite、tag rename、diagnose --fix 和 delete，只输出计划的改动而不写入文件
+    #[arg(long, global = true)]
+    dry_run: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -32,9 +65,12 @@ pub struct Cli {
 enum Commands {
     /// 添加一条新的日志记录
     Add {
-        /// 日志内容
-        #[arg(required = true)]
-        content: String,
+        /// 日志内容，为 `-` 或省略且标准输入不是终端时从标准输入读取
+        content: Option<String>,
+
+        /// 打开 $EDITOR 编写日志内容，同时提供内容时作为初始文本
+        #[arg(short, long)]
+        edit: bool,
 
         /// 日志来源 (例如: git, note, meeting)
         #[arg(short, long, default_value = "manual")]
@@ -47,6 +83,14 @@ enum Commands {
         /// 指定日期 (格式: YYYY-MM-DD)，默认为今天
         #[arg(short, long)]
         date: Option<String>,
+
+        /// 作为待办事项添加，状态可选 todo, in_progress, done
+        #[arg(long)]
+        status: Option<String>,
+
+        /// 当天已有相同内容的日志时跳过，不重复添加
+        #[arg(long)]
+        no_dup: bool,
     },
 
     /// 列出特定日期的日志记录
@@ -58,11 +102,195 @@ enum Commands {
         /// 输出格式 (text, json)
         #[arg(short, long, default_value = "text")]
         format: String,
+
+        /// 按状态过滤 (todo, in_progress, done)，未指定日期时列出所有日期
+        #[arg(long)]
+        status: Option<String>,
+
+        /// 按时间从晚到早排列
+        #[arg(long)]
+        desc: bool,
+
+        /// 排序方式 (time, priority)，priority 按优先级从高到低，相同优先级按时间排列
+        #[arg(long, default_value = "time")]
+        sort: String,
+    },
+
+    /// 设置日志记录的优先级
+    Priority {
+        /// 日志记录 ID
+        #[arg(required = true)]
+        id: String,
+
+        /// 优先级 (1 低, 2 普通, 3 高)，不指定时恢复为普通
+        level: Option<u8>,
+
+        /// 日志所在日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(short, long)]
+        date: Option<String>,
+    },
+
+    /// 将日志记录标记为已完成
+    Done {
+        /// 日志记录 ID
+        #[arg(required = true)]
+        id: String,
+
+        /// 日志所在日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(short, long)]
+        date: Option<String>,
+    },
+
+    /// 为日志记录添加或移除附件（本地文件路径或 URI，只记录引用不复制文件）
+    Attach {
+        /// 日志记录 ID
+        #[arg(required = true)]
+        id: String,
+
+        /// 附件路径或 URI，可指定多个
+        #[arg(required = true)]
+        attachments: Vec<String>,
+
+        /// 日志所在日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(short, long)]
+        date: Option<String>,
+
+        /// 移除附件，可以只写文件名
+        #[arg(short, long)]
+        remove: bool,
+    },
+
+    /// 修改日志记录的内容、来源或标签
+    Edit {
+        /// 日志记录 ID
+        #[arg(required = true)]
+        id: String,
+
+        /// 日志所在日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(short, long)]
+        date: Option<String>,
+
+        /// 新的日志内容
+        #[arg(short, long)]
+        content: Option<String>,
+
+        /// 新的日志来源
+        #[arg(short, long)]
+        source: Option<String>,
+
+        /// 新的标签，可以多次指定，会替换原有标签
+        #[arg(short, long)]
+        tags: Vec<String>,
+    },
+
+    /// 删除日志记录，默认移入回收站
+    Delete {
+        /// 日志记录 ID
+        #[arg(required = true)]
+        id: String,
+
+        /// 日志所在日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(short, long)]
+        date: Option<String>,
+
+        /// 直接删除，不移入回收站
+        #[arg(long)]
+        permanent: bool,
+    },
+
+    /// 管理回收站
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommands,
+    },
+
+    /// 在所有日志中搜索关键字
+    Search {
+        /// 搜索关键字
+        #[arg(required = true)]
+        query: String,
+
+        /// 区分大小写
+        #[arg(short, long)]
+        case_sensitive: bool,
+
+        /// 全词匹配
+        #[arg(short, long)]
+        whole_word: bool,
+
+        /// 输出格式 (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// 列出所有标签及使用次数
+    Tags,
+
+    /// 管理标签
+    Tag {
+        #[command(subcommand)]
+        action: TagCommands,
+    },
+
+    /// 导出日期范围内的日志
+    Export {
+        /// 起始日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(long)]
+        start_date: Option<String>,
+
+        /// 结束日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(long)]
+        end_date: Option<String>,
+
+        /// 导出格式 (csv, markdown)
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+
+        /// 输出文件，默认打印到控制台
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// 统计日期范围内的日志活跃度
+    Stats {
+        /// 起始日期 (格式: YYYY-MM-DD)，默认为结束日期前 29 天
+        #[arg(long)]
+        start: Option<String>,
+
+        /// 结束日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(long)]
+        end: Option<String>,
+
+        /// 输出格式 (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// 从 JSON 或 CSV 文件批量导入日志
+    Import {
+        /// 待导入的文件
+        #[arg(required = true)]
+        file: PathBuf,
+
+        /// 文件格式 (json, csv)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+    },
+
+    /// 持续监视 Git 仓库，自动将新的提交记录为日志，按 Ctrl-C 退出
+    Watch {
+        /// Git 仓库路径，可重复指定，默认使用当前目录
+        #[arg(long = "repo")]
+        repos: Vec<PathBuf>,
+
+        /// 检查新提交的间隔秒数
+        #[arg(short, long, default_value_t = 30)]
+        interval: u64,
     },
 
     /// 生成日志摘要
     Summary {
-        /// 摘要类型 (daily, weekly, monthly, quarterly, custom)
+        /// 摘要类型 (daily, weekly, monthly, quarterly, custom)，daily 默认为今天，可用 --start-date 指定日期
         #[arg(short = 'y', long = "type", default_value = "weekly")]
         type_name: String,
 
@@ -81,13 +309,81 @@ enum Commands {
         /// 输出文件，默认打印到控制台
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// 不调用模型，使用离线模板按日期和标签生成摘要
+        #[arg(long)]
+        offline: bool,
+
+        /// 忽略缓存，强制调用模型重新生成
+        #[arg(long)]
+        no_cache: bool,
+
+        /// 周/月/季度摘要按当前日期倒推 7/30/90 天，而不是按自然周/月/季度对齐
+        #[arg(long)]
+        rolling: bool,
+
+        /// 汇总上次生成摘要之后到今天的日志，还没有生成过摘要时按周摘要的范围
+        #[arg(long, conflicts_with_all = ["start_date", "end_date"])]
+        since_last: bool,
+
+        /// 摘要文件已存在时的处理方式 (suffix, overwrite, skip)，默认使用设置中的处理方式
+        #[arg(long)]
+        on_conflict: Option<String>,
+
+        /// 提示词中日志的分组方式 (date, tag, source)，tag 按标签分组，便于按项目回顾
+        #[arg(long, default_value = "date")]
+        group_by: String,
+
+        /// 将日期范围内的 Git 提交纳入摘要
+        #[arg(long)]
+        git: bool,
+
+        /// Git 仓库路径，可重复指定，默认使用当前目录
+        #[arg(long = "repo")]
+        repos: Vec<PathBuf>,
+
+        /// 摘要输出语言 (auto, zh, zh-TW, en, ja, ko, fr, de, es)，默认使用设置中的语言
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// 输出格式 (markdown, html, text, json)，html 可在浏览器中打开或打印为 PDF，
+        /// json 输出包含标题、日期范围、模型和是否命中缓存的 Markdown 摘要
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        #[command(subcommand)]
+        action: Option<Box<SummaryCommands>>,
     },
 
     /// 打印应用配置信息
     Config,
     
     /// 诊断并修复配置问题
-    Diagnose,
+    Diagnose {
+        /// 创建缺失的目录和设置文件，默认只输出诊断结果
+        #[arg(long, alias = "apply")]
+        fix: bool,
+    },
+
+    /// 检查摘要使用的模型服务是否可用
+    Doctor,
+
+    /// 备份日志目录
+    Backup {
+        /// 备份目标目录，默认在日志目录旁创建带时间戳的目录
+        dest: Option<PathBuf>,
+    },
+
+    /// 将旧月份的每日日志文件合并为月度归档，归档后仍可正常读取和修改
+    Archive {
+        /// 归档该月份 (格式: YYYY-MM) 之前的所有月份，默认为本月之前
+        #[arg(long)]
+        before: Option<String>,
+    },
+
+    /// 将每日日志文件和月度归档导入 SQLite 数据库，可以重复执行，原有文件保持不变
+    #[cfg(feature = "sqlite")]
+    MigrateToSqlite,
 
     /// 注册/卸载本工具为系统命令
     Register {
@@ -97,10 +393,107 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum SummaryCommands {
+    /// 对比两个时间段的日志，分析新的工作重点、停止的工作和整体趋势
+    Compare {
+        /// 时间段 A（基准）的起始日期 (格式: YYYY-MM-DD)
+        #[arg(long)]
+        a_start: String,
+
+        /// 时间段 A 的结束日期 (格式: YYYY-MM-DD)
+        #[arg(long)]
+        a_end: String,
+
+        /// 时间段 B 的起始日期 (格式: YYYY-MM-DD)
+        #[arg(long)]
+        b_start: String,
+
+        /// 时间段 B 的结束日期 (格式: YYYY-MM-DD)，默认为今天
+        #[arg(long)]
+        b_end: Option<String>,
+
+        /// 摘要标题
+        #[arg(short, long, default_value = "对比摘要")]
+        title: String,
+
+        /// 输出文件，默认打印到控制台
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// 摘要输出语言 (auto, zh, zh-TW, en, ja, ko, fr, de, es)，默认使用设置中的语言
+        #[arg(long)]
+        lang: Option<String>,
+    },
+}
+
+impl Commands {
+    /// 是否以 JSON 格式输出结果
+    fn is_json_output(&self) -> bool {
+        let format = match self {
+            Commands::List { format, .. }
+            | Commands::Search { format, .. }
+            | Commands::Stats { format, .. }
+            | Commands::Summary {
+                format,
+                action: None,
+                ..
+            } => format,
+            _ => return false,
+        };
+        format.eq_ignore_ascii_case("json")
+    }
+}
+
+#[derive(Subcommand)]
+enum TagCommands {
+    /// 在所有日志中重命名或合并标签
+    Rename {
+        /// 原标签
+        from: String,
+
+        /// 新标签
+        to: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommands {
+    /// 列出回收站中的日志记录
+    List,
+
+    /// 将日志记录恢复到原来的日期
+    Restore {
+        /// 日志记录 ID
+        id: String,
+    },
+
+    /// 清空回收站
+    Empty {
+        /// 只清除删除时间早于指定天数的记录
+        #[arg(long)]
+        older_than_days: Option<u32>,
+    },
+}
+
 /// 解析命令行参数并运行对应命令
 pub async fn run_cli() -> Result<(), String> {
     let cli = Cli::parse();
+    let json_output = cli.command.as_ref().is_some_and(Commands::is_json_output);
+
+    let result = execute_cli(&cli).await;
+    if let Err(message) = &result {
+        // JSON 模式下错误同样以 JSON 输出到标准错误，便于脚本区分成功和失败
+        if json_output {
+            eprintln!("{}", serde_json::json!({ "error": message }));
+            std::process::exit(1);
+        }
+    }
+    result
+}
 
+/// 执行解析后的命令
+async fn execute_cli(cli: &Cli) -> Result<(), String> {
     // 设置日志级别
     if cli.verbose {
         std::env::set_var("RUST_LOG", "debug");
@@ -108,18 +501,157 @@ pub async fn run_cli() -> Result<(), String> {
         std::env::set_var("RUST_LOG", "info");
     }
 
+    // 命令行指定的档案优先于环境变量，后续加载设置时统一读取该环境变量
+    if let Some(profile) = &cli.profile {
+        Settings::validate_profile_name(profile).map_err(|e| e.to_string())?;
+        std::env::set_var(PROFILE_ENV_VAR, profile);
+    }
+
+    // 设置文件路径同样通过环境变量传递，所有加载和保存设置的位置都会使用该路径
+    if let Some(config) = &cli.config {
+        if config.is_dir() {
+            return Err(Message::SettingsPathIsDir(&config.display()).to_string());
+        }
+        std::env::set_var(CONFIG_ENV_VAR, config);
+    }
+
+    // 输出使用设置中的界面语言，此时只读取设置文件，避免诊断等命令之前就创建设置文件
+    messages::set_language(Settings::configured_language());
+
     // 根据命令执行相应操作
     match &cli.command {
         Some(Commands::Add {
+            content,
+            edit,
+            source,
+            tags,
+            date,
+            status,
+            no_dup,
+        }) => {
+            let content = resolve_entry_content(content.as_deref(), *edit)?;
+            add_log_entry(
+                &content,
+                date.as_deref(),
+                source,
+                tags,
+                status.as_deref(),
+                *no_dup,
+            )?;
+        }
+        Some(Commands::List {
+            date,
+            format,
+            status,
+            desc,
+            sort,
+        }) => {
+            let order = if *desc { SortOrder::Desc } else { SortOrder::Asc };
+            list_log_entries(date.as_deref(), format, status.as_deref(), order, sort)?;
+        }
+        Some(Commands::Priority { id, level, date }) => {
+            set_entry_priority(id, *level, date.as_deref())?;
+        }
+        Some(Commands::Done { id, date }) => {
+            mark_entry_done(id, date.as_deref())?;
+        }
+        Some(Commands::Attach {
+            id,
+            attachments,
+            date,
+            remove,
+        }) => {
+            update_entry_attachments(id, attachments, date.as_deref(), *remove)?;
+        }
+        Some(Commands::Edit {
+            id,
+            date,
             content,
             source,
             tags,
+        }) => {
+            edit_log_entry(id, date.as_deref(), content.as_deref(), source.as_deref(), tags)?;
+        }
+        Some(Commands::Delete {
+            id,
             date,
+            permanent,
+        }) => {
+            delete_log_entry(id, date.as_deref(), *permanent, cli.dry_run)?;
+        }
+        Some(Commands::Trash { action }) => match action {
+            TrashCommands::List => {
+                list_trash()?;
+            }
+            TrashCommands::Restore { id } => {
+                restore_trash_entry(id)?;
+            }
+            TrashCommands::Empty { older_than_days } => {
+                empty_trash(*older_than_days)?;
+            }
+        },
+        Some(Commands::Search {
+            query,
+            case_sensitive,
+            whole_word,
+            format,
+        }) => {
+            let options = SearchOptions {
+                case_sensitive: *case_sensitive,
+                whole_word: *whole_word,
+            };
+            search_log_entries(query, options, format)?;
+        }
+        Some(Commands::Tags) => {
+            list_tags()?;
+        }
+        Some(Commands::Tag { action }) => match action {
+            TagCommands::Rename { from, to } => {
+                rename_tag(from, to, cli.dry_run)?;
+            }
+        },
+        Some(Commands::Export {
+            start_date,
+            end_date,
+            format,
+            output,
         }) => {
-            add_log_entry(content, date.as_deref(), source, tags)?;
+            export_log_entries(
+                start_date.as_deref(),
+                end_date.as_deref(),
+                format,
+                output.as_deref(),
+            )?;
+        }
+        Some(Commands::Stats { start, end, format }) => {
+            show_log_stats(start.as_deref(), end.as_deref(), format)?;
+        }
+        Some(Commands::Import { file, format }) => {
+            import_log_entries(file, format, cli.dry_run)?;
         }
-        Some(Commands::List { date, format }) => {
-            list_log_entries(date.as_deref(), format)?;
+        Some(Commands::Watch { repos, interval }) => {
+            watch_git_commits(repos, *interval).await?;
+        }
+        Some(Commands::Summary {
+            action: Some(action),
+            ..
+        }) => {
+            let SummaryCommands::Compare {
+                a_start,
+                a_end,
+                b_start,
+                b_end,
+                title,
+                output,
+                lang,
+            } = action.as_ref();
+            generate_comparison_summary(
+                (a_start, a_end),
+                (b_start, b_end.as_deref()),
+                title,
+                output.as_deref(),
+                lang.as_deref(),
+            ).await?;
         }
         Some(Commands::Summary {
             type_name,
@@ -127,6 +659,17 @@ pub async fn run_cli() -> Result<(), String> {
             end_date,
             title,
             output,
+            offline,
+            no_cache,
+            rolling,
+            since_last,
+            on_conflict,
+            group_by,
+            git,
+            repos,
+            lang,
+            format,
+            action: None,
         }) => {
             generate_summary(
                 type_name,
@@ -134,13 +677,35 @@ pub async fn run_cli() -> Result<(), String> {
                 end_date.as_deref(),
                 title,
                 output.as_ref().map(|p| p.as_path()),
+                *offline,
+                *no_cache,
+                *rolling,
+                *since_last,
+                on_conflict.as_deref(),
+                group_by,
+                git.then_some(repos.as_slice()),
+                lang.as_deref(),
+                format,
             ).await?;
         }
         Some(Commands::Config) => {
             show_config()?;
         }
-        Some(Commands::Diagnose) => {
-            diagnose_config()?;
+        Some(Commands::Diagnose { fix }) => {
+            diagnose_config(*fix, cli.dry_run)?;
+        }
+        Some(Commands::Doctor) => {
+            check_summary_backend().await?;
+        }
+        Some(Commands::Backup { dest }) => {
+            backup_logs(dest.as_deref())?;
+        }
+        Some(Commands::Archive { before }) => {
+            archive_logs(before.as_deref(), cli.dry_run)?;
+        }
+        #[cfg(feature = "sqlite")]
+        Some(Commands::MigrateToSqlite) => {
+            migrate_to_sqlite(cli.dry_run)?;
         }
         Some(Commands::Register { uninstall }) => {
             register_cli(!uninstall)?;
@@ -161,40 +726,86 @@ fn add_log_entry(
     date_str: Option<&str>,
     source: &str,
     tags: &[String],
+    status: Option<&str>,
+    skip_duplicates: bool,
 ) -> Result<(), String> {
+    let status = status.map(str::parse::<EntryStatus>).transpose()?;
     let settings = load_settings().map_err(|e| e.to_string())?;
-    println!("信息: 使用日志存储目录: {}", settings.log_storage_dir);
+    println!("{}", Message::UsingStorageDir(&settings.log_storage_dir));
     
     // 确保目录存在
     if let Err(e) = settings.ensure_log_dirs_exist() {
-        return Err(format!("创建日志目录失败: {}", e));
+        return Err(Message::CreateLogDirFailed(&e).to_string());
     }
     
     let log_manager = LogManager::new(settings);
 
     let date = parse_date(date_str)?;
 
-    let entry =
+    let mut entry =
         LogEntry::new_with_date(content.to_string(), source.to_string(), tags.iter().cloned().collect(), date);
-    log_manager.add_entry(entry).map_err(|e| e.to_string())?;
+    entry.status = status;
+
+    let entry_id = entry.id.clone();
+    if skip_duplicates {
+        let outcome = log_manager
+            .add_entry_checked(entry, true)
+            .map_err(|e| e.to_string())?;
+        if let AddOutcome::Skipped { existing_id } = outcome {
+            println!("{}", Message::DuplicateSkipped(&existing_id));
+            return Ok(());
+        }
+    } else {
+        log_manager.add_entry(entry).map_err(|e| e.to_string())?;
+    }
 
-    println!("✅ 已添加日志记录到: {}", log_manager.get_log_file_path(&date).display());
+    println!("{}", Message::EntryAdded(&log_manager.get_log_file_path(&date).display()));
+    println!("   ID: {}", entry_id);
     Ok(())
 }
 
 /// 列出日志条目
-fn list_log_entries(date_str: Option<&str>, format: &str) -> Result<(), String> {
+fn list_log_entries(
+    date_str: Option<&str>,
+    format: &str,
+    status: Option<&str>,
+    order: SortOrder,
+    sort: &str,
+) -> Result<(), String> {
+    let by_priority = match sort.to_lowercase().as_str() {
+        "time" => false,
+        "priority" => true,
+        _ => return Err(Message::UnsupportedSort(sort).to_string()),
+    };
+
     let settings = load_settings().map_err(|e| e.to_string())?;
     let log_manager = LogManager::new(settings);
 
+    let status = status.map(str::parse::<EntryStatus>).transpose()?;
+
+    // 按状态过滤且未指定日期时，列出所有日期中该状态的记录
+    if let (Some(status), None) = (status, date_str) {
+        return list_entries_with_status(&log_manager, status, format);
+    }
+
     let date = parse_date(date_str)?;
 
-    let entries = log_manager
-        .get_entries_for_date(&date)
-        .map_err(|e| e.to_string())?;
+    let filter = EntryFilter {
+        status,
+        ..EntryFilter::default()
+    };
+    let mut entries: Vec<LogEntry> = log_manager
+        .get_entries_for_date_sorted(&date, order)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    if by_priority {
+        sort_entries_by_priority(&mut entries);
+    }
 
     if entries.is_empty() {
-        println!("📅 {} 没有任何日志记录", date.format("%Y-%m-%d"));
+        println!("{}", Message::NoEntriesOnDate(&date.format("%Y-%m-%d")));
         return Ok(());
     }
 
@@ -204,21 +815,34 @@ fn list_log_entries(date_str: Option<&str>, format: &str) -> Result<(), String>
             println!("{}", json);
         }
         _ => {
-            println!("📅 日期: {}", date.format("%Y-%m-%d"));
-            println!("📝 共有 {} 条日志记录:", entries.len());
+            println!("{}", Message::ListDate(&date.format("%Y-%m-%d")));
+            println!("{}", Message::ListCount(entries.len()));
             println!();
 
             for (i, entry) in entries.iter().enumerate() {
-                println!("🔹 记录 #{}:", i + 1);
-                println!("   内容: {}", entry.content);
-                println!("   来源: {}", entry.source);
+                println!("{}", Message::EntryNumber(i + 1));
+                println!("   ID: {}", entry.id);
+                println!("   {}: {}", Message::LabelContent, entry.content);
+                println!("   {}: {}", Message::LabelSource, entry.source);
+
+                if let Some(status) = entry.status {
+                    println!("   {}: {}", Message::LabelStatus, status.label());
+                }
+
+                if let Some(priority) = entry.priority {
+                    println!("   {}: {}", Message::LabelPriority, priority_label(priority));
+                }
 
                 if !entry.tags.is_empty() {
-                    println!("   标签: {}", entry.tags.join(", "));
+                    println!("   {}: {}", Message::LabelTags, entry.tags.join(", "));
+                }
+
+                if !entry.attachments.is_empty() {
+                    println!("   {}: {}", Message::LabelAttachments, entry.attachments.join(", "));
                 }
 
                 if let Some(time) = &entry.timestamp {
-                    println!("   时间: {}", time.format("%H:%M:%S"));
+                    println!("   {}: {}", Message::LabelTime, time.format("%H:%M:%S"));
                 }
 
                 println!();
@@ -229,48 +853,784 @@ fn list_log_entries(date_str: Option<&str>, format: &str) -> Result<(), String>
     Ok(())
 }
 
-/// 生成摘要
-async fn generate_summary(
-    type_name: &str,
-    start_date_str: Option<&str>,
-    end_date_str: Option<&str>,
-    title: &str,
-    output_path: Option<&Path>,
+/// 列出所有日期中指定状态的日志条目
+fn list_entries_with_status(
+    log_manager: &LogManager,
+    status: EntryStatus,
+    format: &str,
 ) -> Result<(), String> {
-    let settings = load_settings().map_err(|e| e.to_string())?;
-    let log_manager = LogManager::new(settings.clone());
+    let results = log_manager
+        .get_entries_with_status(status)
+        .map_err(|e| e.to_string())?;
 
-    // 确定摘要类型
-    let summary_type = match type_name.to_lowercase().as_str() {
-        "daily" => SummaryType::Custom, // 自定义一天
-        "weekly" => SummaryType::Weekly,
-        "monthly" => SummaryType::Monthly,
-        "quarterly" => SummaryType::Quarterly,
-        "custom" => SummaryType::Custom,
-        _ => return Err(format!("不支持的摘要类型: {}", type_name)),
+    if results.is_empty() {
+        println!("{}", Message::NoEntriesWithStatus(status.label()));
+        return Ok(());
+    }
+
+    match format.to_lowercase().as_str() {
+        "json" => {
+            let json = serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?;
+            println!("{}", json);
+        }
+        _ => {
+            println!(
+                "{}",
+                Message::EntriesWithStatus {
+                    status: status.label(),
+                    count: results.len(),
+                }
+            );
+            println!();
+
+            for result in &results {
+                println!("🔹 [{}] {}", result.date, result.entry.content);
+                println!("   ID: {}", result.entry.id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 将日志条目标记为已完成
+fn mark_entry_done(id: &str, date_str: Option<&str>) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let date = parse_date(date_str)?;
+
+    let entry = log_manager
+        .set_status(id, &date, Some(EntryStatus::Done))
+        .map_err(|e| e.to_string())?;
+
+    println!("{}", Message::EntryDone(&entry.content));
+    Ok(())
+}
+
+/// 设置日志条目的优先级
+fn set_entry_priority(id: &str, level: Option<u8>, date_str: Option<&str>) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let date = parse_date(date_str)?;
+
+    let entry = log_manager
+        .set_priority(id, &date, level)
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "{}",
+        Message::PrioritySet {
+            priority: priority_label(entry.priority_level()),
+            content: &entry.content,
+        }
+    );
+    Ok(())
+}
+
+/// 为日志条目添加或移除附件
+fn update_entry_attachments(
+    id: &str,
+    attachments: &[String],
+    date_str: Option<&str>,
+    remove: bool,
+) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let date = parse_date(date_str)?;
+
+    let mut entry = None;
+    for attachment in attachments {
+        let result = if remove {
+            log_manager.remove_attachment(id, &date, attachment)
+        } else {
+            log_manager.add_attachment(id, &date, attachment)
+        };
+        entry = Some(result.map_err(|e| e.to_string())?);
+    }
+
+    if let Some(entry) = entry {
+        println!("📎 {}", entry.content);
+        if entry.attachments.is_empty() {
+            println!("   {}", Message::NoAttachments);
+        }
+        for attachment in &entry.attachments {
+            println!("   - {}", attachment);
+        }
+    }
+    Ok(())
+}
+
+/// 修改日志条目，未指定的字段保持不变
+fn edit_log_entry(
+    id: &str,
+    date_str: Option<&str>,
+    content: Option<&str>,
+    source: Option<&str>,
+    tags: &[String],
+) -> Result<(), String> {
+    if content.is_none() && source.is_none() && tags.is_empty() {
+        return Err(Message::EditNothingSpecified.to_string());
+    }
+
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let date = parse_date(date_str)?;
+    let mut entry = find_entry_on_date(&log_manager, id, &date)?;
+
+    if let Some(content) = content {
+        entry.content = content.to_string();
+    }
+    if let Some(source) = source {
+        entry.source = source.to_string();
+    }
+    if !tags.is_empty() {
+        entry.tags = tags.to_vec();
+    }
+
+    log_manager
+        .update_entry(entry.clone())
+        .map_err(|e| e.to_string())?;
+
+    println!("{}", Message::EntryUpdated(&entry.content));
+    Ok(())
+}
+
+/// 删除日志条目
+fn delete_log_entry(
+    id: &str,
+    date_str: Option<&str>,
+    permanent: bool,
+    dry_run: bool,
+) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings).with_dry_run(dry_run);
+
+    let date = parse_date(date_str)?;
+    let entry = find_entry_on_date(&log_manager, id, &date)?;
+
+    log_manager
+        .delete_entry(id, &date, permanent)
+        .map_err(|e| e.to_string())?;
+
+    if dry_run {
+        println!(
+            "{}",
+            Message::WillDelete {
+                permanent,
+                content: &entry.content,
+            }
+        );
+        print_change_plan(&log_manager.take_plan());
+    } else if permanent {
+        println!("{}", Message::DeletedPermanently(&entry.content));
+    } else {
+        println!("{}", Message::MovedToTrash(&entry.content));
+        println!("   {}", Message::RestoreHint(id));
+    }
+    Ok(())
+}
+
+/// 列出回收站中的日志记录
+fn list_trash() -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let trash = log_manager.list_trash().map_err(|e| e.to_string())?;
+
+    if trash.is_empty() {
+        println!("{}", Message::TrashEmpty);
+        return Ok(());
+    }
+
+    println!("{}", Message::TrashCount(trash.len()));
+    println!();
+    for item in &trash {
+        println!("📅 {} (ID: {})", item.date, item.entry.id);
+        println!("   {}: {}", Message::LabelContent, item.entry.content);
+        println!("   {}: {}", Message::LabelDeletedAt, item.deleted_at);
+        println!();
+    }
+
+    Ok(())
+}
+
+/// 恢复回收站中的日志记录
+fn restore_trash_entry(id: &str) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let item = log_manager.restore_entry(id).map_err(|e| e.to_string())?;
+
+    println!(
+        "{}",
+        Message::Restored {
+            date: &item.date,
+            content: &item.entry.content,
+        }
+    );
+    Ok(())
+}
+
+/// 清空回收站
+fn empty_trash(older_than_days: Option<u32>) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let cutoff =
+        older_than_days.map(|days| Local::now() - chrono::Duration::days(i64::from(days)));
+    let purged = log_manager.purge_trash(cutoff).map_err(|e| e.to_string())?;
+
+    println!("{}", Message::TrashPurged(purged));
+    Ok(())
+}
+
+/// 查找指定日期的日志条目，找不到时在错误信息中列出当天可用的 ID
+fn find_entry_on_date(
+    log_manager: &LogManager,
+    id: &str,
+    date: &NaiveDate,
+) -> Result<LogEntry, String> {
+    let entries = log_manager
+        .get_entries_for_date(date)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(entry) = entries.iter().find(|entry| entry.id == id) {
+        return Ok(entry.clone());
+    }
+
+    if entries.is_empty() {
+        return Err(Message::NoEntriesOnDateHint(&date.format("%Y-%m-%d")).to_string());
+    }
+
+    let candidates: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let preview: String = entry.content.lines().next().unwrap_or("").chars().take(40).collect();
+            format!("  {}  {}", entry.id, preview)
+        })
+        .collect();
+
+    Err(Message::EntryNotFoundOnDate {
+        date: &date.format("%Y-%m-%d"),
+        id,
+        candidates: &candidates.join("\n"),
+    }
+    .to_string())
+}
+
+/// 搜索日志条目
+fn search_log_entries(query: &str, options: SearchOptions, format: &str) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let results = log_manager
+        .search_entries(query, options)
+        .map_err(|e| e.to_string())?;
+
+    if results.is_empty() {
+        println!("{}", Message::SearchNoResults(query));
+        return Ok(());
+    }
+
+    match format.to_lowercase().as_str() {
+        "json" => {
+            let json = serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?;
+            println!("{}", json);
+        }
+        _ => {
+            println!(
+                "{}",
+                Message::SearchResults {
+                    count: results.len(),
+                    query,
+                }
+            );
+            println!();
+
+            for result in &results {
+                println!("📅 {} (ID: {})", result.date, result.entry.id);
+                println!("   {}: {}", Message::LabelContent, result.entry.content);
+
+                if !result.entry.tags.is_empty() {
+                    println!("   {}: {}", Message::LabelTags, result.entry.tags.join(", "));
+                }
+
+                println!();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 列出所有标签
+fn list_tags() -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let tags = log_manager.list_tags().map_err(|e| e.to_string())?;
+
+    if tags.is_empty() {
+        println!("{}", Message::NoTags);
+        return Ok(());
+    }
+
+    println!("{}", Message::TagCount(tags.len()));
+    for (tag, count) in &tags {
+        println!("   {} ({})", tag, count);
+    }
+
+    Ok(())
+}
+
+/// 重命名标签
+fn rename_tag(from: &str, to: &str, dry_run: bool) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings).with_dry_run(dry_run);
+
+    let modified = log_manager.rename_tag(from, to).map_err(|e| e.to_string())?;
+
+    if dry_run {
+        println!(
+            "{}",
+            Message::WillRenameTag {
+                from,
+                to,
+                count: modified,
+            }
+        );
+        print_change_plan(&log_manager.take_plan());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        Message::TagRenamed {
+            from,
+            to,
+            count: modified,
+        }
+    );
+    Ok(())
+}
+
+/// 输出日志活跃度统计
+fn show_log_stats(
+    start_date_str: Option<&str>,
+    end_date_str: Option<&str>,
+    format: &str,
+) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let end_date = parse_date(end_date_str)?;
+    let start_date = match start_date_str {
+        Some(_) => parse_date(start_date_str)?,
+        None => end_date - chrono::Duration::days(29),
+    };
+
+    let stats = log_manager
+        .compute_stats(&start_date, &end_date)
+        .map_err(|e| e.to_string())?;
+
+    if format.eq_ignore_ascii_case("json") {
+        let json = serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        Message::StatsHeader {
+            start: &stats.start_date,
+            end: &stats.end_date,
+        }
+    );
+    println!("   {}", Message::StatsTotal(stats.total_entries));
+    println!(
+        "   {}",
+        Message::StatsActiveDays {
+            active: stats.active_days,
+            total: stats.total_days,
+        }
+    );
+    if stats.active_days > 0 {
+        println!(
+            "   {}",
+            Message::StatsDailyAverage(stats.total_entries as f64 / stats.active_days as f64)
+        );
+    }
+    if let Some(busiest) = &stats.busiest_day {
+        println!(
+            "   {}",
+            Message::StatsBusiestDay {
+                date: &busiest.name,
+                count: busiest.count,
+            }
+        );
+    }
+    println!(
+        "   {}",
+        Message::StatsWords {
+            words: stats.total_words,
+            chars: stats.total_chars,
+        }
+    );
+    if stats.total_entries > 0 {
+        println!(
+            "   {}",
+            Message::StatsWordsPerEntry(stats.total_words as f64 / stats.total_entries as f64)
+        );
+    }
+
+    println!();
+    println!("   {:<12} {}", Message::ColumnDate.to_string(), Message::ColumnCount);
+    for day in stats.daily_counts.iter().filter(|day| day.count > 0) {
+        println!("   {:<12} {:>4} {}", day.name, day.count, "▇".repeat(day.count.min(40)));
+    }
+
+    if !stats.tag_counts.is_empty() {
+        println!();
+        println!("   {:<20} {}", Message::ColumnTag.to_string(), Message::ColumnCount);
+        for tag in &stats.tag_counts {
+            println!("   {:<20} {:>4}", tag.name, tag.count);
+        }
+    }
+
+    if !stats.source_counts.is_empty() {
+        println!();
+        println!("   {:<20} {}", Message::ColumnSource.to_string(), Message::ColumnCount);
+        for source in &stats.source_counts {
+            println!("   {:<20} {:>4}", source.name, source.count);
+        }
+    }
+
+    Ok(())
+}
+
+/// 导出日志条目
+fn export_log_entries(
+    start_date_str: Option<&str>,
+    end_date_str: Option<&str>,
+    format: &str,
+    output_path: Option<&Path>,
+) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings);
+
+    let start_date = parse_date(start_date_str)?;
+    let end_date = parse_date(end_date_str)?;
+
+    if start_date > end_date {
+        return Err(Message::StartAfterEnd.to_string());
+    }
+
+    let export_format = match format.to_lowercase().as_str() {
+        "csv" => ExportFormat::Csv,
+        "markdown" | "md" => ExportFormat::Markdown,
+        _ => return Err(Message::UnsupportedExportFormat(format).to_string()),
+    };
+
+    let content = log_manager
+        .export_entries(&start_date, &end_date, export_format)
+        .map_err(|e| e.to_string())?;
+
+    match output_path {
+        Some(path) => {
+            fs::write(path, content).map_err(|e| Message::WriteFileFailed(&e).to_string())?;
+            println!("{}", Message::Exported(&path.display()));
+        }
+        None => {
+            print!("{}", content);
+        }
+    }
+
+    Ok(())
+}
+
+/// 从文件导入日志条目
+fn import_log_entries(file: &Path, format: &str, dry_run: bool) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings).with_dry_run(dry_run);
+
+    let content = fs::read_to_string(file).map_err(|e| Message::ReadFileFailed(&e).to_string())?;
+
+    let (entries, mut errors) = match format.to_lowercase().as_str() {
+        "json" => parse_entries_json(&content).map_err(|e| e.to_string())?,
+        "csv" => parse_entries_csv(&content),
+        _ => return Err(Message::UnsupportedImportFormat(format).to_string()),
+    };
+
+    let mut report = log_manager
+        .import_entries(entries)
+        .map_err(|e| e.to_string())?;
+    errors.append(&mut report.errors);
+
+    if dry_run {
+        println!(
+            "{}",
+            Message::WillImport {
+                added: report.added,
+                skipped: report.skipped,
+            }
+        );
+        print_change_plan(&log_manager.take_plan());
+    } else {
+        println!(
+            "{}",
+            Message::Imported {
+                added: report.added,
+                skipped: report.skipped,
+            }
+        );
+    }
+
+    if !errors.is_empty() {
+        println!("{}", Message::ImportErrors(errors.len()));
+        for error in &errors {
+            println!("   - {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// 监视 Git 仓库并自动导入作者的新提交，按 Ctrl-C 保存进度后退出
+///
+/// 各仓库最后处理的提交记录在日志目录的 `.watch-state.json` 中，重新启动后从该提交继续；
+/// 首次监视的仓库从当前 HEAD 开始，不导入已有的提交
+async fn watch_git_commits(repos: &[PathBuf], interval: u64) -> Result<(), String> {
+    if interval == 0 {
+        return Err("检查间隔必须大于 0 秒".to_string());
+    }
+
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let author = settings.git_author_filter();
+    let state_path = Path::new(&settings.log_storage_dir).join(WATCH_STATE_FILE_NAME);
+    let log_manager = LogManager::new(settings);
+
+    let repos = if repos.is_empty() {
+        vec![PathBuf::from(get_working_directory().map_err(|e| e.to_string())?)]
+    } else {
+        repos.to_vec()
     };
 
-    // 处理自定义日期范围
-    let (start_date, end_date) = if matches!(summary_type, SummaryType::Custom) {
-        let end = match end_date_str {
-            Some(date_str) => parse_date(Some(date_str))?,
-            None => Local::now().naive_local().date(),
-        };
+    let mut state = read_watch_state(&state_path);
+    for repo in &repos {
+        if let btree_map::Entry::Vacant(slot) = state.entry(watch_state_key(repo)) {
+            let head = get_head_commit_id(repo)
+                .map_err(|e| format!("无法读取仓库 {}: {}", repo.display(), e))?;
+            slot.insert(head);
+        }
+    }
+    write_watch_state(&state_path, &state)?;
 
-        let start = match start_date_str {
-            Some(date_str) => parse_date(Some(date_str))?,
-            None => {
-                if type_name.to_lowercase() == "daily" {
-                    end // 如果是daily且未指定开始日期，与结束日期相同
-                } else {
-                    return Err("自定义日期范围需要提供开始日期".to_string());
+    println!(
+        "👀 正在监视 {} 个仓库的新提交，每 {} 秒检查一次，按 Ctrl-C 退出",
+        repos.len(),
+        interval
+    );
+
+    // 只创建一次 Ctrl-C 监听，避免导入期间按下时信号丢失
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        let mut changed = false;
+        for repo in &repos {
+            let Some(last_seen) = state.get_mut(&watch_state_key(repo)) else {
+                continue;
+            };
+            let previous = last_seen.clone();
+
+            match import_new_commits(&log_manager, repo, &author, last_seen) {
+                Ok(imported) => {
+                    for entry in imported {
+                        let time = entry
+                            .created_at_local()
+                            .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        println!("✅ [{}] {} {}", repo.display(), time, entry.content);
+                    }
                 }
+                Err(e) => eprintln!("❌ 检查仓库 {} 失败: {}", repo.display(), e),
             }
-        };
+            changed |= *last_seen != previous;
+        }
 
-        (Some(start), Some(end))
-    } else {
-        (None, None)
+        if changed {
+            write_watch_state(&state_path, &state)?;
+        }
+
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = tokio::time::sleep(Duration::from_secs(interval)) => {}
+        }
+    }
+
+    write_watch_state(&state_path, &state)?;
+    println!("\n👋 已停止监视，处理进度已保存");
+
+    Ok(())
+}
+
+/// 导入仓库中 `last_seen` 之后的新提交，并将 `last_seen` 更新为当前 HEAD
+///
+/// 变基或清理后找不到上次处理的提交时，从当前 HEAD 重新开始
+fn import_new_commits(
+    log_manager: &LogManager,
+    repo: &Path,
+    author: &AuthorFilter,
+    last_seen: &mut String,
+) -> Result<Vec<LogEntry>, AppError> {
+    let head = get_head_commit_id(repo)?;
+    if head == *last_seen {
+        return Ok(Vec::new());
+    }
+
+    let commits = match get_commits_since(repo, author, last_seen) {
+        Ok(commits) => commits,
+        Err(AppError::GitError(e)) if e.code() == git2::ErrorCode::NotFound => {
+            println!(
+                "⚠️  仓库 {} 中找不到上次处理的提交 {}，从当前 HEAD 重新开始",
+                repo.display(),
+                last_seen
+            );
+            *last_seen = head;
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let imported = log_manager.import_git_commits(&commits, &get_repo_name(repo))?;
+    *last_seen = head;
+
+    Ok(imported)
+}
+
+/// 监视状态中仓库的键，使用规范化后的路径
+fn watch_state_key(repo: &Path) -> String {
+    repo.canonicalize()
+        .unwrap_or_else(|_| repo.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 读取各仓库最后处理的提交，文件不存在或无法解析时返回空映射
+fn read_watch_state(path: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 保存各仓库最后处理的提交
+fn write_watch_state(path: &Path, state: &BTreeMap<String, String>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("保存监视进度失败: {}", e))
+}
+
+/// 生成摘要
+#[allow(clippy::too_many_arguments)]
+async fn generate_summary(
+    type_name: &str,
+    start_date_str: Option<&str>,
+    end_date_str: Option<&str>,
+    title: &str,
+    output_path: Option<&Path>,
+    offline: bool,
+    no_cache: bool,
+    rolling: bool,
+    since_last: bool,
+    on_conflict: Option<&str>,
+    group_by_name: &str,
+    git_repos: Option<&[PathBuf]>,
+    language: Option<&str>,
+    format_name: &str,
+) -> Result<(), String> {
+    let json_output = format_name.eq_ignore_ascii_case("json");
+    let format = match format_name.to_lowercase().as_str() {
+        "markdown" | "md" | "json" => SummaryFormat::Markdown,
+        "html" => SummaryFormat::Html,
+        "text" | "txt" | "plain" => SummaryFormat::PlainText,
+        _ => return Err(Message::UnsupportedSummaryFormat(format_name).to_string()),
+    };
+    let group_by = GroupBy::from_name(group_by_name).ok_or_else(|| {
+        Message::UnsupportedGroupBy {
+            value: group_by_name,
+            options: "date, tag, source",
+        }
+        .to_string()
+    })?;
+
+    let settings = load_summary_settings(language)?;
+    let rolling = rolling || settings.summary_rolling_range;
+    let log_manager = LogManager::new(settings.clone());
+    let mut summary_generator = SummaryGenerator::new(settings)
+        .with_offline(offline)
+        .with_force(no_cache);
+    if let Some(name) = on_conflict {
+        let file_conflict = SummaryFileConflict::from_name(name).ok_or_else(|| {
+            Message::UnsupportedConflictPolicy {
+                value: name,
+                options: &SUMMARY_FILE_CONFLICT_POLICIES.join(", "),
+            }
+            .to_string()
+        })?;
+        summary_generator = summary_generator.with_file_conflict(file_conflict);
+    }
+
+    // 确定摘要类型，自上次摘要以来的范围按自定义日期范围处理
+    let summary_type = match type_name.to_lowercase().as_str() {
+        _ if since_last => SummaryType::Custom,
+        "daily" => SummaryType::Daily,
+        "weekly" => SummaryType::Weekly,
+        "monthly" => SummaryType::Monthly,
+        "quarterly" => SummaryType::Quarterly,
+        "custom" => SummaryType::Custom,
+        _ => return Err(Message::UnsupportedSummaryType(type_name).to_string()),
+    };
+
+    // 处理日摘要和自定义日期范围
+    let (start_date, end_date) = match summary_type {
+        _ if since_last => {
+            let (start, end) =
+                summary_generator.since_last_summary_range(Local::now().date_naive(), rolling);
+            (Some(start), Some(end))
+        }
+        SummaryType::Daily => {
+            let date = match start_date_str.or(end_date_str) {
+                Some(date_str) => parse_date(Some(date_str))?,
+                None => Local::now().date_naive(),
+            };
+
+            (Some(date), Some(date))
+        }
+        SummaryType::Custom => {
+            let end = match end_date_str {
+                Some(date_str) => parse_date(Some(date_str))?,
+                None => Local::now().naive_local().date(),
+            };
+
+            let start = match start_date_str {
+                Some(date_str) => parse_date(Some(date_str))?,
+                None => return Err(Message::CustomRangeNeedsStart.to_string()),
+            };
+
+            (Some(start), Some(end))
+        }
+        _ => (None, None),
+    };
+
+    // 纳入 Git 提交但未指定仓库时使用当前目录
+    let git_repo_paths = match git_repos {
+        Some([]) => vec![get_working_directory().map_err(|e| e.to_string())?],
+        Some(repos) => repos.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        None => Vec::new(),
     };
 
     // 创建摘要配置
@@ -279,123 +1639,248 @@ async fn generate_summary(
         start_date,
         end_date,
         title: title.to_string(),
+        include_git: git_repos.is_some(),
+        git_repo_paths,
+        format,
+        group_by,
     };
 
-    // 获取日志数据
-    let logs = match summary_type {
-        SummaryType::Custom => {
-            if let (Some(start), Some(end)) = (start_date, end_date) {
-                log_manager
-                    .get_entries_in_date_range(&start, &end)
-                    .map_err(|e| e.to_string())?
-            } else {
-                return Err("自定义日期范围需要提供开始和结束日期".to_string());
-            }
-        }
-        _ => {
-            // 根据摘要类型自动计算日期范围
-            let (start, end) = calculate_date_range(summary_type);
-            log_manager
-                .get_entries_in_date_range(&start, &end)
-                .map_err(|e| e.to_string())?
-        }
+    // 确定日期范围，周/月/季度摘要根据摘要类型自动计算
+    let (range_start, range_end) = match summary_type {
+        SummaryType::Daily | SummaryType::Custom => match (start_date, end_date) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(Message::CustomRangeNeedsDates.to_string()),
+        },
+        _ => summary_type.date_range(Local::now().date_naive(), rolling),
     };
 
-    if logs.is_empty() {
-        return Err("指定日期范围内没有日志记录".to_string());
+    // 只统计条数，生成摘要时再逐天读取日志
+    let entry_count = log_manager
+        .count_entries_in_date_range(&range_start, &range_end)
+        .map_err(|e| e.to_string())?;
+
+    if entry_count == 0 {
+        return Err(Message::NoEntriesInRange.to_string());
     }
 
     // 生成摘要
-    let summary_generator = SummaryGenerator::new(settings);
-    let summary = summary_generator
-        .generate_summary(logs, config)
+    let period = SummaryPeriod {
+        start_date: range_start,
+        end_date: range_end,
+    };
+    let outcome = summary_generator
+        .generate_summary_outcome(SummaryLogs::Range(period), config)
         .await
         .map_err(|e| e.to_string())?;
 
+    if json_output {
+        let json = serde_json::json!({
+            "title": title,
+            "range": {
+                "start": range_start.format("%Y-%m-%d").to_string(),
+                "end": range_end.format("%Y-%m-%d").to_string(),
+            },
+            "summary": outcome.summary,
+            "model": outcome.model,
+            "cached": outcome.cached,
+            "path": outcome.path,
+        });
+        let json = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+        match output_path {
+            Some(path) => {
+                std::fs::write(path, json).map_err(|e| Message::WriteFileFailed(&e).to_string())?;
+                eprintln!("{}", Message::SummarySavedTo(&path.display()));
+            }
+            None => println!("{}", json),
+        }
+        return Ok(());
+    }
+
+    let summary = outcome.summary;
+    match &outcome.path {
+        Some(path) => eprintln!("{}", Message::SummaryFile(&path.display())),
+        None => eprintln!("{}", Message::SummaryFileExists),
+    }
+
     // 输出摘要
     match output_path {
         Some(path) => {
-            std::fs::write(path, summary).map_err(|e| format!("写入文件失败: {}", e))?;
-            println!("✅ 摘要已保存到: {}", path.display());
+            std::fs::write(path, &summary).map_err(|e| Message::WriteFileFailed(&e).to_string())?;
+            println!("{}", Message::SummarySavedTo(&path.display()));
         }
         None => {
             println!("{}", summary);
         }
     }
+    print_summary_length(&summary);
 
     Ok(())
 }
 
-/// 根据摘要类型计算日期范围
-fn calculate_date_range(summary_type: SummaryType) -> (NaiveDate, NaiveDate) {
-    let now = Utc::now().naive_local().date();
-    
-    match summary_type {
-        SummaryType::Weekly => {
-            // 从当前日期倒推7天
-            let start = now
-                .checked_sub_days(Days::new(7))
-                .unwrap_or(now);
-            (start, now)
-        }
-        SummaryType::Monthly => {
-            // 从当前日期倒推30天
-            let start = now
-                .checked_sub_days(Days::new(30))
-                .unwrap_or(now);
-            (start, now)
-        }
-        SummaryType::Quarterly => {
-            // 从当前日期倒推90天
-            let start = now
-                .checked_sub_days(Days::new(90))
-                .unwrap_or(now);
-            (start, now)
+/// 打印摘要的字数统计，输出到标准错误，不影响重定向的摘要内容
+fn print_summary_length(summary: &str) {
+    eprintln!(
+        "{}",
+        Message::SummaryLength {
+            words: count_words(summary),
+            chars: count_chars(summary),
         }
-        SummaryType::Custom => {
-            // 自定义类型会在函数外部处理
-            (now, now)
+    );
+}
+
+/// 加载生成摘要使用的设置，指定语言时覆盖设置中的摘要语言
+fn load_summary_settings(language: Option<&str>) -> Result<Settings, String> {
+    let mut settings = load_settings().map_err(|e| e.to_string())?;
+    if let Some(language) = language {
+        if summary_language_name(language).is_none() {
+            let supported: Vec<&str> = SUMMARY_LANGUAGES.iter().map(|(code, _)| *code).collect();
+            return Err(Message::UnsupportedSummaryLanguage {
+                value: language,
+                options: &supported.join(", "),
+            }
+            .to_string());
+        }
+        settings.summary_language = language.to_string();
+    }
+    Ok(settings)
+}
+
+/// 对比两个时间段的日志生成摘要
+///
+/// 输出到控制台时以流式方式逐段打印，指定输出文件时生成完成后写入文件
+async fn generate_comparison_summary(
+    period_a: (&str, &str),
+    period_b: (&str, Option<&str>),
+    title: &str,
+    output_path: Option<&Path>,
+    language: Option<&str>,
+) -> Result<(), String> {
+    let settings = load_summary_settings(language)?;
+
+    let period_a = SummaryPeriod::new(parse_date(Some(period_a.0))?, parse_date(Some(period_a.1))?)
+        .map_err(|e| format!("{}: {}", Message::PeriodA, e))?;
+    let period_b = SummaryPeriod::new(parse_date(Some(period_b.0))?, parse_date(period_b.1)?)
+        .map_err(|e| format!("{}: {}", Message::PeriodB, e))?;
+
+    println!(
+        "{}",
+        Message::ComparingPeriods {
+            a: &period_a.label(),
+            b: &period_b.label(),
+        }
+    );
+
+    let summary_generator = SummaryGenerator::new(settings);
+    let stream_to_console = output_path.is_none();
+    let summary = summary_generator
+        .generate_comparison_summary(period_a, period_b, title, |chunk: &str| {
+            if stream_to_console {
+                print!("{}", chunk);
+                let _ = io::stdout().flush();
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, &summary).map_err(|e| Message::WriteFileFailed(&e).to_string())?;
+            println!("{}", Message::ComparisonSavedTo(&path.display()));
         }
+        None => println!(),
     }
+    print_summary_length(&summary);
+
+    Ok(())
 }
 
 /// 显示配置信息
 fn show_config() -> Result<(), String> {
     let settings = load_settings().map_err(|e| e.to_string())?;
 
-    println!("📋 工作日志记录 配置信息:");
-    println!("   日志存储目录: {}", settings.log_storage_dir);
-    println!("   日志输出目录: {}", settings.log_output_dir);
+    println!("{}", Message::ConfigHeader);
+    if let Some(profile) = &settings.profile {
+        println!("{}", Message::ConfigProfile(profile));
+    }
+    println!("{}", Message::ConfigStorageDir(&settings.log_storage_dir));
+    println!("{}", Message::ConfigStorageBackend(&settings.storage_backend));
+    println!("{}", Message::ConfigNormalizeTags(settings.normalize_tags));
+    println!("{}", Message::ConfigOutputDir(&settings.log_output_dir));
+    println!("{}", Message::ConfigFilePattern(&settings.file_name_pattern));
+    if !settings.summary_filename_pattern.is_empty() {
+        println!("{}", Message::ConfigSummaryFilePattern(&settings.summary_filename_pattern));
+    }
 
     if !settings.git_author.is_empty() {
-        println!("   Git 作者: {}", settings.git_author);
+        println!("{}", Message::ConfigGitAuthor(&settings.git_author));
+    }
+
+    if !settings.git_author_email.is_empty() {
+        println!("{}", Message::ConfigGitEmail(&settings.git_author_email));
     }
 
     if !settings.shortcut.is_empty() {
-        println!("   快捷键: {}", settings.shortcut);
+        println!(
+            "{}",
+            Message::ConfigShortcut {
+                shortcut: &settings.shortcut,
+                enabled: settings.enable_shortcut,
+            }
+        );
     }
 
-    println!(
-        "   自动打开窗口: {}",
-        if settings.auto_open_window {
-            "是"
-        } else {
-            "否"
-        }
-    );
+    if settings.enable_reminder {
+        println!("{}", Message::ConfigReminder(&settings.reminder_time));
+    }
+
+    println!("{}", Message::ConfigAutoOpen(settings.auto_open_window));
 
     if settings.use_local_ollama {
-        println!("   使用本地 Ollama: 是");
-        println!("   Ollama 地址: {}", settings.ollama_address);
-        println!("   Ollama 模型: {}", settings.ollama_model);
-    } else if !settings.llm_api_url.is_empty() {
-        println!("   使用远程 LLM API");
-        println!("   API 地址: {}", settings.llm_api_url);
+        println!("{}", Message::ConfigUseOllama);
+        println!("{}", Message::ConfigOllamaAddress(&settings.ollama_address));
+        println!("{}", Message::ConfigOllamaModel(&settings.ollama_model));
+        if !settings.ollama_options.is_empty() {
+            println!(
+                "{}",
+                Message::ConfigOllamaOptions(&serde_json::Value::Object(
+                    settings.ollama_options.clone()
+                ))
+            );
+        }
+    } else if !settings.llm_api_url.is_empty() || settings.api_provider == API_PROVIDER_ANTHROPIC {
+        println!("{}", Message::ConfigRemoteApi);
+        println!("{}", Message::ConfigApiProvider(&settings.api_provider));
+        println!(
+            "{}",
+            Message::ConfigApiUrl(&settings.get_summary_api_url(settings.get_summary_api_type()))
+        );
+        let model = settings.get_summary_model(settings.get_summary_api_type());
+        println!(
+            "{}",
+            Message::ConfigModel(Some(model.as_str()).filter(|model| !model.is_empty()))
+        );
         if !settings.llm_api_key.is_empty() {
-            println!("   API 密钥: ********");
+            println!("{}", Message::ConfigApiKey(&mask_secret(&settings.llm_api_key)));
         }
     }
 
+    println!("{}", Message::ConfigTemperature(&settings.summary_temperature));
+    println!("{}", Message::ConfigMaxTokens(settings.summary_max_tokens));
+    println!("{}", Message::ConfigLogSummaries(settings.log_summaries));
+    println!(
+        "{}",
+        Message::ConfigSummaryLanguage {
+            code: &settings.summary_language,
+            name: summary_language_name(&settings.summary_language),
+        }
+    );
+
+    println!("{}", Message::ConfigEnvHeader);
+    for (var, label) in SETTINGS_ENV_VARS {
+        let applied = settings.env_overrides.iter().any(|item| item.var == var);
+        println!("{}", Message::ConfigEnvVar { var, label, applied });
+    }
+
     Ok(())
 }
 
@@ -404,104 +1889,311 @@ pub fn load_settings() -> Result<Settings, AppError> {
     Settings::load_or_default()
 }
 
+/// 获取要添加的日志内容
+///
+/// 内容为 `-` 或省略且标准输入不是终端时读取标准输入，`edit` 为 true 时打开编辑器编写。
+/// 去掉末尾的换行，保留内容中的其它格式
+fn resolve_entry_content(content: Option<&str>, edit: bool) -> Result<String, String> {
+    let content = if edit {
+        let initial = match content {
+            Some("-") => read_stdin()?,
+            Some(content) => content.to_string(),
+            None => String::new(),
+        };
+        compose_in_editor(&initial)?
+    } else {
+        match content {
+            Some("-") => read_stdin()?,
+            Some(content) => content.to_string(),
+            None if !io::stdin().is_terminal() => read_stdin()?,
+            None => {
+                return Err(Message::ContentMissing.to_string())
+            }
+        }
+    };
+
+    let content = content.trim_end_matches(['\n', '\r']).to_string();
+    if content.trim().is_empty() {
+        return Err(Message::ContentEmpty.to_string());
+    }
+
+    Ok(content)
+}
+
+/// 读取全部标准输入
+fn read_stdin() -> Result<String, String> {
+    let mut buffer = String::new();
+    io::stdin()
+        .read_to_string(&mut buffer)
+        .map_err(|e| Message::ReadStdinFailed(&e).to_string())?;
+    Ok(buffer)
+}
+
+/// 打开编辑器编写内容，返回保存后的文本
+///
+/// 依次使用 $VISUAL、$EDITOR，都未设置时使用系统默认编辑器。
+/// 编辑器命令可以带参数，例如 `code --wait`
+fn compose_in_editor(initial: &str) -> Result<String, String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    let path = std::env::temp_dir().join(format!("work-record-{}.md", std::process::id()));
+    fs::write(&path, initial).map_err(|e| format!("创建临时文件失败: {}", e))?;
+
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or_default();
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => {
+            fs::read_to_string(&path).map_err(|e| format!("读取编辑内容失败: {}", e))
+        }
+        Ok(status) => Err(format!("编辑器 {} 异常退出: {}", editor, status)),
+        Err(e) => Err(format!("无法启动编辑器 {}: {}", editor, e)),
+    };
+    let _ = fs::remove_file(&path);
+
+    result
+}
+
 /// 解析日期字符串，如果为 None 则返回今天的日期
 fn parse_date(date_str: Option<&str>) -> Result<NaiveDate, String> {
     match date_str {
         Some(date_str) => NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-            .map_err(|e| format!("日期格式错误 (应为 YYYY-MM-DD): {}", e)),
+            .map_err(|e| Message::InvalidDate(&e).to_string()),
         None => Ok(Local::now().naive_local().date()),
     }
 }
 
-/// 诊断并修复配置问题
-fn diagnose_config() -> Result<(), String> {
-    // 打印当前配置
-    println!("=== 当前配置信息 ===");
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("work-record");
-    println!("配置目录: {}", config_dir.display());
-    
-    let settings_path = config_dir.join("settings.json");
-    println!("设置文件: {}", settings_path.display());
-    
-    if !config_dir.exists() {
-        println!("配置目录不存在，创建中...");
-        fs::create_dir_all(&config_dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+/// 诊断配置问题
+///
+/// 诊断由 `settings::diagnose` 完成，检查设置文件能否解析、日志目录是否存在且可写、日志能否读取。
+/// 修复时只会创建缺失的目录或设置文件，
+/// 不会改动用户配置的存储位置；未指定 `fix` 时只输出诊断结果
+fn diagnose_config(fix: bool, dry_run: bool) -> Result<(), String> {
+    // 预演模式下只输出修复时会执行的操作
+    let preview = fix && dry_run;
+    let fix = fix && !dry_run;
+
+    let report = settings::diagnose();
+
+    println!("{}", Message::DiagnoseConfigHeader);
+    if let Some(profile) = &report.profile {
+        println!("{}", Message::DiagnoseProfile(profile));
     }
-    
-    // 检查设置文件
-    if settings_path.exists() {
-        println!("设置文件存在，正在读取...");
-        match fs::read_to_string(&settings_path) {
-            Ok(content) => {
-                println!("设置内容: {}", content);
-                // 尝试解析
-                match serde_json::from_str::<Settings>(&content) {
-                    Ok(settings) => {
-                        println!("设置解析成功:");
-                        println!("  - 日志存储目录: {}", settings.log_storage_dir);
-                        println!("  - 日志输出目录: {}", settings.log_output_dir);
-                    },
-                    Err(e) => {
-                        println!("设置解析失败: {}", e);
-                    }
-                }
-            },
-            Err(e) => {
-                println!("读取设置文件失败: {}", e);
+    if let Some(config_dir) = report.settings_path.parent() {
+        println!("{}", Message::DiagnoseConfigDir(&config_dir.display()));
+    }
+    println!("{}", Message::DiagnoseSettingsFile(&report.settings_path.display()));
+    if let Some(settings) = &report.settings {
+        println!("{}", Message::DiagnoseSettingsContent(&settings.to_redacted_json()));
+    }
+
+    println!("\n{}", Message::DiagnoseChecksHeader);
+    for check in &report.checks {
+        if check.passed {
+            println!("✅ {}: {}", check.name, check.message);
+        } else {
+            println!("❌ {}: {}", check.name, check.message);
+            if let Some(suggestion) = &check.suggestion {
+                println!("   {}", suggestion);
+            }
+        }
+    }
+
+    // 不符合文件名格式的 JSON 文件不会出现在日志列表中，提示用户移走
+    if !report.unrecognized_files.is_empty() {
+        let pattern = report
+            .settings
+            .as_ref()
+            .map(|settings| settings.file_name_pattern.as_str())
+            .unwrap_or_default();
+        println!("\n{}", Message::DiagnoseLogFilesHeader);
+        println!(
+            "{}",
+            Message::UnrecognizedLogFiles {
+                count: report.unrecognized_files.len(),
+                pattern,
             }
+        );
+        for path in &report.unrecognized_files {
+            println!("   {}", path.display());
+        }
+    }
+
+    let problems = report.problem_count();
+    if fix {
+        for applied in settings::apply_fixes(&report).map_err(|e| Message::FixFailed(&e).to_string())? {
+            println!("{}", Message::FixApplied(&applied));
         }
+    } else if preview {
+        for planned in report.fixes() {
+            println!("{}", Message::FixPlanned(&planned.describe()));
+        }
+    }
+
+    println!();
+    if problems == 0 {
+        println!("{}", Message::ConfigOk);
+    } else if fix {
+        println!("{}", Message::ProblemsFixed(problems));
+    } else if preview {
+        println!("{}", Message::ProblemsPreviewed(problems));
     } else {
-        println!("设置文件不存在");
+        println!("{}", Message::ProblemsFound(problems));
     }
-    
-    // 尝试修复配置问题
-    println!("\n=== 修复配置问题 ===");
-    let user_home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-    let custom_dir = "/tmp/work_logs".to_string();
-    
-    println!("创建自定义配置，设置日志存储目录为: {}", custom_dir);
-    let settings = Settings {
-        log_storage_dir: custom_dir.clone(),
-        log_output_dir: format!("{}/summaries", custom_dir),
-        git_author: String::new(),
-        auto_open_window: false,
-        shortcut: "Alt+Shift+L".to_string(),
-        enable_shortcut: true,
-        use_local_ollama: true,
-        ollama_address: "http://localhost:11434".to_string(),
-        ollama_model: "llama3".to_string(),
-        llm_api_key: String::new(),
-        llm_api_url: String::new(),
+
+    Ok(())
+}
+
+/// 检查摘要模型服务的连通性，输出实际使用的模型和耗时
+async fn check_summary_backend() -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+
+    println!("{}", Message::CheckingBackend);
+    let info = SummaryGenerator::new(settings)
+        .test_backend()
+        .await
+        .map_err(|e| format!("{}: {}", Message::SummaryBackendUnavailable, e))?;
+
+    println!("{}", Message::BackendAvailable);
+    println!("{}", Message::BackendKind(&info.backend));
+    println!("{}", Message::BackendUrl(&info.url));
+    println!(
+        "{}",
+        Message::BackendModel(Some(info.model.as_str()).filter(|model| !model.is_empty()))
+    );
+    println!("{}", Message::BackendLatency(info.latency_ms));
+    Ok(())
+}
+
+/// 归档指定月份之前的日志
+fn archive_logs(before: Option<&str>, dry_run: bool) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let log_manager = LogManager::new(settings).with_dry_run(dry_run);
+
+    let cutoff = match before {
+        Some(month) => NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+            .map_err(|_| Message::InvalidMonth(month).to_string())?,
+        None => Local::now().date_naive().with_day(1).unwrap_or_default(),
     };
-    
-    // 保存设置
-    println!("保存自定义配置...");
-    let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("序列化配置失败: {}", e))?;
-    
-    fs::write(&settings_path, content)
-        .map_err(|e| format!("保存配置文件失败: {}", e))?;
-    
-    // 确保日志目录存在
-    let storage_path = Path::new(&settings.log_storage_dir);
-    let output_path = Path::new(&settings.log_output_dir);
-    
-    if !storage_path.exists() {
-        println!("创建日志存储目录: {}", storage_path.display());
-        fs::create_dir_all(storage_path)
-            .map_err(|e| format!("创建日志存储目录失败: {}", e))?;
+
+    let archived = log_manager
+        .archive_before(cutoff.year(), cutoff.month())
+        .map_err(|e| e.to_string())?;
+
+    if archived.is_empty() {
+        println!("{}", Message::NothingToArchive(&cutoff.format("%Y-%m")));
+        return Ok(());
     }
-    
-    if !output_path.exists() {
-        println!("创建日志输出目录: {}", output_path.display());
-        fs::create_dir_all(output_path)
-            .map_err(|e| format!("创建日志输出目录失败: {}", e))?;
+
+    if dry_run {
+        for (month, days) in &archived {
+            println!("{}", Message::WillArchive { month, days: *days });
+        }
+        print_change_plan(&log_manager.take_plan());
+        return Ok(());
     }
-    
-    println!("配置问题已修复，现在日志将保存到: {}", settings.log_storage_dir);
-    
+
+    for (month, days) in &archived {
+        println!("{}", Message::Archived { month, days: *days });
+    }
+    println!("{}", Message::ArchivedMonths(archived.len()));
+    Ok(())
+}
+
+/// 将 JSON 日志文件导入 SQLite 数据库
+#[cfg(feature = "sqlite")]
+fn migrate_to_sqlite(dry_run: bool) -> Result<(), String> {
+    use crate::settings::STORAGE_BACKEND_SQLITE;
+
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let uses_sqlite = settings.storage_backend == STORAGE_BACKEND_SQLITE;
+    let log_manager = LogManager::new(settings).with_dry_run(dry_run);
+
+    let report = log_manager.migrate_to_sqlite().map_err(|e| e.to_string())?;
+
+    if dry_run {
+        println!(
+            "{}",
+            Message::WillMigrate {
+                added: report.added,
+                skipped: report.skipped,
+            }
+        );
+        print_change_plan(&log_manager.take_plan());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        Message::Migrated {
+            added: report.added,
+            skipped: report.skipped,
+            path: &log_manager.sqlite_path().display(),
+        }
+    );
+    if !uses_sqlite {
+        println!("{}", Message::MigrateHint(STORAGE_BACKEND_SQLITE));
+    }
+    Ok(())
+}
+
+/// 输出预演模式下计划的改动
+fn print_change_plan(plan: &ChangePlan) {
+    println!("\n{}", Message::DryRunHeader);
+    if plan.is_empty() {
+        println!("{}", Message::NoPlannedChanges);
+        return;
+    }
+
+    if !plan.entries.is_empty() {
+        println!("{}", Message::AffectedEntries(plan.entries.len()));
+        for id in &plan.entries {
+            println!("   {}", id);
+        }
+    }
+
+    if !plan.files.is_empty() {
+        println!("{}", Message::ChangedFiles(plan.files.len()));
+        for change in &plan.files {
+            println!("   [{}] {}", change.kind.label(), change.path.display());
+        }
+    }
+}
+
+/// 备份日志目录
+fn backup_logs(dest: Option<&Path>) -> Result<(), String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+
+    let dest = match dest {
+        Some(dest) => dest.to_path_buf(),
+        None => PathBuf::from(format!(
+            "{}-backup-{}",
+            settings.log_storage_dir.trim_end_matches(['/', '\\']),
+            Local::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+
+    let log_manager = LogManager::new(settings);
+    let copied = log_manager.backup_all(&dest).map_err(|e| e.to_string())?;
+
+    println!(
+        "{}",
+        Message::BackedUp {
+            count: copied,
+            dest: &dest.display(),
+        }
+    );
     Ok(())
 }
 
@@ -1,15 +1,31 @@
 use crate::app_state::AppState;
-use crate::git_utils::{get_daily_commits, get_working_directory};
-use crate::log_manager::{LogEntry, LogManager};
-use crate::settings::Settings;
-use crate::summary::{SummaryConfig, SummaryGenerator, SummaryType};
-use chrono::{NaiveDate, Utc};
+use crate::errors::{CommandError, ErrorCode};
+use crate::git_utils::{
+    get_commits_in_range, get_commits_stats, get_daily_commits, get_repo_name,
+    get_working_directory, CommitStats, GitCommit,
+};
+use crate::log_manager::{
+    normalize_tags, EntryFilter, EntryStatus, LogEntry, LogFileInfo, LogFilePage, LogManager,
+    LogStats, SearchOptions, SearchResult, SortOrder, TrashedEntry, CLIPBOARD_SOURCE,
+};
+use crate::messages::Message;
+use crate::settings::{self, DiagnosisReport, Settings};
+use crate::system_tray::{apply_shortcut, check_shortcut, refresh_tray_menu};
+use crate::summary::{
+    BackendInfo, GroupBy, SummaryConfig, SummaryFormat, SummaryGenerator, SummaryLogs, SummaryOutcome,
+    SummaryPeriod, SummaryPhase, SummaryProgress, SummaryType,
+};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use std::collections::HashMap;
-use std::path::Path;
-use tauri::{AppHandle, GlobalShortcutManager, Manager, State};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, ClipboardManager, Manager, State};
 use log;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use serde::Serialize;
 use serde_json::json;
+use tauri::api::notification::Notification;
 
 #[cfg(any(target_os = "macos", target_os = "linux"))]
 use std::fs;
@@ -23,64 +39,217 @@ use std::process::Command;
 #[cfg(target_os = "windows")]
 use tauri::api::dialog;
 
-/// 添加日志条目
+/// 日志文件列表默认每页数量
+const DEFAULT_LOG_FILES_PAGE_SIZE: usize = 50;
+
+/// 解析 YYYY-MM-DD 格式的日期参数，`label` 用于错误信息，如 `Message::ArgStartDate`
+fn parse_date_arg(date: &str, label: Message<'_>) -> Result<NaiveDate, CommandError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| {
+        CommandError::new(ErrorCode::InvalidDate, Message::InvalidDateArg { label: &label, error: &e }.to_string())
+            .with_details(date)
+    })
+}
+
+/// 日志记录变更后清空标签和来源缓存，并重建托盘菜单
+///
+/// 记录已经保存，清空缓存失败时只记录警告，不影响命令的结果
+fn entries_changed(app_state: &AppState) {
+    if let Err(e) = app_state.invalidate_vocabulary_cache() {
+        log::warn!("清空标签和来源缓存失败: {}", e);
+    }
+    refresh_tray_menu(app_state);
+}
+
+/// 添加日志条目，返回保存后的记录，包含生成的 ID 和创建时间
 #[tauri::command]
 pub async fn add_log_entry(
     content: String,
     source: String,
     tags: Vec<String>,
     app_state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<LogEntry, CommandError> {
     let settings = app_state.get_settings();
     
     // 确保日志目录存在
     if let Err(e) = settings.ensure_log_dirs_exist() {
-        return Err(format!("创建日志目录失败: {}", e));
+        let error = CommandError::from(e);
+        let message = Message::CreateLogDirFailed(&error.message).to_string();
+        return Err(CommandError { message, ..error });
     }
     
+    // 保存时会整理内容和标签，提前整理使返回的记录与保存的内容保持一致
+    let tags = normalize_tags(tags, settings.normalize_tags);
     let log_manager = LogManager::new(settings);
     
-    let entry = LogEntry::new(content, source, tags);
-    match log_manager.add_entry(entry) {
+    let entry = LogEntry::new(content.trim().to_string(), source, tags);
+    match log_manager.add_entry(entry.clone()) {
         Ok(_) => {
-            // 日志记录成功，返回成功
-            Ok(())
+            // 日志记录成功，更新缓存和托盘菜单
+            entries_changed(&app_state);
+            Ok(entry)
         }
         Err(e) => {
             // 记录错误并返回
-            let error_msg = format!("添加日志失败: {}", e);
-            log::error!("{}", error_msg);
-            Err(error_msg)
+            let error = CommandError::from(e).context(Message::AddEntryFailed);
+            log::error!("{}", error);
+            Err(error)
         }
     }
 }
 
+/// 将剪贴板中的文本添加为日志，来源为 `clipboard`，完成后显示系统通知
+#[tauri::command]
+pub async fn quick_add_from_clipboard(
+    app_state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<LogEntry, CommandError> {
+    add_clipboard_entry(&app_handle, &app_state)
+}
+
+/// 读取剪贴板文本并保存为日志，托盘菜单和命令共用
+///
+/// 剪贴板为空或不是文本时返回错误，无论成功与否都会显示通知
+pub(crate) fn add_clipboard_entry(
+    app_handle: &AppHandle,
+    app_state: &AppState,
+) -> Result<LogEntry, CommandError> {
+    let result = save_clipboard_entry(app_handle, app_state);
+    
+    let body = match &result {
+        Ok(entry) => Message::ClipboardEntryAdded(entry.content.lines().next().unwrap_or("")).to_string(),
+        Err(e) => e.message.clone(),
+    };
+    if let Err(e) = Notification::new(&app_handle.config().tauri.bundle.identifier)
+        .title(Message::NotificationTitle.to_string())
+        .body(body)
+        .show()
+    {
+        log::warn!("显示通知失败: {}", e);
+    }
+    
+    result
+}
+
+fn save_clipboard_entry(app_handle: &AppHandle, app_state: &AppState) -> Result<LogEntry, CommandError> {
+    // 非文本内容读取时会返回错误或空值，统一视为没有可添加的内容
+    let text = app_handle
+        .clipboard_manager()
+        .read_text()
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let content = text.trim();
+    if content.is_empty() {
+        return Err(Message::ClipboardEmpty.into());
+    }
+    
+    let entry = LogEntry::new(content.to_string(), CLIPBOARD_SOURCE.to_string(), Vec::new());
+    LogManager::new(app_state.get_settings())
+        .add_entry(entry.clone())
+        .map_err(|e| CommandError::from(e).context(Message::AddEntryFailed))?;
+    entries_changed(app_state);
+    
+    Ok(entry)
+}
+
 /// 获取指定日期的日志条目
+///
+/// 默认按创建时间从早到晚排列，`sort` 为 `desc` 时从晚到早
 #[tauri::command]
 pub async fn get_log_entries(
     date: String,
+    sort: Option<SortOrder>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<LogEntry>, String> {
+) -> Result<Vec<LogEntry>, CommandError> {
     let settings = app_state.get_settings();
     let log_manager = LogManager::new(settings);
     
-    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-        .map_err(|e| format!("日期格式错误：{}", e))?;
+    let date = parse_date_arg(&date, Message::ArgDate)?;
     
     log_manager
-        .get_entries_for_date(&date)
-        .map_err(|e| e.to_string())
+        .get_entries_for_date_sorted(&date, sort.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// 获取指定日期满足过滤条件的日志条目
+#[tauri::command]
+pub async fn get_filtered_log_entries(
+    date: String,
+    filter: Option<EntryFilter>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<LogEntry>, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    log_manager
+        .get_filtered_entries(&date, &filter.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// 获取日期范围内的日志条目，按日期分组
+#[tauri::command]
+pub async fn get_log_entries_range(
+    start_date: String,
+    end_date: String,
+    app_state: State<'_, AppState>,
+) -> Result<HashMap<String, Vec<LogEntry>>, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let start = parse_date_arg(&start_date, Message::ArgStartDate)?;
+    let end = parse_date_arg(&end_date, Message::ArgEndDate)?;
+    
+    if start > end {
+        return Err(Message::StartAfterEndDates { start: &start_date, end: &end_date }.into());
+    }
+    
+    log_manager
+        .get_entries_in_date_range_async(start, end)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 分页获取日志文件列表，按日期从新到旧排列，每项包含当天的日志条数
+///
+/// `offset` 默认为 0，`limit` 默认为 50
+#[tauri::command]
+pub async fn get_log_files_page(
+    offset: Option<usize>,
+    limit: Option<usize>,
+    app_state: State<'_, AppState>,
+) -> Result<LogFilePage, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    log_manager
+        .get_log_files_page(offset.unwrap_or(0), limit.unwrap_or(DEFAULT_LOG_FILES_PAGE_SIZE))
+        .map_err(CommandError::from)
+}
+
+/// 获取所有日志文件的日期、日志条数和最后修改时间，按日期从新到旧排列
+#[tauri::command]
+pub async fn get_log_file_summaries(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<LogFileInfo>, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    log_manager
+        .get_log_file_summaries()
+        .map_err(CommandError::from)
 }
 
 /// 获取日志文件列表
 #[tauri::command]
-pub async fn get_log_files(app_state: State<'_, AppState>) -> Result<Vec<String>, String> {
+pub async fn get_log_files(app_state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
     let settings = app_state.get_settings();
     let log_manager = LogManager::new(settings);
     
     log::info!("收到获取日志文件列表请求");
     
-    match log_manager.get_log_files() {
+    match log_manager.get_log_files_async().await {
         Ok(files) => {
             log::info!("成功获取日志文件列表，共 {} 个文件", files.len());
             if !files.is_empty() {
@@ -99,65 +268,15 @@ pub async fn get_log_files(app_state: State<'_, AppState>) -> Result<Vec<String>
             Ok(files)
         }
         Err(err) => {
-            let error_type = format!("{:?}", err);
-            let error_msg = format!("获取日志文件列表失败: {}", err);
-            log::error!("{}", error_msg);
-            log::error!("错误类型: {}", error_type);
-            
-            // 根据不同的错误类型提供更具体的错误信息
-            let user_friendly_error = match err {
-                crate::errors::AppError::IoError(io_err) => {
-                    log::error!("IO错误细节: {:?}", io_err.kind());
-                    if let Some(ref_err) = io_err.get_ref() {
-                        log::error!("IO错误内部错误: {:?}", ref_err);
-                    }
-                    
-                    match io_err.kind() {
-                        std::io::ErrorKind::NotFound => {
-                            format!("日志目录不存在，请检查配置是否正确: {}", io_err)
-                        },
-                        std::io::ErrorKind::PermissionDenied => {
-                            format!("没有足够权限访问日志目录，请检查权限设置: {}", io_err)
-                        },
-                        std::io::ErrorKind::ConnectionRefused => {
-                            format!("连接被拒绝，无法访问日志目录: {}", io_err)
-                        },
-                        std::io::ErrorKind::ConnectionReset => {
-                            format!("连接被重置，无法访问日志目录: {}", io_err)
-                        },
-                        std::io::ErrorKind::Interrupted => {
-                            format!("操作被中断: {}", io_err)
-                        },
-                        _ => format!("读取日志目录时出现IO错误: {}", io_err)
-                    }
-                },
-                crate::errors::AppError::LogManagerError(msg) => {
-                    log::error!("日志管理器错误详细信息: {}", msg);
-                    format!("日志管理器错误: {}", msg)
-                },
-                crate::errors::AppError::SettingsError(msg) => {
-                    log::error!("配置错误详细信息: {}", msg);
-                    format!("配置错误: {}", msg)
-                },
-                crate::errors::AppError::SerdeError(serde_err) => {
-                    log::error!("序列化错误: {}", serde_err);
-                    format!("解析日志文件时出现错误: {}", serde_err)
-                },
-                crate::errors::AppError::FsError(fs_err) => {
-                    log::error!("文件系统错误: {}", fs_err);
-                    format!("处理日志文件时出现文件系统错误: {}", fs_err)
-                },
-                crate::errors::AppError::GeneralError(gen_err) => {
-                    log::error!("通用错误: {}", gen_err);
-                    format!("获取日志文件时发生错误: {}", gen_err)
-                },
-                _ => {
-                    log::error!("未识别的错误类型: {:?}", err);
-                    error_msg
+            log::error!("获取日志文件列表失败: {:?}", err);
+            if let crate::errors::AppError::IoError(io_err) = &err {
+                if let Some(ref_err) = io_err.get_ref() {
+                    log::error!("IO错误内部错误: {:?}", ref_err);
                 }
-            };
+            }
             
-            Err(user_friendly_error)
+            // 错误代码供界面区分目录不存在、权限不足等情况
+            Err(CommandError::from(err))
         }
     }
 }
@@ -167,90 +286,424 @@ pub async fn get_log_files(app_state: State<'_, AppState>) -> Result<Vec<String>
 pub async fn update_log_entry(
     entry: LogEntry,
     app_state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    log_manager.update_entry(entry).map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(())
+}
+
+/// 将日志条目移动到新的创建时间，可以跨日期
+///
+/// `created_at` 为 RFC 3339 格式的时间
+#[tauri::command]
+pub async fn move_log_entry(
+    entry_id: String,
+    from_date: String,
+    created_at: String,
+    app_state: State<'_, AppState>,
+) -> Result<LogEntry, CommandError> {
     let settings = app_state.get_settings();
     let log_manager = LogManager::new(settings);
     
-    log_manager.update_entry(entry).map_err(|e| e.to_string())
+    let from_date = parse_date_arg(&from_date, Message::ArgDate)?;
+    let created_at = DateTime::parse_from_rfc3339(&created_at)
+        .map_err(|e| {
+            CommandError::new(ErrorCode::InvalidDate, Message::InvalidTime(&e).to_string()).with_details(&created_at)
+        })?
+        .with_timezone(&Local);
+    
+    let entry = log_manager
+        .move_entry(&entry_id, &from_date, created_at)
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(entry)
 }
 
-/// 删除日志条目
+/// 删除日志条目，默认移入回收站，`permanent` 为 true 时直接删除
 #[tauri::command]
 pub async fn delete_log_entry(
     entry_id: String,
     date: String,
+    permanent: Option<bool>,
+    app_state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    log_manager
+        .delete_entry(&entry_id, &date, permanent.unwrap_or(false))
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(())
+}
+
+/// 列出回收站中的日志记录，最近删除的在前
+#[tauri::command]
+pub async fn list_trash(app_state: State<'_, AppState>) -> Result<Vec<TrashedEntry>, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    log_manager.list_trash().map_err(CommandError::from)
+}
+
+/// 将回收站中的日志记录恢复到原来的日期
+#[tauri::command]
+pub async fn restore_log_entry(
+    entry_id: String,
+    app_state: State<'_, AppState>,
+) -> Result<TrashedEntry, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let restored = log_manager
+        .restore_entry(&entry_id)
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(restored)
+}
+
+/// 设置日志条目的完成状态，`status` 为空时清除状态
+#[tauri::command]
+pub async fn set_entry_status(
+    entry_id: String,
+    date: String,
+    status: Option<EntryStatus>,
+    app_state: State<'_, AppState>,
+) -> Result<LogEntry, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    let entry = log_manager
+        .set_status(&entry_id, &date, status)
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(entry)
+}
+
+/// 设置日志条目的优先级（1 低、2 普通、3 高），`priority` 为空时恢复为普通
+#[tauri::command]
+pub async fn set_entry_priority(
+    entry_id: String,
+    date: String,
+    priority: Option<u8>,
     app_state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<LogEntry, CommandError> {
     let settings = app_state.get_settings();
     let log_manager = LogManager::new(settings);
     
-    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-        .map_err(|e| format!("日期格式错误：{}", e))?;
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    let entry = log_manager
+        .set_priority(&entry_id, &date, priority)
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(entry)
+}
+
+/// 为日志条目添加附件，本地路径必须存在，只记录路径不复制文件
+#[tauri::command]
+pub async fn add_entry_attachment(
+    entry_id: String,
+    date: String,
+    attachment: String,
+    app_state: State<'_, AppState>,
+) -> Result<LogEntry, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    let entry = log_manager
+        .add_attachment(&entry_id, &date, &attachment)
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(entry)
+}
+
+/// 移除日志条目的附件，`attachment` 可以是完整路径、URI 或文件名
+#[tauri::command]
+pub async fn remove_entry_attachment(
+    entry_id: String,
+    date: String,
+    attachment: String,
+    app_state: State<'_, AppState>,
+) -> Result<LogEntry, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    let entry = log_manager
+        .remove_attachment(&entry_id, &date, &attachment)
+        .map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(entry)
+}
+
+/// 获取所有未完成的待办事项
+#[tauri::command]
+pub async fn get_open_todos(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SearchResult>, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    log_manager.get_open_entries().map_err(CommandError::from)
+}
+
+/// 在所有日志中搜索关键字
+#[tauri::command]
+pub async fn search_log_entries(
+    query: String,
+    options: Option<SearchOptions>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SearchResult>, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
     
     log_manager
-        .delete_entry(&entry_id, &date)
-        .map_err(|e| e.to_string())
+        .search_entries_async(query, options.unwrap_or_default())
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 获取所有标签及使用次数，按次数降序排列
+///
+/// 结果缓存在应用状态中，日志变更后重新扫描，适合输入时自动补全
+#[tauri::command]
+pub async fn get_all_tags(app_state: State<'_, AppState>) -> Result<Vec<(String, usize)>, CommandError> {
+    app_state.get_tags().map_err(CommandError::from)
+}
+
+/// 获取所有来源及使用次数，按次数降序排列，结果同样带缓存
+#[tauri::command]
+pub async fn get_all_sources(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<(String, usize)>, CommandError> {
+    app_state.get_sources().map_err(CommandError::from)
+}
+
+/// 在所有日志中重命名标签，返回被修改的记录数
+#[tauri::command]
+pub async fn rename_tag(
+    from: String,
+    to: String,
+    app_state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let renamed = log_manager.rename_tag(&from, &to).map_err(CommandError::from)?;
+    entries_changed(&app_state);
+    
+    Ok(renamed)
+}
+
+/// 获取日期范围内的日志活跃度统计，用于图表展示
+#[tauri::command]
+pub async fn get_log_stats(
+    start_date: String,
+    end_date: String,
+    app_state: State<'_, AppState>,
+) -> Result<LogStats, CommandError> {
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings);
+    
+    let start = parse_date_arg(&start_date, Message::ArgStartDate)?;
+    let end = parse_date_arg(&end_date, Message::ArgEndDate)?;
+    
+    log_manager.compute_stats(&start, &end).map_err(CommandError::from)
 }
 
-/// 从 Git 仓库获取提交信息
+/// 从 Git 仓库获取提交信息，`max_commits` 限制返回的提交数
 #[tauri::command]
 pub async fn fetch_git_commits(
     repo_path: Option<String>,
     date: String,
+    max_commits: Option<usize>,
     app_state: State<'_, AppState>,
-) -> Result<Vec<HashMap<String, String>>, String> {
+) -> Result<Vec<GitCommitDto>, CommandError> {
     let settings = app_state.get_settings();
     
     let path = match repo_path {
         Some(path) => path,
-        None => get_working_directory().map_err(|e| e.to_string())?,
+        None => get_working_directory().map_err(CommandError::from)?,
     };
     
-    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-        .map_err(|e| format!("日期格式错误：{}", e))?;
+    let date = parse_date_arg(&date, Message::ArgDate)?;
     
-    let commits = get_daily_commits(Path::new(&path), &settings.git_author, &date)
-        .map_err(|e| e.to_string())?;
+    let commits = get_daily_commits(Path::new(&path), &settings.git_author_filter(), &date, max_commits)
+        .map_err(CommandError::from)?;
+    
+    Ok(commits_to_dtos(Path::new(&path), commits))
+}
+
+/// 从 Git 仓库获取日期范围内的提交信息，起止日期都包含在内，`max_commits` 限制返回的提交数
+#[tauri::command]
+pub async fn fetch_git_commits_range(
+    repo_path: Option<String>,
+    start_date: String,
+    end_date: String,
+    max_commits: Option<usize>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<GitCommitDto>, CommandError> {
+    let settings = app_state.get_settings();
+    
+    let path = match repo_path {
+        Some(path) => path,
+        None => get_working_directory().map_err(CommandError::from)?,
+    };
     
-    // 将 GitCommit 转换为前端可用的格式
-    let result: Vec<HashMap<String, String>> = commits
+    let start = parse_date_arg(&start_date, Message::ArgStartDate)?;
+    let end = parse_date_arg(&end_date, Message::ArgEndDate)?;
+    
+    let commits = get_commits_in_range(Path::new(&path), &settings.git_author_filter(), &start, &end, max_commits)
+        .map_err(CommandError::from)?;
+    
+    Ok(commits_to_dtos(Path::new(&path), commits))
+}
+
+/// 返回给前端的 Git 提交信息
+#[derive(Debug, Clone, Serialize)]
+pub struct GitCommitDto {
+    /// 提交 ID
+    pub id: String,
+    /// 提交标题，即提交消息的第一行
+    pub summary: String,
+    /// 提交消息中标题之后的正文，没有正文时为空
+    pub body: String,
+    /// 完整的提交消息，兼容按 `message` 读取提交的旧界面
+    pub message: String,
+    /// 提交时间，序列化为 RFC 3339 格式
+    pub time: DateTime<Utc>,
+    /// 提交作者
+    pub author: String,
+    /// 改动统计，统计失败时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<CommitStats>,
+}
+
+impl From<GitCommit> for GitCommitDto {
+    fn from(commit: GitCommit) -> Self {
+        let (summary, body) = match commit.message.split_once('\n') {
+            Some((summary, body)) => (summary.trim().to_string(), body.trim().to_string()),
+            None => (commit.message.trim().to_string(), String::new()),
+        };
+
+        Self {
+            id: commit.id,
+            summary,
+            body,
+            message: commit.message,
+            time: commit.time,
+            author: commit.author,
+            stats: None,
+        }
+    }
+}
+
+/// 将 GitCommit 转换为前端可用的格式，并附上改动统计
+///
+/// 统计只是附加信息，失败时记录警告并省略，不影响提交列表的返回
+fn commits_to_dtos(repo_path: &Path, commits: Vec<GitCommit>) -> Vec<GitCommitDto> {
+    let stats = get_commits_stats(repo_path, &commits).unwrap_or_else(|e| {
+        log::warn!("统计提交改动失败: {}", e);
+        Vec::new()
+    });
+    let mut stats = stats.into_iter();
+
+    commits
         .into_iter()
-        .map(|commit| {
-            let mut map = HashMap::new();
-            map.insert("id".to_string(), commit.id);
-            map.insert("message".to_string(), commit.message);
-            map.insert("time".to_string(), commit.time.to_rfc3339());
-            map.insert("author".to_string(), commit.author);
-            map
+        .map(|commit| GitCommitDto {
+            stats: stats.next(),
+            ..GitCommitDto::from(commit)
         })
-        .collect();
+        .collect()
+}
+
+/// 将指定日期的 Git 提交导入为日志记录
+///
+/// 每个提交生成一条 `git-commit` 来源的日志，ID 由提交哈希生成，已导入的提交会被跳过，
+/// 重复执行不会产生重复记录。返回本次新导入的数量
+#[tauri::command]
+pub async fn import_git_commits(
+    repo_path: Option<String>,
+    date: String,
+    app_state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let settings = app_state.get_settings();
+    
+    let path = match repo_path {
+        Some(path) => path,
+        None => get_working_directory().map_err(CommandError::from)?,
+    };
     
-    Ok(result)
+    let date = parse_date_arg(&date, Message::ArgDate)?;
+    
+    let commits = get_daily_commits(Path::new(&path), &settings.git_author_filter(), &date, None)
+        .map_err(CommandError::from)?;
+    
+    // 使用仓库目录名作为标签
+    let repo_name = get_repo_name(Path::new(&path));
+    
+    let imported = LogManager::new(settings)
+        .import_git_commits(&commits, &repo_name)
+        .map_err(|e| CommandError::from(e).context(Message::ImportCommitsFailed))?;
+    if !imported.is_empty() {
+        entries_changed(&app_state);
+    }
+    
+    Ok(imported.len())
 }
 
 /// 生成流式摘要
 /// 
-/// 流式摘要使用事件机制将摘要内容实时推送到前端
+/// 流式摘要使用事件机制将摘要内容实时推送到前端，命令在开始生成后立即返回请求 ID。
+/// 所有事件的内容均为 `{ request_id, payload }`，前端据此区分同时进行的多个摘要任务；
+/// `request_id` 也用于通过 `cancel_summary` 取消生成，未提供时自动生成。
+/// `include_git` 为 true 时将 `repo_paths`（默认当前目录）中的提交一并纳入摘要。
+/// 日志和配置未变化时直接返回缓存的摘要，`force` 为 true 时强制重新生成。
+/// `format` 指定完成事件和保存文件的格式（markdown、html、plain_text），流式片段始终为 Markdown。
+/// `group_by` 指定提示词中日志的分组方式（date、tag、source），默认按日期。
+/// 除文本事件外，还通过 `summary-generation-progress` 事件推送结构化的阶段进度
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_summary_stream(
     summary_type: String,
     start_date: Option<String>,
     end_date: Option<String>,
     title: Option<String>,
+    request_id: Option<String>,
+    include_git: Option<bool>,
+    repo_paths: Option<Vec<String>>,
+    force: Option<bool>,
+    format: Option<SummaryFormat>,
+    group_by: Option<GroupBy>,
     state: State<'_, AppState>,
     app_handle: AppHandle,
-) -> Result<(), String> {
+) -> Result<String, CommandError> {
     log::info!("收到生成流式摘要请求: 类型={}, 标题={:?}", summary_type, title);
     
-    // 发送事件通知前端开始生成
-    app_handle.emit_all("summary-generation-start", ()).map_err(|e| {
-        let err_msg = format!("无法发送摘要开始事件: {}", e);
-        log::error!("{}", err_msg);
-        err_msg
-    })?;
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     
     // 将字符串类型转换为SummaryType枚举
     let summary_type_enum = match summary_type.as_str() {
+        "daily" => SummaryType::Daily,
         "weekly" => SummaryType::Weekly,
         "monthly" => SummaryType::Monthly, 
         "quarterly" => SummaryType::Quarterly,
@@ -259,174 +712,526 @@ pub async fn generate_summary_stream(
     
     // 解析日期范围
     let (start_naive_date, end_naive_date) = match summary_type_enum {
+        SummaryType::Daily => {
+            // 日摘要默认为今天，可通过开始日期指定某一天
+            let date = match start_date.or(end_date) {
+                Some(date) => parse_date_arg(&date, Message::ArgDate)?,
+                None => Local::now().date_naive(),
+            };
+            
+            (date, date)
+        },
         SummaryType::Custom => {
             // 自定义类型需要解析日期
             let start = match start_date {
-                Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-                    .map_err(|e| format!("开始日期格式错误: {}", e))?,
-                None => return Err("自定义摘要类型需要提供开始日期".to_string())
+                Some(date) => parse_date_arg(&date, Message::ArgStartDate)?,
+                None => return Err(Message::CustomSummaryNeedsStart.into())
             };
             
             let end = match end_date {
-                Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-                    .map_err(|e| format!("结束日期格式错误: {}", e))?,
-                None => return Err("自定义摘要类型需要提供结束日期".to_string())
+                Some(date) => parse_date_arg(&date, Message::ArgEndDate)?,
+                None => return Err(Message::CustomSummaryNeedsEnd.into())
             };
             
             (start, end)
         },
         _ => {
             // 使用预定义摘要类型的计算方法
-            calculate_date_range(summary_type_enum)
+            summary_type_enum.date_range(Local::now().date_naive(), state.get_settings().summary_rolling_range)
         }
     };
     
-    // 获取该日期范围内的日志
-    let settings = state.get_settings();
-    let log_manager = LogManager::new(settings.clone());
-    
-    let logs = match log_manager.get_entries_in_date_range(&start_naive_date, &end_naive_date) {
-        Ok(logs) => logs,
-        Err(e) => {
-            let err_msg = format!("获取日志失败: {}", e);
-            log::error!("{}", err_msg);
-            
-            // 发送错误事件
-            app_handle.emit_all("summary-generation-error", err_msg.clone()).ok();
-            return Err(err_msg);
-        }
+    // 纳入 Git 提交但未指定仓库时使用当前目录
+    let include_git = include_git.unwrap_or(false);
+    let git_repo_paths = match repo_paths {
+        Some(paths) if !paths.is_empty() => paths,
+        _ if include_git => vec![get_working_directory().map_err(CommandError::from)?],
+        _ => Vec::new(),
     };
     
-    if logs.is_empty() {
-        let err_msg = format!("指定日期范围内没有找到日志记录");
-        log::warn!("{}", err_msg);
-        
-        // 发送错误事件
-        app_handle.emit_all("summary-generation-error", err_msg.clone()).ok();
-        return Err(err_msg);
-    }
-    
-    // 发送事件通知前端正在处理
-    app_handle.emit_all(
-        "summary-generation-processing", 
-        format!("正在处理 {} 条日志记录...", logs.len())
-    ).ok();
-    
     // 创建摘要配置
     let summary_config = SummaryConfig {
         summary_type: summary_type_enum,
         start_date: Some(start_naive_date),
         end_date: Some(end_naive_date),
         title: title.unwrap_or_else(|| {
-            if summary_type == "custom" {
-                format!("自定义摘要")
-            } else {
-                format!("{}摘要", match summary_type.as_str() {
-                    "weekly" => "周",
-                    "monthly" => "月",
-                    "quarterly" => "季度",
-                    _ => "",
-                })
+            match summary_type_enum {
+                SummaryType::Daily => Message::DailySummaryTitle,
+                SummaryType::Weekly => Message::WeeklySummaryTitle,
+                SummaryType::Monthly => Message::MonthlySummaryTitle,
+                SummaryType::Quarterly => Message::QuarterlySummaryTitle,
+                SummaryType::Custom => Message::CustomSummaryTitle,
             }
+            .to_string()
         }),
+        include_git,
+        git_repo_paths,
+        format: format.unwrap_or_default(),
+        group_by: group_by.unwrap_or_default(),
     };
     
-    // 创建回调函数，用于将流式结果发送给前端
-    let app_handle_clone = app_handle.clone();
-    let progress_callback = move |chunk: &str| {
-        if !chunk.is_empty() {
-            app_handle_clone.emit_all("summary-generation-chunk", chunk).ok();
-        }
-    };
+    // 发送事件通知前端开始生成
+    emit_summary_start(&app_handle, &request_id)?;
     
-    // 使用流式方法生成摘要
-    let summary_generator = SummaryGenerator::new(settings.clone());
-    let result = match summary_generator.generate_summary_with_stream(logs, summary_config, progress_callback).await {
-        Ok(summary) => {
-            log::info!("流式摘要生成成功");
-            
-            // 发送完成事件
-            app_handle.emit_all("summary-generation-complete", summary).map_err(|e| {
-                let err_msg = format!("无法发送摘要完成事件: {}", e);
-                log::error!("{}", err_msg);
-                err_msg
-            })?;
-            
-            Ok(())
-        },
-        Err(e) => {
-            let err_msg = format!("生成摘要失败: {}", e);
-            log::error!("{}", err_msg);
-            
-            // 发送错误事件
-            app_handle.emit_all("summary-generation-error", err_msg.clone()).ok();
-            Err(err_msg)
+    // 登记任务以便前端取消
+    let app_state = state.inner().clone();
+    let cancel_flag = app_state
+        .register_summary_task(&request_id)
+        .map_err(CommandError::from)?;
+    
+    // 在后台生成摘要，结果通过事件推送，命令立即返回请求 ID
+    let task_request_id = request_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let request_id = task_request_id;
+        let generation = run_summary_stream(
+            &app_handle,
+            &app_state,
+            &request_id,
+            cancel_flag,
+            summary_config,
+            force.unwrap_or(false),
+        )
+        .await;
+        if let Err(e) = app_state.remove_summary_task(&request_id) {
+            log::warn!("移除摘要任务 {} 失败: {}", request_id, e);
         }
+        
+        let generation = generation.map(|outcome| SummaryResult::new(outcome.summary, outcome.path));
+        emit_summary_result(&app_handle, &request_id, generation, Message::GenerateSummaryAction);
+    });
+    
+    Ok(request_id)
+}
+
+/// 获取日志并流式生成摘要，每个片段都带上请求 ID 推送给前端
+async fn run_summary_stream(
+    app_handle: &AppHandle,
+    app_state: &AppState,
+    request_id: &str,
+    cancel_flag: Arc<AtomicBool>,
+    summary_config: SummaryConfig,
+    force: bool,
+) -> Result<SummaryOutcome, crate::errors::AppError> {
+    let (Some(start_date), Some(end_date)) = (summary_config.start_date, summary_config.end_date) else {
+        return Err(crate::errors::AppError::SummaryError(Message::MissingSummaryRange.to_string()));
     };
     
-    result
+    // 获取该日期范围内的日志
+    let settings = app_state.get_settings();
+    let log_manager = LogManager::new(settings.clone());
+    
+    // 只统计条数，生成摘要时再逐天读取日志
+    emit_summary_progress(app_handle, request_id, SummaryPhase::Fetching, 0, 0);
+    let entry_count = log_manager
+        .count_entries_in_date_range_async(start_date, end_date)
+        .await
+        .map_err(|e| {
+            log::error!("获取日志失败: {}", e);
+            e
+        })?;
+    
+    if entry_count == 0 {
+        return Err(crate::errors::AppError::SummaryError(Message::NoEntriesInRange.to_string()));
+    }
+    
+    // 发送事件通知前端正在处理
+    emit_summary_progress(app_handle, request_id, SummaryPhase::Fetching, entry_count, entry_count);
+    emit_summary_event(
+        app_handle,
+        "summary-generation-processing",
+        request_id,
+        Message::SummaryProcessing(entry_count).to_string(),
+    ).ok();
+    
+    // 模型服务不流式返回时长时间没有片段，由心跳提示前端仍在生成，生成结束后停止
+    let heartbeat = SummaryHeartbeat::start(app_handle, request_id);
+    let record_progress = heartbeat.activity_recorder();
+    let progress_listener = summary_progress_listener(app_handle, request_id);
+    let record_chunk = heartbeat.activity_recorder();
+    let chunk_callback = summary_chunk_callback(app_handle, request_id);
+    
+    // 创建回调函数，用于将流式结果发送给前端
+    let summary_generator = SummaryGenerator::new(settings)
+        .with_cancel_flag(cancel_flag)
+        .with_force(force)
+        .with_progress_listener(move |progress| {
+            record_progress();
+            progress_listener(progress);
+        });
+    let logs = SummaryLogs::Range(SummaryPeriod { start_date, end_date });
+    summary_generator
+        .generate_summary_with_stream(logs, summary_config, move |chunk: &str| {
+            record_chunk();
+            chunk_callback(chunk);
+        })
+        .await
 }
 
-/// 根据摘要类型计算日期范围
-pub fn calculate_date_range(summary_type: SummaryType) -> (NaiveDate, NaiveDate) {
-    let now = Utc::now().naive_local().date();
+/// 摘要生成心跳的检查间隔
+const SUMMARY_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 摘要生成期间的心跳
+///
+/// 每隔 `SUMMARY_HEARTBEAT_INTERVAL` 检查一次，这段时间内没有收到进度或摘要片段时，
+/// 推送 `summary-generation-processing` 事件说明仍在生成及已用时间。drop 时停止心跳任务
+struct SummaryHeartbeat {
+    last_activity: Arc<Mutex<Instant>>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl SummaryHeartbeat {
+    fn start(app_handle: &AppHandle, request_id: &str) -> Self {
+        let started = Instant::now();
+        let last_activity = Arc::new(Mutex::new(started));
+        
+        let app_handle = app_handle.clone();
+        let request_id = request_id.to_string();
+        let activity = last_activity.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(SUMMARY_HEARTBEAT_INTERVAL);
+            // 第一次 tick 立即完成，跳过
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let idle = activity.lock().map(|last| last.elapsed()).unwrap_or_default();
+                if idle >= SUMMARY_HEARTBEAT_INTERVAL {
+                    emit_summary_event(
+                        &app_handle,
+                        "summary-generation-processing",
+                        &request_id,
+                        Message::SummaryStillGenerating(started.elapsed().as_secs()).to_string(),
+                    )
+                    .ok();
+                }
+            }
+        });
+        
+        Self { last_activity, task }
+    }
     
-    match summary_type {
-        SummaryType::Weekly => {
-            // 从当前日期倒推7天
-            let start = now
-                .checked_sub_days(chrono::Days::new(7))
-                .unwrap_or(now);
-            (start, now)
+    /// 返回记录活动的函数，收到进度或摘要片段时调用
+    fn activity_recorder(&self) -> impl Fn() + Send + Sync + 'static {
+        let last_activity = self.last_activity.clone();
+        move || {
+            if let Ok(mut last) = last_activity.lock() {
+                *last = Instant::now();
+            }
         }
-        SummaryType::Monthly => {
-            // 从当前日期倒推30天
-            let start = now
-                .checked_sub_days(chrono::Days::new(30))
-                .unwrap_or(now);
-            (start, now)
+    }
+}
+
+impl Drop for SummaryHeartbeat {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 摘要生成事件的内容，`request_id` 用于区分同时进行的多个摘要任务
+#[derive(Clone, Serialize)]
+struct SummaryEvent<T: Serialize> {
+    request_id: String,
+    payload: T,
+}
+
+/// 摘要完成事件的内容，附带摘要的字数、字符数和保存的文件路径
+#[derive(Clone, Serialize)]
+struct SummaryResult {
+    summary: String,
+    word_count: usize,
+    char_count: usize,
+    /// 摘要保存的文件路径，调整摘要、对比摘要或跳过保存时为空
+    path: Option<String>,
+}
+
+impl SummaryResult {
+    fn new(summary: String, path: Option<PathBuf>) -> Self {
+        Self {
+            word_count: crate::log_manager::count_words(&summary),
+            char_count: crate::log_manager::count_chars(&summary),
+            summary,
+            path: path.map(|path| path.to_string_lossy().to_string()),
         }
-        SummaryType::Quarterly => {
-            // 从当前日期倒推90天
-            let start = now
-                .checked_sub_days(chrono::Days::new(90))
-                .unwrap_or(now);
-            (start, now)
+    }
+}
+
+/// 向前端推送带请求 ID 的摘要生成事件
+fn emit_summary_event<T: Serialize + Clone>(
+    app_handle: &AppHandle,
+    event: &str,
+    request_id: &str,
+    payload: T,
+) -> tauri::Result<()> {
+    app_handle.emit_all(
+        event,
+        SummaryEvent {
+            request_id: request_id.to_string(),
+            payload,
+        },
+    )
+}
+
+/// 推送摘要开始事件，失败时记录并返回错误
+fn emit_summary_start(app_handle: &AppHandle, request_id: &str) -> Result<(), CommandError> {
+    emit_summary_event(app_handle, "summary-generation-start", request_id, request_id).map_err(|e| {
+        let err = CommandError::from(crate::errors::AppError::from(e)).context(Message::SummaryStartEventFailed);
+        log::error!("{}", err);
+        err
+    })
+}
+
+/// 推送结构化的摘要生成进度
+fn emit_summary_progress(
+    app_handle: &AppHandle,
+    request_id: &str,
+    phase: SummaryPhase,
+    processed: usize,
+    total: usize,
+) {
+    emit_summary_event(
+        app_handle,
+        "summary-generation-progress",
+        request_id,
+        SummaryProgress { phase, processed, total },
+    )
+    .ok();
+}
+
+/// 将摘要生成器报告的进度转发给前端
+fn summary_progress_listener(
+    app_handle: &AppHandle,
+    request_id: &str,
+) -> impl Fn(SummaryProgress) + Send + Sync + 'static {
+    let app_handle = app_handle.clone();
+    let request_id = request_id.to_string();
+    move |progress| {
+        emit_summary_event(&app_handle, "summary-generation-progress", &request_id, progress).ok();
+    }
+}
+
+/// 将流式生成的摘要片段推送给前端
+fn summary_chunk_callback(
+    app_handle: &AppHandle,
+    request_id: &str,
+) -> impl Fn(&str) + Send + Sync + 'static {
+    let app_handle = app_handle.clone();
+    let request_id = request_id.to_string();
+    move |chunk: &str| {
+        if !chunk.is_empty() {
+            emit_summary_event(&app_handle, "summary-generation-chunk", &request_id, chunk).ok();
         }
-        SummaryType::Custom => {
-            // 自定义类型会在函数外部处理
-            (now, now)
+    }
+}
+
+/// 按新的要求调整之前生成的摘要
+/// 
+/// 与 `generate_summary_stream` 使用相同的事件推送调整后的内容，命令立即返回请求 ID，
+/// 同样可以通过该 ID 调用 `cancel_summary` 取消
+#[tauri::command]
+pub async fn refine_summary(
+    previous_summary: String,
+    instruction: String,
+    request_id: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<String, CommandError> {
+    log::info!("收到调整摘要请求: {}", instruction);
+    
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    
+    emit_summary_start(&app_handle, &request_id)?;
+    
+    let app_state = state.inner().clone();
+    let cancel_flag = app_state
+        .register_summary_task(&request_id)
+        .map_err(CommandError::from)?;
+    let summary_generator = SummaryGenerator::new(app_state.get_settings())
+        .with_cancel_flag(cancel_flag)
+        .with_progress_listener(summary_progress_listener(&app_handle, &request_id));
+    
+    let task_request_id = request_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let request_id = task_request_id;
+        let refinement = summary_generator
+            .refine_summary(&previous_summary, &instruction, summary_chunk_callback(&app_handle, &request_id))
+            .await;
+        if let Err(e) = app_state.remove_summary_task(&request_id) {
+            log::warn!("移除摘要任务 {} 失败: {}", request_id, e);
+        }
+        
+        let refinement = refinement.map(|summary| SummaryResult::new(summary, None));
+        emit_summary_result(&app_handle, &request_id, refinement, Message::RefineSummaryAction);
+    });
+    
+    Ok(request_id)
+}
+
+/// 对比两个时间段的日志，流式生成对比摘要
+/// 
+/// 时间段的格式为 `{ start_date, end_date }`，以 `period_a` 为基准分析 `period_b` 的变化。
+/// 与 `generate_summary_stream` 使用相同的事件推送内容，命令立即返回请求 ID
+#[tauri::command]
+pub async fn generate_comparison_summary(
+    period_a: SummaryPeriod,
+    period_b: SummaryPeriod,
+    title: Option<String>,
+    request_id: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<String, CommandError> {
+    log::info!("收到对比摘要请求: {} 与 {}", period_a.label(), period_b.label());
+    
+    let period_a = SummaryPeriod::new(period_a.start_date, period_a.end_date)
+        .map_err(|e| CommandError::from(e).context(Message::PeriodA))?;
+    let period_b = SummaryPeriod::new(period_b.start_date, period_b.end_date)
+        .map_err(|e| CommandError::from(e).context(Message::PeriodB))?;
+    let title = title.unwrap_or_else(|| Message::ComparisonSummaryTitle.to_string());
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    
+    emit_summary_start(&app_handle, &request_id)?;
+    
+    let app_state = state.inner().clone();
+    let cancel_flag = app_state
+        .register_summary_task(&request_id)
+        .map_err(CommandError::from)?;
+    let summary_generator = SummaryGenerator::new(app_state.get_settings())
+        .with_cancel_flag(cancel_flag)
+        .with_progress_listener(summary_progress_listener(&app_handle, &request_id));
+    
+    let task_request_id = request_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let request_id = task_request_id;
+        let comparison = summary_generator
+            .generate_comparison_summary(period_a, period_b, &title, summary_chunk_callback(&app_handle, &request_id))
+            .await;
+        if let Err(e) = app_state.remove_summary_task(&request_id) {
+            log::warn!("移除摘要任务 {} 失败: {}", request_id, e);
+        }
+        
+        let comparison = comparison.map(|summary| SummaryResult::new(summary, None));
+        emit_summary_result(&app_handle, &request_id, comparison, Message::CompareSummaryAction);
+    });
+    
+    Ok(request_id)
+}
+
+/// 推送摘要任务的结果：成功时发送完成事件，取消不视为错误，其他错误发送错误事件
+fn emit_summary_result(
+    app_handle: &AppHandle,
+    request_id: &str,
+    result: Result<SummaryResult, crate::errors::AppError>,
+    action: Message<'_>,
+) {
+    match result {
+        Ok(result) => {
+            log::info!("{}完成: {}，共 {} 字", action, request_id, result.word_count);
+            if let Err(e) = emit_summary_event(app_handle, "summary-generation-complete", request_id, result) {
+                log::error!("无法发送摘要完成事件: {}", e);
+            }
+        },
+        Err(crate::errors::AppError::Cancelled) => {
+            log::info!("{}已取消: {}", action, request_id);
+            emit_summary_event(app_handle, "summary-generation-cancelled", request_id, request_id).ok();
+        },
+        Err(e) => {
+            let err = CommandError::from(e).context(Message::ActionFailed(&action));
+            log::error!("{}", err);
+            emit_summary_event(app_handle, "summary-generation-error", request_id, err).ok();
         }
     }
 }
 
-/// 生成摘要（向后兼容旧接口）
+/// 生成摘要（向后兼容旧接口），返回本次生成的请求 ID
 #[tauri::command(rename_all = "camelCase")]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_summary(
     summary_type: Option<String>,
     start_date: Option<String>,
     end_date: Option<String>,
     title: Option<String>,
+    request_id: Option<String>,
+    format: Option<SummaryFormat>,
     state: State<'_, AppState>,
     app_handle: AppHandle,
-) -> Result<(), String> {
+) -> Result<String, CommandError> {
     log::info!("收到旧版生成摘要请求，转发到流式摘要接口");
     
     // 检查summary_type是否存在
     let actual_summary_type = match summary_type {
         Some(st) => st,
-        None => return Err("缺少摘要类型参数 'summary_type'".to_string())
+        None => return Err(Message::MissingSummaryType.into())
     };
     
     log::debug!("参数处理: 摘要类型={}, 开始日期={:?}, 结束日期={:?}", 
                 actual_summary_type, start_date, end_date);
     
-    generate_summary_stream(actual_summary_type, start_date, end_date, title, state, app_handle).await
+    generate_summary_stream(
+        actual_summary_type,
+        start_date,
+        end_date,
+        title,
+        request_id,
+        None,
+        None,
+        None,
+        format,
+        None,
+        state,
+        app_handle,
+    )
+    .await
+}
+
+/// 取消正在进行的摘要生成
+///
+/// 未指定 `request_id` 时取消所有正在进行的摘要任务
+#[tauri::command(rename_all = "camelCase")]
+pub async fn cancel_summary(
+    request_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let cancelled = state
+        .cancel_summary_task(request_id.as_deref())
+        .map_err(CommandError::from)?;
+    log::info!("请求取消摘要生成: {:?}，命中 {} 个任务", request_id, cancelled);
+    
+    if cancelled == 0 {
+        return Err(Message::NoSummaryTask.into());
+    }
+    
+    Ok(())
+}
+
+/// 检查已保存设置中的摘要服务是否可用，返回实际使用的模型和耗时
+#[tauri::command]
+pub async fn test_summary_backend(app_state: State<'_, AppState>) -> Result<BackendInfo, CommandError> {
+    let settings = app_state.get_settings();
+    
+    SummaryGenerator::new(settings)
+        .test_backend()
+        .await
+        .map_err(|e| CommandError::from(e).context(Message::SummaryBackendUnavailable))
+}
+
+/// 获取自上次生成摘要以来的日期范围，供自定义摘要填充开始和结束日期
+#[tauri::command]
+pub async fn get_since_last_summary_range(app_state: State<'_, AppState>) -> Result<SummaryPeriod, CommandError> {
+    let settings = app_state.get_settings();
+    let rolling = settings.summary_rolling_range;
+    let (start_date, end_date) =
+        SummaryGenerator::new(settings).since_last_summary_range(Local::now().date_naive(), rolling);
+    
+    Ok(SummaryPeriod { start_date, end_date })
+}
+
+/// 获取当前使用的设置档案，默认档案返回 None
+#[tauri::command]
+pub async fn get_active_profile(app_state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    Ok(app_state.profile.clone())
 }
 
 /// 获取应用设置
 #[tauri::command]
-pub async fn get_settings(app_state: State<'_, AppState>) -> Result<Settings, String> {
+pub async fn get_settings(app_state: State<'_, AppState>) -> Result<Settings, CommandError> {
     Ok(app_state.get_settings())
 }
 
@@ -436,46 +1241,73 @@ pub async fn update_settings(
     settings: Settings,
     app_state: State<'_, AppState>,
     app_handle: AppHandle,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    // 校验设置，把所有问题一次性返回给界面，每行一条
+    settings
+        .validate()
+        .map_err(|problems| CommandError::new(ErrorCode::Settings, problems.join("\n")))?;
+
     // 更新设置
     app_state.update_settings(settings.clone())?;
+    // 日志目录可能已变化，重建托盘菜单
+    refresh_tray_menu(&app_state);
 
-    // 注销所有快捷键
-    app_handle.global_shortcut_manager().unregister_all().map_err(|e| e.to_string())?;
+    // 按是否启用快捷键重新注册，注册失败不影响设置保存，通过事件提示界面
+    apply_shortcut(&app_handle, &settings);
 
-    // 如果启用了快捷键，则重新注册
-    if !settings.shortcut.is_empty() {
-        let app_handle = app_handle.clone();
-        app_handle.global_shortcut_manager().register(&settings.shortcut, move || {
-            if let Some(window) = app_handle.get_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
-        }).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 诊断当前档案的配置，返回各项检查结果供设置界面展示，不修改任何文件
+#[tauri::command]
+pub async fn run_diagnostics(app_state: State<'_, AppState>) -> Result<DiagnosisReport, CommandError> {
+    Ok(settings::diagnose_profile(app_state.profile.as_deref()))
+}
+
+/// 执行诊断中可以自动完成的修复（创建缺失的目录和设置文件），返回修复后重新诊断的结果
+#[tauri::command]
+pub async fn apply_diagnosis_fixes(app_state: State<'_, AppState>) -> Result<DiagnosisReport, CommandError> {
+    let profile = app_state.profile.as_deref();
+    let applied = settings::apply_fixes(&settings::diagnose_profile(profile))?;
+    for fix in &applied {
+        log::info!("已{}", fix);
     }
+    
+    Ok(settings::diagnose_profile(profile))
+}
 
-    Ok(())
+/// 检查快捷键能否使用，供设置界面保存前预先检查
+///
+/// 空字符串表示不使用快捷键，视为有效
+#[tauri::command]
+pub async fn validate_shortcut(shortcut: String, app_handle: AppHandle) -> Result<(), CommandError> {
+    let shortcut = shortcut.trim();
+    if shortcut.is_empty() {
+        return Ok(());
+    }
+    
+    check_shortcut(&app_handle, shortcut).map_err(CommandError::from)
 }
 
 /// 选择目录
 #[tauri::command]
-pub async fn select_directory(_app_handle: AppHandle) -> Result<String, String> {
+pub async fn select_directory(_app_handle: AppHandle) -> Result<String, CommandError> {
     let folder = tauri::api::dialog::blocking::FileDialogBuilder::new().pick_folder();
     
     match folder {
         Some(path) => Ok(path.to_string_lossy().to_string()),
-        None => Err("未选择目录".to_string()),
+        None => Err(Message::NoDirectorySelected.into()),
     }
 }
 
 /// 注册命令行工具
 #[tauri::command]
-pub async fn register_cli(app_handle: AppHandle) -> Result<(), String> {
+pub async fn register_cli(app_handle: AppHandle) -> Result<(), CommandError> {
     // 获取应用可执行文件路径
     let base_path = app_handle
         .path_resolver()
         .resolve_resource("../")
-        .ok_or("无法获取应用路径")?
+        .ok_or(Message::AppPathUnavailable)?
         .to_string_lossy()
         .to_string();
     
@@ -531,13 +1363,13 @@ pub async fn register_cli(app_handle: AppHandle) -> Result<(), String> {
             None => {
                 // 如果找不到二进制文件，则尝试使用cargo安装
                 let cargo_install_cmd = "cargo install --path $(find $(pwd) -type d -name src-tauri | head -1) --bin wr-cli";
-                return Err(format!("无法找到可执行文件。\n\n您可以通过以下方式安装命令行工具:\n\n{};\nsudo ln -sf $(which wr-cli) /usr/local/bin/work-record\n\n或者使用提供的打包版本。", cargo_install_cmd));
+                return Err(Message::CliExecutableNotFound(cargo_install_cmd).into());
             }
         };
         
         // 返回需要执行的命令
         let sudo_command = format!("sudo ln -sf \"{}\" \"{}\"", exec_path, link_path);
-        return Err(format!("需要管理员权限来创建命令行工具。\n\n请在终端中手动执行以下命令：\n\n{}\n\n执行后即可使用 work-record 命令", sudo_command));
+        return Err(manual_command_error(Message::CliInstallNeedsAdmin(&sudo_command), &sudo_command));
     }
     
     #[cfg(target_os = "linux")]
@@ -570,14 +1402,14 @@ pub async fn register_cli(app_handle: AppHandle) -> Result<(), String> {
                 } else {
                     // 如果找不到二进制文件，则尝试使用cargo安装
                     let cargo_install_cmd = "cargo install --path $(find $(pwd) -type d -name src-tauri | head -1) --bin wr-cli";
-                    return Err(format!("无法找到可执行文件。\n\n您可以通过以下方式安装命令行工具:\n\n{};\nsudo ln -sf $(which wr-cli) /usr/local/bin/work-record\n\n或者使用提供的打包版本。", cargo_install_cmd));
+                    return Err(Message::CliExecutableNotFound(cargo_install_cmd).into());
                 }
             }
         }
         
         // 返回需要执行的命令
         let sudo_command = format!("sudo ln -sf \"{}\" \"{}\"", exec_path, link_path);
-        return Err(format!("需要管理员权限来创建命令行工具。\n\n请在终端中手动执行以下命令：\n\n{}\n\n执行后即可使用 work-record 命令", sudo_command));
+        return Err(manual_command_error(Message::CliInstallNeedsAdmin(&sudo_command), &sudo_command));
     }
     
     #[cfg(target_os = "windows")]
@@ -588,7 +1420,7 @@ pub async fn register_cli(app_handle: AppHandle) -> Result<(), String> {
         
         // 获取用户主目录
         let home_dir = std::env::var("USERPROFILE")
-            .map_err(|_| "无法获取用户主目录".to_string())?;
+            .map_err(|_| Message::HomeDirUnavailable)?;
         
         // 创建批处理文件在用户目录下
         let batch_path = format!("{}\\work-record.bat", home_dir);
@@ -598,21 +1430,26 @@ pub async fn register_cli(app_handle: AppHandle) -> Result<(), String> {
         
         // 写入批处理文件
         fs::write(&batch_path, batch_content)
-            .map_err(|e| format!("创建批处理文件失败: {}", e))?;
+            .map_err(|e| CommandError::from(crate::errors::AppError::from(e)).context(Message::CreateBatchFileFailed))?;
         
         // 返回需要执行的命令
-        return Err(format!("批处理文件已创建在：{}\n\n请以管理员身份在命令提示符中执行以下命令将目录添加到PATH：\n\nsetx PATH \"%PATH%;{}\" /M", batch_path, home_dir));
+        return Err(Message::BatchFileCreated { path: &batch_path, dir: &home_dir }.into());
     }
     
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
     {
-        Err("当前操作系统不支持命令行注册".to_string())
+        Err(Message::CliRegisterUnsupported.into())
     }
 }
 
+/// 需要用户手动执行命令的错误，前端按错误代码显示命令并提供复制
+fn manual_command_error(message: Message<'_>, command: &str) -> CommandError {
+    CommandError::new(ErrorCode::ManualCommandRequired, message.to_string()).with_details(command)
+}
+
 /// 注销命令行工具
 #[tauri::command]
-pub async fn unregister_cli() -> Result<(), String> {
+pub async fn unregister_cli() -> Result<(), CommandError> {
     #[cfg(target_os = "macos")]
     {
         let link_path = "/usr/local/bin/work-record";
@@ -621,7 +1458,7 @@ pub async fn unregister_cli() -> Result<(), String> {
         if Path::new(link_path).exists() {
             // 返回需要执行的命令
             let sudo_command = format!("sudo rm \"{}\"", link_path);
-            return Err(format!("需要管理员权限来删除命令行工具。\n\n请在终端中手动执行以下命令：\n\n{}", sudo_command));
+            return Err(manual_command_error(Message::CliUninstallNeedsAdmin(&sudo_command), &sudo_command));
         } else {
             return Ok(());
         }
@@ -635,7 +1472,7 @@ pub async fn unregister_cli() -> Result<(), String> {
         if Path::new(link_path).exists() {
             // 返回需要执行的命令
             let sudo_command = format!("sudo rm \"{}\"", link_path);
-            return Err(format!("需要管理员权限来删除命令行工具。\n\n请在终端中手动执行以下命令：\n\n{}", sudo_command));
+            return Err(manual_command_error(Message::CliUninstallNeedsAdmin(&sudo_command), &sudo_command));
         } else {
             return Ok(());
         }
@@ -645,14 +1482,14 @@ pub async fn unregister_cli() -> Result<(), String> {
     {
         // Windows下删除批处理文件
         let home_dir = std::env::var("USERPROFILE")
-            .map_err(|_| "无法获取用户主目录".to_string())?;
+            .map_err(|_| Message::HomeDirUnavailable)?;
         
         let batch_path = format!("{}\\work-record.bat", home_dir);
         
         // 检查批处理文件是否存在
         if Path::new(&batch_path).exists() {
             fs::remove_file(&batch_path)
-                .map_err(|e| format!("删除批处理文件失败: {}", e))?;
+                .map_err(|e| CommandError::from(crate::errors::AppError::from(e)).context(Message::RemoveBatchFileFailed))?;
         }
         
         Ok(())
@@ -660,6 +1497,6 @@ pub async fn unregister_cli() -> Result<(), String> {
     
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
     {
-        Err("当前操作系统不支持命令行注销".to_string())
+        Err(Message::CliUnregisterUnsupported.into())
     }
 } 
\ No newline at end of file
@@ -1,53 +1,232 @@
+use crate::messages::Message;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::ErrorKind;
 use thiserror::Error;
 
-/// 应用错误类型
+/// 应用错误类型，错误信息使用当前界面语言
 #[derive(Debug, Error)]
 pub enum AppError {
     /// IO 错误
-    #[error("IO 错误: {0}")]
+    #[error("{}", Message::IoError(.0))]
     IoError(#[from] std::io::Error),
 
     /// 序列化/反序列化错误
-    #[error("序列化错误: {0}")]
+    #[error("{}", Message::SerdeError(.0))]
     SerdeError(#[from] serde_json::Error),
 
     /// Git 操作错误
-    #[error("Git 错误: {0}")]
+    #[error("{}", Message::GitError(.0))]
     GitError(#[from] git2::Error),
 
     /// 网络请求错误
-    #[error("网络请求错误: {0}")]
+    #[error("{}", Message::NetworkError(.0))]
     ReqwestError(#[from] reqwest::Error),
 
+    /// SQLite 数据库错误
+    #[cfg(feature = "sqlite")]
+    #[error("{}", Message::DatabaseError(.0))]
+    DatabaseError(#[from] rusqlite::Error),
+
     /// 日期解析错误
-    #[error("日期解析错误: {0}")]
+    #[error("{}", Message::DateParseError(.0))]
     ChronoError(#[from] chrono::ParseError),
 
     /// Tauri 错误
-    #[error("Tauri 错误: {0}")]
+    #[cfg(feature = "desktop")]
+    #[error("{}", Message::TauriError(.0))]
     TauriError(#[from] tauri::Error),
 
     /// 文件系统错误
-    #[error("文件系统错误: {0}")]
+    #[error("{}", Message::FsError(.0))]
     FsError(String),
 
     /// 设置错误
-    #[error("设置错误: {0}")]
+    #[error("{}", Message::SettingsError(.0))]
     SettingsError(String),
 
     /// 日志管理错误
-    #[error("日志管理错误: {0}")]
+    #[error("{}", Message::LogManagerError(.0))]
     LogManagerError(String),
 
     /// 摘要生成错误
-    #[error("摘要生成错误: {0}")]
+    #[error("{}", Message::SummaryError(.0))]
     SummaryError(String),
 
+    /// 模型服务认证失败，通常是 API 密钥无效
+    #[error("{0}")]
+    ApiAuthError(String),
+
+    /// 同一天已存在内容相同的日志记录
+    #[error("{}", Message::DuplicateEntry(.0))]
+    DuplicateEntry(String),
+
+    /// 操作已被用户取消
+    #[error("{}", Message::Cancelled)]
+    Cancelled,
+
     /// 通用错误
     #[error("{0}")]
     GeneralError(String),
 }
 
+/// 错误代码，前端据此区分错误类型，例如引导用户检查日志目录或 API 密钥
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// 日志目录或文件不存在
+    StorageNotFound,
+    /// 没有访问日志目录或文件的权限
+    PermissionDenied,
+    /// 其他读写错误
+    Io,
+    /// 日志文件或设置文件内容无法解析
+    InvalidData,
+    /// Git 操作失败
+    Git,
+    /// 网络请求失败
+    Network,
+    /// 模型服务认证失败
+    ApiAuthFailed,
+    /// 日期格式错误
+    InvalidDate,
+    /// 设置错误
+    Settings,
+    /// 日志读写逻辑错误，如记录不存在
+    LogManager,
+    /// 摘要生成失败
+    Summary,
+    /// 当天已存在相同内容的日志
+    DuplicateEntry,
+    /// 操作已取消
+    Cancelled,
+    /// 命令参数不合法
+    InvalidInput,
+    /// 需要用户在终端中手动执行命令，`details` 为要执行的命令
+    ManualCommandRequired,
+    /// 其他错误
+    Internal,
+}
+
+/// 命令返回给前端的错误，`message` 为可直接展示的说明，`details` 为排查用的附加信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// 在错误信息前加上说明，错误代码保持不变
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl AppError {
+    /// 错误对应的错误代码
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::IoError(io_err) => match io_err.kind() {
+                ErrorKind::NotFound => ErrorCode::StorageNotFound,
+                ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+                _ => ErrorCode::Io,
+            },
+            AppError::SerdeError(_) => ErrorCode::InvalidData,
+            AppError::GitError(_) => ErrorCode::Git,
+            AppError::ReqwestError(req_err) => {
+                if req_err.status() == Some(reqwest::StatusCode::UNAUTHORIZED) {
+                    ErrorCode::ApiAuthFailed
+                } else {
+                    ErrorCode::Network
+                }
+            }
+            #[cfg(feature = "sqlite")]
+            AppError::DatabaseError(_) => ErrorCode::Io,
+            AppError::ChronoError(_) => ErrorCode::InvalidDate,
+            #[cfg(feature = "desktop")]
+            AppError::TauriError(_) => ErrorCode::Internal,
+            AppError::FsError(_) => ErrorCode::Io,
+            AppError::SettingsError(_) => ErrorCode::Settings,
+            AppError::LogManagerError(_) => ErrorCode::LogManager,
+            AppError::SummaryError(_) => ErrorCode::Summary,
+            AppError::ApiAuthError(_) => ErrorCode::ApiAuthFailed,
+            AppError::DuplicateEntry(_) => ErrorCode::DuplicateEntry,
+            AppError::Cancelled => ErrorCode::Cancelled,
+            AppError::GeneralError(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+impl From<AppError> for CommandError {
+    fn from(err: AppError) -> Self {
+        let code = err.code();
+        // 目录不存在和权限不足是最常见的配置问题，给出可操作的提示
+        let message = match (&err, code) {
+            (AppError::IoError(io_err), ErrorCode::StorageNotFound) => {
+                Message::StorageNotFoundHint(io_err).to_string()
+            }
+            (AppError::IoError(io_err), ErrorCode::PermissionDenied) => {
+                Message::PermissionDeniedHint(io_err).to_string()
+            }
+            _ => err.to_string(),
+        };
+        let details = match &err {
+            AppError::IoError(io_err) => Some(format!("{:?}", io_err.kind())),
+            AppError::DuplicateEntry(id) => Some(id.clone()),
+            _ => None,
+        };
+
+        CommandError {
+            code,
+            message,
+            details,
+        }
+    }
+}
+
+/// 命令自身的参数校验等错误，统一归为参数错误
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::new(ErrorCode::InvalidInput, message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        CommandError::new(ErrorCode::InvalidInput, message)
+    }
+}
+
+impl From<Message<'_>> for CommandError {
+    fn from(message: Message<'_>) -> Self {
+        CommandError::new(ErrorCode::InvalidInput, message.to_string())
+    }
+}
+
 /// 转换为字符串以便在前端展示
 pub fn error_to_string(err: AppError) -> String {
     err.to_string()
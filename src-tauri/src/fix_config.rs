@@ -1,129 +1,120 @@
-use dirs::home_dir;
-use serde::{Deserialize, Serialize};
+use crate::messages::Message;
+use crate::settings::Settings;
 use std::fs;
-use std::path::{Path, PathBuf};
-
-/// 应用设置结构，与原始设置相同
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Settings {
-    pub log_storage_dir: String,
-    pub log_output_dir: String,
-    pub git_author: String,
-    pub auto_open_window: bool,
-    pub shortcut: String,
-    pub enable_shortcut: bool,
-    pub use_local_ollama: bool,
-    pub ollama_address: String,
-    pub ollama_model: String,
-    pub llm_api_key: String,
-    pub llm_api_url: String,
-}
+use std::path::Path;
 
 /// 诊断并修复配置问题
+///
+/// 使用与应用相同的 `Settings` 读写设置文件，能正常解析的设置文件不会被重写，
+/// 其中的字段原样保留；无法解析时先备份原文件再写入默认设置
 pub fn diagnose_and_fix_config() -> Result<(), String> {
-    println!("开始诊断配置问题...");
-
-    // 获取配置目录
-    let config_dir = get_config_dir();
-    println!("配置目录: {}", config_dir.display());
-
-    // 确保配置目录存在
-    if !config_dir.exists() {
-        println!("配置目录不存在，创建中...");
-        fs::create_dir_all(&config_dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
-    }
-
-    // 设置文件路径
-    let settings_path = config_dir.join("settings.json");
+    println!("{}", Message::FixConfigStart);
 
-    let mut settings = if settings_path.exists() {
-        println!("找到设置文件: {}", settings_path.display());
-        // 读取设置文件
-        let content =
-            fs::read_to_string(&settings_path).map_err(|e| format!("读取设置文件失败: {}", e))?;
+    let profile = Settings::active_profile();
+    let settings_path = Settings::get_settings_path(profile.as_deref());
+    println!(
+        "{}",
+        Message::DiagnoseSettingsFile(&settings_path.display())
+    );
 
-        // 解析设置
-        match serde_json::from_str::<Settings>(&content) {
-            Ok(s) => {
-                println!("成功解析设置");
-                s
-            }
-            Err(e) => {
-                println!("解析设置失败，创建新的设置: {}", e);
-                create_default_settings()
+    let settings = match Settings::load_or_default() {
+        Ok(settings) => {
+            println!("{}", Message::SettingsLoaded);
+            settings
+        }
+        Err(e) => {
+            println!("{}", Message::SettingsRecreated(&e));
+            match Settings::backup_settings_file(profile.as_deref()) {
+                Ok(Some(backup_path)) => {
+                    println!("{}", Message::SettingsBackedUp(&backup_path.display()))
+                }
+                Ok(None) => {}
+                Err(e) => return Err(Message::BackupSettingsFailed(&e).to_string()),
             }
+
+            let settings = Settings {
+                profile,
+                config_path: Some(settings_path),
+                ..Settings::default()
+            };
+            settings
+                .save()
+                .map_err(|e| Message::SaveSettingsFailed(&e).to_string())?;
+            settings
         }
-    } else {
-        println!("设置文件不存在，创建新的设置");
-        create_default_settings()
     };
 
-    // 默认存储目录使用了~/work_records，如果用户指定了其他目录，要尊重用户设置
-    let user_specified_dir = settings.log_storage_dir
-        != home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("work_records")
-            .to_string_lossy()
-            .to_string();
-
-    // 如果用户设置了/tmp/work_logs作为存储目录，使用它
-    if !user_specified_dir {
-        let tmp_dir = "/tmp/work_logs";
-        println!("设置日志存储目录为: {}", tmp_dir);
-        settings.log_storage_dir = tmp_dir.to_string();
-        settings.log_output_dir = format!("{}/summaries", tmp_dir);
-    }
-
-    // 保存设置
-    let content =
-        serde_json::to_string_pretty(&settings).map_err(|e| format!("序列化设置失败: {}", e))?;
-
-    fs::write(&settings_path, content).map_err(|e| format!("保存设置文件失败: {}", e))?;
-
-    println!(
-        "配置已修复，日志存储目录设置为: {}",
-        settings.log_storage_dir
-    );
+    println!("{}", Message::ConfigFixed(&settings.log_storage_dir));
 
     // 确保日志目录存在
     let storage_dir = Path::new(&settings.log_storage_dir);
     if !storage_dir.exists() {
-        println!("创建日志目录: {}", storage_dir.display());
-        fs::create_dir_all(storage_dir).map_err(|e| format!("创建日志目录失败: {}", e))?;
+        println!("{}", Message::CreatingLogDir(&storage_dir.display()));
+        fs::create_dir_all(storage_dir).map_err(|e| Message::CreateLogDirFailed(&e).to_string())?;
     }
 
     let output_dir = Path::new(&settings.log_output_dir);
     if !output_dir.exists() {
-        println!("创建日志输出目录: {}", output_dir.display());
-        fs::create_dir_all(output_dir).map_err(|e| format!("创建日志输出目录失败: {}", e))?;
+        println!("{}", Message::CreatingOutputDir(&output_dir.display()));
+        fs::create_dir_all(output_dir)
+            .map_err(|e| Message::CreateOutputDirFailed(&e).to_string())?;
     }
 
     Ok(())
 }
 
-/// 获取配置目录
-fn get_config_dir() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("work-record")
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{CONFIG_ENV_VAR, SETTINGS_SCHEMA_VERSION};
+    use tempfile::TempDir;
+
+    #[test]
+    fn fixer_keeps_settings_written_by_the_old_cli() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("settings.json");
+        let logs = dir.path().join("logs").to_string_lossy().to_string();
+        let output = dir.path().join("output").to_string_lossy().to_string();
+        // 本测试是唯一设置该环境变量的测试，不会影响其他测试
+        std::env::set_var(CONFIG_ENV_VAR, &path);
+
+        // 旧版命令行工具自己的 Settings 结构写出的设置文件
+        let old_cli = serde_json::json!({
+            "log_storage_dir": logs,
+            "log_output_dir": output,
+            "git_author": "张三",
+            "auto_open_window": false,
+            "shortcut": "CommandOrControl+Shift+L",
+            "enable_shortcut": true,
+            "use_local_ollama": false,
+            "ollama_address": "http://localhost:11434",
+            "ollama_model": "llama3",
+            "llm_api_key": "sk-old",
+            "llm_api_url": "https://api.example.com/v1/chat/completions",
+        });
+        fs::write(&path, old_cli.to_string()).unwrap();
+
+        diagnose_and_fix_config().unwrap();
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        for (field, value) in old_cli.as_object().unwrap() {
+            assert_eq!(&saved[field], value, "{}", field);
+        }
+        assert_eq!(saved["schema_version"], SETTINGS_SCHEMA_VERSION);
+        assert!(Path::new(&logs).is_dir());
+        assert!(Path::new(&output).is_dir());
+
+        // 旧结构中没有的字段原样保留，能正常解析的设置文件不会被重写
+        let mut newer = saved;
+        newer["storage_backend"] = "jsonl".into();
+        newer["normalize_tags"] = true.into();
+        let content = serde_json::to_string_pretty(&newer).unwrap();
+        fs::write(&path, &content).unwrap();
+
+        diagnose_and_fix_config().unwrap();
 
-/// 创建默认设置
-fn create_default_settings() -> Settings {
-    let home_path = home_dir().unwrap_or_else(|| PathBuf::from("."));
-    let tmp_dir = "/tmp/work_logs";
-
-    Settings {
-        log_storage_dir: tmp_dir.to_string(),
-        log_output_dir: format!("{}/summaries", tmp_dir),
-        git_author: String::new(),
-        auto_open_window: false,
-        shortcut: "Alt+Shift+L".to_string(),
-        enable_shortcut: true,
-        use_local_ollama: true,
-        ollama_address: "http://localhost:11434".to_string(),
-        ollama_model: "llama3".to_string(),
-        llm_api_key: String::new(),
-        llm_api_url: String::new(),
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        std::env::remove_var(CONFIG_ENV_VAR);
     }
 }
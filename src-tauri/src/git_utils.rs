@@ -1,6 +1,8 @@
 use crate::errors::AppError;
-use chrono::{DateTime, NaiveDate, TimeZone, Utc};
-use git2::{Commit, Repository};
+use crate::messages::Message;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use git2::{Commit, ErrorCode, Oid, Repository, Signature, Sort};
+use serde::Serialize;
 use std::path::Path;
 
 /// Git 提交信息
@@ -16,14 +18,78 @@ pub struct GitCommit {
     pub author: String,
 }
 
+/// 提交的改动统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CommitStats {
+    /// 改动的文件数
+    pub files_changed: usize,
+    /// 新增的行数
+    pub insertions: usize,
+    /// 删除的行数
+    pub deletions: usize,
+}
+
+/// 提交作者过滤条件
+///
+/// 名称和邮箱都配置时，匹配其中任意一个即可；都为空时不过滤。邮箱比较忽略大小写
+#[derive(Debug, Clone, Default)]
+pub struct AuthorFilter {
+    /// 作者名称
+    pub name: String,
+    /// 作者邮箱
+    pub email: String,
+}
+
+impl AuthorFilter {
+    /// 创建作者过滤条件
+    pub fn new(name: &str, email: &str) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            email: email.trim().to_string(),
+        }
+    }
+
+    /// 是否未设置任何过滤条件
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.email.is_empty()
+    }
+
+    /// 判断提交作者是否匹配
+    pub fn matches(&self, author: &Signature) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let name_matches = !self.name.is_empty() && author.name() == Some(self.name.as_str());
+        let email_matches = !self.email.is_empty()
+            && author
+                .email()
+                .is_some_and(|email| email.eq_ignore_ascii_case(&self.email));
+
+        name_matches || email_matches
+    }
+}
+
 /// 获取 Git 仓库的提交信息
+///
+/// 日期按提交时间在本地时区的日期比较，`since_date` 和 `until_date` 都是闭区间。
+/// 从 HEAD 指向的提交开始遍历，HEAD 分离时同样适用；仓库还没有任何提交时返回空列表。
+/// 提交按时间从新到旧遍历，遇到早于 `since_date` 的提交即停止，不再扫描更早的历史；
+/// `max_commits` 限制返回的提交数，达到上限后停止遍历
 pub fn get_commits_for_author(
     repo_path: &Path,
-    author: &str,
+    author: &AuthorFilter,
     since_date: Option<NaiveDate>,
     until_date: Option<NaiveDate>,
+    max_commits: Option<usize>,
 ) -> Result<Vec<GitCommit>, AppError> {
-    let repo = Repository::open(repo_path)?;
+    let repo = open_repository(repo_path)?;
+    if is_head_unborn(&repo)? {
+        return Ok(Vec::new());
+    }
+
+    // 默认顺序按提交时间从新到旧逐个读取提交，可以在越过起始日期后停止；
+    // 指定 Sort::TIME 会在开始前读取全部历史，无法提前停止
     let mut revwalk = repo.revwalk()?;
 
     // 添加头部引用
@@ -32,21 +98,20 @@ pub fn get_commits_for_author(
     let mut commits = Vec::new();
 
     for oid_result in revwalk {
+        if max_commits.is_some_and(|max_commits| commits.len() >= max_commits) {
+            break;
+        }
+
         let oid = oid_result?;
         let commit = repo.find_commit(oid)?;
 
-        // 过滤作者
-        if !author.is_empty() && commit.author().name() != Some(author) {
-            continue;
-        }
-
         let commit_time = Utc.timestamp_opt(commit.time().seconds(), 0).unwrap();
-        let commit_date = commit_time.date_naive();
+        let commit_date = commit_time.with_timezone(&Local).date_naive();
 
         // 过滤日期
         if let Some(since) = since_date {
             if commit_date < since {
-                continue;
+                break;
             }
         }
 
@@ -56,6 +121,11 @@ pub fn get_commits_for_author(
             }
         }
 
+        // 过滤作者
+        if !author.matches(&commit.author()) {
+            continue;
+        }
+
         let commit_info = extract_commit_info(&commit, commit_time)?;
         commits.push(commit_info);
     }
@@ -63,6 +133,31 @@ pub fn get_commits_for_author(
     Ok(commits)
 }
 
+/// 打开路径所在的 Git 仓库
+///
+/// 从 `repo_path` 开始逐级向上查找，可以从仓库的任意子目录或工作树（worktree）中打开；
+/// 上级目录中都没有仓库时返回说明路径的错误
+pub fn open_repository(repo_path: &Path) -> Result<Repository, AppError> {
+    Repository::discover(repo_path).map_err(|e| {
+        if e.code() == ErrorCode::NotFound {
+            AppError::GitError(git2::Error::from_str(
+                &Message::GitRepoNotFound(&repo_path.display()).to_string(),
+            ))
+        } else {
+            e.into()
+        }
+    })
+}
+
+/// HEAD 是否还没有指向任何提交，例如刚初始化的仓库
+fn is_head_unborn(repo: &Repository) -> Result<bool, AppError> {
+    match repo.head() {
+        Ok(_) => Ok(false),
+        Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => Ok(true),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// 从 Commit 对象提取信息
 fn extract_commit_info(commit: &Commit, time: DateTime<Utc>) -> Result<GitCommit, AppError> {
     let id = commit.id().to_string();
@@ -77,17 +172,181 @@ fn extract_commit_info(commit: &Commit, time: DateTime<Utc>) -> Result<GitCommit
     })
 }
 
-/// 为指定作者整理指定日期的 Git 提交信息
+/// 为指定作者整理指定日期的 Git 提交信息，`max_commits` 限制返回的提交数
 pub fn get_daily_commits(
     repo_path: &Path,
-    author: &str,
+    author: &AuthorFilter,
     date: &NaiveDate,
+    max_commits: Option<usize>,
+) -> Result<Vec<GitCommit>, AppError> {
+    get_commits_in_range(repo_path, author, date, date, max_commits)
+}
+
+/// 为指定作者整理日期范围内的 Git 提交信息，起止日期都包含在内，`max_commits` 限制返回的提交数
+pub fn get_commits_in_range(
+    repo_path: &Path,
+    author: &AuthorFilter,
+    start_date: &NaiveDate,
+    end_date: &NaiveDate,
+    max_commits: Option<usize>,
+) -> Result<Vec<GitCommit>, AppError> {
+    if start_date > end_date {
+        return Err(AppError::GeneralError(Message::StartAfterEnd.to_string()));
+    }
+
+    get_commits_for_author(
+        repo_path,
+        author,
+        Some(*start_date),
+        Some(*end_date),
+        max_commits,
+    )
+}
+
+/// 统计提交相对第一个父提交的改动，顺序与 `commits` 相同，根提交与空树比较
+pub fn get_commits_stats(
+    repo_path: &Path,
+    commits: &[GitCommit],
+) -> Result<Vec<CommitStats>, AppError> {
+    let repo = open_repository(repo_path)?;
+
+    commits
+        .iter()
+        .map(|commit| {
+            let commit = repo.find_commit(Oid::from_str(&commit.id)?)?;
+            let parent_tree = match commit.parents().next() {
+                Some(parent) => Some(parent.tree()?),
+                None => None,
+            };
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+            let stats = diff.stats()?;
+
+            Ok(CommitStats {
+                files_changed: stats.files_changed(),
+                insertions: stats.insertions(),
+                deletions: stats.deletions(),
+            })
+        })
+        .collect()
+}
+
+/// 获取仓库 HEAD 指向的提交 ID
+pub fn get_head_commit_id(repo_path: &Path) -> Result<String, AppError> {
+    let repo = open_repository(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    Ok(head.id().to_string())
+}
+
+/// 获取 HEAD 上在 `since_commit` 之后新增的指定作者的提交，按提交时间从早到晚排列
+///
+/// `since_commit` 及其祖先提交都会被排除；该提交在仓库中不存在时返回错误
+pub fn get_commits_since(
+    repo_path: &Path,
+    author: &AuthorFilter,
+    since_commit: &str,
 ) -> Result<Vec<GitCommit>, AppError> {
-    let next_date = date
-        .succ_opt()
-        .ok_or_else(|| AppError::GeneralError("无法计算下一天日期".to_string()))?;
+    let repo = open_repository(repo_path)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME | Sort::REVERSE)?;
+    revwalk.push_head()?;
+    revwalk.hide(Oid::from_str(since_commit)?)?;
+
+    let mut commits = Vec::new();
+    for oid_result in revwalk {
+        let commit = repo.find_commit(oid_result?)?;
+        if !author.matches(&commit.author()) {
+            continue;
+        }
+
+        let commit_time = Utc.timestamp_opt(commit.time().seconds(), 0).unwrap();
+        commits.push(extract_commit_info(&commit, commit_time)?);
+    }
+
+    Ok(commits)
+}
+
+/// 仓库的显示名称，取仓库工作目录的名称，找不到仓库时取路径本身的目录名，无法解析路径时返回空字符串
+pub fn get_repo_name(repo_path: &Path) -> String {
+    // 从子目录打开时使用仓库工作目录的名称
+    let repo_dir = open_repository(repo_path)
+        .ok()
+        .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+        .unwrap_or_else(|| repo_path.to_path_buf());
+
+    repo_dir
+        .canonicalize()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_default()
+}
 
-    get_commits_for_author(repo_path, author, Some(*date), Some(next_date))
+/// Conventional Commits 格式（`type(scope): subject`）的提交标题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    /// 提交类型，如 feat、fix，统一为小写
+    pub commit_type: String,
+    /// 影响范围，可选
+    pub scope: Option<String>,
+    /// 是否标记为破坏性变更（`feat!:`）
+    pub breaking: bool,
+    /// 提交标题正文
+    pub subject: String,
+}
+
+impl ConventionalCommit {
+    /// 由提交类型和范围生成的标签
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = vec![self.commit_type.clone()];
+        if let Some(scope) = &self.scope {
+            if !tags.contains(scope) {
+                tags.push(scope.clone());
+            }
+        }
+        tags
+    }
+}
+
+/// 解析 Conventional Commits 格式的提交消息，只看第一行
+///
+/// 支持 `type: subject`、`type(scope): subject` 和 `type!: subject`，
+/// 不符合格式的消息返回 None
+pub fn parse_conventional_commit(message: &str) -> Option<ConventionalCommit> {
+    let first_line = message.lines().next()?.trim();
+    let (header, subject) = first_line.split_once(':')?;
+    let subject = subject.trim();
+    if subject.is_empty() {
+        return None;
+    }
+
+    let (header, breaking) = match header.strip_suffix('!') {
+        Some(header) => (header, true),
+        None => (header, false),
+    };
+
+    let (commit_type, scope) = match header.split_once('(') {
+        Some((commit_type, rest)) => {
+            let scope = rest.strip_suffix(')')?.trim();
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (commit_type, Some(scope.to_string()))
+        }
+        None => (header, None),
+    };
+
+    if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some(ConventionalCommit {
+        commit_type: commit_type.to_ascii_lowercase(),
+        scope,
+        breaking,
+        subject: subject.to_string(),
+    })
 }
 
 /// 获取工作目录路径
@@ -95,3 +354,205 @@ pub fn get_working_directory() -> Result<String, AppError> {
     let current_dir = std::env::current_dir()?;
     Ok(current_dir.to_string_lossy().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Time;
+    use tempfile::TempDir;
+
+    const ALICE: (&str, &str) = ("Alice", "alice@example.com");
+    const BOB: (&str, &str) = ("Bob", "bob@example.com");
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// 在 HEAD 上创建一个空提交，提交时间为 `day` 本地时间中午
+    fn commit_on(
+        repo: &Repository,
+        (name, email): (&str, &str),
+        day: NaiveDate,
+        message: &str,
+    ) -> Oid {
+        let time = Local
+            .from_local_datetime(&day.and_hms_opt(12, 0, 0).unwrap())
+            .unwrap();
+        let signature = Signature::new(name, email, &Time::new(time.timestamp(), 0)).unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<&Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    fn messages(commits: &[GitCommit]) -> Vec<&str> {
+        commits
+            .iter()
+            .map(|commit| commit.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn author_filter_matches_name_or_email() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_on(&repo, ALICE, date(2024, 5, 1), "alice 1");
+        commit_on(&repo, BOB, date(2024, 5, 2), "bob");
+        commit_on(
+            &repo,
+            ("Alice Smith", "Alice@Example.com"),
+            date(2024, 5, 3),
+            "alice 2",
+        );
+
+        let commits = |filter: AuthorFilter| {
+            get_commits_for_author(dir.path(), &filter, None, None, None).unwrap()
+        };
+
+        // 邮箱比较忽略大小写，作者名称不同也能匹配
+        assert_eq!(
+            messages(&commits(AuthorFilter::new("", " ALICE@example.com "))),
+            ["alice 2", "alice 1"]
+        );
+        assert_eq!(messages(&commits(AuthorFilter::new("Bob", ""))), ["bob"]);
+        assert_eq!(
+            messages(&commits(AuthorFilter::new("Bob", "alice@example.com"))),
+            ["alice 2", "bob", "alice 1"]
+        );
+        assert_eq!(commits(AuthorFilter::default()).len(), 3);
+        assert!(commits(AuthorFilter::new("Carol", "carol@example.com")).is_empty());
+    }
+
+    #[test]
+    fn commits_in_range_include_both_ends() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        for day in [1, 3, 5, 7] {
+            commit_on(&repo, ALICE, date(2024, 5, day), &format!("day {}", day));
+        }
+        let author = AuthorFilter::default();
+
+        let commits = get_commits_in_range(
+            dir.path(),
+            &author,
+            &date(2024, 5, 3),
+            &date(2024, 5, 5),
+            None,
+        )
+        .unwrap();
+        assert_eq!(messages(&commits), ["day 5", "day 3"]);
+
+        let commits = get_commits_in_range(
+            dir.path(),
+            &author,
+            &date(2024, 5, 4),
+            &date(2024, 5, 4),
+            None,
+        )
+        .unwrap();
+        assert!(commits.is_empty());
+
+        let daily = get_daily_commits(dir.path(), &author, &date(2024, 5, 7), None).unwrap();
+        assert_eq!(messages(&daily), ["day 7"]);
+
+        assert!(get_commits_in_range(
+            dir.path(),
+            &author,
+            &date(2024, 5, 5),
+            &date(2024, 5, 3),
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn unborn_and_detached_head() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let author = AuthorFilter::default();
+
+        // 刚初始化的仓库没有提交
+        assert!(
+            get_commits_for_author(dir.path(), &author, None, None, None)
+                .unwrap()
+                .is_empty()
+        );
+
+        let first = commit_on(&repo, ALICE, date(2024, 5, 1), "first");
+        commit_on(&repo, ALICE, date(2024, 5, 2), "second");
+
+        // HEAD 分离后从其指向的提交开始遍历
+        repo.set_head_detached(first).unwrap();
+        assert!(repo.head_detached().unwrap());
+        let commits = get_commits_for_author(dir.path(), &author, None, None, None).unwrap();
+        assert_eq!(messages(&commits), ["first"]);
+        assert_eq!(get_head_commit_id(dir.path()).unwrap(), first.to_string());
+    }
+
+    #[test]
+    fn repository_is_discovered_from_subdirectory_and_worktree() {
+        let dir = TempDir::new().unwrap();
+        let repo_path = dir.path().join("project");
+        let repo = Repository::init(&repo_path).unwrap();
+        commit_on(&repo, ALICE, date(2024, 5, 1), "initial");
+        let author = AuthorFilter::default();
+
+        let subdir = repo_path.join("src").join("nested");
+        std::fs::create_dir_all(&subdir).unwrap();
+        let commits = get_commits_for_author(&subdir, &author, None, None, None).unwrap();
+        assert_eq!(messages(&commits), ["initial"]);
+        assert_eq!(get_repo_name(&subdir), "project");
+
+        let worktree_path = dir.path().join("project-feature");
+        repo.worktree("feature", &worktree_path, None).unwrap();
+        let commits = get_commits_for_author(&worktree_path, &author, None, None, None).unwrap();
+        assert_eq!(messages(&commits), ["initial"]);
+        assert_eq!(get_repo_name(&worktree_path), "project-feature");
+
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        assert!(open_repository(&outside).is_err());
+    }
+
+    #[test]
+    fn max_commits_and_since_date_stop_the_walk() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let author = AuthorFilter::default();
+
+        // 祖先提交的时间晚于其子提交，全量扫描时会被 since_date 匹配到
+        commit_on(&repo, ALICE, date(2024, 5, 8), "late ancestor");
+        commit_on(&repo, ALICE, date(2024, 4, 1), "old");
+        for day in 10..=13 {
+            commit_on(&repo, ALICE, date(2024, 5, day), &format!("day {}", day));
+        }
+
+        let commits = get_commits_for_author(dir.path(), &author, None, None, Some(2)).unwrap();
+        assert_eq!(messages(&commits), ["day 13", "day 12"]);
+
+        // 遇到早于起始日期的提交后不再继续遍历
+        let commits =
+            get_commits_for_author(dir.path(), &author, Some(date(2024, 5, 5)), None, None)
+                .unwrap();
+        assert_eq!(messages(&commits), ["day 13", "day 12", "day 11", "day 10"]);
+
+        let commits = get_commits_for_author(
+            dir.path(),
+            &author,
+            Some(date(2024, 5, 5)),
+            Some(date(2024, 5, 12)),
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(messages(&commits), ["day 12"]);
+    }
+}
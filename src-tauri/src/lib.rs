@@ -1,26 +1,46 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+//
+// 日志管理（log_manager）、设置（settings）、摘要（summary）和 Git 读取（git_utils）不依赖 Tauri，
+// 关闭默认的 `desktop` 特性即可作为普通库使用；其余模块只在构建桌面应用时编译
+#[cfg(feature = "desktop")]
 use env_logger;
+#[cfg(feature = "desktop")]
 use tauri::Manager;
 
+#[cfg(feature = "desktop")]
 pub mod app_state;
 pub mod cli;
+#[cfg(feature = "desktop")]
 mod commands;
 pub mod errors;
-mod git_utils;
-mod log_manager;
+pub mod fix_config;
+pub mod git_utils;
+pub mod log_manager;
 pub mod log_summary_cli;
+pub mod messages;
+mod redact;
+#[cfg(feature = "desktop")]
+mod reminder;
 pub mod settings;
+pub mod storage;
 pub mod summary;
+#[cfg(feature = "desktop")]
 mod system_tray;
+#[cfg(feature = "desktop")]
+mod window_state;
 
+#[cfg(feature = "desktop")]
 use app_state::AppState;
+#[cfg(feature = "desktop")]
 use system_tray::{get_tray_menu, setup_system_tray};
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 fn show_quick_entry(app_handle: tauri::AppHandle) {
     log::info!("调用了 show_quick_entry 命令");
@@ -33,7 +53,7 @@ fn show_quick_entry(app_handle: tauri::AppHandle) {
     }
 
     log::info!("创建新的 quick_entry 窗口");
-    let _ = tauri::WindowBuilder::new(
+    let window = tauri::WindowBuilder::new(
         &app_handle,
         "quick_entry",
         tauri::WindowUrl::App("quick_entry.html".into()),
@@ -45,9 +65,15 @@ fn show_quick_entry(app_handle: tauri::AppHandle) {
     .inner_size(500.0, 200.0)
     .build();
 
+    // 新建的窗口同样恢复上次的位置和大小
+    if let Ok(window) = window {
+        window_state::track_window(&window);
+    }
+
     log::info!("quick_entry 窗口创建完成");
 }
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 初始化日志系统
@@ -58,7 +84,7 @@ pub fn run() {
     let state = app_state.clone();
 
     tauri::Builder::default()
-        .system_tray(tauri::SystemTray::new().with_menu(get_tray_menu()))
+        .system_tray(tauri::SystemTray::new().with_menu(get_tray_menu(&app_state)))
         .manage(app_state)
         .setup(move |app| {
             let main_window = app.get_window("main").unwrap();
@@ -68,22 +94,61 @@ pub fn run() {
                 main_window.set_title("工作日志记录").unwrap();
             }
 
+            // 恢复窗口上次的位置和大小
+            for label in window_state::TRACKED_WINDOWS {
+                if let Some(window) = app.get_window(label) {
+                    window_state::track_window(&window);
+                }
+            }
+
             setup_system_tray(app.handle(), state.clone())?;
 
+            // 启动每日提醒任务，未开启提醒时任务空转
+            reminder::start_reminder_task(app.handle(), state.clone());
+
             Ok(())
         })
         .on_system_tray_event(system_tray::handle_system_tray_event)
         .invoke_handler(tauri::generate_handler![
             commands::add_log_entry,
+            commands::quick_add_from_clipboard,
             commands::get_log_entries,
+            commands::get_filtered_log_entries,
+            commands::get_log_entries_range,
             commands::get_log_files,
+            commands::get_log_files_page,
+            commands::get_log_file_summaries,
             commands::update_log_entry,
+            commands::move_log_entry,
             commands::delete_log_entry,
+            commands::list_trash,
+            commands::restore_log_entry,
+            commands::set_entry_status,
+            commands::set_entry_priority,
+            commands::add_entry_attachment,
+            commands::remove_entry_attachment,
+            commands::get_open_todos,
+            commands::search_log_entries,
+            commands::get_all_tags,
+            commands::get_all_sources,
+            commands::rename_tag,
+            commands::get_log_stats,
             commands::fetch_git_commits,
+            commands::fetch_git_commits_range,
+            commands::import_git_commits,
             commands::generate_summary_stream,
+            commands::refine_summary,
+            commands::generate_comparison_summary,
             commands::generate_summary,
+            commands::cancel_summary,
+            commands::test_summary_backend,
+            commands::get_since_last_summary_range,
             commands::get_settings,
+            commands::get_active_profile,
             commands::update_settings,
+            commands::validate_shortcut,
+            commands::run_diagnostics,
+            commands::apply_diagnosis_fixes,
             commands::select_directory,
             commands::register_cli,
             commands::unregister_cli,
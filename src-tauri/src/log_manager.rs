@@ -1,11 +1,24 @@
 use crate::errors::AppError;
-use crate::settings::Settings;
-use chrono::{DateTime, Local, NaiveDate, Utc};
+use crate::git_utils::{parse_conventional_commit, GitCommit};
+use crate::messages::Message;
+use crate::settings::{
+    Settings, FILE_NAME_DAY, FILE_NAME_MONTH, FILE_NAME_YEAR, STORAGE_BACKEND_DAILY,
+};
+use crate::storage::{create_storage, StorageBackend};
+#[cfg(feature = "sqlite")]
+use crate::storage::{SqliteStorage, SQLITE_FILE_NAME};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone};
+use fs2::FileExt;
 use log;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
+use std::cmp::Ordering;
+use std::collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 /// 单条日志记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,22 +34,88 @@ pub struct LogEntry {
     /// 标签
     pub tags: Vec<String>,
     /// 时间戳，可选
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_timestamp"
+    )]
     pub timestamp: Option<DateTime<Local>>,
+    /// 完成状态，普通日志为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<EntryStatus>,
+    /// 附件，本地文件的绝对路径或 URI，只记录引用，不复制文件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+    /// 优先级 1-3，数字越大越重要，未设置时视为普通
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// 无法识别的字段，例如新版本或手动添加的字段，重写文件时原样保留并按字段名排序
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
 }
 
+/// 低优先级
+pub const PRIORITY_LOW: u8 = 1;
+/// 普通优先级，未设置优先级的日志按此处理
+pub const PRIORITY_NORMAL: u8 = 2;
+/// 高优先级，摘要时优先列出
+pub const PRIORITY_HIGH: u8 = 3;
+
+/// 优先级的中文名称
+pub fn priority_label(priority: u8) -> &'static str {
+    match priority {
+        PRIORITY_LOW => "低",
+        PRIORITY_HIGH => "高",
+        _ => "普通",
+    }
+}
+
+/// 日志记录的完成状态，用于把日志当作待办清单使用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStatus {
+    /// 待办
+    Todo,
+    /// 进行中
+    InProgress,
+    /// 已完成
+    Done,
+}
+
+impl EntryStatus {
+    /// 状态的中文名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Todo => "待办",
+            Self::InProgress => "进行中",
+            Self::Done => "已完成",
+        }
+    }
+
+    /// 是否为未完成状态
+    pub fn is_open(&self) -> bool {
+        !matches!(self, Self::Done)
+    }
+}
+
+impl std::str::FromStr for EntryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "todo" => Ok(Self::Todo),
+            "in_progress" | "in-progress" | "doing" => Ok(Self::InProgress),
+            "done" => Ok(Self::Done),
+            _ => Err(Message::UnsupportedStatus(s).to_string()),
+        }
+    }
+}
+
+/// 日志记录的时间统一使用本地时区：创建时间带本地时区偏移保存，
+/// 记录所属的日期为创建时间换算到本地时区后的日期
 impl LogEntry {
-    /// 创建新的日志记录
+    /// 以当前本地时间创建新的日志记录
     pub fn new(content: String, source: String, tags: Vec<String>) -> Self {
-        let now = Utc::now();
-        Self {
-            id: format!("{}", now.timestamp_millis()),
-            content,
-            created_at: now.to_rfc3339(),
-            source,
-            tags,
-            timestamp: Some(now.with_timezone(&Local)),
-        }
+        Self::new_with_datetime(content, source, tags, Local::now())
     }
 
     /// 创建指定日期的日志记录
@@ -47,228 +126,3750 @@ impl LogEntry {
         date: NaiveDate,
     ) -> Self {
         // 使用指定日期和当前时间
-        let now = Local::now();
-        let date_time = date.and_time(now.time()).and_local_timezone(Local).unwrap();
+        let date_time = local_datetime(date.and_time(Local::now().time()));
+
+        Self::new_with_datetime(content, source, tags, date_time)
+    }
 
+    /// 创建指定日期时间的日志记录
+    pub fn new_with_datetime(
+        content: String,
+        source: String,
+        tags: Vec<String>,
+        date_time: DateTime<Local>,
+    ) -> Self {
         Self {
-            id: format!("{}", date_time.timestamp_millis()),
+            id: generate_entry_id(),
             content,
             created_at: date_time.to_rfc3339(),
             source,
+            tags: normalize_tags(tags, false),
+            timestamp: Some(date_time),
+            status: None,
+            attachments: Vec::new(),
+            priority: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// 由 Git 提交创建日志记录
+    ///
+    /// ID 由提交哈希生成，重复导入同一提交时 ID 相同，便于去重；
+    /// 内容为提交标题加短哈希，提交时间作为记录时间。仓库名作为标签，
+    /// 符合 Conventional Commits 格式的提交再以类型和范围作为标签
+    pub fn from_git_commit(commit: &GitCommit, repo_name: &str) -> Self {
+        let subject = commit.message.lines().next().unwrap_or("").trim();
+        let short_id = commit.id.get(..7).unwrap_or(&commit.id);
+        let date_time = commit.time.with_timezone(&Local);
+        let mut tags = Vec::new();
+        if !repo_name.is_empty() {
+            tags.push(repo_name.to_string());
+        }
+        if let Some(conventional) = parse_conventional_commit(subject) {
+            for tag in conventional.tags() {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+
+        Self {
+            id: git_commit_entry_id(&commit.id),
+            content: format!("{} ({})", subject, short_id),
+            created_at: date_time.to_rfc3339(),
+            source: GIT_COMMIT_SOURCE.to_string(),
             tags,
             timestamp: Some(date_time),
+            status: None,
+            attachments: Vec::new(),
+            priority: None,
+            extra: BTreeMap::new(),
         }
     }
+
+    /// 记录所属的日期，即存放记录的每日文件对应的日期
+    ///
+    /// 以 `created_at` 为准换算到本地时区，与界面上显示的日期一致
+    pub fn local_date(&self) -> Result<NaiveDate, AppError> {
+        Ok(DateTime::parse_from_rfc3339(&self.created_at)?
+            .with_timezone(&Local)
+            .date_naive())
+    }
+
+    /// 日志的优先级，未设置时为普通
+    pub fn priority_level(&self) -> u8 {
+        self.priority.unwrap_or(PRIORITY_NORMAL)
+    }
+
+    /// 是否为高优先级日志
+    pub fn is_high_priority(&self) -> bool {
+        self.priority_level() >= PRIORITY_HIGH
+    }
+
+    /// 日志内容的字数，中日韩文字每个字计为一个词，其他文字按空白和标点分词
+    pub fn word_count(&self) -> usize {
+        count_words(&self.content)
+    }
+
+    /// 日志内容的字符数，不计空白字符
+    pub fn char_count(&self) -> usize {
+        count_chars(&self.content)
+    }
+
+    /// 获取本地时区下的创建时间
+    pub fn created_at_local(&self) -> Option<DateTime<Local>> {
+        self.timestamp.or_else(|| {
+            DateTime::parse_from_rfc3339(&self.created_at)
+                .ok()
+                .map(|time| time.with_timezone(&Local))
+        })
+    }
 }
 
-/// 日志文件管理器
-pub struct LogManager {
-    settings: Settings,
+/// 将本地日期时间转换为本地时区的时间
+///
+/// 夏令时回拨造成时间重复时取较早的一个；落在夏令时跳过的时段时顺延一小时
+pub fn local_datetime(naive: NaiveDateTime) -> DateTime<Local> {
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .or_else(|| {
+            (naive + chrono::Duration::hours(1))
+                .and_local_timezone(Local)
+                .earliest()
+        })
+        .unwrap_or_else(|| Local.from_utc_datetime(&naive))
 }
 
-impl LogManager {
-    /// 创建新的日志管理器
-    pub fn new(settings: Settings) -> Self {
-        Self { settings }
+/// 统计文本字数
+///
+/// 中日韩文字按空白分词没有意义，每个字计为一个词；其他文字按空白和标点切分，
+/// 包含字母或数字的片段计为一个词，因此中英混排的内容也能得到合理的结果
+pub fn count_words(text: &str) -> usize {
+    let mut words = 0;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            words += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() || (in_word && matches!(c, '\'' | '-' | '_')) {
+            if !in_word {
+                words += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
     }
 
-    /// 获取指定日期的日志文件路径
-    pub fn get_log_file_path(&self, date: &NaiveDate) -> PathBuf {
-        let file_name = format!("{}.json", date.format("%Y-%m-%d"));
-        Path::new(&self.settings.log_storage_dir).join(file_name)
+    words
+}
+
+/// 统计文本字符数，不计空白字符
+pub fn count_chars(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// 是否为中日韩文字（汉字、假名、谚文），不包括全角标点
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}' // 汉字扩展 A
+        | '\u{4E00}'..='\u{9FFF}' // 汉字
+        | '\u{AC00}'..='\u{D7AF}' // 谚文
+        | '\u{F900}'..='\u{FAFF}' // 兼容汉字
+        | '\u{20000}'..='\u{2FA1F}' // 汉字扩展 B 及以后
+    )
+}
+
+/// 附件是否为带协议的 URI，如 `https://...`、`file:///...`、`mailto:...`
+fn is_attachment_uri(attachment: &str) -> bool {
+    match attachment.split_once(':') {
+        // 单个字母的协议是 Windows 盘符，如 `C:\`
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
     }
+}
 
-    /// 获取指定日期的日志记录
-    pub fn get_entries_for_date(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
-        let file_path = self.get_log_file_path(date);
+/// 校验附件并转换为保存的形式：URI 原样保留，本地路径必须存在并转换为绝对路径
+fn normalize_attachment(attachment: &str) -> Result<String, AppError> {
+    let attachment = attachment.trim();
+    if attachment.is_empty() {
+        return Err(AppError::LogManagerError(
+            Message::AttachmentEmpty.to_string(),
+        ));
+    }
+    if is_attachment_uri(attachment) {
+        return Ok(attachment.to_string());
+    }
 
-        if !file_path.exists() {
-            return Ok(Vec::new());
+    let path = Path::new(attachment);
+    if !path.exists() {
+        return Err(AppError::LogManagerError(
+            Message::AttachmentNotFound(attachment).to_string(),
+        ));
+    }
+    let path = fs::canonicalize(path)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 附件显示用的名称：本地路径取文件名，URI 取最后一段路径，取不到时返回原文
+pub fn attachment_name(attachment: &str) -> &str {
+    let name = if is_attachment_uri(attachment) {
+        attachment
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(attachment)
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+    } else {
+        Path::new(attachment)
+            .file_name()
+            .and_then(|name| name.to_str())
+    };
+    match name {
+        Some(name) if !name.is_empty() && !name.ends_with(':') => name,
+        _ => attachment,
+    }
+}
+
+/// 生成日志记录的唯一标识符
+///
+/// 使用 UUID v4，避免同一毫秒内创建多条记录时 ID 冲突。
+/// 旧数据中的数字 ID 仍按字符串比较，无需迁移。
+fn generate_entry_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// 日志过滤条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryFilter {
+    /// 标签过滤，为空时不按标签过滤
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// 来源过滤，为空时不按来源过滤
+    #[serde(default)]
+    pub source: Option<String>,
+    /// 是否要求包含全部标签，默认包含任一标签即可
+    #[serde(default)]
+    pub match_all_tags: bool,
+    /// 状态过滤，为空时不按状态过滤
+    #[serde(default)]
+    pub status: Option<EntryStatus>,
+}
+
+impl EntryFilter {
+    /// 判断日志记录是否满足过滤条件
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        if self.status.is_some() && entry.status != self.status {
+            return false;
         }
 
-        let content = fs::read_to_string(file_path)?;
-        let entries: Vec<LogEntry> = serde_json::from_str(&content)?;
+        if let Some(source) = &self.source {
+            if !source.is_empty() && &entry.source != source {
+                return false;
+            }
+        }
 
-        Ok(entries)
+        match &self.tags {
+            Some(tags) if !tags.is_empty() => {
+                let has_tag = |tag: &String| entry.tags.contains(tag);
+                if self.match_all_tags {
+                    tags.iter().all(has_tag)
+                } else {
+                    tags.iter().any(has_tag)
+                }
+            }
+            _ => true,
+        }
     }
+}
 
-    /// 添加日志记录
-    pub fn add_entry(&self, entry: LogEntry) -> Result<(), AppError> {
-        // 确保日志目录存在
-        self.settings.ensure_log_dirs_exist()?;
+/// 搜索选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// 是否区分大小写，默认不区分
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// 是否全词匹配
+    #[serde(default)]
+    pub whole_word: bool,
+}
 
-        // 从创建时间解析日期
-        let created_at = DateTime::parse_from_rfc3339(&entry.created_at)
-            .map_err(|e| AppError::ChronoError(e))?
-            .with_timezone(&Local);
+/// 搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// 日志所在日期 (YYYY-MM-DD)
+    pub date: String,
+    /// 匹配的日志记录
+    pub entry: LogEntry,
+}
 
-        let date = created_at.date_naive();
-        let file_path = self.get_log_file_path(&date);
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// CSV 表格，列为 date,time,source,tags,content,id
+    Csv,
+    /// Markdown 文档，按日期分节
+    Markdown,
+}
 
-        let mut entries = if file_path.exists() {
-            let content = fs::read_to_string(&file_path)?;
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
+/// 日期范围内的日志活跃度统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogStats {
+    /// 起始日期 (YYYY-MM-DD)
+    pub start_date: String,
+    /// 结束日期 (YYYY-MM-DD)
+    pub end_date: String,
+    /// 日志总数
+    pub total_entries: usize,
+    /// 范围内的总天数
+    pub total_days: usize,
+    /// 有日志的天数
+    pub active_days: usize,
+    /// 每天的日志数，包含没有日志的日期，按日期升序
+    pub daily_counts: Vec<CountItem>,
+    /// 各标签的日志数，按数量降序
+    pub tag_counts: Vec<CountItem>,
+    /// 各来源的日志数，按数量降序
+    pub source_counts: Vec<CountItem>,
+    /// 日志最多的一天，范围内没有日志时为空
+    pub busiest_day: Option<CountItem>,
+    /// 日志内容总字数，计算方式见 [`count_words`]
+    #[serde(default)]
+    pub total_words: usize,
+    /// 日志内容总字符数，不计空白字符
+    #[serde(default)]
+    pub total_chars: usize,
+}
 
-        entries.push(entry);
+/// 统计项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountItem {
+    /// 名称，按天统计时为日期
+    pub name: String,
+    /// 数量
+    pub count: usize,
+}
 
-        let content = serde_json::to_string_pretty(&entries)?;
-        fs::write(file_path, content)?;
+/// 回收站中的日志记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedEntry {
+    /// 被删除的日志记录
+    pub entry: LogEntry,
+    /// 记录原来所在的日期 (YYYY-MM-DD)
+    pub date: String,
+    /// 删除时间 (RFC 3339)
+    pub deleted_at: String,
+}
 
-        Ok(())
-    }
+/// 带重复检查的添加结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum AddOutcome {
+    /// 已添加
+    Added,
+    /// 当天已有相同内容的记录，已跳过
+    Skipped {
+        /// 已存在记录的 ID
+        existing_id: String,
+    },
+}
 
-    /// 更新日志记录
-    pub fn update_entry(&self, updated_entry: LogEntry) -> Result<(), AppError> {
-        // 从创建时间解析日期
-        let created_at = DateTime::parse_from_rfc3339(&updated_entry.created_at)
-            .map_err(|e| AppError::ChronoError(e))?
-            .with_timezone(&Local);
+/// 日志文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFileInfo {
+    /// 日期 (YYYY-MM-DD)
+    pub date: String,
+    /// 当天的日志条数
+    pub entry_count: usize,
+    /// 文件最后修改时间 (RFC 3339)，已归档的日期为归档文件的修改时间，无法获取时为空
+    pub last_modified: String,
+}
 
-        let date = created_at.date_naive();
-        let file_path = self.get_log_file_path(&date);
+/// 日志文件分页结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFilePage {
+    /// 当前页的日志文件，按日期从新到旧排列
+    pub items: Vec<LogFileInfo>,
+    /// 日志文件总数
+    pub total: usize,
+    /// 当前页的起始位置
+    pub offset: usize,
+    /// 每页数量
+    pub limit: usize,
+}
 
-        if !file_path.exists() {
-            return Err(AppError::LogManagerError(format!(
-                "未找到日期 {} 的日志文件",
-                date
-            )));
+/// 日志排序方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// 从早到晚
+    #[default]
+    Asc,
+    /// 从晚到早
+    Desc,
+}
+
+/// 导入结果统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// 新增的记录数
+    pub added: usize,
+    /// 因 ID 已存在而跳过的记录数
+    pub skipped: usize,
+    /// 无法导入的记录及原因
+    pub errors: Vec<String>,
+}
+
+/// 计划中的文件改动类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    /// 新建文件
+    Create,
+    /// 覆盖已有文件
+    Update,
+    /// 删除文件
+    Remove,
+}
+
+impl FileChangeKind {
+    /// 用于显示的中文名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileChangeKind::Create => "新建",
+            FileChangeKind::Update => "修改",
+            FileChangeKind::Remove => "删除",
         }
+    }
+}
+
+/// 计划改动的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedFileChange {
+    /// 文件路径
+    pub path: PathBuf,
+    /// 改动类型
+    pub kind: FileChangeKind,
+}
 
-        let content = fs::read_to_string(&file_path)?;
-        let mut entries: Vec<LogEntry> = serde_json::from_str(&content)?;
+/// 预演模式下记录的变更计划
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangePlan {
+    /// 将被改动的文件，按首次改动的顺序排列
+    pub files: Vec<PlannedFileChange>,
+    /// 受影响的日志记录 ID
+    pub entries: Vec<String>,
+}
 
-        // 查找并更新对应 ID 的记录
-        let mut found = false;
-        for entry in &mut entries {
-            if entry.id == updated_entry.id {
-                *entry = updated_entry.clone();
-                found = true;
-                break;
+impl ChangePlan {
+    /// 是否没有任何改动
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.entries.is_empty()
+    }
+}
+
+/// Git 提交导入的日志来源标识
+pub const GIT_COMMIT_SOURCE: &str = "git-commit";
+
+/// 从剪贴板快速添加的日志来源标识
+pub const CLIPBOARD_SOURCE: &str = "clipboard";
+
+/// 生成摘要后自动记录的日志来源标识，这类日志不会再纳入摘要
+pub const SUMMARY_SOURCE: &str = "summary";
+
+/// 生成摘要后自动记录的日志使用的标签
+pub const SUMMARY_TAG: &str = "summary";
+
+/// CSV 导出的表头
+///
+/// 导入时 `id` 列可以省略，省略或为空时导入的记录使用新生成的 ID
+const CSV_HEADER: [&str; 6] = ["date", "time", "source", "tags", "content", "id"];
+
+/// CSV 中多个标签之间的分隔符
+const CSV_TAG_SEPARATOR: &str = ";";
+
+/// 月度归档文件名后缀，完整文件名为 `YYYY-MM.archive.json`
+const ARCHIVE_SUFFIX: &str = ".archive.json";
+
+/// 回收站目录名，位于日志存储目录下
+const TRASH_DIR_NAME: &str = ".trash";
+
+/// 回收站文件名
+const TRASH_FILE_NAME: &str = "trash.json";
+
+/// 日志目录锁文件名
+const LOCK_FILE_NAME: &str = ".work-record.lock";
+
+/// 获取文件锁的最长等待时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 获取文件锁失败后的重试间隔
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 日志目录的独占文件锁，离开作用域时自动释放
+///
+/// CLI 与 GUI 共享同一存储目录，读-改-写日志文件前必须持有该锁
+struct StorageLock {
+    /// 预演模式下锁文件不存在时为空，此时不加锁
+    file: Option<File>,
+}
+
+impl Drop for StorageLock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            if let Err(e) = FileExt::unlock(file) {
+                log::warn!("释放日志目录锁失败: {}", e);
             }
         }
+    }
+}
+
+/// 某一天的日志记录
+struct DailyFile {
+    /// 日期 (YYYY-MM-DD)
+    date: String,
+    /// 当天的日志记录
+    entries: Vec<LogEntry>,
+}
+
+/// 预演模式的开关和累积的变更计划，由日志管理器和存储后端共享
+#[derive(Debug, Default)]
+pub(crate) struct ChangeRecorder {
+    /// 预演模式，只记录计划的改动而不写入文件
+    dry_run: bool,
+    /// 预演模式下累积的变更计划
+    plan: Mutex<ChangePlan>,
+}
+
+impl ChangeRecorder {
+    fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            plan: Mutex::new(ChangePlan::default()),
+        }
+    }
+
+    /// 是否为预演模式
+    pub(crate) fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    fn lock_plan(&self) -> std::sync::MutexGuard<'_, ChangePlan> {
+        self.plan.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录计划改动的文件，同一文件的相同改动只记录一次
+    pub(crate) fn record_file_change(&self, path: &Path, kind: FileChangeKind) {
+        let mut plan = self.lock_plan();
+        let change = PlannedFileChange {
+            path: path.to_path_buf(),
+            kind,
+        };
+        if !plan.files.contains(&change) {
+            plan.files.push(change);
+        }
+    }
 
-        if !found {
-            return Err(AppError::LogManagerError(format!(
-                "未找到 ID 为 {} 的日志记录",
-                updated_entry.id
-            )));
+    /// 预演模式下记录受影响的日志记录
+    fn record_entry_change(&self, entry_id: &str) {
+        if !self.dry_run {
+            return;
         }
+        let mut plan = self.lock_plan();
+        if !plan.entries.iter().any(|id| id == entry_id) {
+            plan.entries.push(entry_id.to_string());
+        }
+    }
 
-        let updated_content = serde_json::to_string_pretty(&entries)?;
-        fs::write(file_path, updated_content)?;
+    /// 执行写入文件的操作，预演模式下只记录计划
+    pub(crate) fn commit_write(
+        &self,
+        path: &Path,
+        write: impl FnOnce() -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        if self.dry_run {
+            let kind = if path.exists() {
+                FileChangeKind::Update
+            } else {
+                FileChangeKind::Create
+            };
+            self.record_file_change(path, kind);
+            return Ok(());
+        }
+        write()
+    }
 
+    /// 删除文件，预演模式下只记录计划
+    pub(crate) fn commit_remove(&self, path: &Path) -> Result<(), AppError> {
+        if self.dry_run {
+            self.record_file_change(path, FileChangeKind::Remove);
+            return Ok(());
+        }
+        fs::remove_file(path)?;
         Ok(())
     }
+}
 
-    /// 删除日志记录
-    pub fn delete_entry(&self, entry_id: &str, date: &NaiveDate) -> Result<(), AppError> {
-        let file_path = self.get_log_file_path(date);
+/// 每天一个 JSON 文件的存储后端，也是默认的存储方式
+///
+/// 文件名由设置中的文件名格式决定，已按月归档的日期从 `YYYY-MM.archive.json` 中读写
+pub struct DailyFileStorage {
+    settings: Settings,
+    changes: Arc<ChangeRecorder>,
+}
 
-        if !file_path.exists() {
-            return Err(AppError::LogManagerError(format!(
-                "未找到日期 {} 的日志文件",
-                date
-            )));
-        }
+impl DailyFileStorage {
+    pub(crate) fn new(settings: Settings, changes: Arc<ChangeRecorder>) -> Self {
+        Self { settings, changes }
+    }
+
+    /// 按设置中的文件名格式获取指定日期的日志文件路径，格式中的 `/` 表示子目录
+    pub fn get_log_file_path(&self, date: &NaiveDate) -> PathBuf {
+        let file_name = render_file_name(&self.settings.file_name_pattern, date);
+        Path::new(&self.settings.log_storage_dir).join(file_name)
+    }
 
-        let content = fs::read_to_string(&file_path)?;
-        let mut entries: Vec<LogEntry> = serde_json::from_str(&content)?;
+    /// 获取指定日期所在月份的归档文件路径
+    fn get_archive_path(&self, date: &NaiveDate) -> PathBuf {
+        let file_name = format!("{}{}", date.format("%Y-%m"), ARCHIVE_SUFFIX);
+        Path::new(&self.settings.log_storage_dir).join(file_name)
+    }
 
-        let original_len = entries.len();
-        entries.retain(|entry| entry.id != entry_id);
+    /// 恢复无法解析的每日日志文件，返回恢复的记录
+    ///
+    /// 逐条解析文件中的记录，原文件重命名为 `<文件名>.corrupt` 保留，再写回恢复的记录，
+    /// 丢弃的内容记录到日志中。调用方负责持有目录锁
+    fn recover_daily_file(
+        &self,
+        file_path: &Path,
+        content: &str,
+        error: &serde_json::Error,
+    ) -> Result<Vec<LogEntry>, AppError> {
+        log::warn!(
+            "日志文件 {} 无法解析: {}，尝试恢复",
+            file_path.display(),
+            error
+        );
 
-        if entries.len() == original_len {
-            return Err(AppError::LogManagerError(format!(
-                "未找到 ID 为 {} 的日志记录",
-                entry_id
-            )));
+        let (entries, dropped) = salvage_entries(content);
+        for fragment in &dropped {
+            log::warn!("丢弃无法恢复的记录: {}", fragment);
         }
 
-        if entries.is_empty() {
-            // 如果没有记录了，就删除文件
-            fs::remove_file(file_path)?;
-        } else {
-            let updated_content = serde_json::to_string_pretty(&entries)?;
-            fs::write(file_path, updated_content)?;
+        // 预演模式下保留损坏的文件，只记录恢复时会改写该文件
+        if self.changes.is_dry_run() {
+            self.changes
+                .record_file_change(file_path, FileChangeKind::Update);
+            return Ok(entries);
         }
 
-        Ok(())
+        // 已有同名的损坏文件时附加时间，避免覆盖之前保留的文件
+        let mut corrupt_name = file_path.file_name().unwrap_or_default().to_os_string();
+        corrupt_name.push(".corrupt");
+        let mut corrupt_path = file_path.with_file_name(&corrupt_name);
+        if corrupt_path.exists() {
+            corrupt_name.push(format!("-{}", Local::now().format("%Y%m%d%H%M%S")));
+            corrupt_path = file_path.with_file_name(corrupt_name);
+        }
+        fs::rename(file_path, &corrupt_path)?;
+
+        if !entries.is_empty() {
+            write_entries(file_path, &entries)?;
+        }
+
+        log::warn!(
+            "已从 {} 恢复 {} 条记录，丢弃 {} 条，原文件已保存为 {}",
+            file_path.display(),
+            entries.len(),
+            dropped.len(),
+            corrupt_path.display()
+        );
+
+        Ok(entries)
     }
 
-    /// 获取所有日志文件
-    pub fn get_log_files(&self) -> Result<Vec<String>, AppError> {
-        log::info!("开始获取日志文件列表");
+    /// 从月度归档中读取指定日期的日志记录
+    fn read_archived_day(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        let mut archive = read_archive(&self.get_archive_path(date))?;
+        Ok(archive.remove(&date_key(date)).unwrap_or_default())
+    }
 
+    /// 列出日志目录中符合文件名格式的每日日志文件及其日期，不包含月度归档
+    ///
+    /// 文件名格式包含 `/` 时按相应的层数查找子目录，跳过回收站等隐藏目录
+    fn list_daily_files(&self) -> Result<Vec<(NaiveDate, PathBuf)>, AppError> {
+        Ok(self.scan_storage_dir()?.daily_files)
+    }
+
+    /// 列出日志目录中不符合文件名格式的 JSON 文件，这些文件不会出现在日志列表中
+    ///
+    /// 不包含月度归档和隐藏文件，用于诊断时提示用户移走无关文件
+    fn list_unrecognized_files(&self) -> Result<Vec<PathBuf>, AppError> {
+        Ok(self.scan_storage_dir()?.unrecognized)
+    }
+
+    /// 扫描日志目录，返回每日日志文件和不符合文件名格式的 JSON 文件
+    fn scan_storage_dir(&self) -> Result<StorageScan, AppError> {
         // 确保日志目录存在
         self.settings.ensure_log_dirs_exist()?;
 
         let dir = Path::new(&self.settings.log_storage_dir);
-        let mut files = Vec::new();
+        let mut scan = StorageScan::default();
 
         log::debug!("查找日志目录: {}", dir.display());
 
         if !dir.exists() {
             log::warn!("日志目录不存在: {}", dir.display());
-            return Ok(files);
+            return Ok(scan);
         }
 
-        log::debug!("日志目录存在，开始读取文件列表");
+        let pattern = &self.settings.file_name_pattern;
+        let depth = pattern.matches('/').count();
+        collect_daily_files(
+            dir,
+            "",
+            depth,
+            pattern,
+            &mut scan.daily_files,
+            &mut scan.unrecognized,
+        )?;
 
-        // 遍历目录内容
-        for entry_result in fs::read_dir(dir)? {
-            match entry_result {
-                Ok(entry) => {
-                    let path = entry.path();
-                    log::trace!("找到文件: {}", path.display());
+        Ok(scan)
+    }
 
-                    if path.is_file()
-                        && path.extension().and_then(|ext| ext.to_str()) == Some("json")
-                    {
-                        if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
-                            log::debug!("添加日志文件: {}", file_name);
-                            files.push(file_name.to_string());
-                        }
-                    }
-                }
-                Err(e) => {
-                    log::error!("读取目录项失败: {}", e);
-                    continue; // 跳过无法读取的项
-                }
+    /// 读取所有月度归档，返回日期 (YYYY-MM-DD) 到日志记录的映射
+    ///
+    /// 单个归档文件损坏时跳过并记录警告
+    fn load_archives(&self) -> BTreeMap<String, Vec<LogEntry>> {
+        let mut days = BTreeMap::new();
+
+        for path in self.list_archive_paths() {
+            match read_archive(&path) {
+                Ok(archive) => days.extend(archive),
+                Err(e) => log::warn!("跳过无法解析的归档文件 {}: {}", path.display(), e),
             }
         }
 
-        // 按日期排序（最新的在前）
-        files.sort_by(|a, b| b.cmp(a));
+        days
+    }
 
-        log::info!("找到 {} 个日志文件", files.len());
-        if !files.is_empty() {
-            log::debug!("最新的日志文件: {}", files[0]);
+    /// 列出日志目录中的月度归档文件
+    fn list_archive_paths(&self) -> Vec<PathBuf> {
+        let Ok(read_dir) = fs::read_dir(&self.settings.log_storage_dir) else {
+            return Vec::new();
+        };
+
+        read_dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(ARCHIVE_SUFFIX))
+            })
+            .collect()
+    }
+}
+
+impl StorageBackend for DailyFileStorage {
+    fn day_path(&self, date: &NaiveDate) -> PathBuf {
+        self.get_log_file_path(date)
+    }
+
+    /// 读取指定日期的日志记录，每日文件不存在时从月度归档中读取
+    ///
+    /// 每日文件无法解析时尝试恢复其中的有效记录
+    fn read_day(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        let file_path = self.get_log_file_path(date);
+        if file_path.exists() {
+            let content = fs::read_to_string(&file_path)?;
+            return match serde_json::from_str(&content) {
+                Ok(entries) => Ok(entries),
+                Err(e) => self.recover_daily_file(&file_path, &content, &e),
+            };
         }
 
-        Ok(files)
+        self.read_archived_day(date)
     }
 
-    /// 获取指定时间范围内的所有日志
-    pub fn get_entries_in_date_range(
-        &self,
-        start_date: &NaiveDate,
-        end_date: &NaiveDate,
-    ) -> Result<HashMap<String, Vec<LogEntry>>, AppError> {
-        let mut result = HashMap::new();
-        let mut current_date = *start_date;
+    /// 每日文件无法解析时返回 `None`，恢复文件需要持有目录锁
+    fn read_day_unlocked(&self, date: &NaiveDate) -> Result<Option<Vec<LogEntry>>, AppError> {
+        let file_path = self.get_log_file_path(date);
+        if file_path.exists() {
+            let content = fs::read_to_string(&file_path)?;
+            return Ok(serde_json::from_str(&content).ok());
+        }
 
-        while current_date <= *end_date {
-            let entries = self.get_entries_for_date(&current_date)?;
-            if !entries.is_empty() {
-                let date_str = current_date.format("%Y-%m-%d").to_string();
-                result.insert(date_str, entries);
+        self.read_archived_day(date).map(Some)
+    }
+
+    /// 写入指定日期的日志记录，记录为空时删除当天的数据
+    ///
+    /// 当天已归档且没有每日文件时写回月度归档，否则写入每日文件
+    fn write_day(&self, date: &NaiveDate, entries: &[LogEntry]) -> Result<(), AppError> {
+        let file_path = self.get_log_file_path(date);
+
+        if !file_path.exists() {
+            let archive_path = self.get_archive_path(date);
+            let mut archive = read_archive(&archive_path)?;
+            let key = date_key(date);
+            if let Some(day_entries) = archive.get_mut(&key) {
+                if entries.is_empty() {
+                    archive.remove(&key);
+                } else {
+                    *day_entries = entries.to_vec();
+                }
+                if archive.is_empty() {
+                    return self.changes.commit_remove(&archive_path);
+                }
+                return self
+                    .changes
+                    .commit_write(&archive_path, || write_archive(&archive_path, &archive));
             }
-            current_date = current_date.succ_opt().unwrap_or(*end_date);
         }
 
-        Ok(result)
+        if entries.is_empty() {
+            if file_path.exists() {
+                self.changes.commit_remove(&file_path)?;
+            }
+            return Ok(());
+        }
+
+        self.changes
+            .commit_write(&file_path, || write_entries(&file_path, entries))
+    }
+
+    /// 每日文件和月度归档中的日期，同一天只列出一次
+    fn list_dates(&self) -> Result<Vec<NaiveDate>, AppError> {
+        // 按解析出的日期去重排序，不依赖文件名的字符串顺序
+        let mut dates: BTreeSet<NaiveDate> = self
+            .list_daily_files()?
+            .into_iter()
+            .map(|(date, _)| date)
+            .collect();
+        for key in self.load_archives().into_keys() {
+            match NaiveDate::parse_from_str(&key, "%Y-%m-%d") {
+                Ok(date) => {
+                    dates.insert(date);
+                }
+                Err(_) => log::debug!("跳过归档中日期格式无效的记录: {}", key),
+            }
+        }
+
+        Ok(dates.into_iter().collect())
+    }
+
+    /// 读取所有每日文件和月度归档，同一天已有每日文件时忽略归档中的记录
+    ///
+    /// 单个文件损坏时跳过并记录警告，不影响其他文件
+    fn load_all(&self) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        let mut days = BTreeMap::new();
+
+        for (date, file_path) in self.list_daily_files()? {
+            match fs::read_to_string(&file_path)
+                .map_err(AppError::from)
+                .and_then(|content| serde_json::from_str(&content).map_err(AppError::from))
+            {
+                Ok(entries) => {
+                    days.insert(date, entries);
+                }
+                Err(e) => {
+                    log::warn!("跳过无法解析的日志文件 {}: {}", file_path.display(), e);
+                }
+            }
+        }
+
+        for (key, entries) in self.load_archives() {
+            match NaiveDate::parse_from_str(&key, "%Y-%m-%d") {
+                Ok(date) => {
+                    days.entry(date).or_insert(entries);
+                }
+                Err(_) => log::debug!("跳过归档中日期格式无效的记录: {}", key),
+            }
+        }
+
+        Ok(days.into_iter().rev().collect())
+    }
+
+    /// 已归档的日期为归档文件的修改时间
+    fn last_modified(&self, date: &NaiveDate) -> String {
+        let daily_path = self.get_log_file_path(date);
+        if daily_path.exists() {
+            file_modified_time(&daily_path)
+        } else {
+            file_modified_time(&self.get_archive_path(date))
+        }
+    }
+
+    /// 每个日志文件和归档文件只读取一次，无法解析的文件条数记为 0
+    fn summaries(&self) -> Result<Vec<LogFileInfo>, AppError> {
+        let mut summaries = Vec::new();
+
+        for (date, path) in self.list_daily_files()? {
+            let entry_count = fs::read_to_string(&path)
+                .map_err(AppError::from)
+                .and_then(|content| {
+                    serde_json::from_str::<Vec<LogEntry>>(&content).map_err(AppError::from)
+                })
+                .map(|entries| entries.len())
+                .unwrap_or_else(|e| {
+                    log::warn!("无法解析日志文件 {}: {}", path.display(), e);
+                    0
+                });
+
+            summaries.push(LogFileInfo {
+                date: date_key(&date),
+                entry_count,
+                last_modified: file_modified_time(&path),
+            });
+        }
+
+        // 同一天已有每日文件时忽略归档中的记录
+        let known: HashSet<String> = summaries.iter().map(|info| info.date.clone()).collect();
+        for path in self.list_archive_paths() {
+            let archive = match read_archive(&path) {
+                Ok(archive) => archive,
+                Err(e) => {
+                    log::warn!("跳过无法解析的归档文件 {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let last_modified = file_modified_time(&path);
+            for (date, entries) in archive {
+                if !known.contains(&date) {
+                    summaries.push(LogFileInfo {
+                        date,
+                        entry_count: entries.len(),
+                        last_modified: last_modified.clone(),
+                    });
+                }
+            }
+        }
+
+        summaries.sort_by(|a, b| b.date.cmp(&a.date));
+
+        Ok(summaries)
+    }
+}
+
+/// 日志文件管理器
+///
+/// 日志记录的读写交给设置中选择的存储后端，目录锁、预演模式、回收站和归档由管理器负责
+pub struct LogManager {
+    settings: Settings,
+    /// 存储后端
+    storage: Box<dyn StorageBackend>,
+    /// 预演模式的开关和变更计划，与存储后端共享
+    changes: Arc<ChangeRecorder>,
+}
+
+impl LogManager {
+    /// 创建新的日志管理器
+    pub fn new(settings: Settings) -> Self {
+        Self::with_changes(settings, Arc::new(ChangeRecorder::new(false)))
+    }
+
+    fn with_changes(settings: Settings, changes: Arc<ChangeRecorder>) -> Self {
+        Self {
+            storage: create_storage(&settings, changes.clone()),
+            settings,
+            changes,
+        }
+    }
+
+    /// 设置是否为预演模式
+    ///
+    /// 预演模式下归档、导入、重命名标签和删除等操作照常计算结果，
+    /// 但不写入任何文件，计划的改动通过 `take_plan` 获取
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        Self::with_changes(self.settings, Arc::new(ChangeRecorder::new(dry_run)))
+    }
+
+    /// 是否为预演模式
+    pub fn is_dry_run(&self) -> bool {
+        self.changes.is_dry_run()
+    }
+
+    /// 取出预演模式下累积的变更计划
+    pub fn take_plan(&self) -> ChangePlan {
+        std::mem::take(&mut *self.changes.lock_plan())
+    }
+
+    /// 每天一个 JSON 文件的存储，用于归档、备份等只适用于该存储方式的操作
+    fn daily_storage(&self) -> DailyFileStorage {
+        DailyFileStorage::new(self.settings.clone(), self.changes.clone())
+    }
+
+    /// 其他存储方式不支持按月归档等操作时返回错误
+    fn ensure_daily_storage(&self, action: Message<'_>) -> Result<(), AppError> {
+        if self.settings.storage_backend == STORAGE_BACKEND_DAILY {
+            return Ok(());
+        }
+        Err(AppError::LogManagerError(
+            Message::DailyStorageOnly {
+                action: &action,
+                backend: &self.settings.storage_backend,
+            }
+            .to_string(),
+        ))
+    }
+
+    /// 获取保存指定日期日志的文件路径
+    pub fn get_log_file_path(&self, date: &NaiveDate) -> PathBuf {
+        self.storage.day_path(date)
+    }
+
+    /// 读取指定日期的日志记录。调用方负责持有目录锁
+    fn read_day(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        self.storage.read_day(date)
+    }
+
+    /// 不加锁读取指定日期的日志记录，不会创建日志目录或锁文件
+    ///
+    /// 当天的文件损坏需要恢复时才获取目录锁，并在锁内重新读取，
+    /// 期间其他进程已修复该文件时不再重命名为 `.corrupt`
+    fn read_day_unlocked(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        if let Some(entries) = self.storage.read_day_unlocked(date)? {
+            return Ok(entries);
+        }
+
+        let _lock = self.acquire_lock()?;
+        self.read_day(date)
+    }
+
+    /// 写入指定日期的日志记录，记录为空时删除当天的数据。调用方负责持有目录锁
+    fn write_day(&self, date: &NaiveDate, entries: &[LogEntry]) -> Result<(), AppError> {
+        self.storage.write_day(date, entries)
+    }
+
+    /// 将指定月份的每日日志文件合并为一个 `YYYY-MM.archive.json` 归档文件并删除原文件
+    ///
+    /// 归档后的日志仍可正常读取和修改，返回被归档的天数
+    pub fn archive_month(&self, year: i32, month: u32) -> Result<usize, AppError> {
+        let first_day = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| {
+            AppError::LogManagerError(
+                Message::InvalidMonth(&format!("{}-{:02}", year, month)).to_string(),
+            )
+        })?;
+        self.ensure_daily_storage(Message::ArchiveAction)?;
+
+        let _lock = self.acquire_lock()?;
+
+        let daily = self.daily_storage();
+        let archive_path = daily.get_archive_path(&first_day);
+        let mut archive = read_archive(&archive_path)?;
+
+        // 先读取全部每日文件，任一文件损坏时不做任何修改
+        let mut archived_files = Vec::new();
+        for date in first_day
+            .iter_days()
+            .take_while(|date| date.month() == month)
+        {
+            let file_path = daily.get_log_file_path(&date);
+            if !file_path.exists() {
+                continue;
+            }
+
+            let content = fs::read_to_string(&file_path)?;
+            let entries: Vec<LogEntry> = serde_json::from_str(&content).map_err(|e| {
+                AppError::LogManagerError(
+                    Message::ArchiveFileUnreadable {
+                        path: &file_path.display(),
+                        error: &e,
+                    }
+                    .to_string(),
+                )
+            })?;
+
+            let day_entries = archive.entry(date_key(&date)).or_default();
+            for entry in entries {
+                if !day_entries.iter().any(|existing| existing.id == entry.id) {
+                    self.changes.record_entry_change(&entry.id);
+                    day_entries.push(entry);
+                }
+            }
+            archived_files.push(file_path);
+        }
+
+        if archived_files.is_empty() {
+            return Ok(0);
+        }
+
+        // 归档文件写入成功后再删除每日文件
+        self.changes
+            .commit_write(&archive_path, || write_archive(&archive_path, &archive))?;
+        for file_path in &archived_files {
+            self.changes.commit_remove(file_path)?;
+        }
+
+        log::info!(
+            "已将 {}-{:02} 的 {} 个日志文件归档到 {}",
+            year,
+            month,
+            archived_files.len(),
+            archive_path.display()
+        );
+
+        Ok(archived_files.len())
+    }
+
+    /// 归档指定月份之前所有月份的每日日志文件，返回各月份 (YYYY-MM) 归档的天数
+    pub fn archive_before(&self, year: i32, month: u32) -> Result<Vec<(String, usize)>, AppError> {
+        let cutoff = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| {
+            AppError::LogManagerError(
+                Message::InvalidMonth(&format!("{}-{:02}", year, month)).to_string(),
+            )
+        })?;
+        self.ensure_daily_storage(Message::ArchiveAction)?;
+
+        let mut months: Vec<(i32, u32)> = self
+            .daily_storage()
+            .list_daily_files()?
+            .into_iter()
+            .map(|(date, _)| date)
+            .filter(|date| *date < cutoff)
+            .map(|date| (date.year(), date.month()))
+            .collect();
+        months.sort_unstable();
+        months.dedup();
+
+        let mut archived = Vec::new();
+        for (year, month) in months {
+            let days = self.archive_month(year, month)?;
+            archived.push((format!("{}-{:02}", year, month), days));
+        }
+
+        Ok(archived)
+    }
+
+    /// 获取日志目录的独占锁，超时未获取到则返回错误
+    ///
+    /// 预演模式下不创建目录和锁文件，锁文件不存在时直接返回空锁
+    fn acquire_lock(&self) -> Result<StorageLock, AppError> {
+        let dir = Path::new(&self.settings.log_storage_dir);
+        let lock_path = dir.join(LOCK_FILE_NAME);
+        if self.changes.is_dry_run() && !lock_path.exists() {
+            return Ok(StorageLock { file: None });
+        }
+
+        if !dir.exists() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+
+        let started = Instant::now();
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => return Ok(StorageLock { file: Some(file) }),
+                Err(_) if started.elapsed() < LOCK_TIMEOUT => thread::sleep(LOCK_RETRY_INTERVAL),
+                Err(e) => {
+                    return Err(AppError::LogManagerError(
+                        Message::LockTimeout {
+                            secs: LOCK_TIMEOUT.as_secs(),
+                            error: &e,
+                        }
+                        .to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// 将日志目录中的文件复制到 `dest` 目录作为快照，返回复制的文件数
+    ///
+    /// 复制日志目录顶层的文件（不含锁文件）以及按文件名格式存放在子目录中的每日日志文件，
+    /// 复制期间持有目录锁保证快照一致
+    pub fn backup_all(&self, dest: &Path) -> Result<usize, AppError> {
+        let storage_dir = Path::new(&self.settings.log_storage_dir);
+        if !storage_dir.exists() {
+            return Err(AppError::LogManagerError(
+                Message::StorageDirMissing(&storage_dir.display()).to_string(),
+            ));
+        }
+
+        let _lock = self.acquire_lock()?;
+
+        fs::create_dir_all(dest)?;
+        if fs::canonicalize(dest)? == fs::canonicalize(storage_dir)? {
+            return Err(AppError::LogManagerError(
+                Message::BackupDirIsStorageDir.to_string(),
+            ));
+        }
+
+        let mut copied = 0;
+        for entry in fs::read_dir(storage_dir)? {
+            let path = entry?.path();
+            let is_lock_file = path.file_name().is_some_and(|name| name == LOCK_FILE_NAME);
+            if !path.is_file() || is_lock_file {
+                continue;
+            }
+
+            if let Some(file_name) = path.file_name() {
+                fs::copy(&path, dest.join(file_name))?;
+                copied += 1;
+            }
+        }
+
+        // 文件名格式包含子目录时，保留相对路径复制每日日志文件
+        for (_, path) in self.daily_storage().list_daily_files()? {
+            if path.parent() == Some(storage_dir) {
+                continue;
+            }
+
+            if let Ok(relative) = path.strip_prefix(storage_dir) {
+                let target = dest.join(relative);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&path, target)?;
+                copied += 1;
+            }
+        }
+
+        log::info!("已备份 {} 个日志文件到 {}", copied, dest.display());
+        Ok(copied)
+    }
+
+    /// 获取指定日期的日志记录，按创建时间从早到晚排列
+    pub fn get_entries_for_date(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        self.get_entries_for_date_sorted(date, SortOrder::Asc)
+    }
+
+    /// 获取指定日期的日志记录，按创建时间以指定顺序排列
+    ///
+    /// 读取时不持有目录锁，只有当天的日志文件损坏需要恢复时才加锁
+    pub fn get_entries_for_date_sorted(
+        &self,
+        date: &NaiveDate,
+        order: SortOrder,
+    ) -> Result<Vec<LogEntry>, AppError> {
+        let mut entries = self.read_day_unlocked(date)?;
+        sort_entries(&mut entries, order);
+
+        Ok(entries)
+    }
+
+    /// 获取指定日期满足过滤条件的日志记录
+    pub fn get_filtered_entries(
+        &self,
+        date: &NaiveDate,
+        filter: &EntryFilter,
+    ) -> Result<Vec<LogEntry>, AppError> {
+        let entries = self.get_entries_for_date(date)?;
+
+        Ok(entries
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .collect())
+    }
+
+    /// 整理新日志记录：去掉内容的首尾空白并规范化标签，内容为空或只有空白时返回错误
+    fn prepare_new_entry(&self, mut entry: LogEntry) -> Result<LogEntry, AppError> {
+        let content = entry.content.trim();
+        if content.is_empty() {
+            return Err(AppError::LogManagerError(
+                Message::EntryContentEmpty.to_string(),
+            ));
+        }
+        entry.content = content.to_string();
+        entry.tags = normalize_tags(entry.tags, self.settings.normalize_tags);
+        Ok(entry)
+    }
+
+    /// 添加日志记录，检查当天是否已有相同内容的记录
+    ///
+    /// 内容比较时忽略首尾空白并将连续空白视为一个空格。存在重复时，
+    /// `skip_duplicates` 为 true 返回 `AddOutcome::Skipped`，否则返回 `AppError::DuplicateEntry`。
+    /// 与 `add_entry` 相同，保存前整理内容和标签，内容为空时返回错误
+    pub fn add_entry_checked(
+        &self,
+        entry: LogEntry,
+        skip_duplicates: bool,
+    ) -> Result<AddOutcome, AppError> {
+        let entry = self.prepare_new_entry(entry)?;
+        self.settings.ensure_log_dirs_exist()?;
+
+        let date = entry.local_date()?;
+
+        let _lock = self.acquire_lock()?;
+
+        let entries = self.read_day(&date)?;
+
+        let content = normalize_content(&entry.content);
+        if let Some(existing) = entries
+            .iter()
+            .find(|existing| normalize_content(&existing.content) == content)
+        {
+            if skip_duplicates {
+                return Ok(AddOutcome::Skipped {
+                    existing_id: existing.id.clone(),
+                });
+            }
+            return Err(AppError::DuplicateEntry(existing.id.clone()));
+        }
+
+        self.storage.append_entry(&date, entry)?;
+
+        Ok(AddOutcome::Added)
+    }
+
+    /// 将 Git 提交导入为日志记录，返回本次新添加的记录
+    ///
+    /// ID 已存在（提交已导入过）或同一天已有相同内容的提交会被跳过，重复导入不会产生重复记录
+    pub fn import_git_commits(
+        &self,
+        commits: &[GitCommit],
+        repo_name: &str,
+    ) -> Result<Vec<LogEntry>, AppError> {
+        // 按提交所在日期缓存已有的日志 ID
+        let mut existing_ids: HashMap<NaiveDate, HashSet<String>> = HashMap::new();
+        let mut imported = Vec::new();
+
+        for commit in commits {
+            let entry = LogEntry::from_git_commit(commit, repo_name);
+            let entry_date = entry.local_date()?;
+
+            let ids = match existing_ids.entry(entry_date) {
+                hash_map::Entry::Occupied(e) => e.into_mut(),
+                hash_map::Entry::Vacant(e) => {
+                    let ids = self
+                        .get_entries_for_date(&entry_date)?
+                        .into_iter()
+                        .map(|entry| entry.id)
+                        .collect();
+                    e.insert(ids)
+                }
+            };
+
+            if !ids.insert(entry.id.clone()) {
+                continue;
+            }
+
+            if self.add_entry_checked(entry.clone(), true)? == AddOutcome::Added {
+                imported.push(entry);
+            }
+        }
+
+        Ok(imported)
+    }
+
+    /// 添加日志记录，保存前整理内容和标签，内容为空时返回错误
+    pub fn add_entry(&self, entry: LogEntry) -> Result<(), AppError> {
+        let entry = self.prepare_new_entry(entry)?;
+
+        // 确保日志目录存在
+        self.settings.ensure_log_dirs_exist()?;
+
+        // 从创建时间解析日期
+        let date = entry.local_date()?;
+
+        let _lock = self.acquire_lock()?;
+
+        self.storage.append_entry(&date, entry)?;
+
+        Ok(())
+    }
+
+    /// 更新日志记录
+    ///
+    /// 可以修改同一天内的时间，`created_at` 与 `timestamp` 会以 `created_at` 为准保持一致；
+    /// 日期发生变化时返回错误，需改用 `move_entry` 移动记录。
+    /// 记录保持原来的位置，同一天的其他记录不做任何改动；更新内容不带无法识别的字段时保留原有的字段
+    pub fn update_entry(&self, updated_entry: LogEntry) -> Result<(), AppError> {
+        // 从创建时间解析日期
+        let created_at = DateTime::parse_from_rfc3339(&updated_entry.created_at)
+            .map_err(|e| AppError::ChronoError(e))?
+            .with_timezone(&Local);
+
+        let date = created_at.date_naive();
+
+        let _lock = self.acquire_lock()?;
+
+        let mut entries = self.read_day(&date)?;
+
+        // 查找并更新对应 ID 的记录
+        let Some(entry) = entries
+            .iter_mut()
+            .find(|entry| entry.id == updated_entry.id)
+        else {
+            return Err(self.entry_not_found_error(&updated_entry.id, &date));
+        };
+
+        let extra = if updated_entry.extra.is_empty() {
+            std::mem::take(&mut entry.extra)
+        } else {
+            updated_entry.extra
+        };
+        *entry = LogEntry {
+            created_at: created_at.to_rfc3339(),
+            timestamp: Some(created_at),
+            extra,
+            ..updated_entry
+        };
+
+        self.write_day(&date, &entries)?;
+
+        Ok(())
+    }
+
+    /// 将日志记录移动到新的创建时间，可以跨日期
+    ///
+    /// 在同一天内移动时记录保持原来的位置，跨日期时追加到目标日期的末尾
+    pub fn move_entry(
+        &self,
+        entry_id: &str,
+        from_date: &NaiveDate,
+        new_created_at: DateTime<Local>,
+    ) -> Result<LogEntry, AppError> {
+        let to_date = new_created_at.date_naive();
+
+        let _lock = self.acquire_lock()?;
+
+        let mut from_entries = self.read_day(from_date)?;
+        if from_entries.is_empty() {
+            return Err(AppError::LogManagerError(
+                Message::NoLogFileOnDate(from_date).to_string(),
+            ));
+        }
+
+        let index = from_entries
+            .iter()
+            .position(|entry| entry.id == entry_id)
+            .ok_or_else(|| {
+                AppError::LogManagerError(Message::EntryNotFound(entry_id).to_string())
+            })?;
+
+        let mut entry = from_entries.remove(index);
+        entry.created_at = new_created_at.to_rfc3339();
+        entry.timestamp = Some(new_created_at);
+
+        if *from_date == to_date {
+            from_entries.insert(index, entry.clone());
+            self.write_day(from_date, &from_entries)?;
+            return Ok(entry);
+        }
+
+        let mut to_entries = self.read_day(&to_date)?;
+        to_entries.push(entry.clone());
+
+        // 先写入目标日期，避免中途失败时丢失记录
+        self.write_day(&to_date, &to_entries)?;
+        self.write_day(from_date, &from_entries)?;
+
+        Ok(entry)
+    }
+
+    /// 生成更新时找不到记录的错误，记录位于其他日期时提示改用 `move_entry`
+    fn entry_not_found_error(&self, entry_id: &str, date: &NaiveDate) -> AppError {
+        let original_date = self.load_all_entries().ok().and_then(|files| {
+            files
+                .into_iter()
+                .find(|daily| daily.entries.iter().any(|entry| entry.id == entry_id))
+                .map(|daily| daily.date)
+        });
+
+        match original_date {
+            Some(original_date) => AppError::LogManagerError(
+                Message::EntryOnOtherDate {
+                    id: entry_id,
+                    date: &original_date,
+                    target: date,
+                }
+                .to_string(),
+            ),
+            None => AppError::LogManagerError(Message::EntryNotFound(entry_id).to_string()),
+        }
+    }
+
+    /// 设置日志记录的完成状态，传入 None 时清除状态
+    pub fn set_status(
+        &self,
+        entry_id: &str,
+        date: &NaiveDate,
+        status: Option<EntryStatus>,
+    ) -> Result<LogEntry, AppError> {
+        self.modify_entry(entry_id, date, |entry| {
+            entry.status = status;
+            Ok(())
+        })
+    }
+
+    /// 设置日志记录的优先级（1-3），传入 None 时恢复为普通
+    pub fn set_priority(
+        &self,
+        entry_id: &str,
+        date: &NaiveDate,
+        priority: Option<u8>,
+    ) -> Result<LogEntry, AppError> {
+        if let Some(priority) = priority {
+            if !(PRIORITY_LOW..=PRIORITY_HIGH).contains(&priority) {
+                return Err(AppError::LogManagerError(
+                    Message::PriorityOutOfRange {
+                        min: PRIORITY_LOW,
+                        max: PRIORITY_HIGH,
+                        value: priority,
+                    }
+                    .to_string(),
+                ));
+            }
+        }
+
+        self.modify_entry(entry_id, date, |entry| {
+            entry.priority = priority;
+            Ok(())
+        })
+    }
+
+    /// 为日志记录添加附件
+    ///
+    /// 带协议的 URI（如 `https://`）原样保存；其他内容视为本地路径，
+    /// 要求文件存在，保存为绝对路径。已有相同附件时不重复添加
+    pub fn add_attachment(
+        &self,
+        entry_id: &str,
+        date: &NaiveDate,
+        attachment: &str,
+    ) -> Result<LogEntry, AppError> {
+        let attachment = normalize_attachment(attachment)?;
+        self.modify_entry(entry_id, date, |entry| {
+            if !entry.attachments.contains(&attachment) {
+                entry.attachments.push(attachment);
+            }
+            Ok(())
+        })
+    }
+
+    /// 移除日志记录的附件，`attachment` 可以是完整路径、URI 或文件名
+    pub fn remove_attachment(
+        &self,
+        entry_id: &str,
+        date: &NaiveDate,
+        attachment: &str,
+    ) -> Result<LogEntry, AppError> {
+        let attachment = attachment.trim();
+        self.modify_entry(entry_id, date, |entry| {
+            let before = entry.attachments.len();
+            entry
+                .attachments
+                .retain(|a| a != attachment && attachment_name(a) != attachment);
+            if entry.attachments.len() == before {
+                return Err(AppError::LogManagerError(
+                    Message::EntryHasNoAttachment {
+                        id: entry_id,
+                        attachment,
+                    }
+                    .to_string(),
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    /// 在锁内读取指定日期的记录，修改 ID 对应的记录后写回，返回修改后的记录
+    fn modify_entry<F>(
+        &self,
+        entry_id: &str,
+        date: &NaiveDate,
+        modify: F,
+    ) -> Result<LogEntry, AppError>
+    where
+        F: FnOnce(&mut LogEntry) -> Result<(), AppError>,
+    {
+        let _lock = self.acquire_lock()?;
+
+        let mut entries = self.read_day(date)?;
+        if entries.is_empty() {
+            return Err(AppError::LogManagerError(
+                Message::NoLogFileOnDate(date).to_string(),
+            ));
+        }
+
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.id == entry_id)
+            .ok_or_else(|| {
+                AppError::LogManagerError(Message::EntryNotFound(entry_id).to_string())
+            })?;
+        modify(entry)?;
+        let updated = entry.clone();
+
+        self.write_day(date, &entries)?;
+
+        Ok(updated)
+    }
+
+    /// 获取所有未完成（待办或进行中）的日志记录，按日期从旧到新排列
+    pub fn get_open_entries(&self) -> Result<Vec<SearchResult>, AppError> {
+        self.collect_entries_by_status(|status| status.is_open())
+    }
+
+    /// 获取所有指定状态的日志记录，按日期从旧到新排列
+    pub fn get_entries_with_status(
+        &self,
+        status: EntryStatus,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        self.collect_entries_by_status(|entry_status| entry_status == status)
+    }
+
+    /// 收集状态满足条件的日志记录，没有状态的记录不参与筛选
+    fn collect_entries_by_status(
+        &self,
+        predicate: impl Fn(EntryStatus) -> bool,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        let mut results: Vec<SearchResult> = Vec::new();
+
+        for daily in self.load_all_entries()? {
+            for entry in daily.entries {
+                if entry.status.is_some_and(&predicate) {
+                    results.push(SearchResult {
+                        date: daily.date.clone(),
+                        entry,
+                    });
+                }
+            }
+        }
+
+        results.sort_by(|a, b| a.date.cmp(&b.date));
+        Ok(results)
+    }
+
+    /// 删除日志记录
+    ///
+    /// 默认移入回收站，可以通过 `restore_entry` 恢复；`permanent` 为 true 时直接删除。
+    /// 同一天的其他记录保持原有顺序
+    pub fn delete_entry(
+        &self,
+        entry_id: &str,
+        date: &NaiveDate,
+        permanent: bool,
+    ) -> Result<(), AppError> {
+        let _lock = self.acquire_lock()?;
+
+        let mut entries = self.read_day(date)?;
+        if entries.is_empty() {
+            return Err(AppError::LogManagerError(
+                Message::NoLogFileOnDate(date).to_string(),
+            ));
+        }
+
+        let index = entries
+            .iter()
+            .position(|entry| entry.id == entry_id)
+            .ok_or_else(|| {
+                AppError::LogManagerError(Message::EntryNotFound(entry_id).to_string())
+            })?;
+        let removed = entries.remove(index);
+        self.changes.record_entry_change(&removed.id);
+
+        // 先写入回收站，避免中途失败时丢失记录
+        if !permanent {
+            let mut trash = self.read_trash()?;
+            trash.push(TrashedEntry {
+                entry: removed,
+                date: date_key(date),
+                deleted_at: Local::now().to_rfc3339(),
+            });
+            self.write_trash(&trash)?;
+        }
+
+        // 没有记录时删除当天的文件
+        self.write_day(date, &entries)?;
+
+        Ok(())
+    }
+
+    /// 列出回收站中的日志记录，最近删除的在前
+    pub fn list_trash(&self) -> Result<Vec<TrashedEntry>, AppError> {
+        let mut trash = self.read_trash()?;
+        trash.reverse();
+        Ok(trash)
+    }
+
+    /// 将回收站中的日志记录恢复到原来的日期
+    pub fn restore_entry(&self, entry_id: &str) -> Result<TrashedEntry, AppError> {
+        let _lock = self.acquire_lock()?;
+
+        let mut trash = self.read_trash()?;
+        // 同一 ID 被多次删除时恢复最近的一次
+        let index = trash
+            .iter()
+            .rposition(|item| item.entry.id == entry_id)
+            .ok_or_else(|| {
+                AppError::LogManagerError(Message::TrashEntryNotFound(entry_id).to_string())
+            })?;
+
+        let item = trash.remove(index);
+        let date = NaiveDate::parse_from_str(&item.date, "%Y-%m-%d")?;
+
+        let mut entries = self.read_day(&date)?;
+        if entries.iter().any(|entry| entry.id == entry_id) {
+            return Err(AppError::LogManagerError(
+                Message::RestoreConflict {
+                    date: &item.date,
+                    id: entry_id,
+                }
+                .to_string(),
+            ));
+        }
+        entries.push(item.entry.clone());
+
+        // 先恢复记录再更新回收站，避免中途失败时丢失记录
+        self.write_day(&date, &entries)?;
+        self.write_trash(&trash)?;
+
+        Ok(item)
+    }
+
+    /// 清空回收站，指定 `older_than` 时只删除在该时间之前删除的记录，返回清除的数量
+    pub fn purge_trash(&self, older_than: Option<DateTime<Local>>) -> Result<usize, AppError> {
+        let _lock = self.acquire_lock()?;
+
+        let mut trash = self.read_trash()?;
+        let original_len = trash.len();
+
+        if let Some(cutoff) = older_than {
+            // 删除时间无法解析的记录一并清除
+            trash.retain(|item| {
+                DateTime::parse_from_rfc3339(&item.deleted_at)
+                    .is_ok_and(|deleted_at| deleted_at >= cutoff)
+            });
+        } else {
+            trash.clear();
+        }
+
+        let purged = original_len - trash.len();
+        if purged > 0 {
+            self.write_trash(&trash)?;
+        }
+
+        Ok(purged)
+    }
+
+    /// 获取回收站文件路径
+    fn get_trash_path(&self) -> PathBuf {
+        Path::new(&self.settings.log_storage_dir)
+            .join(TRASH_DIR_NAME)
+            .join(TRASH_FILE_NAME)
+    }
+
+    /// 读取回收站，按删除先后排列
+    fn read_trash(&self) -> Result<Vec<TrashedEntry>, AppError> {
+        let path = self.get_trash_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 写入回收站，调用方负责持有目录锁
+    fn write_trash(&self, trash: &[TrashedEntry]) -> Result<(), AppError> {
+        let path = self.get_trash_path();
+        self.changes.commit_write(&path, || {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+
+            write_json_atomic(&path, trash)
+        })
+    }
+
+    /// 获取所有日志文件
+    ///
+    /// 无论存储方式和文件名格式如何，每天都以 `YYYY-MM-DD.json` 的形式列出；
+    /// 已归档月份的日期也一并列出，调用方无需区分是否已归档
+    pub fn get_log_files(&self) -> Result<Vec<String>, AppError> {
+        log::info!("开始获取日志文件列表");
+
+        let dates = self.storage.list_dates()?;
+
+        // 最新的在前
+        let files: Vec<String> = dates
+            .iter()
+            .rev()
+            .map(|date| format!("{}.json", date_key(date)))
+            .collect();
+
+        log::info!("找到 {} 个日志文件", files.len());
+        if !files.is_empty() {
+            log::debug!("最新的日志文件: {}", files[0]);
+        }
+
+        Ok(files)
+    }
+
+    /// 分页获取日志文件信息，按日期从新到旧排列
+    ///
+    /// 只读取当前页文件的内容来统计条数，无法解析的文件条数记为 0
+    pub fn get_log_files_page(&self, offset: usize, limit: usize) -> Result<LogFilePage, AppError> {
+        let files = self.get_log_files()?;
+        let total = files.len();
+
+        let items = files
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|file_name| self.get_log_file_info(file_name.trim_end_matches(".json")))
+            .collect();
+
+        Ok(LogFilePage {
+            items,
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    /// 获取所有日志文件信息，按日期从新到旧排列
+    pub fn get_log_file_summaries(&self) -> Result<Vec<LogFileInfo>, AppError> {
+        self.storage.summaries()
+    }
+
+    /// 获取某天的日志文件信息，读取失败时条数记为 0
+    fn get_log_file_info(&self, date: &str) -> LogFileInfo {
+        let parsed = NaiveDate::parse_from_str(date, "%Y-%m-%d");
+
+        let entry_count = match parsed
+            .map_err(AppError::from)
+            .and_then(|date| self.read_day_unlocked(&date))
+        {
+            Ok(entries) => entries.len(),
+            Err(e) => {
+                log::warn!("无法读取 {} 的日志: {}", date, e);
+                0
+            }
+        };
+
+        let last_modified = match parsed {
+            Ok(parsed) => self.storage.last_modified(&parsed),
+            Err(_) => String::new(),
+        };
+
+        LogFileInfo {
+            date: date.to_string(),
+            entry_count,
+            last_modified,
+        }
+    }
+
+    /// 列出日志目录中不符合文件名格式的 JSON 文件，这些文件不会出现在日志列表中
+    ///
+    /// 不包含月度归档和隐藏文件，用于诊断时提示用户移走无关文件
+    pub fn list_unrecognized_files(&self) -> Result<Vec<PathBuf>, AppError> {
+        self.daily_storage().list_unrecognized_files()
+    }
+
+    /// 获取指定时间范围内的所有日志
+    pub fn get_entries_in_date_range(
+        &self,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<HashMap<String, Vec<LogEntry>>, AppError> {
+        let mut result = HashMap::new();
+        self.for_each_day_in_range(start_date, end_date, |date, entries| {
+            result.insert(date_key(&date), entries);
+            Ok(())
+        })?;
+
+        Ok(result)
+    }
+
+    /// 统计时间范围内的日志条数，起止日期都包含在内，逐天读取不会同时持有所有日志
+    pub fn count_entries_in_date_range(
+        &self,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<usize, AppError> {
+        let mut count = 0;
+        self.for_each_day_in_range(start_date, end_date, |_, entries| {
+            count += entries.len();
+            Ok(())
+        })?;
+
+        Ok(count)
+    }
+
+    /// 按日期从早到晚逐天读取时间范围内的日志并交给回调处理，起止日期都包含在内
+    ///
+    /// 每次只读取一天，适合处理较长的时间范围而不把所有日志同时放在内存中；
+    /// 没有日志的日期会被跳过，回调返回错误时停止读取
+    pub fn for_each_day_in_range<F>(
+        &self,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+        mut f: F,
+    ) -> Result<(), AppError>
+    where
+        F: FnMut(NaiveDate, Vec<LogEntry>) -> Result<(), AppError>,
+    {
+        for date in start_date.iter_days().take_while(|date| date <= end_date) {
+            let entries = self.get_entries_for_date(&date)?;
+            if !entries.is_empty() {
+                f(date, entries)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 读取所有日期的日志记录，按日期从新到旧排列
+    fn load_all_entries(&self) -> Result<Vec<DailyFile>, AppError> {
+        Ok(self
+            .storage
+            .load_all()?
+            .into_iter()
+            .map(|(date, entries)| DailyFile {
+                date: date_key(&date),
+                entries,
+            })
+            .collect())
+    }
+
+    /// 在所有日志文件中搜索包含关键字的记录
+    ///
+    /// 同时匹配日志内容和标签，单个文件损坏时跳过并记录警告；存储后端支持时先用索引筛选候选记录
+    pub fn search_entries(
+        &self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        let query = query.trim();
+        let mut results = Vec::new();
+
+        if query.is_empty() {
+            return Ok(results);
+        }
+
+        for (date, entries) in self.storage.search_candidates(query)? {
+            let date = date_key(&date);
+            for entry in entries {
+                let is_match = text_matches(&entry.content, query, &options)
+                    || entry
+                        .tags
+                        .iter()
+                        .any(|tag| text_matches(tag, query, &options));
+
+                if is_match {
+                    results.push(SearchResult {
+                        date: date.clone(),
+                        entry,
+                    });
+                }
+            }
+        }
+
+        log::info!("搜索 \"{}\" 共找到 {} 条记录", query, results.len());
+
+        Ok(results)
+    }
+
+    /// 在阻塞线程池中执行文件读取，避免占用异步运行时的工作线程
+    ///
+    /// 只用于读取，后台任务使用相同设置的新管理器
+    async fn run_blocking<T, F>(&self, read: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&LogManager) -> Result<T, AppError> + Send + 'static,
+    {
+        let manager = LogManager::new(self.settings.clone());
+        tokio::task::spawn_blocking(move || read(&manager))
+            .await
+            .map_err(|e| AppError::LogManagerError(Message::BackgroundReadFailed(&e).to_string()))?
+    }
+
+    /// `get_entries_in_date_range` 的异步版本，供异步命令调用
+    pub async fn get_entries_in_date_range_async(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<HashMap<String, Vec<LogEntry>>, AppError> {
+        self.run_blocking(move |manager| manager.get_entries_in_date_range(&start_date, &end_date))
+            .await
+    }
+
+    /// `count_entries_in_date_range` 的异步版本，供异步命令调用
+    pub async fn count_entries_in_date_range_async(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<usize, AppError> {
+        self.run_blocking(move |manager| {
+            manager.count_entries_in_date_range(&start_date, &end_date)
+        })
+        .await
+    }
+
+    /// `get_log_files` 的异步版本，供异步命令调用
+    pub async fn get_log_files_async(&self) -> Result<Vec<String>, AppError> {
+        self.run_blocking(|manager| manager.get_log_files()).await
+    }
+
+    /// `search_entries` 的异步版本，供异步命令调用
+    pub async fn search_entries_async(
+        &self,
+        query: String,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>, AppError> {
+        self.run_blocking(move |manager| manager.search_entries(&query, options))
+            .await
+    }
+
+    /// 列出所有标签及其使用次数，按次数降序排列
+    ///
+    /// 同一条记录中重复的标签只计一次
+    pub fn list_tags(&self) -> Result<Vec<(String, usize)>, AppError> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        for daily in self.load_all_entries()? {
+            for entry in daily.entries {
+                let unique_tags: HashSet<&String> =
+                    entry.tags.iter().filter(|tag| !tag.is_empty()).collect();
+                for tag in unique_tags {
+                    *counts.entry(tag.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Ok(tags)
+    }
+
+    /// 列出所有来源及其使用次数，按次数降序排列，忽略空来源
+    pub fn list_sources(&self) -> Result<Vec<(String, usize)>, AppError> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        for daily in self.load_all_entries()? {
+            for entry in daily.entries {
+                let source = entry.source.trim();
+                if !source.is_empty() {
+                    *counts.entry(source.to_string()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut sources: Vec<(String, usize)> = counts.into_iter().collect();
+        sources.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Ok(sources)
+    }
+
+    /// 在所有日志中重命名标签，返回被修改的记录数
+    ///
+    /// 如果记录中已存在目标标签则合并去重，只重写发生变化的文件
+    pub fn rename_tag(&self, from: &str, to: &str) -> Result<usize, AppError> {
+        let from = from.trim();
+        let to = normalize_tag(to, self.settings.normalize_tags);
+        let to = to.as_str();
+
+        if from.is_empty() || to.is_empty() {
+            return Err(AppError::LogManagerError(Message::TagNameEmpty.to_string()));
+        }
+
+        if from == to {
+            return Ok(0);
+        }
+
+        let _lock = self.acquire_lock()?;
+        let mut modified = 0;
+
+        for mut daily in self.load_all_entries()? {
+            let mut is_file_changed = false;
+
+            for entry in daily.entries.iter_mut() {
+                if !entry.tags.iter().any(|tag| tag == from) {
+                    continue;
+                }
+
+                let mut renamed: Vec<String> = Vec::with_capacity(entry.tags.len());
+                for tag in entry.tags.drain(..) {
+                    let tag = if tag == from { to.to_string() } else { tag };
+                    if !renamed.contains(&tag) {
+                        renamed.push(tag);
+                    }
+                }
+                entry.tags = renamed;
+                self.changes.record_entry_change(&entry.id);
+
+                modified += 1;
+                is_file_changed = true;
+            }
+
+            if is_file_changed {
+                log::debug!("重命名标签，更新日期: {}", daily.date);
+                match NaiveDate::parse_from_str(&daily.date, "%Y-%m-%d") {
+                    Ok(date) => self.write_day(&date, &daily.entries)?,
+                    Err(_) => log::warn!("跳过文件名不是日期的日志文件: {}.json", daily.date),
+                }
+            }
+        }
+
+        log::info!(
+            "标签 \"{}\" 已重命名为 \"{}\"，共修改 {} 条记录",
+            from,
+            to,
+            modified
+        );
+
+        Ok(modified)
+    }
+
+    /// 批量导入日志记录
+    ///
+    /// 按创建日期合并到对应的日志文件，与任意日期已有记录 ID 相同的记录会被跳过
+    pub fn import_entries(&self, entries: Vec<LogEntry>) -> Result<ImportReport, AppError> {
+        if !self.changes.is_dry_run() {
+            self.settings.ensure_log_dirs_exist()?;
+        }
+
+        let mut report = ImportReport::default();
+        let mut entries_by_date: BTreeMap<NaiveDate, Vec<LogEntry>> = BTreeMap::new();
+
+        for entry in entries {
+            match entry.local_date() {
+                Ok(date) => {
+                    entries_by_date.entry(date).or_default().push(entry);
+                }
+                Err(e) => report.errors.push(
+                    Message::InvalidCreatedAt {
+                        id: &entry.id,
+                        value: &entry.created_at,
+                        error: &e,
+                    }
+                    .to_string(),
+                ),
+            }
+        }
+
+        let _lock = self.acquire_lock()?;
+
+        // 记录可能被移到了其他日期，需要与所有已保存的记录比较 ID
+        let mut known_ids = self.all_entry_ids()?;
+
+        for (date, incoming) in entries_by_date {
+            let mut existing = self.read_day(&date)?;
+            let original_len = existing.len();
+
+            for entry in incoming {
+                if known_ids.insert(entry.id.clone()) {
+                    self.changes.record_entry_change(&entry.id);
+                    existing.push(entry);
+                    report.added += 1;
+                } else {
+                    report.skipped += 1;
+                }
+            }
+
+            if existing.len() != original_len {
+                self.write_day(&date, &existing)?;
+            }
+        }
+
+        log::info!(
+            "导入完成: 新增 {} 条，跳过 {} 条，失败 {} 条",
+            report.added,
+            report.skipped,
+            report.errors.len()
+        );
+
+        Ok(report)
+    }
+
+    /// 所有已保存记录的 ID，日志目录不存在时为空
+    fn all_entry_ids(&self) -> Result<HashSet<String>, AppError> {
+        if !Path::new(&self.settings.log_storage_dir).exists() {
+            return Ok(HashSet::new());
+        }
+
+        Ok(self
+            .storage
+            .load_all()?
+            .into_iter()
+            .flat_map(|(_, entries)| entries)
+            .map(|entry| entry.id)
+            .collect())
+    }
+
+    /// SQLite 存储的数据库文件路径
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite_path(&self) -> PathBuf {
+        Path::new(&self.settings.log_storage_dir).join(SQLITE_FILE_NAME)
+    }
+
+    /// 将每日日志文件和月度归档中的记录导入 SQLite 数据库
+    ///
+    /// 数据库中已有相同 ID 的记录会被跳过，可以重复执行；原有的 JSON 文件保持不变，
+    /// 导入后将设置中的存储方式改为 `sqlite` 即可使用数据库
+    #[cfg(feature = "sqlite")]
+    pub fn migrate_to_sqlite(&self) -> Result<ImportReport, AppError> {
+        if !self.changes.is_dry_run() {
+            self.settings.ensure_log_dirs_exist()?;
+        }
+
+        let _lock = self.acquire_lock()?;
+
+        let source = self.daily_storage();
+        let target = SqliteStorage::new(self.sqlite_path(), self.changes.clone());
+        let mut report = ImportReport::default();
+
+        for (date, incoming) in source.load_all()? {
+            let mut existing = target.read_day(&date)?;
+            let mut known_ids: HashSet<String> =
+                existing.iter().map(|entry| entry.id.clone()).collect();
+            let original_len = existing.len();
+
+            for entry in incoming {
+                if known_ids.insert(entry.id.clone()) {
+                    self.changes.record_entry_change(&entry.id);
+                    existing.push(entry);
+                    report.added += 1;
+                } else {
+                    report.skipped += 1;
+                }
+            }
+
+            if existing.len() != original_len {
+                target.write_day(&date, &existing)?;
+            }
+        }
+
+        log::info!(
+            "已导入 {} 条记录到 SQLite 数据库，跳过 {} 条",
+            report.added,
+            report.skipped
+        );
+
+        Ok(report)
+    }
+
+    /// 统计日期范围内的日志活跃度，起止日期都包含在内
+    pub fn compute_stats(
+        &self,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<LogStats, AppError> {
+        if start_date > end_date {
+            return Err(AppError::LogManagerError(
+                Message::StartAfterEnd.to_string(),
+            ));
+        }
+
+        let mut stats = LogStats {
+            start_date: start_date.format("%Y-%m-%d").to_string(),
+            end_date: end_date.format("%Y-%m-%d").to_string(),
+            ..LogStats::default()
+        };
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        let mut source_counts: HashMap<String, usize> = HashMap::new();
+
+        for date in start_date.iter_days().take_while(|date| date <= end_date) {
+            let entries = self.get_entries_for_date(&date)?;
+
+            for entry in &entries {
+                let unique_tags: HashSet<&String> =
+                    entry.tags.iter().filter(|tag| !tag.is_empty()).collect();
+                for tag in unique_tags {
+                    *tag_counts.entry(tag.clone()).or_insert(0) += 1;
+                }
+                *source_counts.entry(entry.source.clone()).or_insert(0) += 1;
+                stats.total_words += entry.word_count();
+                stats.total_chars += entry.char_count();
+            }
+
+            let day = CountItem {
+                name: date.format("%Y-%m-%d").to_string(),
+                count: entries.len(),
+            };
+            if day.count > 0 {
+                stats.active_days += 1;
+                // 数量相同时取较早的一天
+                if stats
+                    .busiest_day
+                    .as_ref()
+                    .is_none_or(|busiest| day.count > busiest.count)
+                {
+                    stats.busiest_day = Some(day.clone());
+                }
+            }
+            stats.total_entries += day.count;
+            stats.total_days += 1;
+            stats.daily_counts.push(day);
+        }
+
+        stats.tag_counts = sort_count_items(tag_counts);
+        stats.source_counts = sort_count_items(source_counts);
+
+        Ok(stats)
+    }
+
+    /// 导出日期范围内的日志为指定格式的文本
+    pub fn export_entries(
+        &self,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+        format: ExportFormat,
+    ) -> Result<String, AppError> {
+        let logs = self.get_entries_in_date_range(start_date, end_date)?;
+
+        // 按日期升序输出
+        let mut dates: Vec<&String> = logs.keys().collect();
+        dates.sort();
+
+        let days: Vec<(&str, &[LogEntry])> = dates
+            .into_iter()
+            .map(|date| (date.as_str(), logs[date].as_slice()))
+            .collect();
+
+        match format {
+            ExportFormat::Csv => export_csv(&days),
+            ExportFormat::Markdown => Ok(export_markdown(start_date, end_date, &days)),
+        }
+    }
+}
+
+/// 按创建时间排序日志，时间相同时按 ID 排序保证结果稳定
+///
+/// 无法解析创建时间的记录始终排在最后
+fn sort_entries(entries: &mut [LogEntry], order: SortOrder) {
+    entries.sort_by(|a, b| {
+        let time_a = DateTime::parse_from_rfc3339(&a.created_at).ok();
+        let time_b = DateTime::parse_from_rfc3339(&b.created_at).ok();
+
+        match (time_a, time_b) {
+            (Some(time_a), Some(time_b)) => {
+                let ordering = time_a.cmp(&time_b).then_with(|| a.id.cmp(&b.id));
+                match order {
+                    SortOrder::Asc => ordering,
+                    SortOrder::Desc => ordering.reverse(),
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.id.cmp(&b.id),
+        }
+    });
+}
+
+/// 按优先级从高到低排列日志，优先级相同的保持原有顺序
+pub fn sort_entries_by_priority(entries: &mut [LogEntry]) {
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.priority_level()));
+}
+
+/// 规范化单个标签：去掉首尾空白和开头的 `#`，`lowercase` 为 true 时转为小写
+pub fn normalize_tag(tag: &str, lowercase: bool) -> String {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    if lowercase {
+        tag.to_lowercase()
+    } else {
+        tag.to_string()
+    }
+}
+
+/// 规范化标签列表：逐个规范化后去掉空标签和重复的标签，保持原有顺序
+pub fn normalize_tags(tags: Vec<String>, lowercase: bool) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = normalize_tag(&tag, lowercase);
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// 规范化日志内容用于重复比较：去掉首尾空白，连续空白合并为一个空格
+fn normalize_content(content: &str) -> String {
+    content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 按数量降序、名称升序排列统计项
+fn sort_count_items(counts: HashMap<String, usize>) -> Vec<CountItem> {
+    let mut items: Vec<CountItem> = counts
+        .into_iter()
+        .map(|(name, count)| CountItem { name, count })
+        .collect();
+    items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    items
+}
+
+/// 由提交哈希生成导入日志的 ID
+pub fn git_commit_entry_id(commit_id: &str) -> String {
+    format!("git-{}", commit_id)
+}
+
+/// 获取文件的最后修改时间 (RFC 3339)，无法获取时返回空字符串
+pub(crate) fn file_modified_time(path: &Path) -> String {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| DateTime::<Local>::from(modified).to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// 按文件名格式生成指定日期的日志文件相对路径
+fn render_file_name(pattern: &str, date: &NaiveDate) -> String {
+    pattern
+        .replace(FILE_NAME_YEAR, &format!("{:04}", date.year()))
+        .replace(FILE_NAME_MONTH, &format!("{:02}", date.month()))
+        .replace(FILE_NAME_DAY, &format!("{:02}", date.day()))
+}
+
+/// 按文件名格式从相对路径（以 `/` 分隔）中解析日期，不匹配时返回 None
+fn parse_file_name(pattern: &str, name: &str) -> Option<NaiveDate> {
+    let (mut year, mut month, mut day) = (None, None, None);
+    let mut pattern = pattern;
+    let mut name = name;
+
+    while !pattern.is_empty() {
+        if let Some(rest) = pattern.strip_prefix(FILE_NAME_YEAR) {
+            year = Some(take_digits(&mut name, 4)? as i32);
+            pattern = rest;
+        } else if let Some(rest) = pattern.strip_prefix(FILE_NAME_MONTH) {
+            month = Some(take_digits(&mut name, 2)?);
+            pattern = rest;
+        } else if let Some(rest) = pattern.strip_prefix(FILE_NAME_DAY) {
+            day = Some(take_digits(&mut name, 2)?);
+            pattern = rest;
+        } else {
+            let c = pattern.chars().next()?;
+            name = name.strip_prefix(c)?;
+            pattern = &pattern[c.len_utf8()..];
+        }
+    }
+
+    if !name.is_empty() {
+        return None;
+    }
+
+    NaiveDate::from_ymd_opt(year?, month?, day?)
+}
+
+/// 从文本开头取出指定位数的数字
+fn take_digits(text: &mut &str, len: usize) -> Option<u32> {
+    let digits = text.get(..len)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    *text = &text[len..];
+    digits.parse().ok()
+}
+
+/// 日志目录的扫描结果
+#[derive(Default)]
+struct StorageScan {
+    /// 每日日志文件及其日期
+    daily_files: Vec<(NaiveDate, PathBuf)>,
+    /// 不符合文件名格式的 JSON 文件
+    unrecognized: Vec<PathBuf>,
+}
+
+/// 递归查找符合文件名格式的每日日志文件
+///
+/// `relative` 为 `dir` 相对日志目录的路径（以 `/` 分隔），`depth` 为还可以进入的子目录层数。
+/// 不符合格式的 JSON 文件（隐藏文件除外）记录到 `unrecognized`
+fn collect_daily_files(
+    dir: &Path,
+    relative: &str,
+    depth: usize,
+    pattern: &str,
+    files: &mut Vec<(NaiveDate, PathBuf)>,
+    unrecognized: &mut Vec<PathBuf>,
+) -> Result<(), AppError> {
+    for entry_result in fs::read_dir(dir)? {
+        let path = match entry_result {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                log::error!("读取目录项失败: {}", e);
+                continue; // 跳过无法读取的项
+            }
+        };
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let relative_name = if relative.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", relative, name)
+        };
+
+        if path.is_dir() {
+            if depth > 0 && !name.starts_with('.') {
+                collect_daily_files(
+                    &path,
+                    &relative_name,
+                    depth - 1,
+                    pattern,
+                    files,
+                    unrecognized,
+                )?;
+            }
+        } else if path.is_file() && !name.ends_with(ARCHIVE_SUFFIX) {
+            match parse_file_name(pattern, &relative_name) {
+                Some(date) => {
+                    log::trace!("添加日志文件: {}", relative_name);
+                    files.push((date, path));
+                }
+                None => {
+                    log::debug!("跳过不符合文件名格式的文件: {}", relative_name);
+                    if name.ends_with(".json") && !name.starts_with('.') {
+                        unrecognized.push(path);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 从损坏的日志文件内容中尽量恢复记录，返回恢复的记录和丢弃内容的摘要
+///
+/// 按花括号配对截取数组中的每个顶层对象分别解析，字符串中的括号不计入配对；
+/// 格式化的文件中字符串不会跨行，遇到换行时视为字符串已结束，避免一处缺失引号影响后续记录
+fn salvage_entries(content: &str) -> (Vec<LogEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut dropped = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in content.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' || c == '\n' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                if depth == 0 {
+                    start = Some(index);
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth > 0 {
+                    continue;
+                }
+                if let Some(start) = start.take() {
+                    let object = &content[start..=index];
+                    match serde_json::from_str::<LogEntry>(object) {
+                        Ok(entry) => entries.push(entry),
+                        Err(e) => dropped.push(format!("{} ({})", preview_text(object), e)),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 文件被截断时最后一个对象不完整
+    if let Some(start) = start {
+        dropped.push(format!("{} (内容不完整)", preview_text(&content[start..])));
+    }
+
+    (entries, dropped)
+}
+
+/// 截取文本开头用于日志输出，换行替换为空格
+fn preview_text(text: &str) -> String {
+    let preview: String = text.chars().take(80).collect();
+    preview.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 日期在归档文件中的键 (YYYY-MM-DD)
+pub(crate) fn date_key(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// 读取月度归档文件，文件不存在时返回空映射
+fn read_archive(path: &Path) -> Result<BTreeMap<String, Vec<LogEntry>>, AppError> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// 原子地写入月度归档文件，归档为空时删除文件
+fn write_archive(path: &Path, archive: &BTreeMap<String, Vec<LogEntry>>) -> Result<(), AppError> {
+    if archive.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    write_json_atomic(path, archive)
+}
+
+/// 原子地写入日志文件，文件名格式包含子目录时先创建目录
+fn write_entries(file_path: &Path, entries: &[LogEntry]) -> Result<(), AppError> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_json_atomic(file_path, entries)
+}
+
+/// 将数据序列化为稳定的 JSON 文本
+///
+/// 两个空格缩进，字段按结构体中定义的顺序输出，无法识别的字段和归档中的日期按名称排序，末尾带换行；
+/// 相同的数据总是得到相同的字节，日志目录纳入 Git 管理时差异只包含实际修改的内容
+pub fn to_stable_json<T: Serialize + ?Sized>(value: &T) -> Result<String, AppError> {
+    let mut content = serde_json::to_string_pretty(value)?;
+    content.push('\n');
+    Ok(content)
+}
+
+/// 以固定格式序列化时间戳：RFC 3339，秒的小数部分按需输出，
+/// 时区总是写成 `+08:00` 形式的偏移，UTC 也写成 `+00:00` 而不是 `Z`
+fn serialize_timestamp<S: serde::Serializer>(
+    timestamp: &Option<DateTime<Local>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match timestamp {
+        Some(timestamp) => {
+            serializer.serialize_str(&timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 将数据序列化为稳定的 JSON 后原子地写入文件
+///
+/// 先写入同目录下的临时文件再重命名覆盖，避免写入中途崩溃导致文件损坏
+fn write_json_atomic<T: Serialize + ?Sized>(file_path: &Path, value: &T) -> Result<(), AppError> {
+    let content = to_stable_json(value)?;
+    let tmp_path = file_path.with_extension("json.tmp");
+
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, file_path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        AppError::from(e)
+    })?;
+
+    Ok(())
+}
+
+/// 从 JSON 数组解析待导入的日志记录
+///
+/// 逐条解析，格式错误的元素记录到错误列表而不是中断整个导入
+pub fn parse_entries_json(content: &str) -> Result<(Vec<LogEntry>, Vec<String>), AppError> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(content)?;
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        match serde_json::from_value::<LogEntry>(value) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(
+                Message::ImportRecordInvalid {
+                    number: index + 1,
+                    error: &e,
+                }
+                .to_string(),
+            ),
+        }
+    }
+
+    Ok((entries, errors))
+}
+
+/// 从 CSV 解析待导入的日志记录，列格式与导出一致 (date,time,source,tags,content,id)，`id` 列可以省略
+///
+/// 逐行解析，格式错误的行记录到错误列表而不是中断整个导入
+pub fn parse_entries_csv(content: &str) -> (Vec<LogEntry>, Vec<String>) {
+    // 列数由 `parse_csv_record` 检查，允许个别行省略末尾的 id 列
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        // 表头占第 1 行
+        let line = index + 2;
+        match record {
+            Ok(record) => match parse_csv_record(&record) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => errors.push(Message::CsvLineError { line, error: &e }.to_string()),
+            },
+            Err(e) => errors.push(Message::CsvLineUnreadable { line, error: &e }.to_string()),
+        }
+    }
+
+    (entries, errors)
+}
+
+/// 将一行 CSV 记录转换为日志记录
+fn parse_csv_record(record: &csv::StringRecord) -> Result<LogEntry, String> {
+    // 旧版本导出的文件没有 id 列
+    if record.len() != CSV_HEADER.len() && record.len() != CSV_HEADER.len() - 1 {
+        return Err(Message::CsvColumnCount {
+            legacy: CSV_HEADER.len() - 1,
+            current: CSV_HEADER.len(),
+            actual: record.len(),
+        }
+        .to_string());
+    }
+
+    let date = NaiveDate::parse_from_str(&record[0], "%Y-%m-%d").map_err(|e| {
+        Message::CsvInvalidDate {
+            value: &record[0],
+            error: &e,
+        }
+        .to_string()
+    })?;
+    let time = if record[1].is_empty() {
+        chrono::NaiveTime::MIN
+    } else {
+        chrono::NaiveTime::parse_from_str(&record[1], "%H:%M:%S").map_err(|e| {
+            Message::CsvInvalidTime {
+                value: &record[1],
+                error: &e,
+            }
+            .to_string()
+        })?
+    };
+    let date_time = date
+        .and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| {
+            Message::CsvNonexistentLocalTime {
+                date: &record[0],
+                time: &record[1],
+            }
+            .to_string()
+        })?;
+
+    let content = record[4].to_string();
+    if content.trim().is_empty() {
+        return Err(Message::EntryContentEmpty.to_string());
+    }
+
+    let tags = record[3]
+        .split(CSV_TAG_SEPARATOR)
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    let source = if record[2].is_empty() {
+        "import".to_string()
+    } else {
+        record[2].to_string()
+    };
+
+    let mut entry = LogEntry::new_with_datetime(content, source, tags, date_time);
+    // 保留原来的 ID，重复导入同一文件时可以跳过已导入的记录
+    if let Some(id) = record.get(5).map(str::trim).filter(|id| !id.is_empty()) {
+        entry.id = id.to_string();
+    }
+
+    Ok(entry)
+}
+
+/// 获取日志记录的时间 (HH:MM:SS)，无法解析时返回空字符串
+fn format_entry_time(entry: &LogEntry) -> String {
+    entry
+        .created_at_local()
+        .map(|time| time.format("%H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// 将日志导出为 CSV，引号、逗号和换行由 csv 库负责转义
+fn export_csv(days: &[(&str, &[LogEntry])]) -> Result<String, AppError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer
+        .write_record(CSV_HEADER)
+        .map_err(|e| AppError::LogManagerError(Message::CsvWriteFailed(&e).to_string()))?;
+
+    for (date, entries) in days {
+        for entry in entries.iter() {
+            writer
+                .write_record([
+                    *date,
+                    &format_entry_time(entry),
+                    &entry.source,
+                    &entry.tags.join(CSV_TAG_SEPARATOR),
+                    &entry.content,
+                    &entry.id,
+                ])
+                .map_err(|e| AppError::LogManagerError(Message::CsvWriteFailed(&e).to_string()))?;
+        }
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::LogManagerError(Message::CsvWriteFailed(&e).to_string()))?;
+
+    String::from_utf8(bytes)
+        .map_err(|e| AppError::LogManagerError(Message::CsvNotUtf8(&e).to_string()))
+}
+
+/// 将日志导出为 Markdown
+fn export_markdown(
+    start_date: &NaiveDate,
+    end_date: &NaiveDate,
+    days: &[(&str, &[LogEntry])],
+) -> String {
+    let mut output = format!(
+        "# 工作日志（{} 至 {}）\n\n",
+        start_date.format("%Y-%m-%d"),
+        end_date.format("%Y-%m-%d")
+    );
+
+    for (date, entries) in days {
+        output.push_str(&format!("## {}\n\n", date));
+
+        for entry in entries.iter() {
+            let time = format_entry_time(entry);
+            let time_str = if time.is_empty() {
+                String::new()
+            } else {
+                format!("`{}` ", time)
+            };
+            let tag_str = if entry.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", entry.tags.join(", "))
+            };
+            let status_str = entry
+                .status
+                .map(|status| format!("**{}** ", status.label()))
+                .unwrap_or_default();
+
+            output.push_str(&format!(
+                "- {}{}{}{}\n",
+                time_str, status_str, entry.content, tag_str
+            ));
+            for attachment in &entry.attachments {
+                output.push_str(&format!(
+                    "  - 附件: [{}](<{}>)\n",
+                    attachment_name(attachment),
+                    attachment
+                ));
+            }
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// 判断文本是否匹配搜索关键字
+fn text_matches(text: &str, query: &str, options: &SearchOptions) -> bool {
+    let (text, query) = if options.case_sensitive {
+        (text.to_string(), query.to_string())
+    } else {
+        (text.to_lowercase(), query.to_lowercase())
+    };
+
+    if !options.whole_word {
+        return text.contains(&query);
+    }
+
+    // 全词匹配：要求匹配位置前后均不是字母、数字或下划线
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    text.match_indices(&query).any(|(start, matched)| {
+        let end = start + matched.len();
+        let before_ok = !text[..start].chars().next_back().is_some_and(is_word_char);
+        let after_ok = !text[end..].chars().next().is_some_and(is_word_char);
+        before_ok && after_ok
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    /// 使用临时目录作为日志存储目录的管理器
+    fn test_manager() -> (TempDir, LogManager) {
+        let dir = TempDir::new().unwrap();
+        let settings = Settings {
+            log_storage_dir: dir.path().to_string_lossy().to_string(),
+            log_output_dir: dir.path().join("output").to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+        (dir, LogManager::new(settings))
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// 指定日期本地时间 `hour` 点创建的记录
+    fn entry_at(content: &str, day: NaiveDate, hour: u32) -> LogEntry {
+        let created_at = Local
+            .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+            .unwrap();
+        LogEntry::new_with_datetime(
+            content.to_string(),
+            "manual".to_string(),
+            Vec::new(),
+            created_at,
+        )
+    }
+
+    #[test]
+    fn reads_do_not_create_lock_file() {
+        let (dir, manager) = test_manager();
+
+        let entries = manager.get_entries_for_date(&date(2024, 1, 1)).unwrap();
+
+        assert!(entries.is_empty());
+        assert!(!dir.path().join(LOCK_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn corrupt_daily_file_is_recovered() {
+        let (dir, manager) = test_manager();
+        let day = date(2024, 1, 2);
+        manager.add_entry(entry_at("第一条", day, 9)).unwrap();
+        manager.add_entry(entry_at("第二条", day, 10)).unwrap();
+
+        // 截断文件，只保留第一条完整的记录
+        let path = manager.get_log_file_path(&day);
+        let content = fs::read_to_string(&path).unwrap();
+        let cut = content.find("第二条").unwrap();
+        fs::write(&path, &content[..cut]).unwrap();
+
+        let entries = manager.get_entries_for_date(&day).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "第一条");
+        assert!(dir.path().join("2024-01-02.json.corrupt").exists());
+
+        // 恢复后可以继续写入和读取
+        manager.add_entry(entry_at("第三条", day, 11)).unwrap();
+        let contents: Vec<_> = manager
+            .get_entries_for_date(&day)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, ["第一条", "第三条"]);
+
+        // 两条完整记录之间夹着一个格式错误的对象，前后两条都能恢复，原文件原样保留
+        let day = date(2024, 1, 3);
+        let before = serde_json::to_string_pretty(&entry_at("之前的记录", day, 9)).unwrap();
+        let after = serde_json::to_string_pretty(&entry_at("之后的记录", day, 11)).unwrap();
+        let original = format!(
+            "[\n{},\n{{\"id\": \"broken\", \"content\": , \"source\": \"manual\"}},\n{}\n]",
+            before, after
+        );
+        let path = manager.get_log_file_path(&day);
+        fs::write(&path, &original).unwrap();
+
+        let contents: Vec<_> = manager
+            .get_entries_for_date(&day)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, ["之前的记录", "之后的记录"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("2024-01-03.json.corrupt")).unwrap(),
+            original
+        );
+        assert_eq!(manager.get_entries_for_date(&day).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn for_each_day_in_range_visits_days_in_order() {
+        let (_dir, manager) = test_manager();
+        for day in [date(2024, 3, 5), date(2024, 2, 28), date(2024, 3, 1)] {
+            manager.add_entry(entry_at("记录", day, 9)).unwrap();
+        }
+        // 范围之外的日期不会被读取
+        manager
+            .add_entry(entry_at("范围外", date(2024, 3, 6), 9))
+            .unwrap();
+
+        let mut visited = Vec::new();
+        manager
+            .for_each_day_in_range(&date(2024, 2, 27), &date(2024, 3, 5), |day, entries| {
+                visited.push((day, entries.len()));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            visited,
+            [
+                (date(2024, 2, 28), 1),
+                (date(2024, 3, 1), 1),
+                (date(2024, 3, 5), 1)
+            ]
+        );
+        assert_eq!(
+            manager
+                .count_entries_in_date_range(&date(2024, 2, 27), &date(2024, 3, 5))
+                .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn reimporting_json_and_csv_skips_existing_entries() {
+        let (_dir, source) = test_manager();
+        let first = entry_at("写周报, 包含 \"引号\"", date(2024, 4, 1), 9);
+        let second = entry_at("评审代码", date(2024, 4, 2), 14);
+        source.add_entry(first.clone()).unwrap();
+        source.add_entry(second.clone()).unwrap();
+        let json = serde_json::to_string(&[first, second]).unwrap();
+        let csv = source
+            .export_entries(&date(2024, 4, 1), &date(2024, 4, 2), ExportFormat::Csv)
+            .unwrap();
+
+        let (_dir, target) = test_manager();
+        let (entries, errors) = parse_entries_json(&json).unwrap();
+        assert!(errors.is_empty());
+        let report = target.import_entries(entries).unwrap();
+        assert_eq!((report.added, report.skipped), (2, 0));
+
+        // 同一个 JSON 和导出的 CSV 再次导入时全部跳过
+        let (entries, _) = parse_entries_json(&json).unwrap();
+        let report = target.import_entries(entries).unwrap();
+        assert_eq!((report.added, report.skipped), (0, 2));
+        let (entries, errors) = parse_entries_csv(&csv);
+        assert!(errors.is_empty());
+        let report = target.import_entries(entries).unwrap();
+        assert_eq!((report.added, report.skipped), (0, 2));
+    }
+
+    #[test]
+    fn import_skips_ids_stored_on_other_dates() {
+        let (_dir, manager) = test_manager();
+        let entry = entry_at("跨日期的记录", date(2024, 4, 1), 9);
+        manager.add_entry(entry.clone()).unwrap();
+
+        // 同一 ID 的记录改到了另一天
+        let mut moved = entry;
+        moved.created_at = entry_at("", date(2024, 4, 3), 9).created_at;
+        let report = manager.import_entries(vec![moved]).unwrap();
+
+        assert_eq!((report.added, report.skipped), (0, 1));
+        assert!(manager
+            .get_entries_for_date(&date(2024, 4, 3))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn csv_without_id_column_still_imports() {
+        let csv = "date,time,source,tags,content\n2024-04-01,09:30:00,manual,a;b,旧格式\n";
+
+        let (entries, errors) = parse_entries_csv(csv);
+
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "旧格式");
+        assert_eq!(entries[0].tags, ["a", "b"]);
+    }
+
+    #[test]
+    fn entries_created_in_a_tight_loop_get_distinct_ids() {
+        let ids: HashSet<String> = (0..1000)
+            .map(|_| LogEntry::new("记录".to_string(), "manual".to_string(), Vec::new()).id)
+            .collect();
+
+        assert_eq!(ids.len(), 1000);
+    }
+
+    #[test]
+    fn legacy_numeric_ids_can_still_be_edited() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 1, 3);
+        let created_at = entry_at("", day, 9).created_at;
+        // 旧版本以毫秒时间戳作为 ID
+        fs::write(
+            manager.get_log_file_path(&day),
+            format!(
+                r#"[{{"id":"1704243600000","content":"旧记录","created_at":"{}","source":"manual","tags":[]}}]"#,
+                created_at
+            ),
+        )
+        .unwrap();
+
+        let mut entry = manager.get_entries_for_date(&day).unwrap().remove(0);
+        entry.content = "修改后的旧记录".to_string();
+        manager.update_entry(entry).unwrap();
+
+        let entries = manager.get_entries_for_date(&day).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "1704243600000");
+        assert_eq!(entries[0].content, "修改后的旧记录");
+
+        manager.delete_entry("1704243600000", &day, true).unwrap();
+        assert!(manager.get_entries_for_date(&day).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stable_json_is_byte_identical_across_writes() {
+        let mut entry = entry_at("稳定输出", date(2024, 5, 6), 8);
+        entry.timestamp = Some(
+            Utc.with_ymd_and_hms(2024, 5, 6, 0, 30, 0)
+                .unwrap()
+                .with_timezone(&Local),
+        );
+        entry.extra.insert("zeta".to_string(), serde_json::json!(1));
+        entry
+            .extra
+            .insert("alpha".to_string(), serde_json::json!({ "b": 2, "a": 1 }));
+        let entries = vec![entry];
+
+        let first = to_stable_json(&entries).unwrap();
+        let second = to_stable_json(&entries).unwrap();
+        assert_eq!(first.as_bytes(), second.as_bytes());
+
+        // 读回后再次写入也得到相同的字节
+        let reread: Vec<LogEntry> = serde_json::from_str(&first).unwrap();
+        assert_eq!(
+            to_stable_json(&reread).unwrap().as_bytes(),
+            first.as_bytes()
+        );
+
+        // 时区总是写成偏移量，本地时区为 UTC 时是 +00:00 而不是 Z
+        let timestamp = entries[0].timestamp.unwrap();
+        let expected = timestamp.format("%Y-%m-%dT%H:%M:%S%:z").to_string();
+        assert!(
+            first.contains(&format!("\"timestamp\": \"{}\"", expected)),
+            "{}",
+            first
+        );
+        assert!(!first.contains("Z\""), "{}", first);
+        assert!(first.find("\"alpha\"").unwrap() < first.find("\"zeta\"").unwrap());
+        assert!(first.ends_with("]\n"));
+    }
+
+    #[test]
+    fn csv_export_round_trips_commas_quotes_and_newlines() {
+        let (_dir, manager) = test_manager();
+        let mut entry = entry_at(
+            "第一行, 带逗号\n第二行 \"引号\", 还有 '单引号'",
+            date(2024, 6, 3),
+            16,
+        );
+        entry.source = "manual, import".to_string();
+        entry.tags = vec!["frontend".to_string(), "code \"review\"".to_string()];
+        manager.add_entry(entry.clone()).unwrap();
+
+        let csv = manager
+            .export_entries(&date(2024, 6, 3), &date(2024, 6, 3), ExportFormat::Csv)
+            .unwrap();
+        let (entries, errors) = parse_entries_csv(&csv);
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(entries.len(), 1);
+        let imported = &entries[0];
+        assert_eq!(imported.id, entry.id);
+        assert_eq!(imported.content, entry.content);
+        assert_eq!(imported.source, entry.source);
+        assert_eq!(imported.tags, entry.tags);
+        assert_eq!(imported.created_at_local(), entry.created_at_local());
+    }
+
+    #[test]
+    fn filtered_entries_match_tags_and_source() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 6, 10);
+        for (content, source, tags, hour) in [
+            ("修复前端问题", "manual", vec!["frontend", "bug"], 9),
+            ("前端评审", "git-commit", vec!["frontend"], 10),
+            ("后端接口", "manual", vec!["backend"], 11),
+        ] {
+            let mut entry = entry_at(content, day, hour);
+            entry.source = source.to_string();
+            entry.tags = tags.into_iter().map(str::to_string).collect();
+            manager.add_entry(entry).unwrap();
+        }
+        let contents = |filter: EntryFilter| -> Vec<String> {
+            manager
+                .get_filtered_entries(&day, &filter)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.content)
+                .collect()
+        };
+        let tags = |tags: &[&str]| Some(tags.iter().map(|tag| tag.to_string()).collect());
+
+        assert_eq!(
+            contents(EntryFilter {
+                tags: tags(&["frontend"]),
+                ..EntryFilter::default()
+            }),
+            ["修复前端问题", "前端评审"]
+        );
+        assert_eq!(
+            contents(EntryFilter {
+                tags: tags(&["bug", "backend"]),
+                ..EntryFilter::default()
+            }),
+            ["修复前端问题", "后端接口"]
+        );
+        assert_eq!(
+            contents(EntryFilter {
+                tags: tags(&["frontend", "bug"]),
+                match_all_tags: true,
+                ..EntryFilter::default()
+            }),
+            ["修复前端问题"]
+        );
+        assert_eq!(
+            contents(EntryFilter {
+                source: Some("manual".to_string()),
+                ..EntryFilter::default()
+            }),
+            ["修复前端问题", "后端接口"]
+        );
+        assert_eq!(
+            contents(EntryFilter {
+                tags: tags(&["frontend"]),
+                source: Some("manual".to_string()),
+                ..EntryFilter::default()
+            }),
+            ["修复前端问题"]
+        );
+        // 空的过滤条件不过滤任何记录
+        assert_eq!(
+            contents(EntryFilter {
+                tags: Some(Vec::new()),
+                source: Some(String::new()),
+                ..EntryFilter::default()
+            })
+            .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn entries_are_sorted_by_time_within_a_day() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 7, 1);
+        // 文件中的记录不按时间顺序排列，a 和 b 的时间相同
+        let entries: Vec<LogEntry> = [("c", 11), ("b", 9), ("d", 10), ("a", 9)]
+            .into_iter()
+            .map(|(id, hour)| LogEntry {
+                id: id.to_string(),
+                ..entry_at(id, day, hour)
+            })
+            .collect();
+        fs::write(
+            manager.get_log_file_path(&day),
+            to_stable_json(&entries).unwrap(),
+        )
+        .unwrap();
+
+        let ids = |order: SortOrder| -> Vec<String> {
+            manager
+                .get_entries_for_date_sorted(&day, order)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect()
+        };
+        assert_eq!(ids(SortOrder::Asc), ["a", "b", "d", "c"]);
+        assert_eq!(ids(SortOrder::Desc), ["c", "d", "b", "a"]);
+        assert_eq!(
+            manager
+                .get_entries_for_date(&day)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect::<Vec<_>>(),
+            ids(SortOrder::Asc)
+        );
+    }
+
+    #[test]
+    fn editing_the_time_keeps_the_entry_on_its_day() {
+        let (dir, manager) = test_manager();
+        let day = date(2024, 7, 2);
+        let entry = entry_at("晨会", day, 9);
+        manager.add_entry(entry.clone()).unwrap();
+
+        // 同一天内从 9 点改到 14 点
+        let later = entry_at("", day, 14);
+        manager
+            .update_entry(LogEntry {
+                created_at: later.created_at.clone(),
+                timestamp: None,
+                ..entry.clone()
+            })
+            .unwrap();
+
+        let entries = manager.get_entries_for_date(&day).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].created_at, later.created_at);
+        assert_eq!(entries[0].timestamp, later.timestamp);
+        let files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|file| file.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".json"))
+            .collect();
+        assert_eq!(files, ["2024-07-02.json"]);
+
+        // 修改日期时返回错误并提示使用 move_entry，原记录不变
+        let next_day = entry_at("", date(2024, 7, 3), 9);
+        let err = manager
+            .update_entry(LogEntry {
+                created_at: next_day.created_at,
+                ..entries[0].clone()
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("move_entry"), "{}", err);
+        assert_eq!(manager.get_entries_for_date(&day).unwrap().len(), 1);
+        assert!(manager
+            .get_entries_for_date(&date(2024, 7, 3))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn stats_count_entries_per_day_tag_and_source() {
+        let (_dir, manager) = test_manager();
+        let tagged = |content: &str, day: NaiveDate, source: &str, tags: &[&str]| {
+            let mut entry = entry_at(content, day, 10);
+            entry.source = source.to_string();
+            entry.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            entry
+        };
+        manager
+            .add_entry(tagged("fix login", date(2024, 3, 1), "manual", &["bug"]))
+            .unwrap();
+        manager
+            .add_entry(tagged("write docs", date(2024, 3, 3), "manual", &["docs"]))
+            .unwrap();
+        manager
+            .add_entry(tagged("fix crash", date(2024, 3, 3), "git", &["bug"]))
+            .unwrap();
+        manager
+            .add_entry(tagged("outside", date(2024, 3, 5), "manual", &["bug"]))
+            .unwrap();
+
+        let stats = manager
+            .compute_stats(&date(2024, 3, 1), &date(2024, 3, 4))
+            .unwrap();
+        assert_eq!(stats.total_entries, 3);
+        assert_eq!(stats.total_days, 4);
+        assert_eq!(stats.active_days, 2);
+        assert_eq!(stats.total_words, 6);
+        let daily: Vec<usize> = stats.daily_counts.iter().map(|day| day.count).collect();
+        assert_eq!(daily, [1, 0, 2, 0]);
+        assert_eq!(
+            stats.busiest_day,
+            Some(CountItem {
+                name: "2024-03-03".to_string(),
+                count: 2
+            })
+        );
+        let counts = |items: &[CountItem]| {
+            items
+                .iter()
+                .map(|item| (item.name.clone(), item.count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            counts(&stats.tag_counts),
+            [("bug".to_string(), 2), ("docs".to_string(), 1)]
+        );
+        assert_eq!(
+            counts(&stats.source_counts),
+            [("manual".to_string(), 2), ("git".to_string(), 1)]
+        );
+
+        // 没有日志的范围全部为零
+        let empty = manager
+            .compute_stats(&date(2024, 4, 1), &date(2024, 4, 2))
+            .unwrap();
+        assert_eq!(empty.total_entries, 0);
+        assert_eq!(empty.total_days, 2);
+        assert_eq!(empty.active_days, 0);
+        assert!(empty.busiest_day.is_none());
+        assert!(empty.tag_counts.is_empty());
+        assert!(empty.daily_counts.iter().all(|day| day.count == 0));
+
+        assert!(manager
+            .compute_stats(&date(2024, 3, 2), &date(2024, 3, 1))
+            .is_err());
+    }
+
+    #[test]
+    fn archived_days_stay_readable_and_editable() {
+        let (dir, manager) = test_manager();
+        let first = entry_at("january first", date(2024, 1, 5), 9);
+        manager.add_entry(first.clone()).unwrap();
+        manager
+            .add_entry(entry_at("january second", date(2024, 1, 20), 9))
+            .unwrap();
+        manager
+            .add_entry(entry_at("february", date(2024, 2, 1), 9))
+            .unwrap();
+        manager
+            .add_entry(entry_at("march", date(2024, 3, 1), 9))
+            .unwrap();
+
+        let archived = manager.archive_before(2024, 3).unwrap();
+        assert_eq!(
+            archived,
+            [("2024-01".to_string(), 2), ("2024-02".to_string(), 1)]
+        );
+        assert!(!manager.get_log_file_path(&date(2024, 1, 5)).exists());
+        assert!(dir.path().join("2024-01.archive.json").exists());
+        assert!(manager.get_log_file_path(&date(2024, 3, 1)).exists());
+
+        let entries = manager.get_entries_for_date(&date(2024, 1, 5)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, first.id);
+        assert_eq!(
+            manager.get_entries_for_date(&date(2024, 2, 1)).unwrap()[0].content,
+            "february"
+        );
+
+        // 归档后的记录仍可修改和删除
+        let mut edited = entries[0].clone();
+        edited.content = "january edited".to_string();
+        manager.update_entry(edited).unwrap();
+        assert_eq!(
+            manager.get_entries_for_date(&date(2024, 1, 5)).unwrap()[0].content,
+            "january edited"
+        );
+        let second = &manager.get_entries_for_date(&date(2024, 1, 20)).unwrap()[0];
+        manager
+            .delete_entry(&second.id, &date(2024, 1, 20), true)
+            .unwrap();
+        assert!(manager
+            .get_entries_for_date(&date(2024, 1, 20))
+            .unwrap()
+            .is_empty());
+
+        // 再次归档同一月份不会重复处理
+        assert_eq!(manager.archive_month(2024, 1).unwrap(), 0);
+        assert_eq!(
+            manager.get_entries_for_date(&date(2024, 1, 5)).unwrap()[0].content,
+            "january edited"
+        );
+    }
+
+    #[test]
+    fn duplicate_content_is_skipped_or_rejected() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 6, 3);
+        let original = entry_at("修复 登录问题", day, 9);
+        assert_eq!(
+            manager.add_entry_checked(original.clone(), false).unwrap(),
+            AddOutcome::Added
+        );
+
+        // 完全相同的内容
+        assert_eq!(
+            manager
+                .add_entry_checked(entry_at("修复 登录问题", day, 10), true)
+                .unwrap(),
+            AddOutcome::Skipped {
+                existing_id: original.id.clone()
+            }
+        );
+
+        // 只有空白不同的内容
+        let result = manager.add_entry_checked(entry_at("  修复   登录问题\n", day, 11), false);
+        assert!(
+            matches!(result, Err(AppError::DuplicateEntry(ref id)) if *id == original.id),
+            "{:?}",
+            result
+        );
+        assert_eq!(manager.get_entries_for_date(&day).unwrap().len(), 1);
+
+        // 其他日期或不同内容不算重复
+        assert_eq!(
+            manager
+                .add_entry_checked(entry_at("修复 登录问题", date(2024, 6, 4), 9), false)
+                .unwrap(),
+            AddOutcome::Added
+        );
+        assert_eq!(
+            manager
+                .add_entry_checked(entry_at("修复登录问题", day, 12), false)
+                .unwrap(),
+            AddOutcome::Added
+        );
+        assert_eq!(manager.get_entries_for_date(&day).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn entries_near_midnight_land_on_the_local_day() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 6, 3);
+
+        // 同一时刻用与本地相差若干小时的时区记录，按该时区看已经是另一天
+        for (hour, minute, shift_hours) in [(23, 30, 5), (0, 30, -5)] {
+            let local = local_datetime(day.and_hms_opt(hour, minute, 0).unwrap());
+            let offset = chrono::FixedOffset::east_opt(
+                local.offset().local_minus_utc() + shift_hours * 3600,
+            )
+            .unwrap();
+            let shifted = local.with_timezone(&offset);
+            assert_ne!(shifted.date_naive(), day);
+
+            let mut entry = entry_at("深夜记录", day, 12);
+            entry.created_at = shifted.to_rfc3339();
+            entry.timestamp = None;
+            assert_eq!(entry.local_date().unwrap(), day);
+
+            manager.add_entry(entry.clone()).unwrap();
+            let entries = manager.get_entries_for_date(&day).unwrap();
+            assert!(entries.iter().any(|stored| stored.id == entry.id));
+            assert!(manager
+                .get_entries_for_date(&shifted.date_naive())
+                .unwrap()
+                .is_empty());
+        }
+
+        let dated = LogEntry::new_with_date(
+            "指定日期".to_string(),
+            "manual".to_string(),
+            Vec::new(),
+            day,
+        );
+        assert_eq!(dated.local_date().unwrap(), day);
+    }
+
+    #[test]
+    fn non_log_json_files_are_ignored() {
+        let (dir, manager) = test_manager();
+        manager
+            .add_entry(entry_at("deploy release", date(2024, 5, 2), 9))
+            .unwrap();
+
+        // 内容是合法的日志数组，但文件名不是日期
+        let stray = vec![entry_at("deploy notes", date(2024, 5, 3), 9)];
+        let stray_json = serde_json::to_string(&stray).unwrap();
+        for name in [
+            "notes.json",
+            "trash.json",
+            "2024-13-01.json",
+            "2024-5-3.json",
+        ] {
+            fs::write(dir.path().join(name), &stray_json).unwrap();
+        }
+
+        assert_eq!(manager.get_log_files().unwrap(), ["2024-05-02.json"]);
+
+        let results = manager
+            .search_entries("deploy", SearchOptions::default())
+            .unwrap();
+        let contents: Vec<&str> = results
+            .iter()
+            .map(|result| result.entry.content.as_str())
+            .collect();
+        assert_eq!(contents, ["deploy release"]);
+        assert!(dir.path().join("notes.json").exists());
+    }
+
+    /// 目录下所有文件的路径和内容
+    fn snapshot(dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+        let mut files = BTreeMap::new();
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(snapshot(&path));
+            } else {
+                files.insert(path.clone(), fs::read(&path).unwrap());
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn dry_run_leaves_the_directory_unchanged() {
+        let (dir, manager) = test_manager();
+        let mut january = entry_at("fix login", date(2024, 1, 5), 9);
+        january.tags = vec!["bug".to_string()];
+        manager.add_entry(january.clone()).unwrap();
+        manager
+            .add_entry(entry_at("write docs", date(2024, 2, 1), 9))
+            .unwrap();
+        let before = snapshot(dir.path());
+
+        let dry_run = LogManager::new(manager.settings.clone()).with_dry_run(true);
+        let check = |name: &str, result: Result<(), AppError>| {
+            result.unwrap();
+            assert!(!dry_run.take_plan().is_empty(), "{} 没有记录计划", name);
+            assert_eq!(snapshot(dir.path()), before, "{} 修改了目录", name);
+        };
+
+        check(
+            "delete",
+            dry_run.delete_entry(&january.id, &date(2024, 1, 5), false),
+        );
+        check(
+            "delete permanently",
+            dry_run.delete_entry(&january.id, &date(2024, 1, 5), true),
+        );
+        check("rename tag", dry_run.rename_tag("bug", "defect").map(drop));
+        check("archive", dry_run.archive_before(2024, 3).map(drop));
+        let imported = vec![entry_at("imported", date(2024, 4, 1), 9)];
+        check("import", dry_run.import_entries(imported).map(drop));
+
+        // diagnose --fix 预演时只诊断并列出计划的修复，输出目录缺失也不会被创建
+        let settings_path = dir.path().join("settings.json");
+        let settings = Settings {
+            log_output_dir: dir.path().join("reports").to_string_lossy().to_string(),
+            ..manager.settings.clone()
+        };
+        fs::write(&settings_path, serde_json::to_string(&settings).unwrap()).unwrap();
+        let before = snapshot(dir.path());
+        let report = crate::settings::diagnose_settings_file(settings_path, None);
+        assert!(!report.fixes().is_empty(), "diagnose --fix 没有计划的修复");
+        assert_eq!(snapshot(dir.path()), before, "diagnose --fix 修改了目录");
+
+        assert_eq!(
+            manager.get_entries_for_date(&date(2024, 1, 5)).unwrap()[0].tags,
+            ["bug"]
+        );
+    }
+
+    #[test]
+    fn rewrites_keep_order_ids_and_unknown_fields() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 7, 1);
+        let path = manager.get_log_file_path(&day);
+
+        // 文件中的顺序与创建时间无关，第一条带有当前版本不认识的字段
+        let mut evening = entry_at("evening", day, 18);
+        evening
+            .extra
+            .insert("mood".to_string(), serde_json::json!("good"));
+        let morning = entry_at("morning", day, 9);
+        let noon = entry_at("noon", day, 12);
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&[&evening, &morning, &noon]).unwrap(),
+        )
+        .unwrap();
+        let read_file = || -> Vec<serde_json::Value> {
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap()
+        };
+        let ids = |values: &[serde_json::Value]| -> Vec<String> {
+            values
+                .iter()
+                .map(|value| value["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let before = read_file();
+
+        let mut edited = morning.clone();
+        edited.content = "morning edited".to_string();
+        manager.update_entry(edited).unwrap();
+
+        let after = read_file();
+        assert_eq!(ids(&after), [evening.id.as_str(), &morning.id, &noon.id]);
+        assert_eq!(after[0], before[0]);
+        assert_eq!(after[2], before[2]);
+        assert_eq!(after[0]["mood"], "good");
+        assert_eq!(after[1]["content"], "morning edited");
+
+        manager.delete_entry(&noon.id, &day, false).unwrap();
+        let after_delete = read_file();
+        assert_eq!(ids(&after_delete), [evening.id.as_str(), &morning.id]);
+        assert_eq!(after_delete[0], before[0]);
+        assert_eq!(after_delete[1], after[1]);
+    }
+
+    #[test]
+    fn empty_content_is_rejected_and_content_is_trimmed() {
+        let (_dir, manager) = test_manager();
+        let day = date(2024, 8, 1);
+
+        for content in ["", "   ", " \n\t "] {
+            let result = manager.add_entry(entry_at(content, day, 9));
+            assert!(
+                matches!(result, Err(AppError::LogManagerError(ref message))
+                    if *message == Message::EntryContentEmpty.to_string()),
+                "{:?}: {:?}",
+                content,
+                result
+            );
+            assert!(manager
+                .add_entry_checked(entry_at(content, day, 9), true)
+                .is_err());
+        }
+        assert!(!manager.get_log_file_path(&day).exists());
+
+        manager
+            .add_entry(entry_at("  \n 修复登录问题\t ", day, 9))
+            .unwrap();
+        let entries = manager.get_entries_for_date(&day).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "修复登录问题");
+    }
+
+    #[test]
+    fn messy_tags_are_normalized() {
+        let messy = || {
+            ["Bug ", "bug", "#bug", "  ", "#", " Docs", "#docs"]
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            normalize_tags(messy(), false),
+            ["Bug", "bug", "Docs", "docs"]
+        );
+        assert_eq!(normalize_tags(messy(), true), ["bug", "docs"]);
+
+        // add_entry 按设置决定是否转为小写
+        for (lowercase, expected) in [
+            (false, vec!["Bug", "bug", "Docs", "docs"]),
+            (true, vec!["bug", "docs"]),
+        ] {
+            let (_dir, manager) = test_manager();
+            let manager = LogManager::new(Settings {
+                normalize_tags: lowercase,
+                ..manager.settings.clone()
+            });
+            let day = date(2024, 8, 2);
+            let mut entry = entry_at("整理标签", day, 9);
+            entry.tags = messy();
+            manager.add_entry(entry).unwrap();
+            assert_eq!(
+                manager.get_entries_for_date(&day).unwrap()[0].tags,
+                expected
+            );
+        }
     }
 }
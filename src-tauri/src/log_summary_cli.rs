@@ -1,10 +1,45 @@
 use crate::errors::AppError;
 use crate::log_manager::LogManager;
 use crate::settings::Settings;
-use crate::summary::{SummaryConfig, SummaryGenerator, SummaryType};
-use chrono::{Datelike, Local, NaiveDate};
+use crate::messages::Message;
+use crate::summary::{
+    GroupBy, SummaryConfig, SummaryFormat, SummaryGenerator, SummaryLogs, SummaryPeriod, SummaryType,
+};
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
 use colored::Colorize;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+
+/// 是否输出带颜色的文字
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// 标准输出是终端且未设置 `NO_COLOR` 时使用颜色
+    #[default]
+    Auto,
+    /// 始终使用颜色
+    Always,
+    /// 不使用颜色
+    Never,
+}
+
+impl ColorChoice {
+    /// 是否使用颜色，`auto` 时检查 `NO_COLOR` 环境变量和标准输出是否为终端
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                !no_color && io::stdout().is_terminal()
+            }
+        }
+    }
+
+    /// 按选择开启或关闭之后所有输出的颜色
+    pub fn apply(self) {
+        colored::control::set_override(self.enabled());
+    }
+}
 
 /// 日志摘要处理器
 pub struct LogSummaryCliHandler {
@@ -23,73 +58,58 @@ impl LogSummaryCliHandler {
         let summary_generator = SummaryGenerator::new(settings);
         
         // 创建默认的摘要配置（周摘要）
-        let now = Local::now().date_naive();
-        let title = format!("周工作总结（{} 至 {}）", 
-            now.checked_sub_days(chrono::Days::new(6)).unwrap().format("%Y-%m-%d"),
-            now.format("%Y-%m-%d")
-        );
-        
         let config = SummaryConfig {
             summary_type: SummaryType::Weekly,
-            start_date: Some(now.checked_sub_days(chrono::Days::new(6)).unwrap()),
-            end_date: Some(now),
-            title,
+            start_date: None,
+            end_date: None,
+            title: String::new(),
+            include_git: false,
+            git_repo_paths: Vec::new(),
+            format: SummaryFormat::Markdown,
+            group_by: GroupBy::Date,
         };
         
-        Self {
+        let mut handler = Self {
             log_manager,
             summary_generator,
             config,
-        }
+        };
+        handler.set_summary_type(SummaryType::Weekly);
+        handler
     }
     
     /// 设置摘要类型
     pub fn set_summary_type(&mut self, summary_type: SummaryType) {
         self.config.summary_type = summary_type;
         
+        // 自定义类型保持不变，外部需要设置日期范围和标题
+        let label = match summary_type {
+            SummaryType::Daily => "日",
+            SummaryType::Weekly => "周",
+            SummaryType::Monthly => "月度",
+            SummaryType::Quarterly => "季度",
+            SummaryType::Custom => return,
+        };
+        
         // 更新日期范围和标题
-        let now = Local::now().date_naive();
-        match summary_type {
-            SummaryType::Weekly => {
-                let start_date = now.checked_sub_days(chrono::Days::new(6)).unwrap();
-                self.config.start_date = Some(start_date);
-                self.config.end_date = Some(now);
-                self.config.title = format!("周工作总结（{} 至 {}）", 
-                    start_date.format("%Y-%m-%d"),
-                    now.format("%Y-%m-%d")
-                );
-            },
-            SummaryType::Monthly => {
-                let start_date = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap();
-                self.config.start_date = Some(start_date);
-                self.config.end_date = Some(now);
-                self.config.title = format!("月度工作总结（{} 至 {}）", 
-                    start_date.format("%Y-%m-%d"),
-                    now.format("%Y-%m-%d")
-                );
-            },
-            SummaryType::Quarterly => {
-                let quarter_month = (now.month() - 1) / 3 * 3 + 1;
-                let start_date = NaiveDate::from_ymd_opt(now.year(), quarter_month, 1).unwrap();
-                self.config.start_date = Some(start_date);
-                self.config.end_date = Some(now);
-                self.config.title = format!("季度工作总结（{} 至 {}）", 
-                    start_date.format("%Y-%m-%d"),
-                    now.format("%Y-%m-%d")
-                );
-            },
-            SummaryType::Custom => {
-                // 自定义类型保持不变，外部需要设置日期范围和标题
-            },
-        }
+        let (start_date, end_date) = summary_type.date_range(Local::now().date_naive(), false);
+        self.config.start_date = Some(start_date);
+        self.config.end_date = Some(end_date);
+        self.config.title = if start_date == end_date {
+            format!("{}工作总结（{}）", label, start_date.format("%Y-%m-%d"))
+        } else {
+            format!("{}工作总结（{} 至 {}）", 
+                label,
+                start_date.format("%Y-%m-%d"),
+                end_date.format("%Y-%m-%d")
+            )
+        };
     }
     
     /// 设置自定义日期范围
     pub fn set_custom_date_range(&mut self, start_date: NaiveDate, end_date: NaiveDate) -> Result<(), AppError> {
         if start_date > end_date {
-            return Err(AppError::SummaryError(
-                "开始日期不能晚于结束日期".to_string()
-            ));
+            return Err(AppError::SummaryError(Message::StartAfterEnd.to_string()));
         }
         
         self.config.summary_type = SummaryType::Custom;
@@ -114,22 +134,18 @@ impl LogSummaryCliHandler {
             AppError::SummaryError("未设置结束日期".to_string())
         )?;
         
-        // 获取日期范围内的日志
-        let logs = self.log_manager.get_entries_in_date_range(&start_date, &end_date)?;
+        // 只统计日期范围内的日志条数，生成摘要时再逐天读取
+        let entry_count = self.log_manager.count_entries_in_date_range(&start_date, &end_date)?;
         
         // 如果没有日志，返回错误
-        if logs.is_empty() {
-            return Err(AppError::SummaryError(
-                format!("在 {} 至 {} 期间没有找到日志记录", 
-                    start_date.format("%Y-%m-%d"),
-                    end_date.format("%Y-%m-%d")
-                )
-            ));
+        if entry_count == 0 {
+            return Err(no_logs_error(&start_date, &end_date));
         }
         
         println!("正在生成摘要，请稍候...");
         
         // 生成摘要
+        let logs = SummaryLogs::Range(SummaryPeriod { start_date, end_date });
         let summary = self.summary_generator
             .generate_summary(logs, self.config.clone())
             .await?;
@@ -144,6 +160,11 @@ impl LogSummaryCliHandler {
     
     /// 打印完整日志
     pub fn print_full_logs(&self) -> Result<(), AppError> {
+        self.write_full_logs(&mut io::stdout().lock())
+    }
+
+    /// 将完整日志写入 `out`，是否带颜色由 `ColorChoice::apply` 决定
+    pub fn write_full_logs<W: Write>(&self, out: &mut W) -> Result<(), AppError> {
         // 验证日期范围
         let start_date = self.config.start_date.ok_or_else(|| 
             AppError::SummaryError("未设置开始日期".to_string())
@@ -153,30 +174,18 @@ impl LogSummaryCliHandler {
             AppError::SummaryError("未设置结束日期".to_string())
         )?;
         
-        // 获取日期范围内的日志
-        let logs = self.log_manager.get_entries_in_date_range(&start_date, &end_date)?;
-        
-        // 如果没有日志，返回错误
-        if logs.is_empty() {
-            return Err(AppError::SummaryError(
-                format!("在 {} 至 {} 期间没有找到日志记录", 
-                    start_date.format("%Y-%m-%d"),
-                    end_date.format("%Y-%m-%d")
-                )
-            ));
-        }
-        
-        // 打印标题
-        println!("\n{}\n", self.config.title.bold());
-        
-        // 按日期排序
-        let mut dates: Vec<String> = logs.keys().cloned().collect();
-        dates.sort_by(|a, b| b.cmp(a)); // 日期降序排列
-        
-        // 打印每一天的日志
-        for date in dates {
-            if let Some(entries) = logs.get(&date) {
-                println!("{}", date.blue().bold());
+        // 从结束日期往前逐天读取并打印，第一次读到日志时打印标题
+        let mut has_logs = false;
+        let mut date = end_date;
+        while date >= start_date {
+            let entries = self.log_manager.get_entries_for_date(&date)?;
+            if !entries.is_empty() {
+                if !has_logs {
+                    writeln!(out, "\n{}\n", self.config.title.bold())?;
+                    has_logs = true;
+                }
+                
+                writeln!(out, "{}", date.format("%Y-%m-%d").to_string().blue().bold())?;
                 
                 for entry in entries {
                     let tag_str = if !entry.tags.is_empty() {
@@ -185,13 +194,85 @@ impl LogSummaryCliHandler {
                         String::new()
                     };
                     
-                    println!("- {}{}", entry.content, tag_str.yellow());
+                    writeln!(out, "- {}{}", entry.content, tag_str.yellow())?;
                 }
                 
-                println!();
+                writeln!(out)?;
+            }
+            
+            match date.pred_opt() {
+                Some(previous) => date = previous,
+                None => break,
             }
         }
         
+        // 如果没有日志，返回错误
+        if !has_logs {
+            return Err(no_logs_error(&start_date, &end_date));
+        }
+        
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// 日期范围内没有日志时的错误
+fn no_logs_error(start_date: &NaiveDate, end_date: &NaiveDate) -> AppError {
+    AppError::SummaryError(format!(
+        "在 {} 至 {} 期间没有找到日志记录",
+        start_date.format("%Y-%m-%d"),
+        end_date.format("%Y-%m-%d")
+    ))
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_manager::LogEntry;
+    use chrono::{Local, TimeZone};
+    use tempfile::TempDir;
+
+    /// 日志存储在临时目录、日期范围为 `day` 当天的处理器，当天有一条带标签的记录
+    fn handler_with_entry(day: NaiveDate) -> (TempDir, LogSummaryCliHandler) {
+        let dir = TempDir::new().unwrap();
+        let settings = Settings {
+            log_storage_dir: dir.path().to_string_lossy().to_string(),
+            log_output_dir: dir.path().join("output").to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+        let mut handler = LogSummaryCliHandler::new(settings);
+        handler.set_custom_date_range(day, day).unwrap();
+
+        let created_at = Local
+            .from_local_datetime(&day.and_hms_opt(10, 0, 0).unwrap())
+            .unwrap();
+        let entry = LogEntry::new_with_datetime(
+            "修复登录问题".to_string(),
+            "manual".to_string(),
+            vec!["bug".to_string()],
+            created_at,
+        );
+        handler.log_manager.add_entry(entry).unwrap();
+        (dir, handler)
+    }
+
+    fn full_logs(handler: &LogSummaryCliHandler) -> String {
+        let mut out = Vec::new();
+        handler.write_full_logs(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn color_never_writes_no_escape_sequences() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let (_dir, handler) = handler_with_entry(day);
+
+        // 颜色设置是全局的，在同一个测试中先确认 always 会输出转义序列
+        ColorChoice::Always.apply();
+        assert!(full_logs(&handler).contains("\x1b["));
+
+        ColorChoice::Never.apply();
+        let output = full_logs(&handler);
+        assert!(!output.contains("\x1b["), "{:?}", output);
+        assert!(output.contains("2024-03-01"));
+        assert!(output.contains("- 修复登录问题 [bug]"));
+    }
+}
@@ -0,0 +1,1707 @@
+//! 面向用户的界面文字
+//!
+//! 命令行输出、托盘菜单和返回给前端的错误信息通过 `Message` 生成，按设置中的 `language`
+//! 选择中文或英文。进程启动和设置更新时调用 `set_language`，`Message` 的 `Display`
+//! 使用当前语言；需要指定语言时使用 `Message::text`
+
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 中文界面
+pub const LANGUAGE_ZH: &str = "zh";
+
+/// 英文界面
+pub const LANGUAGE_EN: &str = "en";
+
+/// 支持的界面语言
+pub const LANGUAGES: [&str; 2] = [LANGUAGE_ZH, LANGUAGE_EN];
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    /// 中文，默认语言
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Language {
+    /// 由语言代码获取语言，不支持的代码使用中文
+    pub fn from_code(code: &str) -> Self {
+        if code.eq_ignore_ascii_case(LANGUAGE_EN) {
+            Language::En
+        } else {
+            Language::Zh
+        }
+    }
+
+    /// 语言代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Zh => LANGUAGE_ZH,
+            Language::En => LANGUAGE_EN,
+        }
+    }
+}
+
+/// 当前界面语言，0 为中文，1 为英文
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// 设置当前界面语言
+pub fn set_language(language: Language) {
+    let value = match language {
+        Language::Zh => 0,
+        Language::En => 1,
+    };
+    CURRENT_LANGUAGE.store(value, Ordering::Relaxed);
+}
+
+/// 当前界面语言
+pub fn current_language() -> Language {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::En,
+        _ => Language::Zh,
+    }
+}
+
+/// 界面文字，参数在生成文字时格式化
+pub enum Message<'a> {
+    // 错误信息
+    IoError(&'a dyn Display),
+    SerdeError(&'a dyn Display),
+    GitError(&'a dyn Display),
+    NetworkError(&'a dyn Display),
+    DatabaseError(&'a dyn Display),
+    DateParseError(&'a dyn Display),
+    TauriError(&'a dyn Display),
+    FsError(&'a dyn Display),
+    SettingsError(&'a dyn Display),
+    LogManagerError(&'a dyn Display),
+    SummaryError(&'a dyn Display),
+    DuplicateEntry(&'a dyn Display),
+    Cancelled,
+    StorageNotFoundHint(&'a dyn Display),
+    PermissionDeniedHint(&'a dyn Display),
+
+    // 托盘菜单
+    TrayTodayCount(usize),
+    TrayAddLog,
+    TrayAddFromClipboard,
+    TrayEnableShortcut(Option<&'a str>),
+    TraySettings,
+    TrayOpenMain,
+    TrayQuit,
+    ShortcutRegisterFailed {
+        shortcut: &'a str,
+        error: &'a dyn Display,
+    },
+
+    // 日志记录的字段名
+    LabelContent,
+    LabelSource,
+    LabelStatus,
+    LabelPriority,
+    LabelTags,
+    LabelAttachments,
+    LabelTime,
+    LabelDeletedAt,
+    ColumnDate,
+    ColumnTag,
+    ColumnSource,
+    ColumnCount,
+
+    // 命令行输入
+    CliError(&'a dyn Display),
+    SettingsPathIsDir(&'a dyn Display),
+    InvalidDate(&'a dyn Display),
+    InvalidMonth(&'a str),
+    StartAfterEnd,
+    UnsupportedSort(&'a str),
+    UnsupportedExportFormat(&'a str),
+    UnsupportedImportFormat(&'a str),
+    ContentMissing,
+    ContentEmpty,
+    ReadStdinFailed(&'a dyn Display),
+    ReadFileFailed(&'a dyn Display),
+    WriteFileFailed(&'a dyn Display),
+    EditNothingSpecified,
+
+    // 添加和查看
+    UsingStorageDir(&'a str),
+    CreateLogDirFailed(&'a dyn Display),
+    DuplicateSkipped(&'a str),
+    EntryAdded(&'a dyn Display),
+    NoEntriesOnDate(&'a dyn Display),
+    ListDate(&'a dyn Display),
+    ListCount(usize),
+    EntryNumber(usize),
+    NoEntriesWithStatus(&'a str),
+    EntriesWithStatus {
+        status: &'a str,
+        count: usize,
+    },
+    NoEntriesOnDateHint(&'a dyn Display),
+    EntryNotFoundOnDate {
+        date: &'a dyn Display,
+        id: &'a str,
+        candidates: &'a str,
+    },
+
+    // 修改和删除
+    EntryDone(&'a str),
+    PrioritySet {
+        priority: &'a str,
+        content: &'a str,
+    },
+    NoAttachments,
+    EntryUpdated(&'a str),
+    WillDelete {
+        permanent: bool,
+        content: &'a str,
+    },
+    DeletedPermanently(&'a str),
+    MovedToTrash(&'a str),
+    RestoreHint(&'a str),
+    TrashEmpty,
+    TrashCount(usize),
+    Restored {
+        date: &'a str,
+        content: &'a str,
+    },
+    TrashPurged(usize),
+
+    // 搜索和标签
+    SearchNoResults(&'a str),
+    SearchResults {
+        count: usize,
+        query: &'a str,
+    },
+    NoTags,
+    TagCount(usize),
+    WillRenameTag {
+        from: &'a str,
+        to: &'a str,
+        count: usize,
+    },
+    TagRenamed {
+        from: &'a str,
+        to: &'a str,
+        count: usize,
+    },
+
+    // 统计
+    StatsHeader {
+        start: &'a str,
+        end: &'a str,
+    },
+    StatsTotal(usize),
+    StatsActiveDays {
+        active: usize,
+        total: usize,
+    },
+    StatsDailyAverage(f64),
+    StatsBusiestDay {
+        date: &'a str,
+        count: usize,
+    },
+    StatsWords {
+        words: usize,
+        chars: usize,
+    },
+    StatsWordsPerEntry(f64),
+
+    // 导入导出、归档、备份和迁移
+    Exported(&'a dyn Display),
+    WillImport {
+        added: usize,
+        skipped: usize,
+    },
+    Imported {
+        added: usize,
+        skipped: usize,
+    },
+    ImportErrors(usize),
+    NothingToArchive(&'a dyn Display),
+    WillArchive {
+        month: &'a str,
+        days: usize,
+    },
+    Archived {
+        month: &'a str,
+        days: usize,
+    },
+    ArchivedMonths(usize),
+    BackedUp {
+        count: usize,
+        dest: &'a dyn Display,
+    },
+    WillMigrate {
+        added: usize,
+        skipped: usize,
+    },
+    Migrated {
+        added: usize,
+        skipped: usize,
+        path: &'a dyn Display,
+    },
+    MigrateHint(&'a str),
+
+    // 预演模式
+    DryRunHeader,
+    NoPlannedChanges,
+    AffectedEntries(usize),
+    ChangedFiles(usize),
+
+    // 桌面端命令
+    ArgDate,
+    ArgStartDate,
+    ArgEndDate,
+    InvalidDateArg {
+        label: &'a dyn Display,
+        error: &'a dyn Display,
+    },
+    InvalidTime(&'a dyn Display),
+    StartAfterEndDates {
+        start: &'a str,
+        end: &'a str,
+    },
+    NotificationTitle,
+    ClipboardEntryAdded(&'a str),
+    ClipboardEmpty,
+    AddEntryFailed,
+    ImportCommitsFailed,
+    NoDirectorySelected,
+
+    // 桌面端摘要生成
+    MissingSummaryType,
+    CustomSummaryNeedsStart,
+    CustomSummaryNeedsEnd,
+    DailySummaryTitle,
+    WeeklySummaryTitle,
+    MonthlySummaryTitle,
+    QuarterlySummaryTitle,
+    CustomSummaryTitle,
+    ComparisonSummaryTitle,
+    PeriodA,
+    PeriodB,
+    MissingSummaryRange,
+    NoEntriesInRange,
+    SummaryProcessing(usize),
+    SummaryStillGenerating(u64),
+    SummaryStartEventFailed,
+    GenerateSummaryAction,
+    RefineSummaryAction,
+    CompareSummaryAction,
+    ActionFailed(&'a dyn Display),
+    NoSummaryTask,
+    SummaryBackendUnavailable,
+
+    // 注册命令行工具
+    AppPathUnavailable,
+    HomeDirUnavailable,
+    CliExecutableNotFound(&'a str),
+    CliInstallNeedsAdmin(&'a str),
+    CliUninstallNeedsAdmin(&'a str),
+    BatchFileCreated {
+        path: &'a str,
+        dir: &'a str,
+    },
+    CreateBatchFileFailed,
+    RemoveBatchFileFailed,
+    CliRegisterUnsupported,
+    CliUnregisterUnsupported,
+
+    // 日志记录校验和导入
+    EntryContentEmpty,
+    InvalidCreatedAt {
+        id: &'a str,
+        value: &'a str,
+        error: &'a dyn Display,
+    },
+    ImportRecordInvalid {
+        number: usize,
+        error: &'a dyn Display,
+    },
+    CsvLineError {
+        line: usize,
+        error: &'a dyn Display,
+    },
+    CsvLineUnreadable {
+        line: usize,
+        error: &'a dyn Display,
+    },
+    CsvColumnCount {
+        legacy: usize,
+        current: usize,
+        actual: usize,
+    },
+    CsvInvalidDate {
+        value: &'a str,
+        error: &'a dyn Display,
+    },
+    CsvInvalidTime {
+        value: &'a str,
+        error: &'a dyn Display,
+    },
+    CsvNonexistentLocalTime {
+        date: &'a str,
+        time: &'a str,
+    },
+
+    // 配置诊断
+    DiagnoseConfigHeader,
+    DiagnoseProfile(&'a str),
+    DiagnoseConfigDir(&'a dyn Display),
+    DiagnoseSettingsFile(&'a dyn Display),
+    DiagnoseSettingsContent(&'a dyn Display),
+    DiagnoseChecksHeader,
+    DiagnoseLogFilesHeader,
+    UnrecognizedLogFiles {
+        count: usize,
+        pattern: &'a str,
+    },
+    FixFailed(&'a dyn Display),
+    FixApplied(&'a dyn Display),
+    FixPlanned(&'a dyn Display),
+    ConfigOk,
+    ProblemsFixed(usize),
+    ProblemsPreviewed(usize),
+    ProblemsFound(usize),
+    CheckNameProfile,
+    CheckNameSettingsFile,
+    CheckNameSettingsContent,
+    CheckNameStorageDir,
+    CheckNameOutputDir,
+    CheckNameStorage,
+    ProfileNameHint,
+    SettingsFileParsed,
+    SettingsFileParseFailed(&'a dyn Display),
+    SettingsFileFixHint,
+    SettingsFileMissing,
+    CreateSettingsFileHint,
+    SettingsValid,
+    FixSettingsHint,
+    StorageReadable {
+        backend: &'a str,
+        days: usize,
+    },
+    ReadLogsFailed(&'a dyn Display),
+    StorageHint,
+    DirMissing(&'a dyn Display),
+    CreateDirHint,
+    NotADir(&'a dyn Display),
+    NotADirHint,
+    DirWritable(&'a dyn Display),
+    DirNotWritable(&'a dyn Display),
+    DirPermissionHint,
+    FixCreateSettingsFile(&'a dyn Display),
+    FixCreateDir(&'a dyn Display),
+
+    // 摘要服务检查
+    CheckingBackend,
+    BackendAvailable,
+    BackendKind(&'a str),
+    BackendUrl(&'a str),
+    BackendModel(Option<&'a str>),
+    BackendLatency(u64),
+
+    // 修复配置
+    FixConfigStart,
+    SettingsLoaded,
+    SettingsRecreated(&'a dyn Display),
+    SettingsBackedUp(&'a dyn Display),
+    BackupSettingsFailed(&'a dyn Display),
+    SaveSettingsFailed(&'a dyn Display),
+    ConfigFixed(&'a str),
+    CreatingLogDir(&'a dyn Display),
+    CreatingOutputDir(&'a dyn Display),
+    CreateOutputDirFailed(&'a dyn Display),
+
+    // 日志管理
+    EntryNotFound(&'a str),
+    EntryOnOtherDate {
+        id: &'a str,
+        date: &'a dyn Display,
+        target: &'a dyn Display,
+    },
+    NoLogFileOnDate(&'a dyn Display),
+    TrashEntryNotFound(&'a str),
+    RestoreConflict {
+        date: &'a str,
+        id: &'a str,
+    },
+    UnsupportedStatus(&'a str),
+    PriorityOutOfRange {
+        min: u8,
+        max: u8,
+        value: u8,
+    },
+    AttachmentEmpty,
+    AttachmentNotFound(&'a str),
+    EntryHasNoAttachment {
+        id: &'a str,
+        attachment: &'a str,
+    },
+    TagNameEmpty,
+    ArchiveAction,
+    DailyStorageOnly {
+        action: &'a dyn Display,
+        backend: &'a str,
+    },
+    LockTimeout {
+        secs: u64,
+        error: &'a dyn Display,
+    },
+    StorageDirMissing(&'a dyn Display),
+    BackupDirIsStorageDir,
+    ArchiveFileUnreadable {
+        path: &'a dyn Display,
+        error: &'a dyn Display,
+    },
+    BackgroundReadFailed(&'a dyn Display),
+    CsvWriteFailed(&'a dyn Display),
+    CsvNotUtf8(&'a dyn Display),
+
+    // 摘要服务错误
+    NoSummaryBackend,
+    OllamaModelNotConfigured,
+    OllamaListFailed(&'a dyn Display),
+    OllamaListUnreadable(&'a dyn Display),
+    OllamaModelMissing(&'a str),
+    OllamaCallFailed(&'a dyn Display),
+    OllamaReturnedError(&'a dyn Display),
+    ExternalApiNotConfigured,
+    HttpClientFailed(&'a dyn Display),
+    ApiTimeout,
+    ApiConnectFailed,
+    DashscopeConnectFailed,
+    DashscopeCallFailed,
+    ExternalApiFailed {
+        status: &'a dyn Display,
+        detail: &'a str,
+    },
+    ApiAuthFailed(&'a str),
+    ApiForbidden,
+    ApiNotFound,
+    DashscopeNotFound,
+    ApiRateLimited,
+    ApiServerError(&'a dyn Display),
+    ApiStatusFailed {
+        status: &'a dyn Display,
+        body: &'a str,
+    },
+    ResponseUnreadable,
+    AnthropicKeyMissing,
+    AnthropicModelMissing,
+    AnthropicAuthFailed,
+    AnthropicRateLimited,
+    AnthropicFailed {
+        status: &'a dyn Display,
+        detail: &'a str,
+    },
+    ApiReturnedError(&'a dyn Display),
+    StreamReturnedError(&'a dyn Display),
+    ResponseNotJson {
+        error: &'a dyn Display,
+        snippet: &'a str,
+    },
+    UnrecognizedResponse(&'a str),
+    UnknownError,
+    NothingToRefine,
+    RefineInstructionEmpty,
+    RefineNeedsBackend,
+    CompareNeedsBackend,
+    NoEntriesInBothPeriods,
+
+    // Git 仓库、快捷键与提醒
+    GitRepoNotFound(&'a dyn Display),
+    ShortcutUnavailable {
+        shortcut: &'a str,
+        error: &'a dyn Display,
+    },
+    ReminderNoEntriesToday,
+
+    // 命令行摘要
+    UnsupportedSummaryFormat(&'a str),
+    UnsupportedGroupBy {
+        value: &'a str,
+        options: &'a str,
+    },
+    UnsupportedConflictPolicy {
+        value: &'a str,
+        options: &'a str,
+    },
+    UnsupportedSummaryType(&'a str),
+    UnsupportedSummaryLanguage {
+        value: &'a str,
+        options: &'a str,
+    },
+    CustomRangeNeedsStart,
+    CustomRangeNeedsDates,
+    SummarySavedTo(&'a dyn Display),
+    SummaryFile(&'a dyn Display),
+    SummaryFileExists,
+    SummaryLength {
+        words: usize,
+        chars: usize,
+    },
+    ComparingPeriods {
+        a: &'a str,
+        b: &'a str,
+    },
+    ComparisonSavedTo(&'a dyn Display),
+
+    // 配置信息
+    ConfigHeader,
+    ConfigProfile(&'a str),
+    ConfigStorageDir(&'a str),
+    ConfigStorageBackend(&'a dyn Display),
+    ConfigOutputDir(&'a str),
+    ConfigFilePattern(&'a str),
+    ConfigSummaryFilePattern(&'a str),
+    ConfigGitAuthor(&'a str),
+    ConfigGitEmail(&'a str),
+    ConfigShortcut {
+        shortcut: &'a str,
+        enabled: bool,
+    },
+    ConfigReminder(&'a str),
+    ConfigAutoOpen(bool),
+    ConfigUseOllama,
+    ConfigOllamaAddress(&'a str),
+    ConfigOllamaModel(&'a str),
+    ConfigOllamaOptions(&'a dyn Display),
+    ConfigRemoteApi,
+    ConfigApiProvider(&'a str),
+    ConfigApiUrl(&'a str),
+    ConfigModel(Option<&'a str>),
+    ConfigApiKey(&'a str),
+    ConfigTemperature(&'a dyn Display),
+    ConfigMaxTokens(u32),
+    ConfigSummaryLanguage {
+        code: &'a str,
+        name: Option<&'a str>,
+    },
+    ConfigEnvHeader,
+    ConfigEnvVar {
+        var: &'a str,
+        label: &'a str,
+        applied: bool,
+    },
+
+    // 标签设置
+    ConfigNormalizeTags(bool),
+
+    // 摘要记录设置
+    ConfigLogSummaries(bool),
+}
+
+impl Message<'_> {
+    /// 指定语言的文字
+    pub fn text(&self, language: Language) -> String {
+        LocalizedMessage(self, language).to_string()
+    }
+
+    fn write_zh(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Message::*;
+        match self {
+            IoError(e) => write!(f, "IO 错误: {}", e),
+            SerdeError(e) => write!(f, "序列化错误: {}", e),
+            GitError(e) => write!(f, "Git 错误: {}", e),
+            NetworkError(e) => write!(f, "网络请求错误: {}", e),
+            DatabaseError(e) => write!(f, "数据库错误: {}", e),
+            DateParseError(e) => write!(f, "日期解析错误: {}", e),
+            TauriError(e) => write!(f, "Tauri 错误: {}", e),
+            FsError(e) => write!(f, "文件系统错误: {}", e),
+            SettingsError(e) => write!(f, "设置错误: {}", e),
+            LogManagerError(e) => write!(f, "日志管理错误: {}", e),
+            SummaryError(e) => write!(f, "摘要生成错误: {}", e),
+            DuplicateEntry(id) => write!(f, "当天已存在相同内容的日志记录 (ID: {})", id),
+            Cancelled => write!(f, "操作已取消"),
+            StorageNotFoundHint(e) => write!(f, "日志目录不存在，请检查配置是否正确: {}", e),
+            PermissionDeniedHint(e) => {
+                write!(f, "没有足够权限访问日志目录，请检查权限设置: {}", e)
+            }
+
+            TrayTodayCount(count) => write!(f, "今日: {} 条", count),
+            TrayAddLog => write!(f, "添加日志"),
+            TrayAddFromClipboard => write!(f, "从剪贴板添加"),
+            TrayEnableShortcut(None) => write!(f, "启用快捷键"),
+            TrayEnableShortcut(Some(shortcut)) => write!(f, "启用快捷键 ({})", shortcut),
+            TraySettings => write!(f, "设置"),
+            TrayOpenMain => write!(f, "打开主窗口"),
+            TrayQuit => write!(f, "退出"),
+            ShortcutRegisterFailed { shortcut, error } => write!(
+                f,
+                "快捷键 {} 注册失败，可能格式无效或已被其他应用占用: {}",
+                shortcut, error
+            ),
+
+            LabelContent => write!(f, "内容"),
+            LabelSource => write!(f, "来源"),
+            LabelStatus => write!(f, "状态"),
+            LabelPriority => write!(f, "优先级"),
+            LabelTags => write!(f, "标签"),
+            LabelAttachments => write!(f, "附件"),
+            LabelTime => write!(f, "时间"),
+            LabelDeletedAt => write!(f, "删除时间"),
+            ColumnDate => write!(f, "日期"),
+            ColumnTag => write!(f, "标签"),
+            ColumnSource => write!(f, "来源"),
+            ColumnCount => write!(f, "条数"),
+
+            CliError(e) => write!(f, "错误: {}", e),
+            SettingsPathIsDir(path) => write!(f, "设置文件路径是一个目录: {}", path),
+            InvalidDate(e) => write!(f, "日期格式错误 (应为 YYYY-MM-DD): {}", e),
+            InvalidMonth(month) => write!(f, "月份格式错误，应为 YYYY-MM: {}", month),
+            StartAfterEnd => write!(f, "开始日期不能晚于结束日期"),
+            UnsupportedSort(sort) => write!(f, "不支持的排序方式: {}", sort),
+            UnsupportedExportFormat(format) => write!(f, "不支持的导出格式: {}", format),
+            UnsupportedImportFormat(format) => write!(f, "不支持的导入格式: {}", format),
+            ContentMissing => write!(
+                f,
+                "请提供日志内容，或使用 - 从标准输入读取、使用 --edit 打开编辑器"
+            ),
+            ContentEmpty => write!(f, "日志内容为空，已取消添加"),
+            ReadStdinFailed(e) => write!(f, "读取标准输入失败: {}", e),
+            ReadFileFailed(e) => write!(f, "读取文件失败: {}", e),
+            WriteFileFailed(e) => write!(f, "写入文件失败: {}", e),
+            EditNothingSpecified => write!(f, "请至少指定 --content、--source 或 --tags 中的一项"),
+
+            UsingStorageDir(dir) => write!(f, "信息: 使用日志存储目录: {}", dir),
+            CreateLogDirFailed(e) => write!(f, "创建日志目录失败: {}", e),
+            DuplicateSkipped(id) => {
+                write!(f, "⏭️ 当天已有相同内容的日志记录 (ID: {})，已跳过", id)
+            }
+            EntryAdded(path) => write!(f, "✅ 已添加日志记录到: {}", path),
+            NoEntriesOnDate(date) => write!(f, "📅 {} 没有任何日志记录", date),
+            ListDate(date) => write!(f, "📅 日期: {}", date),
+            ListCount(count) => write!(f, "📝 共有 {} 条日志记录:", count),
+            EntryNumber(number) => write!(f, "🔹 记录 #{}:", number),
+            NoEntriesWithStatus(status) => write!(f, "📋 没有状态为「{}」的记录", status),
+            EntriesWithStatus { status, count } => {
+                write!(f, "📋 状态为「{}」的记录共 {} 条:", status, count)
+            }
+            NoEntriesOnDateHint(date) => {
+                write!(f, "{} 没有日志记录，请使用 --date 指定日志所在日期", date)
+            }
+            EntryNotFoundOnDate {
+                date,
+                id,
+                candidates,
+            } => write!(
+                f,
+                "{} 没有 ID 为 {} 的日志记录，当天可用的 ID:\n{}",
+                date, id, candidates
+            ),
+
+            EntryDone(content) => write!(f, "✅ 已完成: {}", content),
+            PrioritySet { priority, content } => {
+                write!(f, "⭐ 优先级已设为「{}」: {}", priority, content)
+            }
+            NoAttachments => write!(f, "没有附件"),
+            EntryUpdated(content) => write!(f, "✅ 已更新日志记录: {}", content),
+            WillDelete { permanent, content } => {
+                let action = if *permanent {
+                    "永久删除"
+                } else {
+                    "移入回收站"
+                };
+                write!(f, "🔍 将{}日志记录: {}", action, content)
+            }
+            DeletedPermanently(content) => write!(f, "🗑️ 已永久删除日志记录: {}", content),
+            MovedToTrash(content) => write!(f, "🗑️ 已将日志记录移入回收站: {}", content),
+            RestoreHint(id) => write!(f, "可以使用 trash restore {} 恢复", id),
+            TrashEmpty => write!(f, "🗑️ 回收站是空的"),
+            TrashCount(count) => write!(f, "🗑️ 回收站中共有 {} 条日志记录:", count),
+            Restored { date, content } => write!(f, "✅ 已恢复到 {}: {}", date, content),
+            TrashPurged(count) => write!(f, "✅ 已从回收站清除 {} 条日志记录", count),
+
+            SearchNoResults(query) => write!(f, "🔍 没有找到包含 \"{}\" 的日志记录", query),
+            SearchResults { count, query } => {
+                write!(f, "🔍 共找到 {} 条匹配 \"{}\" 的日志记录:", count, query)
+            }
+            NoTags => write!(f, "🏷️  还没有任何标签"),
+            TagCount(count) => write!(f, "🏷️  共有 {} 个标签:", count),
+            WillRenameTag { from, to, count } => write!(
+                f,
+                "🔍 将把标签 \"{}\" 重命名为 \"{}\"，涉及 {} 条记录",
+                from, to, count
+            ),
+            TagRenamed { from, to, count } => write!(
+                f,
+                "✅ 已将标签 \"{}\" 重命名为 \"{}\"，共修改 {} 条记录",
+                from, to, count
+            ),
+
+            StatsHeader { start, end } => write!(f, "📊 {} 至 {} 的日志统计:", start, end),
+            StatsTotal(count) => write!(f, "日志总数: {}", count),
+            StatsActiveDays { active, total } => write!(f, "活跃天数: {} / {}", active, total),
+            StatsDailyAverage(average) => write!(f, "日均条数: {:.1}（按活跃天数）", average),
+            StatsBusiestDay { date, count } => write!(f, "最忙的一天: {} ({} 条)", date, count),
+            StatsWords { words, chars } => write!(f, "总字数: {}（{} 个字符）", words, chars),
+            StatsWordsPerEntry(average) => write!(f, "平均每条: {:.1} 字", average),
+
+            Exported(path) => write!(f, "✅ 日志已导出到: {}", path),
+            WillImport { added, skipped } => {
+                write!(f, "🔍 将新增 {} 条，跳过 {} 条 (ID 已存在)", added, skipped)
+            }
+            Imported { added, skipped } => write!(
+                f,
+                "✅ 导入完成: 新增 {} 条，跳过 {} 条 (ID 已存在)",
+                added, skipped
+            ),
+            ImportErrors(count) => write!(f, "⚠️  {} 条记录无法导入:", count),
+            NothingToArchive(month) => write!(f, "📦 {} 之前没有需要归档的日志文件", month),
+            WillArchive { month, days } => write!(f, "🔍 {}: 将归档 {} 天的日志", month, days),
+            Archived { month, days } => write!(f, "📦 {}: 已归档 {} 天的日志", month, days),
+            ArchivedMonths(count) => write!(f, "✅ 共归档 {} 个月份", count),
+            BackedUp { count, dest } => write!(f, "✅ 已备份 {} 个日志文件到: {}", count, dest),
+            WillMigrate { added, skipped } => write!(
+                f,
+                "🔍 将导入 {} 条记录，跳过 {} 条已存在的记录",
+                added, skipped
+            ),
+            Migrated {
+                added,
+                skipped,
+                path,
+            } => write!(
+                f,
+                "✅ 已导入 {} 条记录到 {}，跳过 {} 条已存在的记录",
+                added, path, skipped
+            ),
+            MigrateHint(backend) => write!(
+                f,
+                "💡 在设置中将 storage_backend 改为 \"{}\" 后使用数据库中的日志",
+                backend
+            ),
+
+            DryRunHeader => write!(f, "=== 预演模式，未写入任何文件 ==="),
+            NoPlannedChanges => write!(f, "没有需要改动的内容"),
+            AffectedEntries(count) => write!(f, "受影响的日志记录: {} 条", count),
+            ChangedFiles(count) => write!(f, "将改动的文件: {} 个", count),
+
+            ArgDate => write!(f, "日期"),
+            ArgStartDate => write!(f, "开始日期"),
+            ArgEndDate => write!(f, "结束日期"),
+            InvalidDateArg { label, error } => write!(f, "{}格式错误：{}", label, error),
+            InvalidTime(e) => write!(f, "时间格式错误：{}", e),
+            StartAfterEndDates { start, end } => {
+                write!(f, "开始日期 {} 不能晚于结束日期 {}", start, end)
+            }
+            NotificationTitle => write!(f, "工作日志"),
+            ClipboardEntryAdded(content) => write!(f, "已添加: {}", content),
+            ClipboardEmpty => write!(f, "剪贴板中没有可添加的文本"),
+            AddEntryFailed => write!(f, "添加日志失败"),
+            ImportCommitsFailed => write!(f, "导入提交失败"),
+            NoDirectorySelected => write!(f, "未选择目录"),
+
+            MissingSummaryType => write!(f, "缺少摘要类型参数 'summary_type'"),
+            CustomSummaryNeedsStart => write!(f, "自定义摘要类型需要提供开始日期"),
+            CustomSummaryNeedsEnd => write!(f, "自定义摘要类型需要提供结束日期"),
+            DailySummaryTitle => write!(f, "日摘要"),
+            WeeklySummaryTitle => write!(f, "周摘要"),
+            MonthlySummaryTitle => write!(f, "月摘要"),
+            QuarterlySummaryTitle => write!(f, "季度摘要"),
+            CustomSummaryTitle => write!(f, "自定义摘要"),
+            ComparisonSummaryTitle => write!(f, "对比摘要"),
+            PeriodA => write!(f, "时间段 A"),
+            PeriodB => write!(f, "时间段 B"),
+            MissingSummaryRange => write!(f, "缺少摘要日期范围"),
+            NoEntriesInRange => write!(f, "指定日期范围内没有找到日志记录"),
+            SummaryProcessing(count) => write!(f, "正在处理 {} 条日志记录...", count),
+            SummaryStillGenerating(secs) => write!(f, "仍在生成摘要（已用 {} 秒）...", secs),
+            SummaryStartEventFailed => write!(f, "无法发送摘要开始事件"),
+            GenerateSummaryAction => write!(f, "生成摘要"),
+            RefineSummaryAction => write!(f, "调整摘要"),
+            CompareSummaryAction => write!(f, "生成对比摘要"),
+            ActionFailed(action) => write!(f, "{}失败", action),
+            NoSummaryTask => write!(f, "没有正在进行的摘要任务"),
+            SummaryBackendUnavailable => write!(f, "摘要服务不可用"),
+
+            AppPathUnavailable => write!(f, "无法获取应用路径"),
+            HomeDirUnavailable => write!(f, "无法获取用户主目录"),
+            CliExecutableNotFound(install) => write!(
+                f,
+                "无法找到可执行文件。\n\n您可以通过以下方式安装命令行工具:\n\n{};\nsudo ln -sf $(which wr-cli) /usr/local/bin/work-record\n\n或者使用提供的打包版本。",
+                install
+            ),
+            CliInstallNeedsAdmin(command) => write!(
+                f,
+                "需要管理员权限来创建命令行工具。\n\n请在终端中手动执行以下命令：\n\n{}\n\n执行后即可使用 work-record 命令",
+                command
+            ),
+            CliUninstallNeedsAdmin(command) => write!(
+                f,
+                "需要管理员权限来删除命令行工具。\n\n请在终端中手动执行以下命令：\n\n{}",
+                command
+            ),
+            BatchFileCreated { path, dir } => write!(
+                f,
+                "批处理文件已创建在：{}\n\n请以管理员身份在命令提示符中执行以下命令将目录添加到PATH：\n\nsetx PATH \"%PATH%;{}\" /M",
+                path, dir
+            ),
+            CreateBatchFileFailed => write!(f, "创建批处理文件失败"),
+            RemoveBatchFileFailed => write!(f, "删除批处理文件失败"),
+            CliRegisterUnsupported => write!(f, "当前操作系统不支持命令行注册"),
+            CliUnregisterUnsupported => write!(f, "当前操作系统不支持命令行注销"),
+
+            EntryContentEmpty => write!(f, "日志内容为空"),
+            InvalidCreatedAt { id, value, error } => {
+                write!(f, "记录 {} 的创建时间 \"{}\" 无效: {}", id, value, error)
+            }
+            ImportRecordInvalid { number, error } => {
+                write!(f, "第 {} 条记录格式错误: {}", number, error)
+            }
+            CsvLineError { line, error } => write!(f, "第 {} 行: {}", line, error),
+            CsvLineUnreadable { line, error } => write!(f, "第 {} 行无法解析: {}", line, error),
+            CsvColumnCount {
+                legacy,
+                current,
+                actual,
+            } => write!(f, "列数应为 {} 或 {}，实际为 {}", legacy, current, actual),
+            CsvInvalidDate { value, error } => write!(f, "日期 \"{}\" 格式错误: {}", value, error),
+            CsvInvalidTime { value, error } => write!(f, "时间 \"{}\" 格式错误: {}", value, error),
+            CsvNonexistentLocalTime { date, time } => {
+                write!(f, "{} {} 在本地时区中不存在", date, time)
+            }
+
+            DiagnoseConfigHeader => write!(f, "=== 当前配置信息 ==="),
+            DiagnoseProfile(profile) => write!(f, "设置档案: {}", profile),
+            DiagnoseConfigDir(dir) => write!(f, "配置目录: {}", dir),
+            DiagnoseSettingsFile(path) => write!(f, "设置文件: {}", path),
+            DiagnoseSettingsContent(json) => write!(f, "设置内容: {}", json),
+            DiagnoseChecksHeader => write!(f, "=== 检查结果 ==="),
+            DiagnoseLogFilesHeader => write!(f, "=== 检查日志文件 ==="),
+            UnrecognizedLogFiles { count, pattern } => write!(
+                f,
+                "⚠️ 以下 {} 个 JSON 文件不符合日志文件名格式 {}，已被忽略，可以移出日志存储目录:",
+                count, pattern
+            ),
+            FixFailed(e) => write!(f, "修复失败: {}", e),
+            FixApplied(fix) => write!(f, "🔧 已{}", fix),
+            FixPlanned(fix) => write!(f, "🔍 将{}", fix),
+            ConfigOk => write!(f, "配置正常，未发现问题"),
+            ProblemsFixed(count) => write!(
+                f,
+                "发现 {} 个问题，已尽可能修复，无法自动修复的问题请按提示处理",
+                count
+            ),
+            ProblemsPreviewed(count) => write!(
+                f,
+                "发现 {} 个问题，预演模式未做任何修改，去掉 --dry-run 重新运行以修复",
+                count
+            ),
+            ProblemsFound(count) => write!(
+                f,
+                "发现 {} 个问题，使用 --fix 重新运行以创建缺失的目录和设置文件",
+                count
+            ),
+            CheckNameProfile => write!(f, "设置档案"),
+            CheckNameSettingsFile => write!(f, "设置文件"),
+            CheckNameSettingsContent => write!(f, "设置内容"),
+            CheckNameStorageDir => write!(f, "日志存储目录"),
+            CheckNameOutputDir => write!(f, "日志输出目录"),
+            CheckNameStorage => write!(f, "日志存储"),
+            ProfileNameHint => write!(f, "使用只包含字母、数字、- 和 _ 的档案名称"),
+            SettingsFileParsed => write!(f, "设置文件解析成功"),
+            SettingsFileParseFailed(e) => write!(f, "设置文件解析失败: {}", e),
+            SettingsFileFixHint => {
+                write!(f, "手动修正该文件，或将其移走后重新诊断以生成默认设置")
+            }
+            SettingsFileMissing => write!(f, "设置文件不存在"),
+            CreateSettingsFileHint => write!(f, "创建默认设置文件"),
+            SettingsValid => write!(f, "设置有效"),
+            FixSettingsHint => write!(f, "在设置中修正上述问题"),
+            StorageReadable { backend, days } => {
+                write!(f, "存储方式 {}，共 {} 天的日志", backend, days)
+            }
+            ReadLogsFailed(e) => write!(f, "无法读取日志: {}", e),
+            StorageHint => write!(f, "检查日志存储目录中的文件和存储方式设置"),
+            DirMissing(path) => write!(f, "目录不存在: {}", path),
+            CreateDirHint => write!(f, "创建该目录"),
+            NotADir(path) => write!(f, "该路径不是目录: {}", path),
+            NotADirHint => write!(f, "在设置中修改为有效的目录"),
+            DirWritable(path) => write!(f, "{} 存在且可写", path),
+            DirNotWritable(e) => write!(f, "目录不可写: {}", e),
+            DirPermissionHint => write!(f, "检查目录权限"),
+            FixCreateSettingsFile(path) => write!(f, "创建默认设置文件: {}", path),
+            FixCreateDir(path) => write!(f, "创建目录: {}", path),
+
+            CheckingBackend => write!(f, "🩺 正在检查摘要服务..."),
+            BackendAvailable => write!(f, "✅ 摘要服务可用"),
+            BackendKind(backend) => write!(f, "   服务类型: {}", backend),
+            BackendUrl(url) => write!(f, "   请求地址: {}", url),
+            BackendModel(Some(model)) => write!(f, "   模型: {}", model),
+            BackendModel(None) => write!(f, "   模型: 未配置"),
+            BackendLatency(ms) => write!(f, "   耗时: {} 毫秒", ms),
+
+            FixConfigStart => write!(f, "开始诊断配置问题..."),
+            SettingsLoaded => write!(f, "成功加载设置"),
+            SettingsRecreated(e) => write!(f, "解析设置失败，创建新的设置: {}", e),
+            SettingsBackedUp(path) => write!(f, "原设置文件已备份到: {}", path),
+            BackupSettingsFailed(e) => write!(f, "备份设置文件失败: {}", e),
+            SaveSettingsFailed(e) => write!(f, "保存设置文件失败: {}", e),
+            ConfigFixed(dir) => write!(f, "配置已修复，日志存储目录: {}", dir),
+            CreatingLogDir(path) => write!(f, "创建日志目录: {}", path),
+            CreatingOutputDir(path) => write!(f, "创建日志输出目录: {}", path),
+            CreateOutputDirFailed(e) => write!(f, "创建日志输出目录失败: {}", e),
+
+            EntryNotFound(id) => write!(f, "未找到 ID 为 {} 的日志记录", id),
+            EntryOnOtherDate { id, date, target } => write!(
+                f,
+                "日志记录 {} 位于 {}，更新不能修改日期（目标日期 {}），请使用 move_entry 移动记录",
+                id, date, target
+            ),
+            NoLogFileOnDate(date) => write!(f, "未找到日期 {} 的日志文件", date),
+            TrashEntryNotFound(id) => write!(f, "回收站中没有 ID 为 {} 的日志记录", id),
+            RestoreConflict { date, id } => {
+                write!(f, "{} 已存在 ID 为 {} 的日志记录，无法恢复", date, id)
+            }
+            UnsupportedStatus(status) => {
+                write!(f, "不支持的状态: {}（可选 todo, in_progress, done）", status)
+            }
+            PriorityOutOfRange { min, max, value } => {
+                write!(f, "优先级必须在 {} 到 {} 之间: {}", min, max, value)
+            }
+            AttachmentEmpty => write!(f, "附件不能为空"),
+            AttachmentNotFound(path) => write!(f, "附件文件不存在: {}", path),
+            EntryHasNoAttachment { id, attachment } => {
+                write!(f, "日志记录 {} 没有附件 {}", id, attachment)
+            }
+            TagNameEmpty => write!(f, "标签名称不能为空"),
+            ArchiveAction => write!(f, "按月归档"),
+            DailyStorageOnly { action, backend } => write!(
+                f,
+                "{}只适用于每天一个文件的存储方式，当前存储方式为 {}",
+                action, backend
+            ),
+            LockTimeout { secs, error } => write!(
+                f,
+                "等待日志文件锁超时 ({} 秒)，可能有其他进程正在写入: {}",
+                secs, error
+            ),
+            StorageDirMissing(path) => write!(f, "日志存储目录不存在: {}", path),
+            BackupDirIsStorageDir => write!(f, "备份目录不能与日志存储目录相同"),
+            ArchiveFileUnreadable { path, error } => {
+                write!(f, "无法解析日志文件 {}，已取消归档: {}", path, error)
+            }
+            BackgroundReadFailed(e) => write!(f, "读取日志的后台任务失败: {}", e),
+            CsvWriteFailed(e) => write!(f, "写入 CSV 失败: {}", e),
+            CsvNotUtf8(e) => write!(f, "CSV 内容不是有效的 UTF-8: {}", e),
+
+            NoSummaryBackend => write!(f, "未配置任何模型服务，摘要将使用离线模板生成"),
+            OllamaModelNotConfigured => write!(f, "未配置 Ollama 模型名称"),
+            OllamaListFailed(status) => write!(f, "获取 Ollama 模型列表失败: {}", status),
+            OllamaListUnreadable(e) => write!(f, "无法解析 Ollama 模型列表: {}", e),
+            OllamaModelMissing(model) => {
+                write!(f, "未找到 Ollama 模型 '{}'，请先执行 `ollama pull {}`", model, model)
+            }
+            OllamaCallFailed(status) => write!(f, "Ollama API 调用失败: {}", status),
+            OllamaReturnedError(e) => write!(f, "Ollama 返回错误: {}", e),
+            ExternalApiNotConfigured => write!(f, "未配置外部 API URL 或 API Key"),
+            HttpClientFailed(e) => write!(f, "创建HTTP客户端失败: {}", e),
+            ApiTimeout => write!(f, "API请求超时，请检查网络连接或稍后重试"),
+            ApiConnectFailed => write!(f, "无法连接到API服务器，请检查网络连接和API地址"),
+            DashscopeConnectFailed => write!(f, "无法连接到阿里云百炼API，请检查网络连接和API地址"),
+            DashscopeCallFailed => write!(f, "阿里云百炼API调用失败: 请检查URL格式与API Key是否正确"),
+            ExternalApiFailed { status, detail } => {
+                write!(f, "外部 API 调用失败: {} - {}", status, detail)
+            }
+            ApiAuthFailed(detail) => write!(
+                f,
+                "API认证失败: 无效的API密钥。请在设置中检查您的API密钥。{}",
+                detail
+            ),
+            ApiForbidden => write!(f, "API访问被拒绝: 您没有权限访问此资源。请检查API密钥权限。"),
+            ApiNotFound => write!(f, "API资源未找到: 请检查API地址是否正确。"),
+            DashscopeNotFound => write!(f, "百联API资源未找到: 请检查API地址是否正确。确认您使用的是兼容模式URL: https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions"),
+            ApiRateLimited => write!(f, "API请求过多: 已超出速率限制。请稍后再试。"),
+            ApiServerError(status) => write!(f, "API服务器错误 {}: 服务暂时不可用。请稍后再试。", status),
+            ApiStatusFailed { status, body } => {
+                write!(f, "API请求失败: 状态码 {}, 响应: {}", status, body)
+            }
+            ResponseUnreadable => write!(f, "无法读取响应内容"),
+            AnthropicKeyMissing => write!(f, "未配置 Anthropic API Key"),
+            AnthropicModelMissing => write!(f, "未配置 Anthropic 模型名称，请在设置中填写模型 (例如 claude-3-5-sonnet-latest)"),
+            AnthropicAuthFailed => write!(f, "Anthropic API认证失败: 无效的API密钥。请在设置中检查您的API密钥。"),
+            AnthropicRateLimited => write!(f, "Anthropic API请求过多: 已超出速率限制。请稍后再试。"),
+            AnthropicFailed { status, detail } => {
+                write!(f, "Anthropic API调用失败: {} - {}", status, detail)
+            }
+            ApiReturnedError(message) => write!(f, "API返回错误: {}", message),
+            StreamReturnedError(message) => write!(f, "API流式响应返回错误: {}", message),
+            ResponseNotJson { error, snippet } => {
+                write!(f, "API响应不是有效的 JSON ({}): {}", error, snippet)
+            }
+            UnrecognizedResponse(snippet) => write!(f, "无法识别API响应格式: {}", snippet),
+            UnknownError => write!(f, "未知错误"),
+            NothingToRefine => write!(f, "没有可以调整的摘要内容"),
+            RefineInstructionEmpty => write!(f, "调整要求不能为空"),
+            RefineNeedsBackend => write!(f, "未配置模型服务或处于离线模式，无法调整摘要"),
+            CompareNeedsBackend => write!(f, "未配置模型服务或处于离线模式，无法生成对比摘要"),
+            NoEntriesInBothPeriods => write!(f, "两个时间段内都没有日志记录"),
+
+            GitRepoNotFound(path) => write!(f, "{} 及其上级目录中没有找到 Git 仓库", path),
+            ShortcutUnavailable { shortcut, error } => write!(
+                f,
+                "快捷键 {} 无法注册，可能已被其他应用占用: {}",
+                shortcut, error
+            ),
+            ReminderNoEntriesToday => write!(f, "今天还没有记录工作日志，记得补上今天的工作内容"),
+
+            UnsupportedSummaryFormat(format) => write!(f, "不支持的摘要格式: {}", format),
+            UnsupportedGroupBy { value, options } => write!(f, "不支持的分组方式: {}，可选: {}", value, options),
+            UnsupportedConflictPolicy { value, options } => write!(f, "不支持的文件冲突处理方式: {}，可选: {}", value, options),
+            UnsupportedSummaryType(summary_type) => write!(f, "不支持的摘要类型: {}", summary_type),
+            UnsupportedSummaryLanguage { value, options } => write!(f, "不支持的摘要语言: {}，可选: {}", value, options),
+            CustomRangeNeedsStart => write!(f, "自定义日期范围需要提供开始日期"),
+            CustomRangeNeedsDates => write!(f, "自定义日期范围需要提供开始和结束日期"),
+            SummarySavedTo(path) => write!(f, "✅ 摘要已保存到: {}", path),
+            SummaryFile(path) => write!(f, "📁 摘要文件: {}", path),
+            SummaryFileExists => write!(f, "⏭️ 同名摘要文件已存在，未保存本次摘要"),
+            SummaryLength { words, chars } => write!(f, "📏 摘要共 {} 字（{} 个字符）", words, chars),
+            ComparingPeriods { a, b } => write!(f, "对比时间段 A（{}）与时间段 B（{}）", a, b),
+            ComparisonSavedTo(path) => write!(f, "✅ 对比摘要已保存到: {}", path),
+
+            ConfigHeader => write!(f, "📋 工作日志记录 配置信息:"),
+            ConfigProfile(profile) => write!(f, "   设置档案: {}", profile),
+            ConfigStorageDir(dir) => write!(f, "   日志存储目录: {}", dir),
+            ConfigStorageBackend(backend) => write!(f, "   日志存储方式: {}", backend),
+            ConfigOutputDir(dir) => write!(f, "   日志输出目录: {}", dir),
+            ConfigFilePattern(pattern) => write!(f, "   日志文件名格式: {}", pattern),
+            ConfigSummaryFilePattern(pattern) => write!(f, "   摘要文件名格式: {}", pattern),
+            ConfigGitAuthor(author) => write!(f, "   Git 作者: {}", author),
+            ConfigGitEmail(email) => write!(f, "   Git 邮箱: {}", email),
+            ConfigShortcut { shortcut, enabled } => write!(
+                f,
+                "   快捷键: {}{}",
+                shortcut,
+                if *enabled { "" } else { " (未启用)" }
+            ),
+            ConfigReminder(time) => write!(f, "   每日提醒: {}", time),
+            ConfigAutoOpen(yes) => write!(f, "   自动打开窗口: {}", if *yes { "是" } else { "否" }),
+            ConfigUseOllama => write!(f, "   使用本地 Ollama: 是"),
+            ConfigOllamaAddress(address) => write!(f, "   Ollama 地址: {}", address),
+            ConfigOllamaModel(model) => write!(f, "   Ollama 模型: {}", model),
+            ConfigOllamaOptions(options) => write!(f, "   Ollama 生成参数: {}", options),
+            ConfigRemoteApi => write!(f, "   使用远程 LLM API"),
+            ConfigApiProvider(provider) => write!(f, "   API 提供方: {}", provider),
+            ConfigApiUrl(url) => write!(f, "   API 地址: {}", url),
+            ConfigModel(Some(model)) => write!(f, "   模型: {}", model),
+            ConfigModel(None) => write!(f, "   模型: 未配置"),
+            ConfigApiKey(key) => write!(f, "   API 密钥: {}", key),
+            ConfigTemperature(temperature) => write!(f, "   采样温度: {}", temperature),
+            ConfigMaxTokens(tokens) => write!(f, "   最大输出 token 数: {}", tokens),
+            ConfigSummaryLanguage { code, name } => {
+                write!(f, "   摘要语言: {} ({})", code, name.unwrap_or("不支持"))
+            }
+            ConfigEnvHeader => write!(f, "\n🔧 环境变量覆盖 (优先级: 环境变量 > 设置文件 > 默认值):"),
+            ConfigEnvVar { var, label, applied } => write!(
+                f,
+                "   {} ({}): {}",
+                var,
+                label,
+                if *applied { "已生效" } else { "未设置" }
+            ),
+
+            ConfigNormalizeTags(yes) => write!(f, "   标签转为小写: {}", if *yes { "是" } else { "否" }),
+
+            ConfigLogSummaries(yes) => write!(f, "   记录摘要到日志: {}", if *yes { "是" } else { "否" }),
+        }
+    }
+
+    fn write_en(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Message::*;
+        match self {
+            IoError(e) => write!(f, "I/O error: {}", e),
+            SerdeError(e) => write!(f, "Serialization error: {}", e),
+            GitError(e) => write!(f, "Git error: {}", e),
+            NetworkError(e) => write!(f, "Network request error: {}", e),
+            DatabaseError(e) => write!(f, "Database error: {}", e),
+            DateParseError(e) => write!(f, "Date parse error: {}", e),
+            TauriError(e) => write!(f, "Tauri error: {}", e),
+            FsError(e) => write!(f, "File system error: {}", e),
+            SettingsError(e) => write!(f, "Settings error: {}", e),
+            LogManagerError(e) => write!(f, "Log manager error: {}", e),
+            SummaryError(e) => write!(f, "Summary error: {}", e),
+            DuplicateEntry(id) => write!(
+                f,
+                "An entry with the same content already exists on this day (ID: {})",
+                id
+            ),
+            Cancelled => write!(f, "Operation cancelled"),
+            StorageNotFoundHint(e) => write!(
+                f,
+                "Log directory does not exist, please check the settings: {}",
+                e
+            ),
+            PermissionDeniedHint(e) => write!(
+                f,
+                "No permission to access the log directory, please check its permissions: {}",
+                e
+            ),
+
+            TrayTodayCount(count) => write!(f, "Today: {}", count),
+            TrayAddLog => write!(f, "Add Entry"),
+            TrayAddFromClipboard => write!(f, "Add from Clipboard"),
+            TrayEnableShortcut(None) => write!(f, "Enable Shortcut"),
+            TrayEnableShortcut(Some(shortcut)) => write!(f, "Enable Shortcut ({})", shortcut),
+            TraySettings => write!(f, "Settings"),
+            TrayOpenMain => write!(f, "Open Main Window"),
+            TrayQuit => write!(f, "Quit"),
+            ShortcutRegisterFailed { shortcut, error } => write!(
+                f,
+                "Failed to register shortcut {}, it may be invalid or used by another app: {}",
+                shortcut, error
+            ),
+
+            LabelContent => write!(f, "Content"),
+            LabelSource => write!(f, "Source"),
+            LabelStatus => write!(f, "Status"),
+            LabelPriority => write!(f, "Priority"),
+            LabelTags => write!(f, "Tags"),
+            LabelAttachments => write!(f, "Attachments"),
+            LabelTime => write!(f, "Time"),
+            LabelDeletedAt => write!(f, "Deleted at"),
+            ColumnDate => write!(f, "Date"),
+            ColumnTag => write!(f, "Tag"),
+            ColumnSource => write!(f, "Source"),
+            ColumnCount => write!(f, "Count"),
+
+            CliError(e) => write!(f, "Error: {}", e),
+            SettingsPathIsDir(path) => write!(f, "The settings path is a directory: {}", path),
+            InvalidDate(e) => write!(f, "Invalid date (expected YYYY-MM-DD): {}", e),
+            InvalidMonth(month) => write!(f, "Invalid month (expected YYYY-MM): {}", month),
+            StartAfterEnd => write!(f, "The start date cannot be after the end date"),
+            UnsupportedSort(sort) => write!(f, "Unsupported sort order: {}", sort),
+            UnsupportedExportFormat(format) => write!(f, "Unsupported export format: {}", format),
+            UnsupportedImportFormat(format) => write!(f, "Unsupported import format: {}", format),
+            ContentMissing => write!(
+                f,
+                "Please provide the entry content, use - to read from stdin, or --edit to open an editor"
+            ),
+            ContentEmpty => write!(f, "The entry content is empty, nothing was added"),
+            ReadStdinFailed(e) => write!(f, "Failed to read stdin: {}", e),
+            ReadFileFailed(e) => write!(f, "Failed to read the file: {}", e),
+            WriteFileFailed(e) => write!(f, "Failed to write the file: {}", e),
+            EditNothingSpecified => write!(
+                f,
+                "Please specify at least one of --content, --source or --tags"
+            ),
+
+            UsingStorageDir(dir) => write!(f, "Info: using log storage directory: {}", dir),
+            CreateLogDirFailed(e) => write!(f, "Failed to create the log directory: {}", e),
+            DuplicateSkipped(id) => write!(
+                f,
+                "⏭️ An entry with the same content already exists on this day (ID: {}), skipped",
+                id
+            ),
+            EntryAdded(path) => write!(f, "✅ Entry added to: {}", path),
+            NoEntriesOnDate(date) => write!(f, "📅 No entries on {}", date),
+            ListDate(date) => write!(f, "📅 Date: {}", date),
+            ListCount(count) => write!(f, "📝 {} entries:", count),
+            EntryNumber(number) => write!(f, "🔹 Entry #{}:", number),
+            NoEntriesWithStatus(status) => write!(f, "📋 No entries with status \"{}\"", status),
+            EntriesWithStatus { status, count } => {
+                write!(f, "📋 {} entries with status \"{}\":", count, status)
+            }
+            NoEntriesOnDateHint(date) => write!(
+                f,
+                "No entries on {}, use --date to specify the day of the entry",
+                date
+            ),
+            EntryNotFoundOnDate {
+                date,
+                id,
+                candidates,
+            } => write!(
+                f,
+                "No entry with ID {} on {}, available IDs on that day:\n{}",
+                id, date, candidates
+            ),
+
+            EntryDone(content) => write!(f, "✅ Done: {}", content),
+            PrioritySet { priority, content } => {
+                write!(f, "⭐ Priority set to \"{}\": {}", priority, content)
+            }
+            NoAttachments => write!(f, "No attachments"),
+            EntryUpdated(content) => write!(f, "✅ Entry updated: {}", content),
+            WillDelete { permanent, content } => {
+                let action = if *permanent {
+                    "permanently delete"
+                } else {
+                    "move to trash"
+                };
+                write!(f, "🔍 Would {} entry: {}", action, content)
+            }
+            DeletedPermanently(content) => write!(f, "🗑️ Entry permanently deleted: {}", content),
+            MovedToTrash(content) => write!(f, "🗑️ Entry moved to trash: {}", content),
+            RestoreHint(id) => write!(f, "Use trash restore {} to restore it", id),
+            TrashEmpty => write!(f, "🗑️ The trash is empty"),
+            TrashCount(count) => write!(f, "🗑️ {} entries in the trash:", count),
+            Restored { date, content } => write!(f, "✅ Restored to {}: {}", date, content),
+            TrashPurged(count) => write!(f, "✅ Purged {} entries from the trash", count),
+
+            SearchNoResults(query) => write!(f, "🔍 No entries matching \"{}\"", query),
+            SearchResults { count, query } => {
+                write!(f, "🔍 Found {} entries matching \"{}\":", count, query)
+            }
+            NoTags => write!(f, "🏷️  No tags yet"),
+            TagCount(count) => write!(f, "🏷️  {} tags:", count),
+            WillRenameTag { from, to, count } => write!(
+                f,
+                "🔍 Would rename tag \"{}\" to \"{}\" in {} entries",
+                from, to, count
+            ),
+            TagRenamed { from, to, count } => write!(
+                f,
+                "✅ Renamed tag \"{}\" to \"{}\" in {} entries",
+                from, to, count
+            ),
+
+            StatsHeader { start, end } => write!(f, "📊 Log statistics from {} to {}:", start, end),
+            StatsTotal(count) => write!(f, "Total entries: {}", count),
+            StatsActiveDays { active, total } => write!(f, "Active days: {} / {}", active, total),
+            StatsDailyAverage(average) => {
+                write!(f, "Entries per day: {:.1} (active days)", average)
+            }
+            StatsBusiestDay { date, count } => {
+                write!(f, "Busiest day: {} ({} entries)", date, count)
+            }
+            StatsWords { words, chars } => {
+                write!(f, "Total words: {} ({} characters)", words, chars)
+            }
+            StatsWordsPerEntry(average) => write!(f, "Words per entry: {:.1}", average),
+
+            Exported(path) => write!(f, "✅ Entries exported to: {}", path),
+            WillImport { added, skipped } => write!(
+                f,
+                "🔍 Would add {} entries, skip {} (ID already exists)",
+                added, skipped
+            ),
+            Imported { added, skipped } => write!(
+                f,
+                "✅ Import finished: added {}, skipped {} (ID already exists)",
+                added, skipped
+            ),
+            ImportErrors(count) => write!(f, "⚠️  {} entries could not be imported:", count),
+            NothingToArchive(month) => write!(f, "📦 No log files to archive before {}", month),
+            WillArchive { month, days } => {
+                write!(f, "🔍 {}: would archive {} days of logs", month, days)
+            }
+            Archived { month, days } => write!(f, "📦 {}: archived {} days of logs", month, days),
+            ArchivedMonths(count) => write!(f, "✅ Archived {} months", count),
+            BackedUp { count, dest } => write!(f, "✅ Backed up {} log files to: {}", count, dest),
+            WillMigrate { added, skipped } => write!(
+                f,
+                "🔍 Would import {} entries, skip {} existing entries",
+                added, skipped
+            ),
+            Migrated {
+                added,
+                skipped,
+                path,
+            } => write!(
+                f,
+                "✅ Imported {} entries into {}, skipped {} existing entries",
+                added, path, skipped
+            ),
+            MigrateHint(backend) => write!(
+                f,
+                "💡 Set storage_backend to \"{}\" in the settings to use the database",
+                backend
+            ),
+
+            DryRunHeader => write!(f, "=== Dry run, no files were written ==="),
+            NoPlannedChanges => write!(f, "Nothing to change"),
+            AffectedEntries(count) => write!(f, "Affected entries: {}", count),
+            ChangedFiles(count) => write!(f, "Files to change: {}", count),
+
+            ArgDate => write!(f, "date"),
+            ArgStartDate => write!(f, "start date"),
+            ArgEndDate => write!(f, "end date"),
+            InvalidDateArg { label, error } => {
+                write!(f, "Invalid {} (expected YYYY-MM-DD): {}", label, error)
+            }
+            InvalidTime(e) => write!(f, "Invalid time (expected RFC 3339): {}", e),
+            StartAfterEndDates { start, end } => write!(
+                f,
+                "The start date {} cannot be after the end date {}",
+                start, end
+            ),
+            NotificationTitle => write!(f, "Work Record"),
+            ClipboardEntryAdded(content) => write!(f, "Added: {}", content),
+            ClipboardEmpty => write!(f, "The clipboard has no text to add"),
+            AddEntryFailed => write!(f, "Failed to add the entry"),
+            ImportCommitsFailed => write!(f, "Failed to import commits"),
+            NoDirectorySelected => write!(f, "No directory selected"),
+
+            MissingSummaryType => write!(f, "Missing summary type parameter 'summary_type'"),
+            CustomSummaryNeedsStart => write!(f, "A custom summary requires a start date"),
+            CustomSummaryNeedsEnd => write!(f, "A custom summary requires an end date"),
+            DailySummaryTitle => write!(f, "Daily Summary"),
+            WeeklySummaryTitle => write!(f, "Weekly Summary"),
+            MonthlySummaryTitle => write!(f, "Monthly Summary"),
+            QuarterlySummaryTitle => write!(f, "Quarterly Summary"),
+            CustomSummaryTitle => write!(f, "Custom Summary"),
+            ComparisonSummaryTitle => write!(f, "Comparison Summary"),
+            PeriodA => write!(f, "Period A"),
+            PeriodB => write!(f, "Period B"),
+            MissingSummaryRange => write!(f, "The summary date range is missing"),
+            NoEntriesInRange => write!(f, "No entries found in the given date range"),
+            SummaryProcessing(count) => write!(f, "Processing {} entries...", count),
+            SummaryStillGenerating(secs) => {
+                write!(f, "Still generating the summary ({} seconds so far)...", secs)
+            }
+            SummaryStartEventFailed => write!(f, "Failed to send the summary start event"),
+            GenerateSummaryAction => write!(f, "Summary generation"),
+            RefineSummaryAction => write!(f, "Summary refinement"),
+            CompareSummaryAction => write!(f, "Comparison summary generation"),
+            ActionFailed(action) => write!(f, "{} failed", action),
+            NoSummaryTask => write!(f, "No summary is being generated"),
+            SummaryBackendUnavailable => write!(f, "The summary service is unavailable"),
+
+            AppPathUnavailable => write!(f, "Unable to get the application path"),
+            HomeDirUnavailable => write!(f, "Unable to get the home directory"),
+            CliExecutableNotFound(install) => write!(
+                f,
+                "Unable to find the executable.\n\nYou can install the command line tool with:\n\n{};\nsudo ln -sf $(which wr-cli) /usr/local/bin/work-record\n\nor use the packaged release.",
+                install
+            ),
+            CliInstallNeedsAdmin(command) => write!(
+                f,
+                "Administrator permission is required to create the command line tool.\n\nPlease run the following command in a terminal:\n\n{}\n\nThen the work-record command is available",
+                command
+            ),
+            CliUninstallNeedsAdmin(command) => write!(
+                f,
+                "Administrator permission is required to remove the command line tool.\n\nPlease run the following command in a terminal:\n\n{}",
+                command
+            ),
+            BatchFileCreated { path, dir } => write!(
+                f,
+                "The batch file was created at: {}\n\nRun the following command in an administrator command prompt to add the directory to PATH:\n\nsetx PATH \"%PATH%;{}\" /M",
+                path, dir
+            ),
+            CreateBatchFileFailed => write!(f, "Failed to create the batch file"),
+            RemoveBatchFileFailed => write!(f, "Failed to remove the batch file"),
+            CliRegisterUnsupported => {
+                write!(f, "Registering the command line tool is not supported on this OS")
+            }
+            CliUnregisterUnsupported => {
+                write!(f, "Unregistering the command line tool is not supported on this OS")
+            }
+
+            EntryContentEmpty => write!(f, "The entry content is empty"),
+            InvalidCreatedAt { id, value, error } => write!(
+                f,
+                "Entry {} has an invalid creation time \"{}\": {}",
+                id, value, error
+            ),
+            ImportRecordInvalid { number, error } => {
+                write!(f, "Record {} is malformed: {}", number, error)
+            }
+            CsvLineError { line, error } => write!(f, "Line {}: {}", line, error),
+            CsvLineUnreadable { line, error } => {
+                write!(f, "Line {} could not be parsed: {}", line, error)
+            }
+            CsvColumnCount {
+                legacy,
+                current,
+                actual,
+            } => write!(
+                f,
+                "Expected {} or {} columns, found {}",
+                legacy, current, actual
+            ),
+            CsvInvalidDate { value, error } => write!(f, "Invalid date \"{}\": {}", value, error),
+            CsvInvalidTime { value, error } => write!(f, "Invalid time \"{}\": {}", value, error),
+            CsvNonexistentLocalTime { date, time } => {
+                write!(f, "{} {} does not exist in the local time zone", date, time)
+            }
+
+            DiagnoseConfigHeader => write!(f, "=== Current configuration ==="),
+            DiagnoseProfile(profile) => write!(f, "Profile: {}", profile),
+            DiagnoseConfigDir(dir) => write!(f, "Config directory: {}", dir),
+            DiagnoseSettingsFile(path) => write!(f, "Settings file: {}", path),
+            DiagnoseSettingsContent(json) => write!(f, "Settings: {}", json),
+            DiagnoseChecksHeader => write!(f, "=== Checks ==="),
+            DiagnoseLogFilesHeader => write!(f, "=== Log files ==="),
+            UnrecognizedLogFiles { count, pattern } => write!(
+                f,
+                "⚠️ The following {} JSON files do not match the log file name pattern {} and are ignored, they can be moved out of the log storage directory:",
+                count, pattern
+            ),
+            FixFailed(e) => write!(f, "Fix failed: {}", e),
+            FixApplied(fix) => write!(f, "🔧 Done: {}", fix),
+            FixPlanned(fix) => write!(f, "🔍 Would {}", fix),
+            ConfigOk => write!(f, "The configuration is fine, no problems found"),
+            ProblemsFixed(count) => write!(
+                f,
+                "Found {} problems and fixed what could be fixed, follow the suggestions for the rest",
+                count
+            ),
+            ProblemsPreviewed(count) => write!(
+                f,
+                "Found {} problems, nothing was changed in the dry run, run again without --dry-run to fix them",
+                count
+            ),
+            ProblemsFound(count) => write!(
+                f,
+                "Found {} problems, run again with --fix to create the missing directories and settings file",
+                count
+            ),
+            CheckNameProfile => write!(f, "Profile"),
+            CheckNameSettingsFile => write!(f, "Settings file"),
+            CheckNameSettingsContent => write!(f, "Settings"),
+            CheckNameStorageDir => write!(f, "Log storage directory"),
+            CheckNameOutputDir => write!(f, "Log output directory"),
+            CheckNameStorage => write!(f, "Log storage"),
+            ProfileNameHint => write!(
+                f,
+                "Use a profile name made of letters, digits, - and _ only"
+            ),
+            SettingsFileParsed => write!(f, "The settings file was parsed"),
+            SettingsFileParseFailed(e) => write!(f, "Failed to parse the settings file: {}", e),
+            SettingsFileFixHint => write!(
+                f,
+                "Fix the file by hand, or move it away and diagnose again to create the default settings"
+            ),
+            SettingsFileMissing => write!(f, "The settings file does not exist"),
+            CreateSettingsFileHint => write!(f, "Create the default settings file"),
+            SettingsValid => write!(f, "The settings are valid"),
+            FixSettingsHint => write!(f, "Fix the problems above in the settings"),
+            StorageReadable { backend, days } => {
+                write!(f, "Storage backend {}, {} days of logs", backend, days)
+            }
+            ReadLogsFailed(e) => write!(f, "Failed to read the logs: {}", e),
+            StorageHint => write!(
+                f,
+                "Check the files in the log storage directory and the storage backend setting"
+            ),
+            DirMissing(path) => write!(f, "The directory does not exist: {}", path),
+            CreateDirHint => write!(f, "Create the directory"),
+            NotADir(path) => write!(f, "The path is not a directory: {}", path),
+            NotADirHint => write!(f, "Change it to a valid directory in the settings"),
+            DirWritable(path) => write!(f, "{} exists and is writable", path),
+            DirNotWritable(e) => write!(f, "The directory is not writable: {}", e),
+            DirPermissionHint => write!(f, "Check the directory permissions"),
+            FixCreateSettingsFile(path) => write!(f, "create the default settings file: {}", path),
+            FixCreateDir(path) => write!(f, "create directory: {}", path),
+
+            CheckingBackend => write!(f, "🩺 Checking the summary service..."),
+            BackendAvailable => write!(f, "✅ The summary service is available"),
+            BackendKind(backend) => write!(f, "   Backend: {}", backend),
+            BackendUrl(url) => write!(f, "   URL: {}", url),
+            BackendModel(Some(model)) => write!(f, "   Model: {}", model),
+            BackendModel(None) => write!(f, "   Model: not configured"),
+            BackendLatency(ms) => write!(f, "   Latency: {} ms", ms),
+
+            FixConfigStart => write!(f, "Diagnosing the configuration..."),
+            SettingsLoaded => write!(f, "Settings loaded"),
+            SettingsRecreated(e) => {
+                write!(f, "Failed to parse the settings, creating new settings: {}", e)
+            }
+            SettingsBackedUp(path) => write!(f, "The old settings file was backed up to: {}", path),
+            BackupSettingsFailed(e) => write!(f, "Failed to back up the settings file: {}", e),
+            SaveSettingsFailed(e) => write!(f, "Failed to save the settings file: {}", e),
+            ConfigFixed(dir) => write!(f, "Configuration fixed, log storage directory: {}", dir),
+            CreatingLogDir(path) => write!(f, "Creating the log directory: {}", path),
+            CreatingOutputDir(path) => write!(f, "Creating the log output directory: {}", path),
+            CreateOutputDirFailed(e) => {
+                write!(f, "Failed to create the log output directory: {}", e)
+            }
+
+            EntryNotFound(id) => write!(f, "No entry found with ID {}", id),
+            EntryOnOtherDate { id, date, target } => write!(
+                f,
+                "Entry {} is on {}, an update cannot change its date (target date {}); use move_entry to move it",
+                id, date, target
+            ),
+            NoLogFileOnDate(date) => write!(f, "No log file found for {}", date),
+            TrashEntryNotFound(id) => write!(f, "No entry with ID {} in the trash", id),
+            RestoreConflict { date, id } => write!(
+                f,
+                "An entry with ID {} already exists on {}, cannot restore it",
+                id, date
+            ),
+            UnsupportedStatus(status) => write!(
+                f,
+                "Unsupported status: {} (expected todo, in_progress or done)",
+                status
+            ),
+            PriorityOutOfRange { min, max, value } => {
+                write!(f, "Priority must be between {} and {}: {}", min, max, value)
+            }
+            AttachmentEmpty => write!(f, "The attachment cannot be empty"),
+            AttachmentNotFound(path) => write!(f, "The attachment file does not exist: {}", path),
+            EntryHasNoAttachment { id, attachment } => {
+                write!(f, "Entry {} has no attachment {}", id, attachment)
+            }
+            TagNameEmpty => write!(f, "The tag name cannot be empty"),
+            ArchiveAction => write!(f, "Monthly archiving"),
+            DailyStorageOnly { action, backend } => write!(
+                f,
+                "{} only works with the daily file storage, the current storage is {}",
+                action, backend
+            ),
+            LockTimeout { secs, error } => write!(
+                f,
+                "Timed out waiting for the log file lock ({} s), another process may be writing: {}",
+                secs, error
+            ),
+            StorageDirMissing(path) => write!(f, "The log storage directory does not exist: {}", path),
+            BackupDirIsStorageDir => {
+                write!(f, "The backup directory cannot be the log storage directory")
+            }
+            ArchiveFileUnreadable { path, error } => write!(
+                f,
+                "Unable to parse log file {}, archiving cancelled: {}",
+                path, error
+            ),
+            BackgroundReadFailed(e) => write!(f, "The background task reading logs failed: {}", e),
+            CsvWriteFailed(e) => write!(f, "Failed to write CSV: {}", e),
+            CsvNotUtf8(e) => write!(f, "The CSV content is not valid UTF-8: {}", e),
+
+            NoSummaryBackend => write!(f, "No summary service is configured, summaries use the offline template"),
+            OllamaModelNotConfigured => write!(f, "No Ollama model is configured"),
+            OllamaListFailed(status) => write!(f, "Failed to list Ollama models: {}", status),
+            OllamaListUnreadable(e) => write!(f, "Unable to parse the Ollama model list: {}", e),
+            OllamaModelMissing(model) => {
+                write!(f, "Ollama model '{}' not found; run `ollama pull {}`", model, model)
+            }
+            OllamaCallFailed(status) => write!(f, "Ollama API call failed: {}", status),
+            OllamaReturnedError(e) => write!(f, "Ollama returned an error: {}", e),
+            ExternalApiNotConfigured => write!(f, "The external API URL or API key is not configured"),
+            HttpClientFailed(e) => write!(f, "Failed to create the HTTP client: {}", e),
+            ApiTimeout => write!(f, "The API request timed out, check the network or try again later"),
+            ApiConnectFailed => write!(f, "Unable to connect to the API server, check the network and the API URL"),
+            DashscopeConnectFailed => write!(f, "Unable to connect to the DashScope API, check the network and the API URL"),
+            DashscopeCallFailed => write!(f, "DashScope API call failed: check the URL format and the API key"),
+            ExternalApiFailed { status, detail } => {
+                write!(f, "External API call failed: {} - {}", status, detail)
+            }
+            ApiAuthFailed(detail) => write!(
+                f,
+                "API authentication failed: invalid API key. Check the API key in the settings. {}",
+                detail
+            ),
+            ApiForbidden => write!(f, "API access denied: no permission for this resource. Check the API key permissions."),
+            ApiNotFound => write!(f, "API resource not found: check the API URL."),
+            DashscopeNotFound => write!(f, "DashScope API resource not found: check the API URL. Make sure you use the compatible-mode URL: https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions"),
+            ApiRateLimited => write!(f, "Too many API requests: rate limit exceeded. Try again later."),
+            ApiServerError(status) => write!(f, "API server error {}: the service is temporarily unavailable. Try again later.", status),
+            ApiStatusFailed { status, body } => {
+                write!(f, "API request failed: status {}, response: {}", status, body)
+            }
+            ResponseUnreadable => write!(f, "Unable to read the response"),
+            AnthropicKeyMissing => write!(f, "No Anthropic API key is configured"),
+            AnthropicModelMissing => write!(f, "No Anthropic model is configured, set one in the settings (e.g. claude-3-5-sonnet-latest)"),
+            AnthropicAuthFailed => write!(f, "Anthropic API authentication failed: invalid API key. Check the API key in the settings."),
+            AnthropicRateLimited => write!(f, "Too many Anthropic API requests: rate limit exceeded. Try again later."),
+            AnthropicFailed { status, detail } => {
+                write!(f, "Anthropic API call failed: {} - {}", status, detail)
+            }
+            ApiReturnedError(message) => write!(f, "The API returned an error: {}", message),
+            StreamReturnedError(message) => write!(f, "The API stream returned an error: {}", message),
+            ResponseNotJson { error, snippet } => {
+                write!(f, "The API response is not valid JSON ({}): {}", error, snippet)
+            }
+            UnrecognizedResponse(snippet) => write!(f, "Unrecognized API response format: {}", snippet),
+            UnknownError => write!(f, "Unknown error"),
+            NothingToRefine => write!(f, "There is no summary to refine"),
+            RefineInstructionEmpty => write!(f, "The refine instruction cannot be empty"),
+            RefineNeedsBackend => write!(f, "No summary service is configured or offline mode is on, cannot refine the summary"),
+            CompareNeedsBackend => write!(f, "No summary service is configured or offline mode is on, cannot generate a comparison summary"),
+            NoEntriesInBothPeriods => write!(f, "There are no entries in either period"),
+
+            GitRepoNotFound(path) => write!(f, "No Git repository found in {} or its parent directories", path),
+            ShortcutUnavailable { shortcut, error } => write!(
+                f,
+                "Shortcut {} cannot be registered, it may be used by another app: {}",
+                shortcut, error
+            ),
+            ReminderNoEntriesToday => write!(f, "No work log recorded today yet, remember to add today's work"),
+
+            UnsupportedSummaryFormat(format) => write!(f, "Unsupported summary format: {}", format),
+            UnsupportedGroupBy { value, options } => write!(f, "Unsupported grouping: {}, options: {}", value, options),
+            UnsupportedConflictPolicy { value, options } => write!(f, "Unsupported file conflict policy: {}, options: {}", value, options),
+            UnsupportedSummaryType(summary_type) => write!(f, "Unsupported summary type: {}", summary_type),
+            UnsupportedSummaryLanguage { value, options } => write!(f, "Unsupported summary language: {}, options: {}", value, options),
+            CustomRangeNeedsStart => write!(f, "A custom date range needs a start date"),
+            CustomRangeNeedsDates => write!(f, "A custom date range needs a start and an end date"),
+            SummarySavedTo(path) => write!(f, "✅ Summary saved to: {}", path),
+            SummaryFile(path) => write!(f, "📁 Summary file: {}", path),
+            SummaryFileExists => write!(f, "⏭️ A summary file with the same name exists, this summary was not saved"),
+            SummaryLength { words, chars } => write!(f, "📏 Summary: {} words ({} characters)", words, chars),
+            ComparingPeriods { a, b } => write!(f, "Comparing period A ({}) with period B ({})", a, b),
+            ComparisonSavedTo(path) => write!(f, "✅ Comparison summary saved to: {}", path),
+
+            ConfigHeader => write!(f, "📋 Work Record configuration:"),
+            ConfigProfile(profile) => write!(f, "   Settings profile: {}", profile),
+            ConfigStorageDir(dir) => write!(f, "   Log storage directory: {}", dir),
+            ConfigStorageBackend(backend) => write!(f, "   Log storage backend: {}", backend),
+            ConfigOutputDir(dir) => write!(f, "   Log output directory: {}", dir),
+            ConfigFilePattern(pattern) => write!(f, "   Log file name pattern: {}", pattern),
+            ConfigSummaryFilePattern(pattern) => write!(f, "   Summary file name pattern: {}", pattern),
+            ConfigGitAuthor(author) => write!(f, "   Git author: {}", author),
+            ConfigGitEmail(email) => write!(f, "   Git email: {}", email),
+            ConfigShortcut { shortcut, enabled } => write!(
+                f,
+                "   Shortcut: {}{}",
+                shortcut,
+                if *enabled { "" } else { " (disabled)" }
+            ),
+            ConfigReminder(time) => write!(f, "   Daily reminder: {}", time),
+            ConfigAutoOpen(yes) => write!(f, "   Open window automatically: {}", if *yes { "yes" } else { "no" }),
+            ConfigUseOllama => write!(f, "   Use local Ollama: yes"),
+            ConfigOllamaAddress(address) => write!(f, "   Ollama address: {}", address),
+            ConfigOllamaModel(model) => write!(f, "   Ollama model: {}", model),
+            ConfigOllamaOptions(options) => write!(f, "   Ollama options: {}", options),
+            ConfigRemoteApi => write!(f, "   Use remote LLM API"),
+            ConfigApiProvider(provider) => write!(f, "   API provider: {}", provider),
+            ConfigApiUrl(url) => write!(f, "   API URL: {}", url),
+            ConfigModel(Some(model)) => write!(f, "   Model: {}", model),
+            ConfigModel(None) => write!(f, "   Model: not configured"),
+            ConfigApiKey(key) => write!(f, "   API key: {}", key),
+            ConfigTemperature(temperature) => write!(f, "   Temperature: {}", temperature),
+            ConfigMaxTokens(tokens) => write!(f, "   Max output tokens: {}", tokens),
+            ConfigSummaryLanguage { code, name } => {
+                write!(f, "   Summary language: {} ({})", code, name.unwrap_or("unsupported"))
+            }
+            ConfigEnvHeader => write!(f, "\n🔧 Environment overrides (priority: environment > settings file > defaults):"),
+            ConfigEnvVar { var, label, applied } => write!(
+                f,
+                "   {} ({}): {}",
+                var,
+                label,
+                if *applied { "applied" } else { "not set" }
+            ),
+
+            ConfigNormalizeTags(yes) => write!(f, "   Lowercase tags: {}", if *yes { "yes" } else { "no" }),
+
+            ConfigLogSummaries(yes) => write!(f, "   Record summaries in the log: {}", if *yes { "yes" } else { "no" }),
+        }
+    }
+}
+
+/// 以指定语言格式化的界面文字
+struct LocalizedMessage<'m, 'a>(&'m Message<'a>, Language);
+
+impl Display for LocalizedMessage<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Language::Zh => self.0.write_zh(f),
+            Language::En => self.0.write_en(f),
+        }
+    }
+}
+
+impl Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        LocalizedMessage(self, current_language()).fmt(f)
+    }
+}
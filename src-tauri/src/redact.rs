@@ -0,0 +1,111 @@
+/// 遮盖敏感信息后显示的内容
+pub const MASK: &str = "****";
+
+/// 其后的取值需要遮盖的标记，比较时忽略大小写
+const SECRET_MARKERS: [&str; 3] = ["authorization:", "x-api-key:", "bearer "];
+
+/// 遮盖单个密钥，为空时返回空字符串
+pub fn mask_secret(secret: &str) -> String {
+    if secret.is_empty() {
+        String::new()
+    } else {
+        MASK.to_string()
+    }
+}
+
+/// 遮盖文本中的敏感信息
+///
+/// 替换文本中出现的 `secrets`，并遮盖 `Authorization`、`x-api-key` 请求头和 `Bearer` 令牌的取值，
+/// 用于打印或记录设置、请求和响应内容之前
+pub fn redact_text(text: &str, secrets: &[&str]) -> String {
+    let mut result = text.to_string();
+    for secret in secrets {
+        let secret = secret.trim();
+        if !secret.is_empty() {
+            result = result.replace(secret, MASK);
+        }
+    }
+
+    mask_marked_values(&result)
+}
+
+/// 遮盖标记之后的取值，取值到空白、引号或分隔符为止
+fn mask_marked_values(text: &str) -> String {
+    // 只转换 ASCII 字符，字节位置与原文一致
+    let lower = text.to_ascii_lowercase();
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while let Some((start, len)) = SECRET_MARKERS
+        .iter()
+        .filter_map(|marker| lower[pos..].find(marker).map(|i| (pos + i, marker.len())))
+        .min_by_key(|(start, _)| *start)
+    {
+        let mut value_start = skip_spaces(text, start + len);
+        // `Authorization: Bearer xxx` 只遮盖令牌本身
+        if lower[value_start..].starts_with("bearer ") {
+            value_start = skip_spaces(text, value_start + "bearer ".len());
+        }
+
+        let value_end = text[value_start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | ';'))
+            .map_or(text.len(), |i| value_start + i);
+
+        output.push_str(&text[pos..value_start]);
+        if value_end > value_start && &text[value_start..value_end] != MASK {
+            output.push_str(MASK);
+        } else {
+            output.push_str(&text[value_start..value_end]);
+        }
+        pos = value_end;
+    }
+
+    output.push_str(&text[pos..]);
+    output
+}
+
+/// 跳过 `index` 处开始的空格，返回第一个非空格字符的位置
+fn skip_spaces(text: &str, index: usize) -> usize {
+    index + text[index..].len() - text[index..].trim_start_matches(' ').len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+
+    #[test]
+    fn settings_output_masks_api_key() {
+        let settings = Settings {
+            llm_api_key: "sk-test-secret".to_string(),
+            ..Settings::default()
+        };
+
+        for output in [settings.to_redacted_json(), format!("{:?}", settings)] {
+            assert!(!output.contains("sk-test-secret"), "{}", output);
+            assert!(output.contains("\"llm_api_key\":"), "{}", output);
+            assert!(output.contains(MASK), "{}", output);
+        }
+    }
+
+    #[test]
+    fn authorization_header_values_are_masked() {
+        let request = "POST /v1/chat\nAuthorization: Bearer sk-test-secret\nx-api-key: key-123\n";
+
+        let redacted = redact_text(request, &[]);
+
+        assert_eq!(
+            redacted,
+            "POST /v1/chat\nAuthorization: Bearer ****\nx-api-key: ****\n"
+        );
+        // 已遮盖的文本再次遮盖时保持不变
+        assert_eq!(redact_text(&redacted, &[]), redacted);
+    }
+
+    #[test]
+    fn configured_secrets_are_masked_anywhere() {
+        let redacted = redact_text("请求失败: invalid key sk-test-secret", &["sk-test-secret"]);
+
+        assert_eq!(redacted, "请求失败: invalid key ****");
+    }
+}
@@ -0,0 +1,82 @@
+use crate::app_state::AppState;
+use crate::log_manager::LogManager;
+use crate::messages::Message;
+use chrono::{Duration, Local, NaiveDateTime, NaiveTime};
+use tauri::api::notification::Notification;
+use tauri::AppHandle;
+
+/// 检查提醒时间的间隔，设置修改后最迟在一个间隔内生效
+const REMINDER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// 启动每日提醒任务
+///
+/// 每天到设置的提醒时间检查当天是否已有日志，没有时发送系统通知。
+/// 每次检查都重新读取设置，开关和提醒时间的修改无需重启应用
+pub fn start_reminder_task(app_handle: AppHandle, state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        // 下一次提醒的时间及其对应的提醒设置
+        let mut scheduled: Option<(NaiveTime, NaiveDateTime)> = None;
+
+        loop {
+            let settings = state.get_settings();
+            let now = Local::now().naive_local();
+
+            scheduled = match settings
+                .reminder_time()
+                .filter(|_| settings.enable_reminder)
+            {
+                None => None,
+                Some(time) => {
+                    let fire_at = match scheduled {
+                        Some((scheduled_time, fire_at)) if scheduled_time == time => fire_at,
+                        _ => next_reminder_at(now, time),
+                    };
+
+                    if now >= fire_at {
+                        remind_if_no_entries(&app_handle, &state);
+                        Some((time, next_reminder_at(now, time)))
+                    } else {
+                        Some((time, fire_at))
+                    }
+                }
+            };
+
+            tokio::time::sleep(REMINDER_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 计算 `now` 之后的下一次提醒时间，今天已过提醒时间时为明天
+fn next_reminder_at(now: NaiveDateTime, time: NaiveTime) -> NaiveDateTime {
+    let today = now.date().and_time(time);
+    if today > now {
+        today
+    } else {
+        today + Duration::days(1)
+    }
+}
+
+/// 当天没有日志记录时发送提醒通知
+fn remind_if_no_entries(app_handle: &AppHandle, state: &AppState) {
+    let today = Local::now().date_naive();
+    let entries = match LogManager::new(state.get_settings()).get_entries_for_date(&today) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("检查今日日志失败，跳过提醒: {}", e);
+            return;
+        }
+    };
+
+    if !entries.is_empty() {
+        log::info!("今日已有 {} 条日志，无需提醒", entries.len());
+        return;
+    }
+
+    if let Err(e) = Notification::new(&app_handle.config().tauri.bundle.identifier)
+        .title(Message::NotificationTitle.to_string())
+        .body(Message::ReminderNoEntriesToday.to_string())
+        .show()
+    {
+        log::warn!("显示提醒通知失败: {}", e);
+    }
+}
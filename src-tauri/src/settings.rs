@@ -1,25 +1,152 @@
 use crate::errors::AppError;
+use crate::git_utils::AuthorFilter;
+use crate::log_manager::LogManager;
+use crate::messages::{Language, Message, LANGUAGES, LANGUAGE_ZH};
+use crate::redact::mask_secret;
+use chrono::{Local, NaiveTime};
 use dirs::home_dir;
 use log;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// 当前设置文件的结构版本，新增需要转换旧数据的字段时递增并在 `Settings::migrate` 中处理
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// 指定设置档案的环境变量
+pub const PROFILE_ENV_VAR: &str = "WORK_RECORD_PROFILE";
+
+/// 指定设置文件路径的环境变量，优先于档案
+pub const CONFIG_ENV_VAR: &str = "WORK_RECORD_CONFIG";
+
+/// 日志文件名格式中的年份占位符
+pub const FILE_NAME_YEAR: &str = "{year}";
+
+/// 日志文件名格式中的月份占位符
+pub const FILE_NAME_MONTH: &str = "{month}";
+
+/// 日志文件名格式中的日期占位符
+pub const FILE_NAME_DAY: &str = "{day}";
+
+/// 默认的每日日志文件名格式
+pub const DEFAULT_FILE_NAME_PATTERN: &str = "{year}-{month}-{day}.json";
+
+/// 摘要文件名格式中的摘要类型占位符，例如 `weekly`
+pub const SUMMARY_FILE_TYPE: &str = "{type}";
+
+/// 摘要文件名格式中的开始日期占位符
+pub const SUMMARY_FILE_START: &str = "{start}";
+
+/// 摘要文件名格式中的结束日期占位符
+pub const SUMMARY_FILE_END: &str = "{end}";
+
+/// 摘要文件名格式中的生成日期占位符
+pub const SUMMARY_FILE_DATE: &str = "{date}";
+
+/// 摘要文件名格式中的标题占位符
+pub const SUMMARY_FILE_TITLE: &str = "{title}";
+
+/// 每天一个 JSON 文件的存储方式
+pub const STORAGE_BACKEND_DAILY: &str = "daily";
+
+/// 所有日志保存在一个 JSON Lines 文件中的存储方式
+pub const STORAGE_BACKEND_JSONL: &str = "jsonl";
+
+/// 所有日志保存在一个 SQLite 数据库中的存储方式
+pub const STORAGE_BACKEND_SQLITE: &str = "sqlite";
+
+/// 支持的日志存储方式
+pub const STORAGE_BACKENDS: [&str; 3] = [
+    STORAGE_BACKEND_DAILY,
+    STORAGE_BACKEND_JSONL,
+    STORAGE_BACKEND_SQLITE,
+];
+
+/// 摘要文件已存在时的处理方式：添加数字后缀、覆盖或跳过保存
+pub const SUMMARY_FILE_CONFLICT_POLICIES: [&str; 3] = ["suffix", "overwrite", "skip"];
+
+/// 可以覆盖设置的环境变量及对应的设置项
+pub const SETTINGS_ENV_VARS: [(&str, &str); 7] = [
+    ("WORK_RECORD_LOG_DIR", "日志存储目录"),
+    ("WORK_RECORD_OUTPUT_DIR", "日志输出目录"),
+    ("WORK_RECORD_OLLAMA_ADDRESS", "Ollama 地址"),
+    ("WORK_RECORD_OLLAMA_MODEL", "Ollama 模型"),
+    ("WORK_RECORD_LLM_API_URL", "LLM API 地址"),
+    ("WORK_RECORD_LLM_API_KEY", "LLM API 密钥"),
+    ("WORK_RECORD_LLM_MODEL", "LLM 模型"),
+];
+
+/// 一项生效的环境变量覆盖
+#[derive(Clone, PartialEq)]
+pub struct EnvOverride {
+    /// 环境变量名
+    pub var: String,
+    /// 设置文件中的原值
+    pub file_value: String,
+    /// 环境变量的值
+    pub value: String,
+}
+
 /// 应用设置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// 设置文件中缺失的字段使用默认值填充，旧版本的设置文件可以直接加载。
+/// `Debug` 输出会遮盖 API 密钥
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
+    /// 设置文件结构版本，旧版本文件中缺失时为 0
+    #[serde(default)]
+    pub schema_version: u32,
+    /// 设置所属的档案名称，None 为默认档案；由设置文件名决定，不写入文件
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// 加载设置时使用的设置文件路径，保存时写回该文件；为空时按档案使用默认位置
+    #[serde(skip)]
+    pub config_path: Option<PathBuf>,
+    /// 已生效的环境变量覆盖，保存时还原为设置文件中的值
+    #[serde(skip)]
+    pub env_overrides: Vec<EnvOverride>,
     /// 日志记录文件存储目录
     pub log_storage_dir: String,
+    /// 日志存储方式 (daily, jsonl, sqlite)：默认每天一个 JSON 文件，jsonl 将所有日志保存在一个 `log.jsonl` 文件中，
+    /// 便于备份和同步；sqlite 保存在 `log.sqlite3` 数据库中，适合日志很多时的搜索和统计。
+    /// 切换后不会自动迁移已有的日志，可以先导出再导入，或用 `migrate-to-sqlite` 命令导入到数据库
+    #[serde(default = "default_storage_backend")]
+    pub storage_backend: String,
+    /// 添加日志时是否将标签转为小写，避免 `Bug` 和 `bug` 被当作不同的标签
+    #[serde(default)]
+    pub normalize_tags: bool,
     /// 日志生成目录
     pub log_output_dir: String,
+    /// 摘要文件名格式，相对摘要输出目录且不含扩展名，支持 `{type}`、`{start}`、`{end}`、`{date}`、
+    /// `{title}` 占位符，包含 `/` 时按子目录存放，例如 `{type}/{start}_{end}`；为空时使用内置的命名方式
+    #[serde(default)]
+    pub summary_filename_pattern: String,
+    /// 摘要文件已存在时的处理方式 (suffix, overwrite, skip)，默认添加 `-2`、`-3` 等数字后缀
+    #[serde(default = "default_summary_file_conflict")]
+    pub summary_file_conflict: String,
+    /// 每日日志文件名格式，相对日志存储目录，支持 `{year}`、`{month}`、`{day}` 占位符，
+    /// 包含 `/` 时按子目录存放，例如 `{year}/{month}/{day}.json`
+    #[serde(default = "default_file_name_pattern")]
+    pub file_name_pattern: String,
     /// Git 作者名称
     pub git_author: String,
+    /// Git 作者邮箱，与作者名称任一匹配的提交都会被收录
+    #[serde(default)]
+    pub git_author_email: String,
     /// 是否在启动时自动打开窗口
     pub auto_open_window: bool,
     /// 快捷键
     pub shortcut: String,
     /// 是否启用快捷键
     pub enable_shortcut: bool,
+    /// 是否开启每日提醒，到提醒时间当天还没有记录时发送通知
+    #[serde(default)]
+    pub enable_reminder: bool,
+    /// 每日提醒时间，格式为 HH:MM
+    #[serde(default = "default_reminder_time")]
+    pub reminder_time: String,
     /// 是否使用本地 Ollama 服务
     pub use_local_ollama: bool,
     /// Ollama 服务地址
@@ -30,6 +157,83 @@ pub struct Settings {
     pub llm_api_key: String,
     /// LLM API URL
     pub llm_api_url: String,
+    /// 外部 LLM 服务提供方 (openai, anthropic)
+    #[serde(default = "default_api_provider")]
+    pub api_provider: String,
+    /// 外部 LLM 模型名称，为空时使用服务提供方的默认模型
+    #[serde(default)]
+    pub llm_model: String,
+    /// 摘要生成使用的系统提示词
+    #[serde(default = "default_system_prompt")]
+    pub system_prompt: String,
+    /// 各摘要类型的提示词模板
+    #[serde(default)]
+    pub summary_prompts: SummaryPrompts,
+    /// 摘要 API 调用失败后的最大重试次数（仅对超时、429 和 5xx 重试）
+    #[serde(default = "default_summary_max_retries")]
+    pub summary_max_retries: u32,
+    /// 日志过长时是否先分批提炼再汇总，上下文窗口很大的模型可以关闭
+    #[serde(default = "default_summary_chunking_enabled")]
+    pub summary_chunking_enabled: bool,
+    /// 单次提示词中日志内容的最大字符数，超过后分批提炼
+    #[serde(default = "default_max_prompt_chars")]
+    pub max_prompt_chars: usize,
+    /// 周/月/季度摘要是否按当前日期倒推 7/30/90 天，默认按自然周/月/季度对齐
+    #[serde(default)]
+    pub summary_rolling_range: bool,
+    /// 提示词中的每条日志是否带上记录时间 (HH:MM)，便于模型分析工作的先后顺序和时间分布
+    #[serde(default = "default_summary_include_time")]
+    pub summary_include_time: bool,
+    /// 生成摘要后是否在今天的日志中记录一条 `summary` 来源的日志，内容为摘要标题和保存的文件路径
+    #[serde(default)]
+    pub log_summaries: bool,
+    /// 生成摘要的采样温度，越高内容越发散
+    #[serde(default = "default_summary_temperature")]
+    pub summary_temperature: f32,
+    /// 生成摘要的最大输出 token 数
+    #[serde(default = "default_summary_max_tokens")]
+    pub summary_max_tokens: u32,
+    /// 额外的 Ollama 生成参数，例如 `num_ctx`、`top_p`、`seed`，合并到请求的 `options` 中，
+    /// 同名参数覆盖采样温度和最大输出 token 数
+    #[serde(default)]
+    pub ollama_options: serde_json::Map<String, serde_json::Value>,
+    /// 摘要的输出语言代码，`auto` 表示与日志内容相同
+    #[serde(default = "default_summary_language")]
+    pub summary_language: String,
+    /// 界面语言代码，用于命令行输出、托盘菜单和错误信息，与摘要语言无关
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+/// 各摘要类型的提示词模板
+///
+/// 模板支持 `{title}`（摘要标题）和 `{logs}`（日志内容）占位符，
+/// 不包含 `{logs}` 时日志内容追加在提示词之后
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryPrompts {
+    /// 日摘要模板
+    pub daily: String,
+    /// 周摘要模板
+    pub weekly: String,
+    /// 月摘要模板
+    pub monthly: String,
+    /// 季度摘要模板
+    pub quarterly: String,
+    /// 自定义日期范围摘要模板
+    pub custom: String,
+}
+
+impl Default for SummaryPrompts {
+    fn default() -> Self {
+        Self {
+            daily: "请对以下工作日志进行日总结「{title}」，梳理当天完成的工作和遇到的问题，并列出明日待办。".to_string(),
+            weekly: "请对以下工作日志进行周总结「{title}」，分析工作内容、成果和存在的问题，提出改进建议。".to_string(),
+            monthly: "请对以下工作日志进行月度总结「{title}」，总结月度工作重点、成果和经验教训，提出下月工作计划。".to_string(),
+            quarterly: "请对以下工作日志进行季度总结「{title}」，分析季度目标完成情况、主要项目进展、成果和问题，提出下季度规划。".to_string(),
+            custom: "请对以下指定时间范围内的工作日志进行总结「{title}」，分析关键工作内容、成果和经验教训。".to_string(),
+        }
+    }
 }
 
 impl Default for Settings {
@@ -43,26 +247,179 @@ impl Default for Settings {
 
         // 获取系统 Git 用户
         let git_author = get_system_git_author().unwrap_or_else(|_| String::from(""));
+        let git_author_email = get_system_git_email().unwrap_or_default();
 
         Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            profile: None,
+            config_path: None,
+            env_overrides: Vec::new(),
             log_storage_dir: default_log_dir,
+            storage_backend: default_storage_backend(),
+            normalize_tags: false,
             log_output_dir: default_output_dir,
+            summary_filename_pattern: String::new(),
+            summary_file_conflict: default_summary_file_conflict(),
+            file_name_pattern: default_file_name_pattern(),
             git_author,
+            git_author_email,
             auto_open_window: false,
             shortcut: "Alt+Shift+L".to_string(),
             enable_shortcut: true,
+            enable_reminder: false,
+            reminder_time: default_reminder_time(),
             use_local_ollama: true,
             ollama_address: "http://localhost:11434".to_string(),
             ollama_model: "llama3".to_string(),
             llm_api_key: String::new(),
             llm_api_url: String::new(),
+            api_provider: default_api_provider(),
+            llm_model: String::new(),
+            system_prompt: default_system_prompt(),
+            summary_prompts: SummaryPrompts::default(),
+            summary_max_retries: default_summary_max_retries(),
+            summary_chunking_enabled: default_summary_chunking_enabled(),
+            max_prompt_chars: default_max_prompt_chars(),
+            summary_rolling_range: false,
+            summary_include_time: default_summary_include_time(),
+            log_summaries: false,
+            summary_temperature: default_summary_temperature(),
+            summary_max_tokens: default_summary_max_tokens(),
+            ollama_options: serde_json::Map::new(),
+            summary_language: default_summary_language(),
+            language: default_language(),
         }
     }
 }
 
+impl fmt::Debug for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_string(&self.redacted()) {
+            Ok(json) => write!(f, "Settings {}", json),
+            Err(_) => f.write_str("Settings { .. }"),
+        }
+    }
+}
+
+/// Anthropic 服务提供方标识
+pub const API_PROVIDER_ANTHROPIC: &str = "anthropic";
+
+/// Anthropic Messages API 默认地址
+const DEFAULT_ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+
+/// OpenAI 兼容 API 的默认模型
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
+
+/// 百炼 API 的默认模型
+const DEFAULT_DASHSCOPE_MODEL: &str = "qwen-max";
+
+/// 默认的系统提示词
+pub fn default_system_prompt() -> String {
+    "你是一个专业的工作日志分析助手，擅长总结工作内容并提出见解。".to_string()
+}
+
+/// 默认每天一个 JSON 文件
+fn default_storage_backend() -> String {
+    STORAGE_BACKEND_DAILY.to_string()
+}
+
+/// 默认的每日日志文件名格式
+fn default_file_name_pattern() -> String {
+    DEFAULT_FILE_NAME_PATTERN.to_string()
+}
+
+/// 默认的摘要 API 最大重试次数，即最多请求 3 次
+fn default_summary_max_retries() -> u32 {
+    2
+}
+
+/// 默认的每日提醒时间
+fn default_reminder_time() -> String {
+    "18:00".to_string()
+}
+
+/// 默认开启分批摘要
+fn default_summary_chunking_enabled() -> bool {
+    true
+}
+
+/// 默认的单次日志内容上限，约合常见 32k 上下文模型的安全范围
+fn default_max_prompt_chars() -> usize {
+    24000
+}
+
+/// 默认的摘要采样温度
+fn default_summary_temperature() -> f32 {
+    0.7
+}
+
+/// 默认的摘要最大输出 token 数
+fn default_summary_max_tokens() -> u32 {
+    4000
+}
+
+/// 摘要采样温度的允许范围
+const SUMMARY_TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+
+/// 摘要语言与日志内容相同，不额外指定
+pub const SUMMARY_LANGUAGE_AUTO: &str = "auto";
+
+/// 支持的摘要语言代码及名称
+pub const SUMMARY_LANGUAGES: [(&str, &str); 9] = [
+    (SUMMARY_LANGUAGE_AUTO, "与日志相同"),
+    ("zh", "简体中文"),
+    ("zh-TW", "繁体中文"),
+    ("en", "英文"),
+    ("ja", "日文"),
+    ("ko", "韩文"),
+    ("fr", "法文"),
+    ("de", "德文"),
+    ("es", "西班牙文"),
+];
+
+/// 获取摘要语言代码对应的名称，不支持的代码返回 None
+pub fn summary_language_name(code: &str) -> Option<&'static str> {
+    SUMMARY_LANGUAGES
+        .iter()
+        .find(|(language, _)| language.eq_ignore_ascii_case(code))
+        .map(|(_, name)| *name)
+}
+
+/// 默认的摘要语言
+fn default_summary_language() -> String {
+    SUMMARY_LANGUAGE_AUTO.to_string()
+}
+
+/// 默认的界面语言
+fn default_language() -> String {
+    LANGUAGE_ZH.to_string()
+}
+
+/// 默认在提示词中带上日志的记录时间
+fn default_summary_include_time() -> bool {
+    true
+}
+
+/// 默认在摘要文件名后添加数字后缀，不覆盖已有的摘要
+fn default_summary_file_conflict() -> String {
+    SUMMARY_FILE_CONFLICT_POLICIES[0].to_string()
+}
+
+/// 默认的外部 LLM 服务提供方
+fn default_api_provider() -> String {
+    "openai".to_string()
+}
+
 impl Settings {
     /// 获取设置文件路径
-    fn get_settings_path() -> PathBuf {
+    ///
+    /// 默认档案为 `settings.json`，其他档案为 `settings.<档案名>.json`；
+    /// 设置了环境变量 `WORK_RECORD_CONFIG` 时直接使用其指定的路径
+    pub fn get_settings_path(profile: Option<&str>) -> PathBuf {
+        if let Some(path) = Self::config_path_override() {
+            return path;
+        }
+
         // 设置文件存在用户配置目录下
         let config_dir = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -73,24 +430,105 @@ impl Settings {
             fs::create_dir_all(&config_dir).unwrap_or_else(|_| {});
         }
 
-        config_dir.join("settings.json")
+        match profile {
+            Some(profile) => config_dir.join(format!("settings.{}.json", profile)),
+            None => config_dir.join("settings.json"),
+        }
+    }
+
+    /// 从环境变量 `WORK_RECORD_PROFILE` 读取当前档案，未设置或为空时返回 None
+    pub fn active_profile() -> Option<String> {
+        std::env::var(PROFILE_ENV_VAR)
+            .ok()
+            .map(|profile| profile.trim().to_string())
+            .filter(|profile| !profile.is_empty())
+    }
+
+    /// 从环境变量 `WORK_RECORD_CONFIG` 读取设置文件路径，未设置或为空时返回 None
+    pub fn config_path_override() -> Option<PathBuf> {
+        std::env::var(CONFIG_ENV_VAR)
+            .ok()
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// 检查档案名称，只允许字母、数字、`-` 和 `_`，避免拼出其他路径
+    pub fn validate_profile_name(profile: &str) -> Result<(), AppError> {
+        let valid = !profile.is_empty()
+            && profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::SettingsError(format!(
+                "无效的档案名称 \"{}\"，只能包含字母、数字、- 和 _",
+                profile
+            )))
+        }
     }
 
-    /// 加载设置或使用默认值
+    /// 加载当前档案的设置或使用默认值
     pub fn load_or_default() -> Result<Self, AppError> {
-        let settings_path = Self::get_settings_path();
+        Self::load_profile(Self::active_profile().as_deref())
+    }
+
+    /// 读取当前档案设置文件中的界面语言，不会创建或修改设置文件，读取失败时使用默认语言
+    pub fn configured_language() -> Language {
+        let path = Self::get_settings_path(Self::active_profile().as_deref());
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .map(|settings| Language::from_code(&settings.language))
+            .unwrap_or_default()
+    }
 
-        let settings = if settings_path.exists() {
-            match fs::read_to_string(&settings_path) {
-                Ok(content) => serde_json::from_str(&content).map_err(AppError::from)?,
-                Err(_) => Self::default(),
+    /// 加载指定档案的设置，设置文件不存在时创建默认设置
+    pub fn load_profile(profile: Option<&str>) -> Result<Self, AppError> {
+        if let Some(profile) = profile {
+            Self::validate_profile_name(profile)?;
+        }
+        Self::load_from_path(&Self::get_settings_path(profile), profile)
+    }
+
+    /// 从指定的设置文件加载设置，文件不存在时在该位置创建默认设置
+    ///
+    /// 之后调用 `save` 会写回同一文件
+    pub fn load_from_path(settings_path: &Path, profile: Option<&str>) -> Result<Self, AppError> {
+        let mut settings = if settings_path.exists() {
+            match fs::read_to_string(settings_path) {
+                Ok(content) => {
+                    let mut settings: Self = serde_json::from_str(&content)?;
+                    settings.profile = profile.map(str::to_string);
+                    settings.config_path = Some(settings_path.to_path_buf());
+                    if settings.migrate() {
+                        log::info!("设置文件已升级到版本 {}", SETTINGS_SCHEMA_VERSION);
+                        if let Err(e) = settings.save() {
+                            log::warn!("保存升级后的设置失败: {}", e);
+                        }
+                    }
+                    settings
+                }
+                Err(_) => Self {
+                    profile: profile.map(str::to_string),
+                    config_path: Some(settings_path.to_path_buf()),
+                    ..Self::default()
+                },
             }
         } else {
-            let default_settings = Self::default();
+            let default_settings = Self {
+                profile: profile.map(str::to_string),
+                config_path: Some(settings_path.to_path_buf()),
+                ..Self::default()
+            };
             let _ = default_settings.save();
             default_settings
         };
 
+        // 环境变量优先于设置文件，在保存之后应用，避免写回文件
+        settings.apply_env_overrides();
+
         // 确保日志目录存在
         if let Err(e) = settings.ensure_log_dirs_exist() {
             log::warn!("无法创建日志目录: {}", e);
@@ -99,14 +537,125 @@ impl Settings {
         Ok(settings)
     }
 
-    /// 保存设置到文件
+    /// 将旧版本的设置升级到当前版本，返回是否发生了升级
+    ///
+    /// 缺失的字段在反序列化时已按默认值填充，这里只处理需要转换的旧数据
+    pub fn migrate(&mut self) -> bool {
+        if self.schema_version >= SETTINGS_SCHEMA_VERSION {
+            if self.schema_version > SETTINGS_SCHEMA_VERSION {
+                log::warn!(
+                    "设置文件版本 {} 高于当前支持的版本 {}，部分设置可能被忽略",
+                    self.schema_version,
+                    SETTINGS_SCHEMA_VERSION
+                );
+            }
+            return false;
+        }
+
+        // 版本 0 -> 1：引入 schema_version，新增字段均由默认值补齐，无需转换
+        self.schema_version = SETTINGS_SCHEMA_VERSION;
+        true
+    }
+
+    /// 使用环境变量覆盖对应的设置项，空值的环境变量会被忽略
+    pub fn apply_env_overrides(&mut self) {
+        for (var, _) in SETTINGS_ENV_VARS {
+            let value = match std::env::var(var) {
+                Ok(value) if !value.trim().is_empty() => value,
+                _ => continue,
+            };
+            let field = match self.env_field_mut(var) {
+                Some(field) => field,
+                None => continue,
+            };
+
+            let file_value = std::mem::replace(field, value.clone());
+            log::info!("设置项已被环境变量 {} 覆盖", var);
+            self.env_overrides.retain(|item| item.var != var);
+            self.env_overrides.push(EnvOverride {
+                var: var.to_string(),
+                file_value,
+                value,
+            });
+        }
+    }
+
+    /// 返回去掉环境变量覆盖的副本，用于保存到设置文件
+    ///
+    /// 覆盖后又被修改过的设置项保留修改后的值
+    pub fn without_env_overrides(&self) -> Self {
+        let mut settings = self.clone();
+        for item in &self.env_overrides {
+            if let Some(field) = settings.env_field_mut(&item.var) {
+                if *field == item.value {
+                    *field = item.file_value.clone();
+                }
+            }
+        }
+        settings.env_overrides.clear();
+        settings
+    }
+
+    /// 环境变量对应的设置项
+    fn env_field_mut(&mut self, var: &str) -> Option<&mut String> {
+        match var {
+            "WORK_RECORD_LOG_DIR" => Some(&mut self.log_storage_dir),
+            "WORK_RECORD_OUTPUT_DIR" => Some(&mut self.log_output_dir),
+            "WORK_RECORD_OLLAMA_ADDRESS" => Some(&mut self.ollama_address),
+            "WORK_RECORD_OLLAMA_MODEL" => Some(&mut self.ollama_model),
+            "WORK_RECORD_LLM_API_URL" => Some(&mut self.llm_api_url),
+            "WORK_RECORD_LLM_API_KEY" => Some(&mut self.llm_api_key),
+            "WORK_RECORD_LLM_MODEL" => Some(&mut self.llm_model),
+            _ => None,
+        }
+    }
+
+    /// 返回遮盖了 API 密钥的副本，用于打印或记录设置
+    pub fn redacted(&self) -> Self {
+        Self {
+            llm_api_key: mask_secret(&self.llm_api_key),
+            ..self.clone()
+        }
+    }
+
+    /// 遮盖 API 密钥后格式化为 JSON，用于展示设置内容
+    pub fn to_redacted_json(&self) -> String {
+        serde_json::to_string_pretty(&self.redacted()).unwrap_or_default()
+    }
+
+    /// 保存设置到加载时的设置文件或所属档案的设置文件，环境变量覆盖的值不会写入
     pub fn save(&self) -> Result<(), AppError> {
-        let settings_path = Self::get_settings_path();
-        let content = serde_json::to_string_pretty(self)?;
+        let settings_path = self
+            .config_path
+            .clone()
+            .unwrap_or_else(|| Self::get_settings_path(self.profile.as_deref()));
+        if let Some(dir) = settings_path.parent() {
+            if !dir.as_os_str().is_empty() && !dir.exists() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        let content = serde_json::to_string_pretty(&self.without_env_overrides())?;
         fs::write(settings_path, content)?;
         Ok(())
     }
 
+    /// 备份指定档案的设置文件为 `<设置文件名>.bak-YYYYMMDD-HHMMSS`
+    ///
+    /// 设置文件不存在时返回 None
+    pub fn backup_settings_file(profile: Option<&str>) -> Result<Option<PathBuf>, AppError> {
+        let settings_path = Self::get_settings_path(profile);
+        if !settings_path.exists() {
+            return Ok(None);
+        }
+
+        let mut backup_name = settings_path.file_name().unwrap_or_default().to_os_string();
+        backup_name.push(format!(".bak-{}", Local::now().format("%Y%m%d-%H%M%S")));
+        let backup_path = settings_path.with_file_name(backup_name);
+        fs::copy(&settings_path, &backup_path)?;
+
+        Ok(Some(backup_path))
+    }
+
     /// 确保日志目录存在
     pub fn ensure_log_dirs_exist(&self) -> Result<(), AppError> {
         let storage_dir = Path::new(&self.log_storage_dir);
@@ -125,10 +674,170 @@ impl Settings {
         Ok(())
     }
 
+    /// 校验设置，返回所有发现的问题
+    ///
+    /// 只做本地检查（路径、快捷键格式、摘要服务配置是否完整），不会访问网络
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        validate_dir("日志存储目录", &self.log_storage_dir, &mut problems);
+        validate_dir("日志输出目录", &self.log_output_dir, &mut problems);
+        if !STORAGE_BACKENDS.contains(&self.storage_backend.as_str()) {
+            problems.push(format!(
+                "不支持的日志存储方式 \"{}\"，可选: {}",
+                self.storage_backend,
+                STORAGE_BACKENDS.join(", ")
+            ));
+        }
+        #[cfg(not(feature = "sqlite"))]
+        if self.storage_backend == STORAGE_BACKEND_SQLITE {
+            problems.push("当前版本未启用 sqlite 特性，无法使用 SQLite 存储".to_string());
+        }
+        validate_file_name_pattern(&self.file_name_pattern, &mut problems);
+        validate_summary_filename_pattern(&self.summary_filename_pattern, &mut problems);
+        if !SUMMARY_FILE_CONFLICT_POLICIES.contains(&self.summary_file_conflict.as_str()) {
+            problems.push(format!(
+                "不支持的摘要文件冲突处理方式 \"{}\"，可选: {}",
+                self.summary_file_conflict,
+                SUMMARY_FILE_CONFLICT_POLICIES.join(", ")
+            ));
+        }
+
+        if !self.shortcut.is_empty() {
+            if let Err(problem) = validate_shortcut(&self.shortcut) {
+                problems.push(problem);
+            }
+        }
+
+        if self.reminder_time().is_none() {
+            problems.push(format!(
+                "提醒时间 \"{}\" 无效，格式应为 HH:MM，例如 18:00",
+                self.reminder_time
+            ));
+        }
+
+        if self.use_local_ollama {
+            if !is_http_url(&self.ollama_address) {
+                problems.push(format!(
+                    "Ollama 服务地址 \"{}\" 无效，应以 http:// 或 https:// 开头",
+                    self.ollama_address
+                ));
+            }
+            if self.ollama_model.trim().is_empty() {
+                problems.push("使用本地 Ollama 时必须填写模型名称".to_string());
+            }
+        } else if self.api_provider == API_PROVIDER_ANTHROPIC {
+            if self.llm_api_key.trim().is_empty() {
+                problems.push("使用 Anthropic 时必须填写 API Key".to_string());
+            }
+            if self.llm_model.trim().is_empty() {
+                problems.push("使用 Anthropic 时必须填写模型名称".to_string());
+            }
+            if !self.llm_api_url.trim().is_empty() && !is_http_url(&self.llm_api_url) {
+                problems.push(format!("LLM API URL \"{}\" 无效", self.llm_api_url));
+            }
+        } else if self.api_provider != "openai" {
+            problems.push(format!(
+                "不支持的服务提供方 \"{}\"，可选值为 openai 或 anthropic",
+                self.api_provider
+            ));
+        } else if !self.llm_api_url.trim().is_empty() {
+            // 未填写 API URL 时使用离线摘要，不要求其它字段
+            if !is_http_url(&self.llm_api_url) {
+                problems.push(format!(
+                    "LLM API URL \"{}\" 无效，应以 http:// 或 https:// 开头",
+                    self.llm_api_url
+                ));
+            }
+            if self.llm_api_key.trim().is_empty() {
+                problems.push("使用外部 LLM API 时必须填写 API Key".to_string());
+            }
+        }
+
+        if self.max_prompt_chars == 0 {
+            problems.push("单次提示词的最大字符数必须大于 0".to_string());
+        }
+
+        if !SUMMARY_TEMPERATURE_RANGE.contains(&self.summary_temperature) {
+            problems.push(format!(
+                "摘要采样温度 {} 无效，必须在 {} 到 {} 之间",
+                self.summary_temperature,
+                SUMMARY_TEMPERATURE_RANGE.start(),
+                SUMMARY_TEMPERATURE_RANGE.end()
+            ));
+        }
+
+        if self.summary_max_tokens == 0 {
+            problems.push("摘要最大输出 token 数必须大于 0".to_string());
+        }
+
+        if let Some(num_ctx) = self.ollama_options.get("num_ctx") {
+            if num_ctx.as_u64().is_none_or(|num_ctx| num_ctx == 0) {
+                problems.push(format!("Ollama 参数 num_ctx 必须是正整数: {}", num_ctx));
+            }
+        }
+        if let Some(top_p) = self.ollama_options.get("top_p") {
+            if top_p
+                .as_f64()
+                .is_none_or(|top_p| !(0.0..=1.0).contains(&top_p))
+            {
+                problems.push(format!("Ollama 参数 top_p 必须在 0 到 1 之间: {}", top_p));
+            }
+        }
+        if let Some(seed) = self.ollama_options.get("seed") {
+            if !seed.is_i64() && !seed.is_u64() {
+                problems.push(format!("Ollama 参数 seed 必须是整数: {}", seed));
+            }
+        }
+
+        if summary_language_name(&self.summary_language).is_none() {
+            problems.push(format!(
+                "不支持的摘要语言 \"{}\"，可选: {}",
+                self.summary_language,
+                SUMMARY_LANGUAGES.map(|(code, _)| code).join(", ")
+            ));
+        }
+
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            problems.push(format!(
+                "不支持的界面语言 \"{}\"，可选: {}",
+                self.language,
+                LANGUAGES.join(", ")
+            ));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// 解析每日提醒时间，格式无效时返回 None
+    pub fn reminder_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.reminder_time.trim(), "%H:%M").ok()
+    }
+
+    /// 获取筛选 Git 提交使用的作者条件
+    pub fn git_author_filter(&self) -> AuthorFilter {
+        AuthorFilter::new(&self.git_author, &self.git_author_email)
+    }
+
+    /// 是否配置了可用的摘要模型服务
+    ///
+    /// 未启用本地 Ollama 且没有配置外部 API 地址时返回 false，此时摘要使用离线模板生成
+    pub fn has_summary_backend(&self) -> bool {
+        self.use_local_ollama
+            || self.api_provider == API_PROVIDER_ANTHROPIC
+            || !self.llm_api_url.trim().is_empty()
+    }
+
     /// 获取摘要API类型
     pub fn get_summary_api_type(&self) -> u8 {
         if self.use_local_ollama {
             0 // 本地Ollama
+        } else if self.api_provider == API_PROVIDER_ANTHROPIC {
+            3 // Anthropic Claude
         } else if self.llm_api_url.contains("dashscope.aliyuncs.com") {
             2 // 百炼API
         } else {
@@ -146,18 +855,579 @@ impl Settings {
         }
     }
 
+    /// 获取摘要使用的模型名称
+    ///
+    /// 外部 API 优先使用 llm_model，未配置时回退到服务提供方的默认模型；
+    /// Anthropic 没有默认模型，返回空字符串由调用方提示用户配置
+    pub fn get_summary_model(&self, api_type: u8) -> String {
+        let model = self.llm_model.trim();
+
+        match api_type {
+            0 => self.ollama_model.clone(),
+            _ if !model.is_empty() => model.to_string(),
+            2 => DEFAULT_DASHSCOPE_MODEL.to_string(),
+            3 => String::new(),
+            _ => DEFAULT_OPENAI_MODEL.to_string(),
+        }
+    }
+
     /// 获取摘要API URL
     pub fn get_summary_api_url(&self, api_type: u8) -> String {
         match api_type {
             0 => format!("{}/api/generate", self.ollama_address),
+            3 => {
+                // 允许只填写基础地址，自动补全 Messages API 路径
+                let url = self.llm_api_url.trim().trim_end_matches('/');
+                if url.is_empty() {
+                    DEFAULT_ANTHROPIC_API_URL.to_string()
+                } else if url.ends_with("/v1/messages") {
+                    url.to_string()
+                } else {
+                    format!("{}/v1/messages", url)
+                }
+            }
             _ => self.llm_api_url.clone(),
         }
     }
 }
 
+/// 检查日志文件名格式：每个占位符恰好出现一次，以 `.json` 结尾，且只能指向日志目录内的相对路径
+fn validate_file_name_pattern(pattern: &str, problems: &mut Vec<String>) {
+    for placeholder in [FILE_NAME_YEAR, FILE_NAME_MONTH, FILE_NAME_DAY] {
+        if pattern.matches(placeholder).count() != 1 {
+            problems.push(format!(
+                "日志文件名格式 \"{}\" 必须包含且只包含一个 {}",
+                pattern, placeholder
+            ));
+        }
+    }
+
+    if !pattern.ends_with(".json") || pattern.ends_with(".archive.json") {
+        problems.push(format!(
+            "日志文件名格式 \"{}\" 必须以 .json 结尾，且不能以 .archive.json 结尾",
+            pattern
+        ));
+    }
+
+    // 以 `.` 开头的目录会被当作隐藏目录跳过，`..` 会指向日志目录之外
+    let invalid_path = pattern.contains('\\')
+        || pattern
+            .split('/')
+            .any(|component| component.is_empty() || component.starts_with('.'));
+    if invalid_path {
+        problems.push(format!(
+            "日志文件名格式 \"{}\" 只能使用 / 分隔子目录，且各级名称不能为空或以 . 开头",
+            pattern
+        ));
+    }
+}
+
+/// 检查摘要文件名格式，为空表示使用内置的命名方式
+fn validate_summary_filename_pattern(pattern: &str, problems: &mut Vec<String>) {
+    if pattern.trim().is_empty() {
+        return;
+    }
+
+    // 以 `.` 开头的名称会与摘要缓存等内部文件混在一起，`..` 会指向输出目录之外
+    let invalid_path = pattern.contains('\\')
+        || pattern
+            .split('/')
+            .any(|component| component.trim().is_empty() || component.starts_with('.'));
+    if invalid_path {
+        problems.push(format!(
+            "摘要文件名格式 \"{}\" 只能使用 / 分隔子目录，且各级名称不能为空或以 . 开头",
+            pattern
+        ));
+    }
+}
+
+/// 检查目录是否为绝对路径，且已存在或可以创建
+fn validate_dir(label: &str, dir: &str, problems: &mut Vec<String>) {
+    let path = Path::new(dir);
+    if dir.trim().is_empty() {
+        problems.push(format!("{}不能为空", label));
+        return;
+    }
+    if !path.is_absolute() {
+        problems.push(format!("{} \"{}\" 必须是绝对路径", label, dir));
+        return;
+    }
+    if path.exists() {
+        if !path.is_dir() {
+            problems.push(format!("{} \"{}\" 已存在但不是目录", label, dir));
+        }
+        return;
+    }
+
+    // 目录不存在时，检查最近的已存在上级目录能否创建子目录
+    match path.ancestors().skip(1).find(|p| p.exists()) {
+        Some(parent) if !parent.is_dir() => {
+            problems.push(format!(
+                "{} \"{}\" 无法创建：{} 不是目录",
+                label,
+                dir,
+                parent.display()
+            ));
+        }
+        Some(parent) => {
+            let readonly = fs::metadata(parent)
+                .map(|m| m.permissions().readonly())
+                .unwrap_or(true);
+            if readonly {
+                problems.push(format!(
+                    "{} \"{}\" 无法创建：{} 不可写",
+                    label,
+                    dir,
+                    parent.display()
+                ));
+            }
+        }
+        None => problems.push(format!("{} \"{}\" 无法创建", label, dir)),
+    }
+}
+
+/// 是否为 http(s) 地址
+fn is_http_url(url: &str) -> bool {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"));
+    rest.is_some_and(|host| !host.is_empty() && !host.starts_with('/'))
+}
+
+/// 检查快捷键格式，无法识别时返回提示信息
+pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
+    if is_valid_shortcut(shortcut) {
+        Ok(())
+    } else {
+        Err(format!(
+            "快捷键 \"{}\" 无法识别，格式应为修饰键加按键，例如 Alt+Shift+L",
+            shortcut
+        ))
+    }
+}
+
+/// 检查快捷键格式是否可以被全局快捷键注册识别
+///
+/// 格式为若干修饰键加一个按键，以 `+` 分隔，例如 `CmdOrCtrl+Shift+L`
+fn is_valid_shortcut(shortcut: &str) -> bool {
+    const MODIFIERS: &[&str] = &[
+        "ALT",
+        "OPTION",
+        "SHIFT",
+        "CTRL",
+        "CONTROL",
+        "CMD",
+        "COMMAND",
+        "SUPER",
+        "META",
+        "CMDORCTRL",
+        "CMDORCONTROL",
+        "COMMANDORCTRL",
+        "COMMANDORCONTROL",
+    ];
+    const NAMED_KEYS: &[&str] = &[
+        "SPACE",
+        "TAB",
+        "ENTER",
+        "RETURN",
+        "BACKSPACE",
+        "DELETE",
+        "ESC",
+        "ESCAPE",
+        "INSERT",
+        "HOME",
+        "END",
+        "PAGEUP",
+        "PAGEDOWN",
+        "UP",
+        "DOWN",
+        "LEFT",
+        "RIGHT",
+        "PLUS",
+    ];
+
+    let parts: Vec<String> = shortcut
+        .split('+')
+        .map(|part| part.trim().to_uppercase())
+        .collect();
+    let Some((key, modifiers)) = parts.split_last() else {
+        return false;
+    };
+
+    if !modifiers.iter().all(|m| MODIFIERS.contains(&m.as_str())) {
+        return false;
+    }
+
+    let is_function_key = key
+        .strip_prefix('F')
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=24).contains(&n));
+    let is_single_char = key.chars().count() == 1 && key.chars().all(|c| c.is_ascii_graphic());
+
+    is_function_key || is_single_char || NAMED_KEYS.contains(&key.as_str())
+}
+
 /// 获取系统 Git 用户名
 fn get_system_git_author() -> Result<String, AppError> {
     let config = git2::Config::open_default()?;
     let name = config.get_string("user.name")?;
     Ok(name)
 }
+
+/// 获取系统 Git 用户邮箱
+fn get_system_git_email() -> Result<String, AppError> {
+    let config = git2::Config::open_default()?;
+    let email = config.get_string("user.email")?;
+    Ok(email)
+}
+
+/// 诊断项可以自动执行的修复
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiagnosisFix {
+    /// 在该位置写入默认设置
+    CreateSettingsFile { path: PathBuf },
+    /// 创建缺失的目录
+    CreateDir { path: PathBuf },
+}
+
+impl DiagnosisFix {
+    /// 修复操作的说明
+    pub fn describe(&self) -> String {
+        match self {
+            DiagnosisFix::CreateSettingsFile { path } => {
+                Message::FixCreateSettingsFile(&path.display()).to_string()
+            }
+            DiagnosisFix::CreateDir { path } => Message::FixCreateDir(&path.display()).to_string(),
+        }
+    }
+}
+
+/// 单项诊断结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisCheck {
+    /// 检查项标识，如 `settings_file`、`log_storage_dir`
+    pub id: String,
+    /// 检查项名称
+    pub name: String,
+    /// 是否通过
+    pub passed: bool,
+    /// 检查结果说明
+    pub message: String,
+    /// 未通过时的处理建议
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// 可以通过 `apply_fixes` 自动执行的修复
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<DiagnosisFix>,
+}
+
+impl DiagnosisCheck {
+    fn pass(id: &str, name: Message<'_>, message: impl ToString) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            passed: true,
+            message: message.to_string(),
+            suggestion: None,
+            fix: None,
+        }
+    }
+
+    fn fail(id: &str, name: Message<'_>, message: impl ToString, suggestion: Message<'_>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            passed: false,
+            message: message.to_string(),
+            suggestion: Some(suggestion.to_string()),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: DiagnosisFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// 配置诊断报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisReport {
+    /// 使用的设置档案，默认档案为空
+    pub profile: Option<String>,
+    /// 设置文件路径
+    pub settings_path: PathBuf,
+    /// 诊断使用的设置，API 密钥已遮盖；设置文件无法解析时为空
+    pub settings: Option<Settings>,
+    /// 各项检查的结果
+    pub checks: Vec<DiagnosisCheck>,
+    /// 日志存储目录中不符合文件名格式、不会出现在日志列表中的 JSON 文件
+    pub unrecognized_files: Vec<PathBuf>,
+}
+
+impl DiagnosisReport {
+    /// 未通过的检查项数量
+    pub fn problem_count(&self) -> usize {
+        self.checks.iter().filter(|check| !check.passed).count()
+    }
+
+    /// 可以自动执行的修复
+    pub fn fixes(&self) -> Vec<&DiagnosisFix> {
+        self.checks
+            .iter()
+            .filter_map(|check| check.fix.as_ref())
+            .collect()
+    }
+}
+
+/// 诊断当前档案的配置，不修改任何文件
+pub fn diagnose() -> DiagnosisReport {
+    diagnose_profile(Settings::active_profile().as_deref())
+}
+
+/// 诊断指定档案的配置：设置文件能否读取、设置是否有效、日志目录是否存在且可写、存储后端能否读取
+///
+/// 只读取文件，缺失的目录和设置文件记录为可修复的问题，由 `apply_fixes` 处理
+pub fn diagnose_profile(profile: Option<&str>) -> DiagnosisReport {
+    diagnose_settings_file(Settings::get_settings_path(profile), profile)
+}
+
+/// 按指定的设置文件诊断档案的配置，检查项与 `diagnose_profile` 相同
+pub fn diagnose_settings_file(settings_path: PathBuf, profile: Option<&str>) -> DiagnosisReport {
+    let mut report = DiagnosisReport {
+        profile: profile.map(str::to_string),
+        settings_path: settings_path.clone(),
+        settings: None,
+        checks: Vec::new(),
+        unrecognized_files: Vec::new(),
+    };
+
+    if let Some(Err(e)) = profile.map(Settings::validate_profile_name) {
+        report.checks.push(DiagnosisCheck::fail(
+            "profile",
+            Message::CheckNameProfile,
+            e,
+            Message::ProfileNameHint,
+        ));
+        return report;
+    }
+
+    // 检查设置文件，无法解析时后续检查没有可用的设置
+    let settings = if settings_path.exists() {
+        let parsed = fs::read_to_string(&settings_path)
+            .map_err(AppError::from)
+            .and_then(|content| Ok(serde_json::from_str::<Settings>(&content)?));
+        match parsed {
+            Ok(settings) => {
+                report.checks.push(DiagnosisCheck::pass(
+                    "settings_file",
+                    Message::CheckNameSettingsFile,
+                    Message::SettingsFileParsed,
+                ));
+                settings
+            }
+            Err(e) => {
+                report.checks.push(DiagnosisCheck::fail(
+                    "settings_file",
+                    Message::CheckNameSettingsFile,
+                    Message::SettingsFileParseFailed(&e),
+                    Message::SettingsFileFixHint,
+                ));
+                return report;
+            }
+        }
+    } else {
+        report.checks.push(
+            DiagnosisCheck::fail(
+                "settings_file",
+                Message::CheckNameSettingsFile,
+                Message::SettingsFileMissing,
+                Message::CreateSettingsFileHint,
+            )
+            .with_fix(DiagnosisFix::CreateSettingsFile {
+                path: settings_path.clone(),
+            }),
+        );
+        Settings {
+            profile: profile.map(str::to_string),
+            ..Settings::default()
+        }
+    };
+
+    report.checks.push(match settings.validate() {
+        Ok(()) => DiagnosisCheck::pass(
+            "settings_valid",
+            Message::CheckNameSettingsContent,
+            Message::SettingsValid,
+        ),
+        Err(problems) => DiagnosisCheck::fail(
+            "settings_valid",
+            Message::CheckNameSettingsContent,
+            problems.join("\n"),
+            Message::FixSettingsHint,
+        ),
+    });
+
+    let dirs = [
+        (
+            "log_storage_dir",
+            Message::CheckNameStorageDir,
+            &settings.log_storage_dir,
+        ),
+        (
+            "log_output_dir",
+            Message::CheckNameOutputDir,
+            &settings.log_output_dir,
+        ),
+    ];
+    let mut dirs_ok = true;
+    for (id, name, dir) in dirs {
+        let check = diagnose_dir(id, name, Path::new(dir));
+        dirs_ok &= check.passed;
+        report.checks.push(check);
+    }
+
+    // 读取日志时会创建缺失的目录，目录都正常时才检查存储后端
+    if dirs_ok {
+        let log_manager = LogManager::new(settings.clone());
+        report.checks.push(match log_manager.get_log_files() {
+            Ok(files) => DiagnosisCheck::pass(
+                "storage_backend",
+                Message::CheckNameStorage,
+                Message::StorageReadable {
+                    backend: &settings.storage_backend,
+                    days: files.len(),
+                },
+            ),
+            Err(e) => DiagnosisCheck::fail(
+                "storage_backend",
+                Message::CheckNameStorage,
+                Message::ReadLogsFailed(&e),
+                Message::StorageHint,
+            ),
+        });
+        report.unrecognized_files = log_manager.list_unrecognized_files().unwrap_or_default();
+    }
+
+    report.settings = Some(settings.redacted());
+    report
+}
+
+/// 检查目录是否存在且可写
+fn diagnose_dir(id: &str, name: Message<'_>, path: &Path) -> DiagnosisCheck {
+    if !path.exists() {
+        return DiagnosisCheck::fail(
+            id,
+            name,
+            Message::DirMissing(&path.display()),
+            Message::CreateDirHint,
+        )
+        .with_fix(DiagnosisFix::CreateDir {
+            path: path.to_path_buf(),
+        });
+    }
+
+    if !path.is_dir() {
+        return DiagnosisCheck::fail(
+            id,
+            name,
+            Message::NotADir(&path.display()),
+            Message::NotADirHint,
+        );
+    }
+
+    match check_dir_writable(path) {
+        Ok(()) => DiagnosisCheck::pass(id, name, Message::DirWritable(&path.display())),
+        Err(e) => DiagnosisCheck::fail(
+            id,
+            name,
+            Message::DirNotWritable(&e),
+            Message::DirPermissionHint,
+        ),
+    }
+}
+
+/// 通过写入并删除临时文件检查目录是否可写
+fn check_dir_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(".work-record-write-test");
+    fs::write(&probe, b"")?;
+    fs::remove_file(probe)
+}
+
+/// 执行诊断报告中可以自动完成的修复，返回已执行操作的说明
+///
+/// 已存在的设置文件和目录不会被覆盖，执行后可以重新诊断确认结果
+pub fn apply_fixes(report: &DiagnosisReport) -> Result<Vec<String>, AppError> {
+    let mut applied = Vec::new();
+
+    for fix in report.fixes() {
+        match fix {
+            DiagnosisFix::CreateSettingsFile { path } => {
+                if path.exists() {
+                    continue;
+                }
+                Settings {
+                    profile: report.profile.clone(),
+                    config_path: Some(path.clone()),
+                    ..Settings::default()
+                }
+                .save()?;
+            }
+            DiagnosisFix::CreateDir { path } => {
+                if path.exists() {
+                    continue;
+                }
+                fs::create_dir_all(path)?;
+            }
+        }
+        applied.push(fix.describe());
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn legacy_settings_file_is_migrated() {
+        let dir = TempDir::new().unwrap();
+        let logs = dir.path().join("logs").to_string_lossy().to_string();
+        let output = dir.path().join("output").to_string_lossy().to_string();
+        let path = dir.path().join("settings.json");
+        // 引入版本号之前的设置文件，只有部分字段
+        let legacy = serde_json::json!({
+            "log_storage_dir": logs,
+            "log_output_dir": output,
+            "ollama_model": "qwen2:7b",
+            "enable_shortcut": false,
+        });
+        fs::write(&path, legacy.to_string()).unwrap();
+
+        let settings = Settings::load_from_path(&path, None).unwrap();
+
+        let expected = Settings {
+            log_storage_dir: logs,
+            log_output_dir: output,
+            ollama_model: "qwen2:7b".to_string(),
+            enable_shortcut: false,
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            ..Settings::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&settings).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+
+        // 升级后的设置写回了文件
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["schema_version"], SETTINGS_SCHEMA_VERSION);
+        assert_eq!(saved["ollama_model"], "qwen2:7b");
+        assert_eq!(saved["storage_backend"], STORAGE_BACKEND_DAILY);
+    }
+}
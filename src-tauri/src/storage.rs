@@ -0,0 +1,733 @@
+//! 日志记录的存储后端
+//!
+//! `LogManager` 通过 `StorageBackend` 读写日志记录，目录锁、预演模式和回收站仍由管理器负责。
+//! 设置中的 `storage_backend` 选择具体的存储方式：
+//! - `daily`：每天一个 JSON 文件（默认），实现见 `log_manager::DailyFileStorage`
+//! - `jsonl`：所有记录保存在一个 JSON Lines 文件中，每行一条记录
+//! - `sqlite`：所有记录保存在一个 SQLite 数据库中，按日期建立索引，内容和标签建立全文索引；
+//!   需要启用 `sqlite` 特性（默认启用），未启用时改用每日文件存储
+
+use crate::errors::AppError;
+use crate::log_manager::{
+    date_key, file_modified_time, ChangeRecorder, DailyFileStorage, LogEntry, LogFileInfo,
+};
+use crate::settings::{Settings, STORAGE_BACKEND_JSONL, STORAGE_BACKEND_SQLITE};
+use chrono::NaiveDate;
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, Transaction};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+#[cfg(feature = "sqlite")]
+use std::sync::Mutex;
+
+/// JSON Lines 存储的文件名，位于日志存储目录下
+pub const JSONL_FILE_NAME: &str = "log.jsonl";
+
+/// SQLite 存储的数据库文件名，位于日志存储目录下
+pub const SQLITE_FILE_NAME: &str = "log.sqlite3";
+
+/// SQLite 数据库的表结构
+///
+/// `entries.data` 保存完整的记录 JSON，`position` 为记录在当天的顺序；
+/// `entries_fts` 的 rowid 与 `entries.seq` 对应，使用 trigram 分词以支持中文的子串匹配
+#[cfg(feature = "sqlite")]
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS entries (
+    seq INTEGER PRIMARY KEY,
+    id TEXT NOT NULL,
+    date TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS entries_date ON entries (date, position);
+CREATE VIRTUAL TABLE IF NOT EXISTS entries_fts USING fts5(content, tags, tokenize = 'trigram');
+";
+
+/// trigram 全文索引能匹配的最短关键字长度，更短的关键字逐条比较
+#[cfg(feature = "sqlite")]
+const FTS_MIN_QUERY_CHARS: usize = 3;
+
+/// 日志记录的存储后端
+///
+/// 除 `read_day_unlocked` 外，所有方法都假定调用方已持有日志目录锁；写入类方法在预演模式下只记录计划的改动
+pub trait StorageBackend: Send + Sync {
+    /// 保存指定日期日志的文件路径
+    fn day_path(&self, date: &NaiveDate) -> PathBuf;
+
+    /// 读取指定日期的日志记录，按保存的顺序返回
+    fn read_day(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError>;
+
+    /// 不持有目录锁读取指定日期的日志记录
+    ///
+    /// 数据需要修复才能读取时返回 `None`，由调用方获取目录锁后改用 `read_day`；默认直接调用 `read_day`
+    fn read_day_unlocked(&self, date: &NaiveDate) -> Result<Option<Vec<LogEntry>>, AppError> {
+        self.read_day(date).map(Some)
+    }
+
+    /// 用给定的记录替换指定日期的全部日志，记录为空时删除当天的数据
+    fn write_day(&self, date: &NaiveDate, entries: &[LogEntry]) -> Result<(), AppError>;
+
+    /// 在指定日期的末尾追加一条记录
+    fn append_entry(&self, date: &NaiveDate, entry: LogEntry) -> Result<(), AppError> {
+        let mut entries = self.read_day(date)?;
+        entries.push(entry);
+        self.write_day(date, &entries)
+    }
+
+    /// 有日志记录的所有日期，从早到晚排列
+    fn list_dates(&self) -> Result<Vec<NaiveDate>, AppError>;
+
+    /// 读取所有日期的日志记录，按日期从新到旧排列
+    fn load_all(&self) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError>;
+
+    /// 指定日期的数据最后修改的时间 (RFC 3339)，无法获取时返回空字符串
+    fn last_modified(&self, date: &NaiveDate) -> String;
+
+    /// 可能包含关键字的记录，按日期从新到旧排列
+    ///
+    /// 后端可以借助索引缩小范围，返回的记录不一定都匹配，由调用方按搜索选项逐条比较；
+    /// 默认返回所有记录
+    fn search_candidates(&self, _query: &str) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        self.load_all()
+    }
+
+    /// 所有日期的日志条数和修改时间，按日期从新到旧排列
+    fn summaries(&self) -> Result<Vec<LogFileInfo>, AppError> {
+        Ok(self
+            .load_all()?
+            .into_iter()
+            .map(|(date, entries)| LogFileInfo {
+                date: date_key(&date),
+                entry_count: entries.len(),
+                last_modified: self.last_modified(&date),
+            })
+            .collect())
+    }
+}
+
+/// 按设置创建存储后端
+pub(crate) fn create_storage(
+    settings: &Settings,
+    changes: Arc<ChangeRecorder>,
+) -> Box<dyn StorageBackend> {
+    let dir = Path::new(&settings.log_storage_dir);
+    match settings.storage_backend.as_str() {
+        STORAGE_BACKEND_JSONL => {
+            Box::new(JsonLinesStorage::new(dir.join(JSONL_FILE_NAME), changes))
+        }
+        #[cfg(feature = "sqlite")]
+        STORAGE_BACKEND_SQLITE => Box::new(SqliteStorage::new(dir.join(SQLITE_FILE_NAME), changes)),
+        #[cfg(not(feature = "sqlite"))]
+        STORAGE_BACKEND_SQLITE => {
+            log::warn!("未启用 sqlite 特性，改用每日文件存储");
+            Box::new(DailyFileStorage::new(settings.clone(), changes))
+        }
+        _ => Box::new(DailyFileStorage::new(settings.clone(), changes)),
+    }
+}
+
+/// 所有日志记录保存在一个 JSON Lines 文件中的存储后端
+///
+/// 添加记录时直接追加一行，修改和删除时重写整个文件。记录所属的日期由创建时间决定，
+/// 无法解析的行在读取时跳过并记录警告，重写文件时原样保留
+pub struct JsonLinesStorage {
+    path: PathBuf,
+    changes: Arc<ChangeRecorder>,
+}
+
+/// JSON Lines 文件中的一行
+struct Line {
+    /// 行的原文，重写文件时其他日期的行原样写回
+    raw: String,
+    /// 解析出的记录及其所属日期，无法解析时为空
+    entry: Option<(NaiveDate, LogEntry)>,
+}
+
+impl JsonLinesStorage {
+    pub(crate) fn new(path: PathBuf, changes: Arc<ChangeRecorder>) -> Self {
+        Self { path, changes }
+    }
+
+    /// 读取文件中的所有行，文件不存在时返回空列表
+    fn read_lines(&self) -> Result<Vec<Line>, AppError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        let lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let entry = serde_json::from_str::<LogEntry>(line)
+                    .map_err(AppError::from)
+                    .and_then(|entry| Ok((entry.local_date()?, entry)))
+                    .map_err(|e| {
+                        log::warn!(
+                            "跳过 {} 第 {} 行无法解析的记录: {}",
+                            self.path.display(),
+                            index + 1,
+                            e
+                        );
+                    })
+                    .ok();
+                Line {
+                    raw: line.to_string(),
+                    entry,
+                }
+            })
+            .collect();
+
+        Ok(lines)
+    }
+
+    /// 按日期分组所有能解析的记录，同一天的记录保持文件中的顺序
+    fn read_days(&self) -> Result<BTreeMap<NaiveDate, Vec<LogEntry>>, AppError> {
+        let mut days: BTreeMap<NaiveDate, Vec<LogEntry>> = BTreeMap::new();
+        for line in self.read_lines()? {
+            if let Some((date, entry)) = line.entry {
+                days.entry(date).or_default().push(entry);
+            }
+        }
+
+        Ok(days)
+    }
+
+    /// 先写入同目录下的临时文件再重命名覆盖整个文件
+    fn write_lines(&self, lines: &[String]) -> Result<(), AppError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = lines.join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &self.path).map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            AppError::from(e)
+        })?;
+
+        Ok(())
+    }
+}
+
+impl StorageBackend for JsonLinesStorage {
+    fn day_path(&self, _date: &NaiveDate) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn read_day(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        Ok(self.read_days()?.remove(date).unwrap_or_default())
+    }
+
+    /// 当天的新记录写在原来第一条记录所在的位置，其他日期的行和无法解析的行原样保留；
+    /// 当天原来没有记录时写在末尾
+    fn write_day(&self, date: &NaiveDate, entries: &[LogEntry]) -> Result<(), AppError> {
+        let mut day_lines = entries
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut lines = Vec::new();
+        for line in self.read_lines()? {
+            match line.entry {
+                Some((line_date, _)) if line_date == *date => lines.append(&mut day_lines),
+                _ => lines.push(line.raw),
+            }
+        }
+        lines.append(&mut day_lines);
+
+        self.changes
+            .commit_write(&self.path, || self.write_lines(&lines))
+    }
+
+    /// 以追加方式写入一行，不读取也不改动已有的内容
+    fn append_entry(&self, _date: &NaiveDate, entry: LogEntry) -> Result<(), AppError> {
+        let line = serde_json::to_string(&entry)?;
+
+        self.changes.commit_write(&self.path, || {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let mut file = OpenOptions::new()
+                .create(true)
+                .read(true)
+                .append(true)
+                .open(&self.path)?;
+
+            // 文件末尾缺少换行时先补上，避免与上一条记录写在同一行
+            let mut last = [b'\n'];
+            if file.metadata()?.len() > 0 {
+                file.seek(SeekFrom::End(-1))?;
+                file.read_exact(&mut last)?;
+            }
+            if last[0] != b'\n' {
+                writeln!(file)?;
+            }
+            writeln!(file, "{}", line)?;
+            Ok(())
+        })
+    }
+
+    fn list_dates(&self) -> Result<Vec<NaiveDate>, AppError> {
+        Ok(self.read_days()?.into_keys().collect())
+    }
+
+    fn load_all(&self) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        Ok(self.read_days()?.into_iter().rev().collect())
+    }
+
+    /// 所有日期共用一个文件，返回该文件的修改时间
+    fn last_modified(&self, _date: &NaiveDate) -> String {
+        file_modified_time(&self.path)
+    }
+}
+
+/// 所有日志记录保存在一个 SQLite 数据库中的存储后端
+///
+/// 按日期读取走索引，搜索时先用全文索引筛选候选记录；数据库文件在第一次写入时创建，
+/// 连接在第一次使用时打开并一直保留
+#[cfg(feature = "sqlite")]
+pub struct SqliteStorage {
+    path: PathBuf,
+    conn: Mutex<Option<Connection>>,
+    changes: Arc<ChangeRecorder>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStorage {
+    pub(crate) fn new(path: PathBuf, changes: Arc<ChangeRecorder>) -> Self {
+        Self {
+            path,
+            conn: Mutex::new(None),
+            changes,
+        }
+    }
+
+    /// 使用数据库连接，连接尚未打开时打开数据库并建表
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, AppError> {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let conn = match conn.as_mut() {
+            Some(conn) => conn,
+            None => {
+                if let Some(parent) = self.path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let opened = Connection::open(&self.path)?;
+                opened.execute_batch(SQLITE_SCHEMA)?;
+                conn.insert(opened)
+            }
+        };
+
+        Ok(f(conn)?)
+    }
+
+    /// 执行查询，数据库文件不存在时返回空结果而不创建文件
+    fn query<T: Default>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, AppError> {
+        if !self.path.exists() {
+            return Ok(T::default());
+        }
+        self.with_conn(f)
+    }
+
+    /// 在事务中执行写入，预演模式下只记录计划
+    fn write(&self, f: impl FnOnce(&Transaction) -> rusqlite::Result<()>) -> Result<(), AppError> {
+        self.changes.commit_write(&self.path, || {
+            self.with_conn(|conn| {
+                let tx = conn.transaction()?;
+                f(&tx)?;
+                tx.commit()
+            })
+        })
+    }
+
+    /// 按 `(日期, 记录 JSON)` 的查询结果解析记录，同一天的记录合并在一起并保持查询的顺序
+    fn parse_rows(
+        rows: Vec<(String, String)>,
+    ) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        let mut days: Vec<(NaiveDate, Vec<LogEntry>)> = Vec::new();
+        for (date, data) in rows {
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")?;
+            let entry = serde_json::from_str(&data)?;
+            match days.last_mut() {
+                Some((last, entries)) if *last == date => entries.push(entry),
+                _ => days.push((date, vec![entry])),
+            }
+        }
+
+        Ok(days)
+    }
+
+    /// 查询 `(日期, 记录 JSON)` 形式的结果
+    fn query_rows(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        let rows = self.query(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map(params, |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })?;
+
+        Self::parse_rows(rows)
+    }
+}
+
+/// 插入一条记录及其全文索引
+#[cfg(feature = "sqlite")]
+fn insert_entry(
+    tx: &Transaction,
+    date: &str,
+    position: i64,
+    entry: &LogEntry,
+    data: &str,
+) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO entries (id, date, position, data) VALUES (?1, ?2, ?3, ?4)",
+        params![entry.id, date, position, data],
+    )?;
+    tx.execute(
+        "INSERT INTO entries_fts (rowid, content, tags) VALUES (?1, ?2, ?3)",
+        params![tx.last_insert_rowid(), entry.content, entry.tags.join(" ")],
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+impl StorageBackend for SqliteStorage {
+    fn day_path(&self, _date: &NaiveDate) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn read_day(&self, date: &NaiveDate) -> Result<Vec<LogEntry>, AppError> {
+        let mut days = self.query_rows(
+            "SELECT date, data FROM entries WHERE date = ?1 ORDER BY position",
+            [date_key(date)],
+        )?;
+        Ok(days.pop().map(|(_, entries)| entries).unwrap_or_default())
+    }
+
+    /// 删除当天原有的记录后按顺序重新插入，整个过程在一个事务中完成
+    fn write_day(&self, date: &NaiveDate, entries: &[LogEntry]) -> Result<(), AppError> {
+        let key = date_key(date);
+        let rows = entries
+            .iter()
+            .map(|entry| Ok((entry, serde_json::to_string(entry)?)))
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        self.write(|tx| {
+            tx.execute(
+                "DELETE FROM entries_fts WHERE rowid IN (SELECT seq FROM entries WHERE date = ?1)",
+                [&key],
+            )?;
+            tx.execute("DELETE FROM entries WHERE date = ?1", [&key])?;
+            for (position, (entry, data)) in rows.iter().enumerate() {
+                insert_entry(tx, &key, position as i64, entry, data)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// 直接插入一条记录，不读取当天已有的记录
+    fn append_entry(&self, date: &NaiveDate, entry: LogEntry) -> Result<(), AppError> {
+        let key = date_key(date);
+        let data = serde_json::to_string(&entry)?;
+
+        self.write(|tx| {
+            let position: i64 = tx.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM entries WHERE date = ?1",
+                [&key],
+                |row| row.get(0),
+            )?;
+            insert_entry(tx, &key, position, &entry, &data)
+        })
+    }
+
+    fn list_dates(&self) -> Result<Vec<NaiveDate>, AppError> {
+        let dates: Vec<String> = self.query(|conn| {
+            let mut stmt = conn.prepare("SELECT DISTINCT date FROM entries ORDER BY date")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        Ok(dates
+            .iter()
+            .map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn load_all(&self) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        self.query_rows(
+            "SELECT date, data FROM entries ORDER BY date DESC, position",
+            [],
+        )
+    }
+
+    fn last_modified(&self, _date: &NaiveDate) -> String {
+        file_modified_time(&self.path)
+    }
+
+    /// 关键字足够长时用全文索引筛选，全文索引不区分大小写，返回的记录可能多于实际匹配的记录
+    fn search_candidates(&self, query: &str) -> Result<Vec<(NaiveDate, Vec<LogEntry>)>, AppError> {
+        if query.chars().count() < FTS_MIN_QUERY_CHARS {
+            return self.load_all();
+        }
+
+        // 作为短语匹配，避免关键字中的运算符被解析
+        let phrase = format!("\"{}\"", query.replace('"', "\"\""));
+        self.query_rows(
+            "SELECT date, data FROM entries \
+             WHERE seq IN (SELECT rowid FROM entries_fts WHERE entries_fts MATCH ?1) \
+             ORDER BY date DESC, position",
+            [phrase],
+        )
+    }
+
+    /// 直接按日期统计条数，不读取记录内容
+    fn summaries(&self) -> Result<Vec<LogFileInfo>, AppError> {
+        let counts: Vec<(String, i64)> = self.query(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT date, COUNT(*) FROM entries GROUP BY date ORDER BY date DESC")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })?;
+
+        let last_modified = file_modified_time(&self.path);
+        Ok(counts
+            .into_iter()
+            .map(|(date, count)| LogFileInfo {
+                date,
+                entry_count: count as usize,
+                last_modified: last_modified.clone(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_manager::LogManager;
+    use chrono::{Local, TimeZone};
+    use tempfile::TempDir;
+
+    /// 使用指定存储方式、以临时目录保存日志的设置
+    fn test_settings(dir: &TempDir, backend: &str) -> Settings {
+        Settings {
+            log_storage_dir: dir.path().to_string_lossy().to_string(),
+            log_output_dir: dir.path().join("output").to_string_lossy().to_string(),
+            storage_backend: backend.to_string(),
+            ..Settings::default()
+        }
+    }
+
+    fn manager_with_backend(backend: &str) -> (TempDir, LogManager) {
+        let dir = TempDir::new().unwrap();
+        let manager = LogManager::new(test_settings(&dir, backend));
+        (dir, manager)
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// 指定日期本地时间 `hour` 点创建的记录
+    fn entry_at(content: &str, day: NaiveDate, hour: u32) -> LogEntry {
+        let created_at = Local
+            .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+            .unwrap();
+        LogEntry::new_with_datetime(
+            content.to_string(),
+            "manual".to_string(),
+            Vec::new(),
+            created_at,
+        )
+    }
+
+    fn contents(entries: &[LogEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.content.as_str()).collect()
+    }
+
+    #[test]
+    fn json_lines_storage_round_trips_through_log_manager() {
+        let (dir, manager) = manager_with_backend(STORAGE_BACKEND_JSONL);
+        let path = dir.path().join(JSONL_FILE_NAME);
+        let (may_1, may_2) = (date(2024, 5, 1), date(2024, 5, 2));
+
+        let review = entry_at("评审代码", may_1, 9);
+        let fix = entry_at("修复登录问题", may_1, 14);
+        let release = entry_at("发布版本", may_2, 10);
+        for entry in [review.clone(), fix.clone(), release.clone()] {
+            manager.add_entry(entry).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+        assert!(!dir.path().join("2024-05-01.json").exists());
+
+        // 无法解析的行在读取时跳过，重写文件时保留
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{not json").unwrap();
+        drop(file);
+
+        let mut updated = fix.clone();
+        updated.content = "修复登录和注册问题".to_string();
+        manager.update_entry(updated).unwrap();
+        manager.delete_entry(&review.id, &may_1, true).unwrap();
+
+        assert_eq!(
+            contents(&manager.get_entries_for_date(&may_1).unwrap()),
+            ["修复登录和注册问题"]
+        );
+        assert_eq!(
+            contents(&manager.get_entries_for_date(&may_2).unwrap()),
+            ["发布版本"]
+        );
+        assert!(manager
+            .get_entries_for_date(&date(2024, 5, 3))
+            .unwrap()
+            .is_empty());
+
+        let range = manager
+            .get_entries_in_date_range(&may_1, &date(2024, 5, 7))
+            .unwrap();
+        assert_eq!(range.len(), 2);
+        assert_eq!(contents(&range["2024-05-01"]), ["修复登录和注册问题"]);
+        assert_eq!(contents(&range["2024-05-02"]), ["发布版本"]);
+
+        let file = fs::read_to_string(&path).unwrap();
+        assert_eq!(file.lines().count(), 3);
+        assert!(file.lines().any(|line| line == "{not json"));
+        assert!(!file.contains("评审代码"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_storage_round_trips_through_log_manager() {
+        let (dir, manager) = manager_with_backend(STORAGE_BACKEND_SQLITE);
+        let (may_1, may_2) = (date(2024, 5, 1), date(2024, 5, 2));
+        assert!(manager.get_entries_for_date(&may_1).unwrap().is_empty());
+        assert!(!dir.path().join(SQLITE_FILE_NAME).exists());
+
+        let review = entry_at("评审代码", may_1, 9);
+        let fix = entry_at("修复登录问题", may_1, 14);
+        for entry in [review.clone(), fix.clone(), entry_at("发布版本", may_2, 10)] {
+            manager.add_entry(entry).unwrap();
+        }
+        assert!(dir.path().join(SQLITE_FILE_NAME).exists());
+
+        let mut updated = fix;
+        updated.content = "修复登录和注册问题".to_string();
+        manager.update_entry(updated).unwrap();
+        manager.delete_entry(&review.id, &may_1, true).unwrap();
+
+        assert_eq!(
+            contents(&manager.get_entries_for_date(&may_1).unwrap()),
+            ["修复登录和注册问题"]
+        );
+        let range = manager
+            .get_entries_in_date_range(&may_1, &date(2024, 5, 7))
+            .unwrap();
+        assert_eq!(range.len(), 2);
+        assert_eq!(contents(&range["2024-05-02"]), ["发布版本"]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_search_uses_the_full_text_index() {
+        let dir = TempDir::new().unwrap();
+        let storage = SqliteStorage::new(
+            dir.path().join(SQLITE_FILE_NAME),
+            Arc::new(ChangeRecorder::default()),
+        );
+        let (may_1, may_2) = (date(2024, 5, 1), date(2024, 5, 2));
+        let mut tagged = entry_at("评审代码", may_1, 9);
+        tagged.tags = vec!["Backend".to_string()];
+        storage.append_entry(&may_1, tagged).unwrap();
+        storage
+            .append_entry(&may_1, entry_at("修复登录问题", may_1, 14))
+            .unwrap();
+        storage
+            .append_entry(&may_2, entry_at("Release Login page", may_2, 10))
+            .unwrap();
+
+        let candidates = |query: &str| -> Vec<Vec<String>> {
+            storage
+                .search_candidates(query)
+                .unwrap()
+                .into_iter()
+                .map(|(_, entries)| entries.into_iter().map(|entry| entry.content).collect())
+                .collect()
+        };
+
+        // 关键字足够长时只返回全文索引匹配的记录，标签也会被索引，匹配不区分大小写
+        assert_eq!(candidates("登录问题"), [vec!["修复登录问题"]]);
+        assert_eq!(candidates("backend"), [vec!["评审代码"]]);
+        assert_eq!(candidates("LOGIN"), [vec!["Release Login page"]]);
+        assert!(candidates("不存在的内容").is_empty());
+
+        // 关键字过短时返回所有记录，按日期从新到旧排列
+        assert_eq!(
+            candidates("登录"),
+            [vec!["Release Login page"], vec!["评审代码", "修复登录问题"]]
+        );
+
+        // 关键字中的引号和运算符按普通文字匹配
+        assert!(candidates("\"登录 OR").is_empty());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn migrate_to_sqlite_imports_daily_files_once() {
+        let (dir, daily) = manager_with_backend("daily");
+        let (may_1, may_2) = (date(2024, 5, 1), date(2024, 5, 2));
+        for entry in [
+            entry_at("评审代码", may_1, 9),
+            entry_at("修复登录问题", may_1, 14),
+            entry_at("发布版本", may_2, 10),
+        ] {
+            daily.add_entry(entry).unwrap();
+        }
+
+        // 预演模式不创建数据库
+        let report = LogManager::new(test_settings(&dir, "daily"))
+            .with_dry_run(true)
+            .migrate_to_sqlite()
+            .unwrap();
+        assert_eq!(report.added, 3);
+        assert!(!daily.sqlite_path().exists());
+
+        let report = daily.migrate_to_sqlite().unwrap();
+        assert_eq!((report.added, report.skipped), (3, 0));
+        assert_eq!(daily.sqlite_path(), dir.path().join(SQLITE_FILE_NAME));
+        assert!(dir.path().join("2024-05-01.json").exists());
+
+        // 重复执行时跳过已导入的记录
+        let report = daily.migrate_to_sqlite().unwrap();
+        assert_eq!((report.added, report.skipped), (0, 3));
+
+        let sqlite = LogManager::new(test_settings(&dir, STORAGE_BACKEND_SQLITE));
+        assert_eq!(
+            contents(&sqlite.get_entries_for_date(&may_1).unwrap()),
+            ["评审代码", "修复登录问题"]
+        );
+        assert_eq!(
+            contents(&sqlite.get_entries_for_date(&may_2).unwrap()),
+            ["发布版本"]
+        );
+    }
+}
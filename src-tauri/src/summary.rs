@@ -1,17 +1,34 @@
 use crate::errors::AppError;
-use crate::log_manager::LogEntry;
-use crate::settings::Settings;
-use chrono::{Datelike, Local, NaiveDate};
+use crate::git_utils::{get_commits_in_range, GitCommit};
+use crate::log_manager::{
+    date_key, git_commit_entry_id, LogEntry, LogManager, GIT_COMMIT_SOURCE, SUMMARY_SOURCE,
+    SUMMARY_TAG,
+};
+use crate::messages::Message;
+use crate::redact::redact_text;
+use crate::settings::{
+    default_system_prompt, summary_language_name, Settings, SummaryPrompts, SUMMARY_FILE_DATE,
+    SUMMARY_FILE_END, SUMMARY_FILE_START, SUMMARY_FILE_TITLE, SUMMARY_FILE_TYPE, SUMMARY_LANGUAGE_AUTO,
+};
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{btree_map, BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
 use futures_util::StreamExt;
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum SummaryType {
+    /// 日摘要
+    Daily,
     /// 周摘要
     Weekly,
     /// 月摘要
@@ -22,6 +39,84 @@ pub enum SummaryType {
     Custom,
 }
 
+impl SummaryType {
+    /// 摘要类型的英文标识，用于文件名等场景
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Quarterly => "quarterly",
+            Self::Custom => "custom",
+        }
+    }
+    
+    /// 以 `today` 为当前日期计算摘要的日期范围（含首尾）
+    ///
+    /// 默认按自然周（周一至周日）、自然月和自然季度对齐；`rolling` 为 true 时改为倒推 7/30/90 天。
+    /// 自定义类型的范围由调用方指定，这里返回当天
+    pub fn date_range(&self, today: NaiveDate, rolling: bool) -> (NaiveDate, NaiveDate) {
+        match self {
+            Self::Daily => {
+                // 仅当天
+                (today, today)
+            }
+            Self::Weekly if rolling => {
+                // 从当前日期倒推7天
+                let start = today
+                    .checked_sub_days(Days::new(7))
+                    .unwrap_or(today);
+                (start, today)
+            }
+            Self::Weekly => {
+                // 当前 ISO 周的周一至周日
+                let start = today
+                    .checked_sub_days(Days::new(today.weekday().num_days_from_monday() as u64))
+                    .unwrap_or(today);
+                let end = start.checked_add_days(Days::new(6)).unwrap_or(today);
+                (start, end)
+            }
+            Self::Monthly if rolling => {
+                // 从当前日期倒推30天
+                let start = today
+                    .checked_sub_days(Days::new(30))
+                    .unwrap_or(today);
+                (start, today)
+            }
+            Self::Monthly => {
+                // 当月1日至月末
+                let start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today);
+                let end = start
+                    .checked_add_months(Months::new(1))
+                    .and_then(|date| date.pred_opt())
+                    .unwrap_or(today);
+                (start, end)
+            }
+            Self::Quarterly if rolling => {
+                // 从当前日期倒推90天
+                let start = today
+                    .checked_sub_days(Days::new(90))
+                    .unwrap_or(today);
+                (start, today)
+            }
+            Self::Quarterly => {
+                // 当前季度首日至季度末
+                let quarter_month = (today.month() - 1) / 3 * 3 + 1;
+                let start = NaiveDate::from_ymd_opt(today.year(), quarter_month, 1).unwrap_or(today);
+                let end = start
+                    .checked_add_months(Months::new(3))
+                    .and_then(|date| date.pred_opt())
+                    .unwrap_or(today);
+                (start, end)
+            }
+            Self::Custom => {
+                // 自定义类型会在函数外部处理
+                (today, today)
+            }
+        }
+    }
+}
+
 /// 摘要生成配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SummaryConfig {
@@ -33,6 +128,257 @@ pub struct SummaryConfig {
     pub end_date: Option<NaiveDate>,
     /// 摘要标题
     pub title: String,
+    /// 是否将日期范围内的 Git 提交一并纳入摘要
+    #[serde(default)]
+    pub include_git: bool,
+    /// 读取提交的 Git 仓库路径
+    #[serde(default)]
+    pub git_repo_paths: Vec<String>,
+    /// 摘要输出格式
+    #[serde(default)]
+    pub format: SummaryFormat,
+    /// 提示词中日志的分组方式
+    #[serde(default)]
+    pub group_by: GroupBy,
+}
+
+/// 提示词中日志的分组方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    /// 按日期分组，每天一段
+    #[default]
+    Date,
+    /// 按标签分组，带多个标签的日志出现在每个标签下，没有标签的日志归入「无标签」
+    Tag,
+    /// 按日志来源分组
+    Source,
+}
+
+impl GroupBy {
+    /// 按名称解析分组方式 (date, tag, source)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "date" => Some(Self::Date),
+            "tag" => Some(Self::Tag),
+            "source" => Some(Self::Source),
+            _ => None,
+        }
+    }
+}
+
+/// 摘要输出格式
+///
+/// 模型始终生成 Markdown，其他格式在生成完成后转换，缓存中保存的仍是 Markdown
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryFormat {
+    /// Markdown 原文
+    #[default]
+    Markdown,
+    /// 完整的 HTML 文档，可直接在浏览器中打开或打印为 PDF
+    Html,
+    /// 去掉 Markdown 标记的纯文本
+    PlainText,
+}
+
+impl SummaryFormat {
+    /// 保存摘要文件使用的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SummaryFormat::Markdown => "md",
+            SummaryFormat::Html => "html",
+            SummaryFormat::PlainText => "txt",
+        }
+    }
+
+    /// 将 Markdown 格式的摘要转换为当前格式，`title` 用作 HTML 文档标题
+    pub fn render(&self, markdown: &str, title: &str) -> String {
+        match self {
+            SummaryFormat::Markdown => markdown.to_string(),
+            SummaryFormat::Html => markdown_to_html_document(markdown, title),
+            SummaryFormat::PlainText => markdown_to_plain_text(markdown),
+        }
+    }
+}
+
+/// 摘要文件已存在时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFileConflict {
+    /// 在文件名后添加 `-2`、`-3` 等数字后缀，保留已有的摘要
+    #[default]
+    Suffix,
+    /// 覆盖已有的摘要文件
+    Overwrite,
+    /// 保留已有的摘要文件，不保存新生成的摘要
+    Skip,
+}
+
+impl SummaryFileConflict {
+    /// 按名称解析处理方式，名称与设置中的 `summary_file_conflict` 相同
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "suffix" => Some(Self::Suffix),
+            "overwrite" => Some(Self::Overwrite),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// HTML 摘要的样式，打印时去掉背景和页边距外的留白，便于导出 PDF
+const HTML_SUMMARY_STYLE: &str = "\
+body { max-width: 800px; margin: 2em auto; padding: 0 1em; font-family: -apple-system, \"PingFang SC\", \"Microsoft YaHei\", sans-serif; line-height: 1.6; color: #222; }
+h1, h2, h3 { line-height: 1.3; }
+code { background: #f4f4f4; padding: 0 0.2em; border-radius: 3px; }
+pre { background: #f4f4f4; padding: 0.8em; overflow-x: auto; }
+pre code { padding: 0; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; }
+@media print { body { max-width: none; margin: 0; } pre, code { background: none; } }";
+
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+/// 将 Markdown 转换为带样式的完整 HTML 文档
+fn markdown_to_html_document(markdown: &str, title: &str) -> String {
+    let mut body = String::new();
+    html::push_html(&mut body, Parser::new_ext(markdown, markdown_options()));
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        HTML_SUMMARY_STYLE,
+        body
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 去掉 Markdown 标记，只保留文字；列表项以 `- ` 开头，块之间以空行分隔
+fn markdown_to_plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+    let mut list_depth = 0usize;
+
+    for event in Parser::new_ext(markdown, markdown_options()) {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(&content),
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::TaskListMarker(done) => text.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::Start(Tag::List(_)) => {
+                if list_depth > 0 && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                list_depth += 1;
+            }
+            Event::End(Tag::List(_)) => {
+                list_depth -= 1;
+                if list_depth == 0 {
+                    text.push('\n');
+                }
+            }
+            Event::Start(Tag::Item) => {
+                text.push_str(&"  ".repeat(list_depth.saturating_sub(1)));
+                text.push_str("- ");
+            }
+            Event::End(Tag::Item) if !text.ends_with('\n') => text.push('\n'),
+            Event::End(Tag::TableCell) => text.push('\t'),
+            Event::End(Tag::TableHead | Tag::TableRow) => {
+                if text.ends_with('\t') {
+                    text.pop();
+                }
+                text.push('\n');
+            }
+            Event::End(Tag::Paragraph) if list_depth > 0 => text.push('\n'),
+            Event::End(
+                Tag::Paragraph
+                | Tag::Heading(..)
+                | Tag::CodeBlock(_)
+                | Tag::BlockQuote
+                | Tag::Table(_),
+            )
+            | Event::Rule => {
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+
+    text.trim_end().to_string() + "\n"
+}
+
+/// 提示词模板中的标题占位符
+const TITLE_PLACEHOLDER: &str = "{title}";
+
+/// 提示词模板中的日志内容占位符
+const LOGS_PLACEHOLDER: &str = "{logs}";
+
+/// 摘要缓存目录名，位于摘要输出目录下
+const SUMMARY_CACHE_DIR: &str = ".summary-cache";
+
+/// 流式生成的摘要在未配置文件名格式时使用的命名方式
+const STREAM_SUMMARY_FILENAME_PATTERN: &str = "{date}-{title}";
+
+/// 记录上次摘要结束日期的检查点文件名，位于摘要输出目录下
+const SUMMARY_CHECKPOINT_FILE: &str = ".summary-checkpoint.json";
+
+/// 重试的初始等待时间
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// 重试的最长等待时间
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Anthropic Messages API 版本
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+/// 调整摘要时作为对话开头的用户消息，之前的摘要作为对它的回复
+const REFINE_CONTEXT_PROMPT: &str = "请根据我的工作日志生成工作摘要。";
+
+/// 对比摘要的提示词，`{title}` 为摘要标题，两个时间段的日志追加在提示词之后
+const COMPARISON_PROMPT: &str = "请对比以下两个时间段的工作日志，生成对比摘要「{title}」。\
+以时间段 A 为基准，分析时间段 B 中新出现的工作重点、已停止或明显减少的工作、持续推进的事项，\
+以及工作量和方向上的整体趋势，最后给出简要结论和建议。";
+
+/// 对比摘要中的一个时间段，包含首尾两天
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SummaryPeriod {
+    /// 开始日期
+    pub start_date: NaiveDate,
+    /// 结束日期
+    pub end_date: NaiveDate,
+}
+
+impl SummaryPeriod {
+    /// 创建时间段，开始日期晚于结束日期时返回错误
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> Result<Self, AppError> {
+        if start_date > end_date {
+            return Err(AppError::SummaryError(Message::StartAfterEndDates {
+                start: &start_date.to_string(),
+                end: &end_date.to_string(),
+            }.to_string()));
+        }
+        Ok(Self { start_date, end_date })
+    }
+    
+    /// 时间段的显示文本，例如 `2024-05-01 至 2024-05-31`
+    pub fn label(&self) -> String {
+        format!(
+            "{} 至 {}",
+            self.start_date.format("%Y-%m-%d"),
+            self.end_date.format("%Y-%m-%d")
+        )
+    }
 }
 
 /// LLM API 响应
@@ -41,10 +387,150 @@ struct OllamaResponse {
     response: String,
 }
 
+/// Ollama `/api/tags` 响应，列出本地已下载的模型
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModelInfo>,
+}
+
+/// Ollama 本地模型信息
+#[derive(Debug, Deserialize)]
+struct OllamaModelInfo {
+    /// 模型名称，带标签，例如 `llama3:latest`
+    name: String,
+}
+
+/// 对话消息，按 OpenAI 的 messages 格式序列化
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatMessage {
+    /// 消息角色：user 或 assistant
+    pub role: String,
+    /// 消息内容
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// 创建用户消息
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+
+    /// 创建助手消息
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// 摘要生成阶段
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryPhase {
+    /// 读取日志
+    Fetching,
+    /// 构建提示词
+    Prompting,
+    /// 流式输出摘要
+    Streaming,
+    /// 分批提炼日志要点
+    Reducing,
+}
+
+/// 摘要生成进度，供前端显示进度并自行翻译阶段名称
+///
+/// 读取日志阶段为已读取/总日志条数，分批提炼阶段为已完成/总批次数，
+/// 流式输出阶段为已输出的字符数，总数未知时为 0
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SummaryProgress {
+    /// 当前阶段
+    pub phase: SummaryPhase,
+    /// 已处理数量
+    pub processed: usize,
+    /// 总数量
+    pub total: usize,
+}
+
+/// 进度回调
+type ProgressListener = Arc<dyn Fn(SummaryProgress) + Send + Sync>;
+
+/// 摘要生成结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryOutcome {
+    /// 按配置的输出格式渲染后的摘要
+    pub summary: String,
+    /// 生成摘要使用的模型，使用离线模板时为空
+    pub model: Option<String>,
+    /// 是否直接使用了缓存的摘要
+    pub cached: bool,
+    /// 摘要实际保存的文件路径，文件已存在且按设置跳过保存时为空
+    pub path: Option<PathBuf>,
+}
+
+/// 生成摘要使用的日志
+#[derive(Debug, Clone)]
+pub enum SummaryLogs {
+    /// 调用方已读取的日志，日期 (YYYY-MM-DD) 到日志记录的映射
+    Loaded(HashMap<String, Vec<LogEntry>>),
+    /// 从日志目录逐天读取时间段内的日志
+    ///
+    /// 按日期分组调用模型时逐天生成提示词，不会同时持有整个时间段的日志；
+    /// 离线模板和按标签、来源分组仍需要一次读取全部日志
+    Range(SummaryPeriod),
+}
+
+impl From<HashMap<String, Vec<LogEntry>>> for SummaryLogs {
+    fn from(logs: HashMap<String, Vec<LogEntry>>) -> Self {
+        SummaryLogs::Loaded(logs)
+    }
+}
+
+/// 上次成功生成摘要的检查点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryCheckpoint {
+    /// 上次摘要覆盖到的最后一天
+    pub last_end_date: NaiveDate,
+    /// 检查点的更新时间
+    pub updated_at: String,
+}
+
+/// 摘要服务连通性检查的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendInfo {
+    /// 服务类型 (ollama, openai, dashscope, anthropic)
+    pub backend: String,
+    /// 请求地址
+    pub url: String,
+    /// 实际使用的模型
+    pub model: String,
+    /// 检查耗时（毫秒）
+    pub latency_ms: u64,
+}
+
+/// 连通性检查使用的提示词，尽量减少输出
+const BACKEND_TEST_PROMPT: &str = "这是一次连通性测试，请只回复 OK。";
+
 /// 摘要生成器
 pub struct SummaryGenerator {
     settings: Settings,
     client: Client,
+    /// 取消标记，置为 true 后流式生成会在下一个数据块前中止
+    cancel_flag: Arc<AtomicBool>,
+    /// 强制使用离线模板生成摘要，不调用模型
+    offline: bool,
+    /// 忽略已缓存的摘要，强制重新生成
+    force: bool,
+    /// 结构化进度回调
+    progress_listener: Option<ProgressListener>,
+    /// 已确认配置的 Ollama 模型存在，分批提炼时不再重复检查
+    ollama_model_checked: AtomicBool,
+    /// 摘要文件已存在时的处理方式
+    file_conflict: SummaryFileConflict,
 }
 
 impl SummaryGenerator {
@@ -61,9 +547,113 @@ impl SummaryGenerator {
             });
             
         Self {
-            settings,
             client,
+            cancel_flag: Arc::new(AtomicBool::new(false)),
+            offline: false,
+            force: false,
+            progress_listener: None,
+            ollama_model_checked: AtomicBool::new(false),
+            file_conflict: SummaryFileConflict::from_name(&settings.summary_file_conflict).unwrap_or_default(),
+            settings,
+        }
+    }
+
+    /// 设置是否强制使用离线模板生成摘要
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// 设置是否忽略缓存强制重新生成
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// 设置摘要文件已存在时的处理方式，默认使用设置中的处理方式
+    pub fn with_file_conflict(mut self, file_conflict: SummaryFileConflict) -> Self {
+        self.file_conflict = file_conflict;
+        self
+    }
+
+    /// 是否使用离线模板：显式指定离线或未配置任何模型服务
+    fn use_offline(&self) -> bool {
+        self.offline || !self.settings.has_summary_backend()
+    }
+
+    /// 使用外部提供的取消标记，便于在其他任务中取消生成
+    pub fn with_cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = cancel_flag;
+        self
+    }
+
+    /// 设置结构化进度回调，在各阶段推进时调用
+    pub fn with_progress_listener<L>(mut self, listener: L) -> Self
+    where
+        L: Fn(SummaryProgress) + Send + Sync + 'static,
+    {
+        self.progress_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// 通知进度回调
+    fn report_progress(&self, phase: SummaryPhase, processed: usize, total: usize) {
+        if let Some(listener) = &self.progress_listener {
+            listener(SummaryProgress { phase, processed, total });
+        }
+    }
+
+    /// 遮盖文本中的 API 密钥和认证请求头，用于记录日志和生成错误信息
+    fn redact(&self, text: &str) -> String {
+        redact_text(text, &[&self.settings.llm_api_key])
+    }
+    
+    /// 系统提示词，设置中留空时使用内置默认提示词
+    ///
+    /// 指定了摘要语言时在末尾追加输出语言的要求
+    fn system_prompt(&self) -> String {
+        let mut prompt_system = if self.settings.system_prompt.trim().is_empty() {
+            default_system_prompt()
+        } else {
+            self.settings.system_prompt.clone()
+        };
+        
+        if self.settings.summary_include_time {
+            prompt_system.push_str("\n日志条目开头的 HH:MM 是记录时间，可据此分析工作的先后顺序和时间分布。");
         }
+        
+        let language = &self.settings.summary_language;
+        if !language.eq_ignore_ascii_case(SUMMARY_LANGUAGE_AUTO) {
+            if let Some(name) = summary_language_name(language) {
+                prompt_system.push_str(&format!(
+                    "\n请始终使用{}（{}）输出回复内容，无论日志使用何种语言。",
+                    name, language
+                ));
+            }
+        }
+        
+        prompt_system
+    }
+    
+    /// Ollama 的生成参数，`num_predict` 对应最大输出 token 数
+    ///
+    /// 设置中的额外参数合并在后，同名时覆盖默认值
+    fn ollama_options(&self) -> serde_json::Value {
+        let mut options = serde_json::Map::new();
+        options.insert("temperature".to_string(), json!(self.settings.summary_temperature));
+        options.insert("num_predict".to_string(), json!(self.settings.summary_max_tokens));
+        for (key, value) in &self.settings.ollama_options {
+            options.insert(key.clone(), value.clone());
+        }
+        serde_json::Value::Object(options)
+    }
+    
+    /// 如果已请求取消则返回取消错误
+    fn check_cancelled(&self) -> Result<(), AppError> {
+        if self.cancel_flag.load(Ordering::Relaxed) {
+            return Err(AppError::Cancelled);
+        }
+        Ok(())
     }
 
     /// 获取摘要API类型
@@ -74,145 +664,652 @@ impl SummaryGenerator {
     /// 生成摘要
     pub async fn generate_summary(
         &self,
-        logs: HashMap<String, Vec<LogEntry>>,
+        logs: impl Into<SummaryLogs>,
         config: SummaryConfig,
     ) -> Result<String, AppError> {
-        // 将日志合并为一个字符串
-        let mut logs_content = String::new();
-        
-        for (date, entries) in logs.iter() {
-            logs_content.push_str(&format!("## {}\n", date));
+        Ok(self.generate_summary_outcome(logs, config).await?.summary)
+    }
+
+    /// 生成摘要，同时返回使用的模型以及是否命中缓存
+    pub async fn generate_summary_outcome(
+        &self,
+        logs: impl Into<SummaryLogs>,
+        config: SummaryConfig,
+    ) -> Result<SummaryOutcome, AppError> {
+        let logs = without_summary_entries(logs.into());
+        let mut model = None;
+        let mut cached = false;
+        let last_log_date;
+        let summary = if self.use_offline() {
+            log::info!("未配置模型服务或指定离线模式，使用离线模板生成摘要");
+            let logs = self.load_summary_logs(&logs)?;
+            last_log_date = last_log_date_of(&logs);
+            generate_offline_summary(&logs, &config)
+        } else {
+            let (sections, last_date) = self.summary_log_sections(&logs, &config)?;
+            last_log_date = last_date;
+            let cache_key = self.summary_cache_key(&sections, &config);
+            model = Some(self.settings.get_summary_model(self.get_summary_api_type()));
             
-            for entry in entries {
-                logs_content.push_str(&format!("- {}\n", entry.content));
+            match self.read_cached_summary(&cache_key) {
+                Some(summary) => {
+                    cached = true;
+                    summary
+                }
+                None => {
+                    // 将日志合并为一个字符串，超出长度限制时先分批提炼
+                    let logs_content = self.prepare_logs_content(&sections, &config.title).await?;
+                    
+                    // 生成提示
+                    let (prompt, prompt_system) = self.build_prompt(&config.summary_type, &config.title, &logs_content);
+                    
+                    // 调用LLM API生成摘要
+                    let summary = self.generate_once(&prompt, &prompt_system).await?;
+                    self.write_cached_summary(&cache_key, &summary);
+                    summary
+                }
             }
-            
-            logs_content.push('\n');
+        };
+        
+        let summary = config.format.render(&summary, &config.title);
+        
+        // 保存摘要到文件
+        let file_name = self.get_summary_filename(&config);
+        let path = self.save_summary_file(&file_name, &summary)?;
+        self.advance_checkpoint(last_log_date, &config);
+        self.record_summary_entry(&config, path.as_deref());
+        
+        Ok(SummaryOutcome {
+            summary,
+            model,
+            cached,
+            path,
+        })
+    }
+
+    /// 按 API 类型以非流式方式调用一次模型
+    async fn generate_once(&self, prompt: &str, prompt_system: &str) -> Result<String, AppError> {
+        match self.get_summary_api_type() {
+            0 => self.generate_with_ollama(prompt, prompt_system).await,
+            3 => self.generate_with_anthropic(prompt, prompt_system).await,
+            _ => self.generate_with_external_api(prompt, prompt_system).await,
+        }
+    }
+
+    /// 读取全部日志，调用方已读取的日志直接返回
+    fn load_summary_logs<'a>(&self, logs: &'a SummaryLogs) -> Result<Cow<'a, HashMap<String, Vec<LogEntry>>>, AppError> {
+        match logs {
+            SummaryLogs::Loaded(logs) => Ok(Cow::Borrowed(logs)),
+            SummaryLogs::Range(period) => {
+                let logs = LogManager::new(self.settings.clone())
+                    .get_entries_in_date_range(&period.start_date, &period.end_date)?;
+                Ok(Cow::Owned(without_summary_day_entries(logs)))
+            }
+        }
+    }
+    
+    /// 生成提示词中的日志段落，同时返回日志中最晚的日期
+    ///
+    /// 需要读取的日志按日期分组时逐天读取并格式化，其他情况先读取全部日志
+    fn summary_log_sections(
+        &self,
+        logs: &SummaryLogs,
+        config: &SummaryConfig,
+    ) -> Result<(Vec<String>, Option<NaiveDate>), AppError> {
+        if let (SummaryLogs::Range(period), GroupBy::Date) = (logs, config.group_by) {
+            return self.read_log_sections(period, config);
         }
         
-        // 生成提示
-        let prompt = match config.summary_type {
-            SummaryType::Weekly => "对以下工作日志进行周总结，分析工作内容、成果和存在的问题，提出改进建议。",
-            SummaryType::Monthly => "对以下工作日志进行月度总结，总结月度工作重点、成果和经验教训，提出下月工作计划。",
-            SummaryType::Quarterly => "对以下工作日志进行季度总结，分析季度目标完成情况、主要项目进展、成果和问题，提出下季度规划。",
-            SummaryType::Custom => "对以下指定时间范围内的工作日志进行总结，分析关键工作内容、成果和经验教训。",
+        let logs = self.load_summary_logs(logs)?;
+        Ok((self.build_log_sections(&logs, config), last_log_date_of(&logs)))
+    }
+    
+    /// 逐天读取时间段内的日志，格式化为按日期排列的段落，同时返回最后一个有日志的日期
+    ///
+    /// 与 `build_log_sections` 按日期分组时的结果相同，但每天的日志格式化后即被丢弃
+    fn read_log_sections(
+        &self,
+        period: &SummaryPeriod,
+        config: &SummaryConfig,
+    ) -> Result<(Vec<String>, Option<NaiveDate>), AppError> {
+        let commits = if config.include_git {
+            self.collect_git_commits_in_range(config, period.start_date, period.end_date)
+        } else {
+            BTreeMap::new()
         };
+        let (days, last_date) = self.read_day_sections(period, &commits, self.settings.summary_include_time)?;
+        let (sections, high_priority) = days.finish();
         
-        let full_prompt = format!("{}\n\n{}", prompt, logs_content);
+        let earliest = config.start_date.unwrap_or(period.start_date);
+        let sections = self.finish_log_sections(sections, high_priority, Some(date_key(&earliest)));
+        Ok((sections, last_date))
+    }
+    
+    /// 逐天读取时间段内的日志并格式化为段落，跳过生成摘要时自动记录的日志
+    ///
+    /// 同时返回最后一个有日志的日期
+    fn read_day_sections<'c>(
+        &self,
+        period: &SummaryPeriod,
+        commits: &'c BTreeMap<String, Vec<GitCommit>>,
+        include_time: bool,
+    ) -> Result<(DaySections<'c>, Option<NaiveDate>), AppError> {
+        let mut days = DaySections::new(commits, include_time);
+        let mut last_date = None;
+        LogManager::new(self.settings.clone()).for_each_day_in_range(
+            &period.start_date,
+            &period.end_date,
+            |date, entries| {
+                self.check_cancelled()?;
+                let entries: Vec<LogEntry> = entries
+                    .into_iter()
+                    .filter(|entry| entry.source != SUMMARY_SOURCE)
+                    .collect();
+                if !entries.is_empty() {
+                    days.push_day(&date_key(&date), &entries);
+                    last_date = Some(date);
+                }
+                Ok(())
+            },
+        )?;
         
-        // 调用LLM API生成摘要
-        let summary = if self.settings.use_local_ollama {
-            self.generate_with_ollama(&full_prompt).await?
+        Ok((days, last_date))
+    }
+    
+    /// 将日志格式化为按日期排列的段落，按配置附带当天的 Git 提交
+    ///
+    /// 范围之前遗留的未完成待办会追加为单独一节，便于在摘要中跟进
+    fn build_log_sections(&self, logs: &HashMap<String, Vec<LogEntry>>, config: &SummaryConfig) -> Vec<String> {
+        let commits = if config.include_git {
+            self.collect_git_commits(logs, config)
         } else {
-            self.generate_with_external_api(&full_prompt).await?
+            BTreeMap::new()
+        };
+        let include_time = self.settings.summary_include_time;
+        let sections = match config.group_by {
+            GroupBy::Date => format_log_sections(logs, &commits, include_time),
+            GroupBy::Tag => {
+                let mut sections = format_grouped_sections(logs, &commits, include_time, "无标签", |entry| {
+                    entry.tags.iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect()
+                });
+                // 同一条日志可能出现在多个标签下，提醒模型不要重复计算
+                sections.insert(0, "> 以下日志按标签分组，带多个标签的日志会在每个标签下重复出现\n\n".to_string());
+                sections
+            }
+            GroupBy::Source => format_grouped_sections(logs, &commits, include_time, "未知来源", |entry| {
+                let source = entry.source.trim();
+                if source.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![source.to_string()]
+                }
+            }),
         };
         
-        // 保存摘要到文件
-        let file_name = self.get_summary_filename(&config);
-        let file_path = Path::new(&self.settings.log_output_dir).join(file_name);
+        let earliest = config
+            .start_date
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .or_else(|| logs.keys().min().cloned());
+        self.finish_log_sections(sections, format_high_priority_section(logs), earliest)
+    }
+    
+    /// 在日志段落前加上高优先级日志的汇总，再追加 `earliest` 之前遗留的未完成待办
+    fn finish_log_sections(
+        &self,
+        mut sections: Vec<String>,
+        high_priority: Option<String>,
+        earliest: Option<String>,
+    ) -> Vec<String> {
+        let include_time = self.settings.summary_include_time;
         
-        // 确保目录存在
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)?;
-            }
+        // 高优先级的日志单独列在最前面，提醒模型优先体现
+        if let Some(section) = high_priority {
+            sections.insert(0, section);
         }
         
-        // 保存摘要
-        fs::write(&file_path, &summary)?;
+        let open_entries = match LogManager::new(self.settings.clone()).get_open_entries() {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("读取未完成待办失败: {}", e);
+                Vec::new()
+            }
+        };
+        let carried_over: Vec<_> = open_entries
+            .into_iter()
+            .filter(|result| earliest.as_ref().is_some_and(|earliest| &result.date < earliest))
+            .collect();
+        
+        if !carried_over.is_empty() {
+            let mut section = "## 之前遗留的未完成事项\n".to_string();
+            for result in carried_over {
+                section.push_str(&format!("- {}（{}）\n", format_entry_line(&result.entry, include_time), result.date));
+            }
+            section.push('\n');
+            sections.push(section);
+        }
         
-        Ok(summary)
+        sections
     }
-
-    /// 使用本地 Ollama 生成摘要
-    async fn generate_with_ollama(&self, prompt: &str) -> Result<String, AppError> {
-        let url = format!("{}/api/generate", self.settings.ollama_address);
+    
+    /// 计算摘要缓存键
+    ///
+    /// 覆盖日志内容、日期范围、提示词模板、模型配置以及温度等生成参数，任何一项变化都会重新生成
+    fn summary_cache_key(&self, sections: &[String], config: &SummaryConfig) -> String {
+        let api_type = self.get_summary_api_type();
+        let (prompt_template, prompt_system) = self.build_prompt(&config.summary_type, &config.title, "");
+        let date_range = format!("{:?}-{:?}", config.start_date, config.end_date);
+        let chunking = format!("{}-{}", self.settings.summary_chunking_enabled, self.settings.max_prompt_chars);
+        let api_url = self.settings.get_summary_api_url(api_type);
+        let model = self.settings.get_summary_model(api_type);
+        let generation = format!(
+            "{}-{}-{}",
+            self.settings.summary_temperature,
+            self.settings.summary_max_tokens,
+            serde_json::Value::Object(self.settings.ollama_options.clone())
+        );
         
-        let response = self.client
-            .post(&url)
-            .json(&json!({
-                "model": self.settings.ollama_model,
-                "prompt": prompt,
-                "system": "你是一个专业的工作日志分析助手，擅长总结工作内容并提出见解。",
-                "stream": false
-            }))
-            .send()
-            .await
-            .map_err(AppError::ReqwestError)?;
+        let mut parts = vec![
+            prompt_template.as_str(),
+            prompt_system.as_str(),
+            date_range.as_str(),
+            chunking.as_str(),
+            api_url.as_str(),
+            model.as_str(),
+            generation.as_str(),
+        ];
+        parts.extend(sections.iter().map(String::as_str));
         
-        if !response.status().is_success() {
-            return Err(AppError::SummaryError(format!(
-                "Ollama API 调用失败: {}",
-                response.status()
-            )));
+        format!("{:016x}", fnv1a_hash(&parts))
+    }
+    
+    /// 缓存文件路径：`{摘要输出目录}/.summary-cache/{缓存键}.md`
+    fn cached_summary_path(&self, cache_key: &str) -> PathBuf {
+        Path::new(&self.settings.log_output_dir)
+            .join(SUMMARY_CACHE_DIR)
+            .join(format!("{}.md", cache_key))
+    }
+    
+    /// 读取已缓存的摘要，强制重新生成时始终返回 None
+    fn read_cached_summary(&self, cache_key: &str) -> Option<String> {
+        if self.force {
+            return None;
         }
         
-        let ollama_response: OllamaResponse = response
-            .json()
-            .await
-            .map_err(AppError::ReqwestError)?;
+        let summary = fs::read_to_string(self.cached_summary_path(cache_key)).ok()?;
+        log::info!("日志与配置未变化，使用缓存的摘要: {}", cache_key);
+        Some(summary)
+    }
+    
+    /// 缓存生成的摘要，写入失败只记录警告
+    fn write_cached_summary(&self, cache_key: &str, summary: &str) {
+        let path = self.cached_summary_path(cache_key);
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, summary));
         
-        Ok(ollama_response.response)
+        if let Err(e) = result {
+            log::warn!("缓存摘要失败: {}", e);
+        }
     }
-
-    /// 使用外部 API 生成摘要
-    async fn generate_with_external_api(&self, prompt: &str) -> Result<String, AppError> {
-        if self.settings.llm_api_url.is_empty() || self.settings.llm_api_key.is_empty() {
-            return Err(AppError::SummaryError(
-                "未配置外部 API URL 或 API Key".to_string()
-            ));
+    
+    /// 检查点文件路径：`{摘要输出目录}/.summary-checkpoint.json`
+    fn checkpoint_path(&self) -> PathBuf {
+        Path::new(&self.settings.log_output_dir).join(SUMMARY_CHECKPOINT_FILE)
+    }
+    
+    /// 读取上次生成摘要的检查点，文件不存在或无法解析时返回 None
+    pub fn read_checkpoint(&self) -> Option<SummaryCheckpoint> {
+        let content = fs::read_to_string(self.checkpoint_path()).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(checkpoint) => Some(checkpoint),
+            Err(e) => {
+                log::warn!("摘要检查点文件格式错误，将被忽略: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// 从上次摘要结束的次日到今天的日期范围
+    ///
+    /// 还没有检查点时退回周摘要的日期范围；上次摘要已经覆盖到今天时只包含今天
+    pub fn since_last_summary_range(&self, today: NaiveDate, rolling: bool) -> (NaiveDate, NaiveDate) {
+        match self.read_checkpoint() {
+            Some(checkpoint) => {
+                let start = checkpoint
+                    .last_end_date
+                    .succ_opt()
+                    .unwrap_or(checkpoint.last_end_date)
+                    .min(today);
+                (start, today)
+            }
+            None => SummaryType::Weekly.date_range(today, rolling),
         }
+    }
+    
+    /// 摘要保存成功后推进检查点，写入失败只记录警告
+    ///
+    /// 结束日期优先使用配置中的结束日期，否则取日志中最晚的日期，且不晚于今天；
+    /// 为较早的时间段补生成摘要时不会让检查点倒退
+    fn advance_checkpoint(&self, last_log_date: Option<NaiveDate>, config: &SummaryConfig) {
+        let today = Local::now().date_naive();
+        let end = config.end_date.or(last_log_date).unwrap_or(today).min(today);
         
-        // 检测是否为百炼API (百炼API的base_url包含 dashscope.aliyuncs.com)
-        let is_dashscope = self.settings.llm_api_url.contains("dashscope.aliyuncs.com");
+        if self.read_checkpoint().is_some_and(|checkpoint| checkpoint.last_end_date >= end) {
+            return;
+        }
         
-        log::info!("使用外部API生成摘要, URL: {}, 是否为百炼API: {}", self.settings.llm_api_url, is_dashscope);
+        let checkpoint = SummaryCheckpoint {
+            last_end_date: end,
+            updated_at: Local::now().to_rfc3339(),
+        };
+        let path = self.checkpoint_path();
+        let result = serde_json::to_string_pretty(&checkpoint)
+            .map_err(std::io::Error::other)
+            .and_then(|content| {
+                path.parent().map_or(Ok(()), fs::create_dir_all)?;
+                fs::write(&path, content)
+            });
+        
+        if let Err(e) = result {
+            log::warn!("更新摘要检查点失败: {}", e);
+        }
+    }
+    
+    /// 将日志整理为提示词中的日志内容
+    ///
+    /// 开启分批摘要且日志超过 `max_prompt_chars` 时，先将日志按日期切分成多批分别提炼要点，
+    /// 再以各批要点作为最终摘要的输入；要点合计仍然过长时继续分批，直到不再缩短为止
+    async fn prepare_logs_content(&self, sections: &[String], title: &str) -> Result<String, AppError> {
+        let max_chars = self.settings.max_prompt_chars;
+        
+        if !self.settings.summary_chunking_enabled || max_chars == 0 {
+            return Ok(sections.concat());
+        }
+        
+        let mut batches = split_into_batches(sections, max_chars);
+        let mut content_len = sections.iter().map(|s| s.chars().count()).sum::<usize>();
+        
+        while batches.len() > 1 {
+            let partials = self.summarize_batches(&batches, title).await?;
+            let partials_len = partials.iter().map(|s| s.chars().count()).sum::<usize>();
+            
+            // 要点没有变短时停止，避免无限循环
+            if partials_len >= content_len {
+                log::warn!("分批提炼后内容没有缩短，直接使用当前结果生成最终摘要");
+                return Ok(partials.concat());
+            }
+            
+            content_len = partials_len;
+            batches = split_into_batches(&partials, max_chars);
+        }
+        
+        Ok(batches.concat())
+    }
+    
+    /// 读取摘要日期范围内的 Git 提交，按本地日期分组
+    ///
+    /// 日期范围优先取配置，否则取日志的首尾日期；无法读取的仓库只记录警告并跳过
+    fn collect_git_commits(
+        &self,
+        logs: &HashMap<String, Vec<LogEntry>>,
+        config: &SummaryConfig,
+    ) -> BTreeMap<String, Vec<GitCommit>> {
+        let log_dates: Vec<NaiveDate> = logs
+            .keys()
+            .filter_map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .collect();
+        let (start, end) = match (
+            config.start_date.or_else(|| log_dates.iter().min().copied()),
+            config.end_date.or_else(|| log_dates.iter().max().copied()),
+        ) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return BTreeMap::new(),
+        };
+        
+        self.collect_git_commits_in_range(config, start, end)
+    }
+    
+    /// 读取起止日期之间的 Git 提交，按本地日期分组
+    fn collect_git_commits_in_range(
+        &self,
+        config: &SummaryConfig,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> BTreeMap<String, Vec<GitCommit>> {
+        let mut commits_by_date: BTreeMap<String, Vec<GitCommit>> = BTreeMap::new();
+        
+        for repo_path in &config.git_repo_paths {
+            let commits = match get_commits_in_range(
+                Path::new(repo_path),
+                &self.settings.git_author_filter(),
+                &start,
+                &end,
+                None,
+            ) {
+                Ok(commits) => commits,
+                Err(e) => {
+                    log::warn!("读取 Git 仓库 {} 的提交失败: {}", repo_path, e);
+                    continue;
+                }
+            };
+            
+            for commit in commits {
+                let date = commit.time.with_timezone(&Local).date_naive();
+                commits_by_date
+                    .entry(date.format("%Y-%m-%d").to_string())
+                    .or_default()
+                    .push(commit);
+            }
+        }
+        
+        for commits in commits_by_date.values_mut() {
+            commits.sort_by_key(|commit| commit.time);
+            commits.dedup_by(|a, b| a.id == b.id);
+        }
+        
+        log::info!(
+            "已读取 {} 条 Git 提交",
+            commits_by_date.values().map(|commits| commits.len()).sum::<usize>()
+        );
+        
+        commits_by_date
+    }
+    
+    /// 逐批提炼日志要点，返回每批的要点文本
+    async fn summarize_batches(&self, batches: &[String], title: &str) -> Result<Vec<String>, AppError> {
+        let prompt_system = self.system_prompt();
+        
+        let total = batches.len();
+        let mut partials = Vec::with_capacity(total);
+        
+        for (index, batch) in batches.iter().enumerate() {
+            self.check_cancelled()?;
+            self.report_progress(SummaryPhase::Reducing, index, total);
+            log::info!("正在提炼第 {}/{} 批日志，长度 {} 字符", index + 1, total, batch.chars().count());
+            
+            let prompt = format!(
+                "以下是「{}」的第 {}/{} 部分工作日志，请按日期顺序提炼其中的关键工作内容、成果和问题，\
+                 保留重要细节，输出简洁的要点列表，不要添加总结性评价：\n\n{}",
+                title,
+                index + 1,
+                total,
+                batch
+            );
+            let partial = self.generate_once(&prompt, &prompt_system).await?;
+            partials.push(format!("### 第 {} 部分要点\n{}\n\n", index + 1, partial.trim()));
+        }
+        self.report_progress(SummaryPhase::Reducing, total, total);
+        
+        Ok(partials)
+    }
+
+    /// 使用本地 Ollama 生成摘要
+    async fn generate_with_ollama(&self, prompt: &str, prompt_system: &str) -> Result<String, AppError> {
+        self.check_ollama_model().await?;
+        let url = format!("{}/api/generate", self.settings.ollama_address);
+        
+        let request = self.client
+            .post(&url)
+            .json(&json!({
+                "model": self.settings.ollama_model,
+                "prompt": prompt,
+                "system": prompt_system,
+                "stream": false,
+                "options": self.ollama_options()
+            }));
+        
+        let response = self
+            .send_with_retry(request)
+            .await
+            .map_err(AppError::ReqwestError)?;
+        
+        if !response.status().is_success() {
+            return Err(AppError::SummaryError(
+                Message::OllamaCallFailed(&response.status()).to_string()
+            ));
+        }
+        
+        let ollama_response: OllamaResponse = response
+            .json()
+            .await
+            .map_err(AppError::ReqwestError)?;
+        
+        Ok(ollama_response.response)
+    }
+
+    /// 检查当前配置的摘要服务是否可用，返回实际使用的模型和耗时
+    ///
+    /// Ollama 只检查模型列表，外部 API 发送一条很短的提示词
+    pub async fn test_backend(&self) -> Result<BackendInfo, AppError> {
+        if !self.settings.has_summary_backend() {
+            return Err(AppError::SummaryError(Message::NoSummaryBackend.to_string()));
+        }
+        
+        let api_type = self.get_summary_api_type();
+        let backend = match api_type {
+            0 => "ollama",
+            2 => "dashscope",
+            3 => "anthropic",
+            _ => "openai",
+        };
+        
+        let started = Instant::now();
+        match api_type {
+            0 => self.check_ollama_model().await?,
+            _ => {
+                self.generate_once(BACKEND_TEST_PROMPT, BACKEND_TEST_PROMPT).await?;
+            }
+        }
+        
+        Ok(BackendInfo {
+            backend: backend.to_string(),
+            url: self.settings.get_summary_api_url(api_type),
+            model: self.settings.get_summary_model(api_type),
+            latency_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// 检查配置的 Ollama 模型是否已下载到本地
+    ///
+    /// 通过 `GET {ollama_address}/api/tags` 列出本地模型，未找到时返回提示下载模型的错误；
+    /// 同一个生成器只检查一次
+    pub async fn check_ollama_model(&self) -> Result<(), AppError> {
+        if self.ollama_model_checked.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        
+        let model = self.settings.ollama_model.trim();
+        if model.is_empty() {
+            return Err(AppError::SummaryError(Message::OllamaModelNotConfigured.to_string()));
+        }
+        
+        let url = format!("{}/api/tags", self.settings.ollama_address);
+        let response = self.client.get(&url).send().await.map_err(|e| {
+            log::error!(
+                "无法连接 Ollama 服务 {}，请确认 Ollama 已启动: {}",
+                self.settings.ollama_address, e
+            );
+            // 保留原始错误，流式生成时连接失败会改用离线模板
+            AppError::ReqwestError(e)
+        })?;
+        
+        if !response.status().is_success() {
+            return Err(AppError::SummaryError(
+                Message::OllamaListFailed(&response.status()).to_string()
+            ));
+        }
+        
+        let text = response.text().await.map_err(AppError::ReqwestError)?;
+        let models = parse_ollama_models(&text)?;
+        if !models.iter().any(|name| ollama_model_matches(name, model)) {
+            return Err(AppError::SummaryError(Message::OllamaModelMissing(model).to_string()));
+        }
+        
+        self.ollama_model_checked.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 使用外部 API 生成摘要
+    async fn generate_with_external_api(&self, prompt: &str, prompt_system: &str) -> Result<String, AppError> {
+        if self.settings.llm_api_url.is_empty() || self.settings.llm_api_key.is_empty() {
+            return Err(AppError::SummaryError(Message::ExternalApiNotConfigured.to_string()));
+        }
+        
+        // 检测是否为百炼API (百炼API的base_url包含 dashscope.aliyuncs.com)
+        let is_dashscope = self.settings.llm_api_url.contains("dashscope.aliyuncs.com");
+        
+        let model = self.settings.get_summary_model(self.get_summary_api_type());
+        
+        log::info!("使用外部API生成摘要, URL: {}, 模型: {}, 是否为百炼API: {}", self.settings.llm_api_url, model, is_dashscope);
         
         // 构建请求体
         let request_body = if is_dashscope {
             // 百炼API使用与OpenAI兼容的格式
             json!({
-                "model": "qwen-max", // 默认使用通义千问Max模型
+                "model": model,
                 "messages": [
                     {
                         "role": "system", 
-                        "content": "你是一个专业的工作日志分析助手，擅长总结工作内容并提出见解。"
+                        "content": prompt_system
                     },
                     {
                         "role": "user",
                         "content": prompt
                     }
                 ],
-                "temperature": 0.7,
-                "max_tokens": 4000
+                "temperature": self.settings.summary_temperature,
+                "max_tokens": self.settings.summary_max_tokens
             })
         } else {
             // 标准OpenAI格式
             json!({
-                "model": "gpt-4",
+                "model": model,
                 "messages": [
                     {
                         "role": "system",
-                        "content": "你是一个专业的工作日志分析助手，擅长总结工作内容并提出见解。"
+                        "content": prompt_system
                     },
                     {
                         "role": "user",
                         "content": prompt
                     }
                 ],
-                "temperature": 0.7,
-                "max_tokens": 4000
+                "temperature": self.settings.summary_temperature,
+                "max_tokens": self.settings.summary_max_tokens
             })
         };
         
         log::debug!("API请求体: {}", serde_json::to_string_pretty(&request_body).unwrap_or_default());
         
         // 发送请求
-        let response = self.client
+        let request = self.client
             .post(&self.settings.llm_api_url)
             .header("Authorization", format!("Bearer {}", self.settings.llm_api_key))
-            .json(&request_body)
-            .send()
+            .json(&request_body);
+        
+        let response = self
+            .send_with_retry(request)
             .await
             .map_err(|e| {
                 log::error!("API请求失败: {}", e);
@@ -220,14 +1317,14 @@ impl SummaryGenerator {
                 // 针对不同连接错误提供更具体的错误信息
                 if e.is_timeout() {
                     log::error!("API请求超时");
-                    AppError::SummaryError("API请求超时，请检查网络连接或稍后重试".to_string())
+                    AppError::SummaryError(Message::ApiTimeout.to_string())
                 } else if e.is_connect() {
                     if is_dashscope {
                         log::error!("阿里云百炼API连接错误");
-                        AppError::SummaryError("无法连接到阿里云百炼API，请检查网络连接和API地址".to_string())
+                        AppError::SummaryError(Message::DashscopeConnectFailed.to_string())
                     } else {
                         log::error!("API连接错误");
-                        AppError::SummaryError("无法连接到API服务器，请检查网络连接和API地址".to_string())
+                        AppError::SummaryError(Message::ApiConnectFailed.to_string())
                     }
                 } else {
                     AppError::ReqwestError(e)
@@ -236,93 +1333,137 @@ impl SummaryGenerator {
         
         let status = response.status();
         if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
+            let error_text = self.redact(&response.text().await.unwrap_or_default());
             log::error!("API返回错误状态: {}, 响应: {}", status, error_text);
             
             // 提供更详细的错误信息
             let error_msg = if is_dashscope && status.as_u16() == 404 {
-                "阿里云百炼API调用失败: 请检查URL格式与API Key是否正确".to_string()
+                Message::DashscopeCallFailed.to_string()
             } else {
-                format!("外部 API 调用失败: {} - {}", status, error_text)
+                Message::ExternalApiFailed { status: &status, detail: &error_text }.to_string()
             };
             
+            if status == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(AppError::ApiAuthError(
+                    Message::ApiAuthFailed(&error_text).to_string()
+                ));
+            }
             return Err(AppError::SummaryError(error_msg));
         }
         
         // 获取响应JSON
         let response_text = response.text().await.map_err(AppError::ReqwestError)?;
-        log::debug!("API原始响应: {}", response_text);
-        
-        let json_response: serde_json::Value = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                log::error!("解析JSON响应失败: {}", e);
-                AppError::SerdeError(e)
-            })?;
+        log::debug!("API原始响应: {}", self.redact(&response_text));
         
-        // 提取回复，支持不同的响应格式
-        let content = if is_dashscope {
-            // 尝试获取百炼API的响应格式
-            // 首先尝试标准OpenAI兼容格式
-            let standard_format = json_response
-                .get("choices")
-                .and_then(|choices| choices.get(0))
-                .and_then(|choice| choice.get("message"))
-                .and_then(|message| message.get("content"))
-                .and_then(|content| content.as_str());
-                
-            if standard_format.is_some() {
-                standard_format
-            } else {
-                // 尝试阿里云旧版格式
-                json_response
-                    .get("output")
-                    .and_then(|output| output.get("text"))
-                    .and_then(|text| text.as_str())
-            }
-        } else {
-            // 标准OpenAI格式
-            json_response
-                .get("choices")
-                .and_then(|choices| choices.get(0))
-                .and_then(|choice| choice.get("message"))
-                .and_then(|message| message.get("content"))
-                .and_then(|content| content.as_str())
-        };
-        
-        match content {
-            Some(text) => {
+        // 提取回复，兼容 OpenAI、百炼以及各类 OpenAI 兼容服务的响应格式
+        match parse_non_stream_response(&response_text) {
+            Ok(text) => {
                 log::info!("成功提取响应内容，长度: {} 字符", text.len());
-                Ok(text.to_string())
+                Ok(text)
             },
-            None => {
-                log::error!("无法从响应中提取内容: {}", response_text);
-                
-                // 尝试查找错误信息
-                let error_message = json_response
-                    .get("error")
-                    .and_then(|e| e.get("message"))
-                    .and_then(|m| m.as_str())
-                    .unwrap_or("未知错误");
-                    
-                Err(AppError::SummaryError(format!("无法解析API响应: {}", error_message)))
+            Err(AppError::SummaryError(message)) => {
+                log::error!("无法从响应中提取内容: {}", self.redact(&response_text));
+                Err(AppError::SummaryError(self.redact(&message)))
             }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 构建 Anthropic Messages API 请求
+    fn build_anthropic_request(
+        &self,
+        prompt_system: &str,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<reqwest::RequestBuilder, AppError> {
+        if self.settings.llm_api_key.is_empty() {
+            return Err(AppError::SummaryError(Message::AnthropicKeyMissing.to_string()));
+        }
+        
+        let api_type = self.get_summary_api_type();
+        let model = self.settings.get_summary_model(api_type);
+        
+        if model.is_empty() {
+            return Err(AppError::SummaryError(Message::AnthropicModelMissing.to_string()));
         }
+        
+        let api_url = self.settings.get_summary_api_url(api_type);
+        log::info!("使用 Anthropic API 生成摘要, URL: {}, 模型: {}", api_url, model);
+        
+        Ok(self.client
+            .post(&api_url)
+            .header("x-api-key", &self.settings.llm_api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .json(&json!({
+                "model": model,
+                "system": prompt_system,
+                "messages": messages,
+                "temperature": self.settings.summary_temperature,
+                "max_tokens": self.settings.summary_max_tokens,
+                "stream": stream
+            })))
+    }
+    
+    /// 使用 Anthropic Claude 生成摘要
+    async fn generate_with_anthropic(&self, prompt: &str, prompt_system: &str) -> Result<String, AppError> {
+        let request = self.build_anthropic_request(prompt_system, &[ChatMessage::user(prompt)], false)?;
+        let response = self
+            .send_with_retry(request)
+            .await
+            .map_err(AppError::ReqwestError)?;
+        
+        let response = check_anthropic_status(response, &self.settings.llm_api_key).await?;
+        let response_text = response.text().await.map_err(AppError::ReqwestError)?;
+        log::debug!("Anthropic API原始响应: {}", self.redact(&response_text));
+        
+        parse_non_stream_response(&response_text)
+    }
+    
+    /// 使用 Anthropic Claude 流式生成摘要
+    async fn generate_with_anthropic_stream<F>(
+        &self,
+        messages: &[ChatMessage],
+        prompt_system: &str,
+        progress_callback: &F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn(&str) + Sync,
+    {
+        let request = self.build_anthropic_request(prompt_system, messages, true)?;
+        let response = self
+            .send_with_retry(request)
+            .await
+            .map_err(AppError::ReqwestError)?;
+        
+        let response = check_anthropic_status(response, &self.settings.llm_api_key).await?;
+        
+        read_event_stream(response, progress_callback, &self.cancel_flag).await
     }
 
-    /// 获取摘要文件名
+    /// 获取摘要文件名，配置了摘要文件名格式时按格式生成
     fn get_summary_filename(&self, config: &SummaryConfig) -> String {
+        let pattern = self.settings.summary_filename_pattern.trim();
+        if !pattern.is_empty() {
+            return self.render_summary_filename(pattern, config);
+        }
+        
         let now = Local::now();
+        let ext = config.format.extension();
         
         match config.summary_type {
+            SummaryType::Daily => {
+                let date = config.end_date.unwrap_or_else(|| now.date_naive());
+                format!("daily_summary_{}.{}", date.format("%Y-%m-%d"), ext)
+            }
             SummaryType::Weekly => {
-                format!("weekly_summary_{}.md", now.format("%Y-%m-%d"))
+                format!("weekly_summary_{}.{}", now.format("%Y-%m-%d"), ext)
             }
             SummaryType::Monthly => {
-                format!("monthly_summary_{}-{}.md", now.year(), now.month())
+                format!("monthly_summary_{}-{}.{}", now.year(), now.month(), ext)
             }
             SummaryType::Quarterly => {
                 let quarter = (now.month() - 1) / 3 + 1;
-                format!("quarterly_summary_{}-Q{}.md", now.year(), quarter)
+                format!("quarterly_summary_{}-Q{}.{}", now.year(), quarter, ext)
             }
             SummaryType::Custom => {
                 let start = config
@@ -335,104 +1476,389 @@ impl SummaryGenerator {
                     .unwrap_or_else(|| now.date_naive())
                     .format("%Y-%m-%d")
                     .to_string();
-                format!("custom_summary_{}_{}.md", start, end)
+                format!("custom_summary_{}_{}.{}", start, end, ext)
+            }
+        }
+    }
+
+    /// 按文件名格式生成相对摘要输出目录的文件路径，并加上输出格式的扩展名
+    ///
+    /// 周/月/季度摘要未指定日期时按设置计算日期范围；标题中不能用于文件名的字符替换为 `_`，
+    /// 路径中的空名称、`.` 和 `..` 会被忽略，保证文件写在摘要输出目录之内
+    fn render_summary_filename(&self, pattern: &str, config: &SummaryConfig) -> String {
+        let today = Local::now().date_naive();
+        let (range_start, range_end) = config
+            .summary_type
+            .date_range(today, self.settings.summary_rolling_range);
+        let start = config.start_date.unwrap_or(range_start);
+        let end = config.end_date.unwrap_or(range_end);
+        
+        let rendered = pattern
+            .replace(SUMMARY_FILE_TYPE, config.summary_type.as_str())
+            .replace(SUMMARY_FILE_START, &start.format("%Y-%m-%d").to_string())
+            .replace(SUMMARY_FILE_END, &end.format("%Y-%m-%d").to_string())
+            .replace(SUMMARY_FILE_DATE, &today.format("%Y-%m-%d").to_string())
+            .replace(SUMMARY_FILE_TITLE, &sanitize_file_name(&config.title));
+        let path = rendered
+            .split('/')
+            .map(str::trim)
+            .filter(|component| !component.is_empty() && *component != "." && *component != "..")
+            .collect::<Vec<_>>()
+            .join("/");
+        let path = if path.is_empty() { config.summary_type.as_str().to_string() } else { path };
+        
+        format!("{}.{}", path, config.format.extension())
+    }
+
+    /// 按文件冲突处理方式将摘要保存到摘要输出目录，返回实际写入的路径
+    ///
+    /// 文件已存在时按设置添加数字后缀、覆盖或跳过保存，跳过时返回 None
+    fn save_summary_file(&self, file_name: &str, summary: &str) -> Result<Option<PathBuf>, AppError> {
+        let path = Path::new(&self.settings.log_output_dir).join(file_name);
+        let path = match self.file_conflict {
+            SummaryFileConflict::Skip if path.exists() => {
+                log::info!("摘要文件已存在，跳过保存: {:?}", path);
+                return Ok(None);
             }
+            SummaryFileConflict::Suffix => unique_file_path(&path),
+            _ => path,
+        };
+        
+        // 确保目录存在
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                log::error!("创建目录失败: {}", e);
+                AppError::IoError(e)
+            })?;
+        }
+        
+        fs::write(&path, summary).map_err(|e| {
+            log::error!("保存摘要到文件失败: {}", e);
+            AppError::IoError(e)
+        })?;
+        
+        log::info!("摘要已保存到文件: {:?}", path);
+        Ok(Some(path))
+    }
+
+    /// 开启 `log_summaries` 时，在今天的日志中记录已生成的摘要
+    ///
+    /// 记录失败只输出警告，不影响摘要结果
+    fn record_summary_entry(&self, config: &SummaryConfig, path: Option<&Path>) {
+        if !self.settings.log_summaries {
+            return;
+        }
+
+        let content = match path {
+            Some(path) => format!("已生成{}: {}", config.title, path.display()),
+            None => format!("已生成{}", config.title),
+        };
+        let entry = LogEntry::new(
+            content,
+            SUMMARY_SOURCE.to_string(),
+            vec![SUMMARY_TAG.to_string()],
+        );
+        if let Err(e) = LogManager::new(self.settings.clone()).add_entry(entry) {
+            log::warn!("记录摘要日志失败: {}", e);
         }
     }
 
     /// 使用流式处理生成摘要，并通过回调函数通知进度
     pub async fn generate_summary_with_stream<F>(
         &self,
-        logs: HashMap<String, Vec<LogEntry>>,
+        logs: impl Into<SummaryLogs>,
         config: SummaryConfig,
         progress_callback: F,
-    ) -> Result<String, AppError>
+    ) -> Result<SummaryOutcome, AppError>
     where
         F: Fn(&str) + Send + Sync + 'static,
     {
-        // 将日志合并为一个字符串
-        let mut logs_str = String::new();
-        for (date, entries) in logs.iter() {
-            logs_str.push_str(&format!("## {}\n", date));
-            for entry in entries {
-                logs_str.push_str(&format!("- {}\n", entry.content));
+        let logs = without_summary_entries(logs.into());
+        let mut model = None;
+        let mut cached = false;
+        let last_log_date;
+        let summary = if self.use_offline() {
+            log::info!("未配置模型服务或指定离线模式，使用离线模板生成摘要");
+            let logs = self.load_summary_logs(&logs)?;
+            last_log_date = last_log_date_of(&logs);
+            let summary = generate_offline_summary(&logs, &config);
+            progress_callback(&summary);
+            summary
+        } else {
+            let (sections, last_date) = self.summary_log_sections(&logs, &config)?;
+            last_log_date = last_date;
+            let cache_key = self.summary_cache_key(&sections, &config);
+            let summary_model = self.settings.get_summary_model(self.get_summary_api_type());
+            
+            if let Some(summary) = self.read_cached_summary(&cache_key) {
+                progress_callback(&summary);
+                model = Some(summary_model);
+                cached = true;
+                summary
+            } else {
+                match self.generate_llm_summary_stream(&sections, &config, &progress_callback).await {
+                    Ok(summary) => {
+                        self.write_cached_summary(&cache_key, &summary);
+                        model = Some(summary_model);
+                        summary
+                    }
+                    // 本地模型未启动等连接失败的情况下，退回离线模板，保证界面有内容可看
+                    Err(AppError::ReqwestError(e)) if e.is_connect() => {
+                        log::warn!("无法连接模型服务: {}，改用离线模板生成摘要", e);
+                        let offline_logs = self.load_summary_logs(&logs)?;
+                        let summary = generate_offline_summary(&offline_logs, &config);
+                        progress_callback(&summary);
+                        summary
+                    }
+                    Err(e) => return Err(e),
+                }
             }
-            logs_str.push('\n');
+        };
+        
+        // 流式输出的是 Markdown 原文，完成后再转换为要求的格式
+        let summary = config.format.render(&summary, &config.title);
+        
+        // 流处理完成后，将结果写入文件
+        let pattern = match self.settings.summary_filename_pattern.trim() {
+            "" => STREAM_SUMMARY_FILENAME_PATTERN,
+            pattern => pattern,
+        };
+        let filename = self.render_summary_filename(pattern, &config);
+        let path = self.save_summary_file(&filename, &summary)?;
+        self.advance_checkpoint(last_log_date, &config);
+        self.record_summary_entry(&config, path.as_deref());
+        
+        Ok(SummaryOutcome {
+            summary,
+            model,
+            cached,
+            path,
+        })
+    }
+    
+    /// 按新的要求调整之前生成的摘要，以流式方式输出调整后的内容
+    ///
+    /// 之前的摘要作为助手的回复、调整要求作为新的用户消息，组成多轮对话发送给模型，
+    /// 调整后的结果不会写入摘要文件或缓存
+    pub async fn refine_summary<F>(
+        &self,
+        previous_summary: &str,
+        instruction: &str,
+        progress_callback: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        if previous_summary.trim().is_empty() {
+            return Err(AppError::SummaryError(Message::NothingToRefine.to_string()));
+        }
+        if instruction.trim().is_empty() {
+            return Err(AppError::SummaryError(Message::RefineInstructionEmpty.to_string()));
+        }
+        if self.use_offline() {
+            return Err(AppError::SummaryError(Message::RefineNeedsBackend.to_string()));
+        }
+        
+        let messages = [
+            ChatMessage::user(REFINE_CONTEXT_PROMPT),
+            ChatMessage::assistant(previous_summary),
+            ChatMessage::user(instruction.trim()),
+        ];
+        
+        self.generate_stream_with_retry(&messages, &self.system_prompt(), progress_callback)
+            .await
+    }
+    
+    /// 对比两个时间段的工作日志，以流式方式输出对比摘要
+    ///
+    /// 分别读取两个时间段的日志，过长时各自先分批提炼，再让模型以时间段 A 为基准
+    /// 分析时间段 B 的新重点、停止的工作和整体趋势。对比摘要需要模型服务，不写入摘要文件或缓存
+    pub async fn generate_comparison_summary<F>(
+        &self,
+        period_a: SummaryPeriod,
+        period_b: SummaryPeriod,
+        title: &str,
+        progress_callback: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        if self.use_offline() {
+            return Err(AppError::SummaryError(Message::CompareNeedsBackend.to_string()));
+        }
+        
+        let periods = [("A", period_a), ("B", period_b)];
+        let no_commits = BTreeMap::new();
+        let mut period_sections = Vec::new();
+        for (_, period) in &periods {
+            self.report_progress(SummaryPhase::Fetching, period_sections.len(), periods.len());
+            let (days, _) = self.read_day_sections(period, &no_commits, self.settings.summary_include_time)?;
+            period_sections.push(days.finish().0);
+        }
+        self.report_progress(SummaryPhase::Fetching, periods.len(), periods.len());
+        
+        if period_sections.iter().all(Vec::is_empty) {
+            return Err(AppError::SummaryError(Message::NoEntriesInBothPeriods.to_string()));
+        }
+        
+        let mut prompt = COMPARISON_PROMPT.replace(TITLE_PLACEHOLDER, title);
+        for ((name, period), sections) in periods.iter().zip(&period_sections) {
+            let content = if sections.is_empty() {
+                "（该时间段没有日志记录）".to_string()
+            } else {
+                self.prepare_logs_content(sections, title).await?
+            };
+            prompt.push_str(&format!("\n\n## 时间段 {}（{}）\n\n{}", name, period.label(), content.trim_end()));
         }
+        self.report_progress(SummaryPhase::Prompting, 1, 1);
+        
+        self.generate_stream_with_retry(&[ChatMessage::user(prompt)], &self.system_prompt(), progress_callback)
+            .await
+    }
+    
+    /// 调用模型流式生成摘要内容
+    async fn generate_llm_summary_stream<F>(
+        &self,
+        sections: &[String],
+        config: &SummaryConfig,
+        progress_callback: &F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        // 将日志合并为一个字符串，超出长度限制时先分批提炼，只有最终汇总阶段以流式输出
+        let logs_str = self.prepare_logs_content(sections, &config.title).await?;
         
         // 根据摘要类型构建提示词
-        let (prompt, prompt_system) = self.build_prompt(&config.summary_type, &config.title);
+        self.report_progress(SummaryPhase::Prompting, 0, 1);
+        let (prompt, prompt_system) = self.build_prompt(&config.summary_type, &config.title, &logs_str);
+        self.report_progress(SummaryPhase::Prompting, 1, 1);
         
-        // 根据API类型选择不同的处理方式
-        match self.get_summary_api_type() {
-            0 => {
-                // 本地API (非流式)
-                // 对于本地API，我们暂时不支持流式处理，而是模拟进度
-                progress_callback("正在使用本地模型分析日志...");
-                
-                let summary = self.generate_with_local_api(&prompt, &logs_str).await?;
-                
-                // 模拟几次更新以提供一些反馈
-                let segments = vec![
-                    "正在生成摘要...",
-                    "分析工作内容...",
-                    "整理关键活动...",
-                ];
-                
-                for segment in segments {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(800)).await;
-                    progress_callback(segment);
+        // 根据API类型选择不同的处理方式，各后端都以增量文本回调进度
+        self.generate_stream_with_retry(&[ChatMessage::user(prompt)], &prompt_system, progress_callback)
+            .await
+    }
+    
+    /// 按 API 类型流式生成摘要
+    ///
+    /// 尚未输出任何内容时遇到网络中断会按退避策略重试；
+    /// 已经输出部分内容后失败则直接返回错误，避免前端收到重复内容
+    async fn generate_stream_with_retry<F>(
+        &self,
+        messages: &[ChatMessage],
+        prompt_system: &str,
+        progress_callback: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        let has_emitted = AtomicBool::new(false);
+        let streamed_chars = AtomicUsize::new(0);
+        let tracked_callback = |text: &str| {
+            has_emitted.store(true, Ordering::Relaxed);
+            let chars = text.chars().count();
+            let processed = streamed_chars.fetch_add(chars, Ordering::Relaxed) + chars;
+            self.report_progress(SummaryPhase::Streaming, processed, 0);
+            progress_callback(text);
+        };
+        
+        let max_retries = self.settings.summary_max_retries;
+        let mut attempt = 0;
+        
+        loop {
+            self.check_cancelled()?;
+            
+            let result = match self.get_summary_api_type() {
+                0 => self.generate_with_ollama_stream(messages, prompt_system, &tracked_callback).await,
+                3 => self.generate_with_anthropic_stream(messages, prompt_system, &tracked_callback).await,
+                _ => self.generate_with_external_api_stream(messages, prompt_system, &tracked_callback).await,
+            };
+            
+            match result {
+                Err(AppError::ReqwestError(e))
+                    if attempt < max_retries
+                        && !has_emitted.load(Ordering::Relaxed)
+                        && is_retryable_reqwest_error(&e) =>
+                {
+                    let delay = retry_delay(attempt);
+                    attempt += 1;
+                    log::warn!(
+                        "流式响应中断: {}，{} 毫秒后进行第 {}/{} 次重试",
+                        e,
+                        delay.as_millis(),
+                        attempt,
+                        max_retries
+                    );
+                    tokio::time::sleep(delay).await;
                 }
-                
-                // 最后发送完整结果
-                Ok(summary)
+                result => return result,
             }
-            _ => {
-                // 外部API (流式)
-                let summary = self.generate_with_external_api_stream(&prompt, &prompt_system, &logs_str, progress_callback).await?;
-                
-                // 流处理完成后，将结果写入文件
-                let output_dir = &self.settings.log_output_dir;
-                let date_format = Local::now().format("%Y-%m-%d").to_string();
-                let filename = format!("{}-{}.md", date_format, &config.title);
-                let path = Path::new(output_dir).join(filename);
-                
-                // 确保目录存在
-                if let Some(parent) = path.parent() {
-                    if !parent.exists() {
-                        fs::create_dir_all(parent).map_err(|e| {
-                            log::error!("创建目录失败: {}", e);
-                            AppError::IoError(e)
-                        })?;
-                    }
-                }
-                
-                fs::write(&path, &summary).map_err(|e| {
-                    log::error!("保存摘要到文件失败: {}", e);
-                    AppError::IoError(e)
-                })?;
-                
-                log::info!("摘要已保存到文件: {:?}", path);
-                
-                Ok(summary)
+        }
+    }
+    
+    /// 发送请求，遇到超时、连接失败、429 或 5xx 时按指数退避重试
+    ///
+    /// 4xx（如认证失败）不会重试，最终的响应或错误原样返回由调用方处理
+    async fn send_with_retry(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let max_retries = self.settings.summary_max_retries;
+        let mut attempt = 0;
+        
+        loop {
+            // JSON 请求体总是可以克隆，无法克隆时只发送一次
+            let current = match request.try_clone() {
+                Some(current) if attempt < max_retries => current,
+                _ => return request.send().await,
+            };
+            
+            let result = current.send().await;
+            let should_retry = match &result {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(e) => is_retryable_reqwest_error(e),
+            };
+            
+            if !should_retry {
+                return result;
+            }
+            
+            let delay = retry_delay(attempt);
+            attempt += 1;
+            match &result {
+                Ok(response) => log::warn!(
+                    "API返回状态 {}，{} 毫秒后进行第 {}/{} 次重试",
+                    response.status(),
+                    delay.as_millis(),
+                    attempt,
+                    max_retries
+                ),
+                Err(e) => log::warn!(
+                    "API请求失败: {}，{} 毫秒后进行第 {}/{} 次重试",
+                    e,
+                    delay.as_millis(),
+                    attempt,
+                    max_retries
+                ),
             }
+            tokio::time::sleep(delay).await;
         }
     }
     
     /// 外部API流式生成摘要
     async fn generate_with_external_api_stream<F>(
         &self,
-        prompt: &str,
+        messages: &[ChatMessage],
         prompt_system: &str,
-        logs: &str,
-        progress_callback: F,
+        progress_callback: &F,
     ) -> Result<String, AppError>
     where
-        F: Fn(&str) + Send + Sync + 'static,
+        F: Fn(&str) + Sync,
     {
         let api_type = self.settings.get_summary_api_type();
         let api_key = self.settings.get_summary_api_key(api_type);
         let api_url = self.settings.get_summary_api_url(api_type);
+        let model = self.settings.get_summary_model(api_type);
         
         // 创建客户端
         let client = reqwest::Client::builder()
@@ -441,34 +1867,34 @@ impl SummaryGenerator {
             .build()
             .map_err(|e| {
                 log::error!("创建HTTP客户端失败: {}", e);
-                AppError::SummaryError(format!("创建HTTP客户端失败: {}", e))
+                AppError::SummaryError(Message::HttpClientFailed(&e).to_string())
             })?;
         
-        // 构建请求体
-        let msgs = vec![
+        // 构建请求体，系统提示词之后依次附上对话消息
+        let mut msgs = vec![
             serde_json::json!({
                 "role": "system",
                 "content": prompt_system
-            }),
-            serde_json::json!({
-                "role": "user",
-                "content": format!("{}\n\n{}", prompt, logs)
             })
         ];
+        msgs.extend(messages.iter().map(|message| serde_json::json!(message)));
         
         let request_body = if api_type == 2 {
             // 百联 API
             serde_json::json!({
-                "model": "qwen-max",
+                "model": model,
                 "messages": msgs,
+                "temperature": self.settings.summary_temperature,
+                "max_tokens": self.settings.summary_max_tokens,
                 "stream": true
             })
         } else {
             // OpenAI 兼容 API
             serde_json::json!({
-                "model": "gpt-3.5-turbo",
+                "model": model,
                 "messages": msgs,
-                "temperature": 0.7,
+                "temperature": self.settings.summary_temperature,
+                "max_tokens": self.settings.summary_max_tokens,
                 "stream": true
             })
         };
@@ -480,125 +1906,113 @@ impl SummaryGenerator {
             .header("Authorization", format!("Bearer {}", api_key));
             
         // 发送请求
-        let response = request
-            .json(&request_body)
-            .send()
+        let response = self
+            .send_with_retry(request.json(&request_body))
             .await
             .map_err(|e| {
-                // 详细的错误处理
-                let error_msg = if e.is_timeout() {
-                    log::error!("API请求超时: {}", e);
-                    if api_type == 2 {
-                        format!("百联API请求超时: {}。请检查网络连接或稍后重试。", e)
-                    } else {
-                        format!("API请求超时: {}。请检查网络连接或稍后重试。", e)
-                    }
-                } else if e.is_connect() {
-                    log::error!("API连接失败: {}", e);
-                    if api_type == 2 {
-                        format!("无法连接到百联API: {}。请检查网络环境是否允许访问该服务。", e)
-                    } else {
-                        format!("API连接失败: {}。请检查网络连接或API配置。", e)
-                    }
-                } else {
-                    log::error!("API请求失败: {}", e);
-                    format!("发送API请求失败: {}", e)
-                };
-                AppError::SummaryError(error_msg)
+                // 保留原始错误，连接失败时由调用方改用离线模板，超时等错误可以重试
+                log::error!("API请求失败: {}", e);
+                AppError::ReqwestError(e)
             })?;
             
         // 检查响应状态
         if !response.status().is_success() {
             let status = response.status();
-            let text = response.text().await.unwrap_or_else(|_| "无法读取响应内容".to_string());
+            let text = response.text().await.unwrap_or_else(|_| Message::ResponseUnreadable.to_string());
+            let text = self.redact(&text);
             
             log::error!("API错误: 状态 {}, 响应: {}", status, text);
             
             let error_msg = match status.as_u16() {
-                401 => format!("API认证失败: 无效的API密钥。请在设置中检查您的API密钥。"),
-                403 => format!("API访问被拒绝: 您没有权限访问此资源。请检查API密钥权限。"),
+                401 => Message::ApiAuthFailed("").to_string(),
+                403 => Message::ApiForbidden.to_string(),
                 404 => {
                     if api_type == 2 {
-                        format!("百联API资源未找到: 请检查API地址是否正确。确认您使用的是兼容模式URL: https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions")
+                        Message::DashscopeNotFound.to_string()
                     } else {
-                        format!("API资源未找到: 请检查API地址是否正确。")
+                        Message::ApiNotFound.to_string()
                     }
                 },
-                429 => format!("API请求过多: 已超出速率限制。请稍后再试。"),
-                _ if status.as_u16() >= 500 => format!("API服务器错误 {}: 服务暂时不可用。请稍后再试。", status),
-                _ => format!("API请求失败: 状态码 {}, 响应: {}", status, text),
+                429 => Message::ApiRateLimited.to_string(),
+                _ if status.as_u16() >= 500 => Message::ApiServerError(&status).to_string(),
+                _ => Message::ApiStatusFailed { status: &status, body: &text }.to_string(),
             };
             
+            if status == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(AppError::ApiAuthError(error_msg));
+            }
             return Err(AppError::SummaryError(error_msg));
         }
         
-        // 处理响应
-        let mut result = String::new();
+        read_event_stream(response, progress_callback, &self.cancel_flag).await
+    }
+
+    /// 使用本地 Ollama 流式生成摘要
+    ///
+    /// 通过 `/api/chat` 发送对话消息，流式响应为逐行 JSON，每行的 message.content 字段是一段增量文本
+    async fn generate_with_ollama_stream<F>(
+        &self,
+        messages: &[ChatMessage],
+        prompt_system: &str,
+        progress_callback: &F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn(&str) + Sync,
+    {
+        self.check_ollama_model().await?;
+        let url = format!("{}/api/chat", self.settings.ollama_address);
+        
+        let mut msgs = vec![json!({
+            "role": "system",
+            "content": prompt_system
+        })];
+        msgs.extend(messages.iter().map(|message| json!(message)));
+        
+        let request = self.client
+            .post(&url)
+            .json(&json!({
+                "model": self.settings.ollama_model,
+                "messages": msgs,
+                "stream": true,
+                "options": self.ollama_options()
+            }));
+        
+        let response = self
+            .send_with_retry(request)
+            .await
+            .map_err(AppError::ReqwestError)?;
         
-        // 使用流式处理
+        if !response.status().is_success() {
+            return Err(AppError::SummaryError(
+                Message::OllamaCallFailed(&response.status()).to_string()
+            ));
+        }
+        
+        let mut result = String::new();
+        let mut lines = LineBuffer::default();
         let mut stream = response.bytes_stream();
         
         while let Some(item) = stream.next().await {
-            match item {
-                Ok(bytes) => {
-                    let chunk = String::from_utf8_lossy(&bytes);
-                    
-                    // 解析SSE格式数据
-                    for line in chunk.lines() {
-                        if line.starts_with("data: ") {
-                            let data = &line[6..]; // 去掉 "data: " 前缀
-                            
-                            // 检查流是否结束
-                            if data == "[DONE]" {
-                                continue;
-                            }
-                            
-                            // 尝试解析JSON
-                            match serde_json::from_str::<serde_json::Value>(data) {
-                                Ok(json) => {
-                                    // 尝试提取内容
-                                    // 首先尝试OpenAI格式
-                                    if let Some(choices) = json.get("choices") {
-                                        if let Some(choice) = choices.get(0) {
-                                            if let Some(delta) = choice.get("delta") {
-                                                if let Some(content) = delta.get("content") {
-                                                    if let Some(text) = content.as_str() {
-                                                        result.push_str(text);
-                                                        progress_callback(text);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    // 尝试百联格式
-                                    else if let Some(output) = json.get("output") {
-                                        if let Some(choices) = output.get("choices") {
-                                            if let Some(choice) = choices.get(0) {
-                                                if let Some(text) = choice.get("text") {
-                                                    if let Some(content) = text.as_str() {
-                                                        result.push_str(content);
-                                                        progress_callback(content);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                },
-                                Err(e) => {
-                                    log::debug!("解析JSON失败: {} - 数据: {}", e, data);
-                                    // 继续处理下一行数据
-                                }
-                            }
-                        }
-                    }
-                },
-                Err(e) => {
-                    log::error!("读取响应流失败: {}", e);
-                    return Err(AppError::SummaryError(format!("读取响应流失败: {}", e)));
+            // 取消时直接返回，丢弃响应流即可关闭 HTTP 连接
+            self.check_cancelled()?;
+            
+            // 网络中断保留为 ReqwestError，以便在尚未输出内容时重试
+            let bytes = item.map_err(|e| {
+                log::error!("读取 Ollama 响应流失败: {}", e);
+                AppError::ReqwestError(e)
+            })?;
+            
+            for line in lines.push(&bytes) {
+                if handle_ollama_line(&line, &mut result, progress_callback)? {
+                    return Ok(result);
                 }
             }
         }
         
+        if let Some(line) = lines.finish() {
+            handle_ollama_line(&line, &mut result, progress_callback)?;
+        }
+        
         Ok(result)
     }
 
@@ -633,26 +2047,1202 @@ impl SummaryGenerator {
         logs_content
     }
     
-    /// 根据摘要类型和标题构建提示词
-    fn build_prompt(&self, summary_type: &SummaryType, title: &str) -> (String, String) {
-        let prompt_system = "你是一个专业的工作日志分析助手，擅长总结工作内容并提出见解。".to_string();
+    /// 根据摘要类型、标题和日志内容构建提示词，返回 (用户提示词, 系统提示词)
+    ///
+    /// 模板来自设置，留空时使用内置默认模板
+    fn build_prompt(&self, summary_type: &SummaryType, title: &str, logs: &str) -> (String, String) {
+        let prompts = &self.settings.summary_prompts;
+        let defaults = SummaryPrompts::default();
         
-        let prompt = match summary_type {
-            SummaryType::Weekly => format!("请对以下工作日志进行周总结「{}」，分析工作内容、成果和存在的问题，提出改进建议。", title),
-            SummaryType::Monthly => format!("请对以下工作日志进行月度总结「{}」，总结月度工作重点、成果和经验教训，提出下月工作计划。", title),
-            SummaryType::Quarterly => format!("请对以下工作日志进行季度总结「{}」，分析季度目标完成情况、主要项目进展、成果和问题，提出下季度规划。", title),
-            SummaryType::Custom => format!("请对以下指定时间范围内的工作日志进行总结「{}」，分析关键工作内容、成果和经验教训。", title),
+        let (template, default_template) = match summary_type {
+            SummaryType::Daily => (&prompts.daily, defaults.daily),
+            SummaryType::Weekly => (&prompts.weekly, defaults.weekly),
+            SummaryType::Monthly => (&prompts.monthly, defaults.monthly),
+            SummaryType::Quarterly => (&prompts.quarterly, defaults.quarterly),
+            SummaryType::Custom => (&prompts.custom, defaults.custom),
         };
         
-        (prompt, prompt_system)
+        let template = if template.trim().is_empty() {
+            default_template
+        } else {
+            template.clone()
+        };
+        
+        let prompt_system = self.system_prompt();
+        
+        (render_prompt(&template, title, logs), prompt_system)
+    }
+}
+
+/// 不调用模型，按日期和标签统计生成确定性的 Markdown 摘要
+///
+/// 用于未配置模型服务、显式离线或本地模型不可达的场景
+pub fn generate_offline_summary(logs: &HashMap<String, Vec<LogEntry>>, config: &SummaryConfig) -> String {
+    let mut dates: Vec<&String> = logs.keys().collect();
+    dates.sort();
+    
+    let total_entries: usize = logs.values().map(|entries| entries.len()).sum();
+    let type_label = match config.summary_type {
+        SummaryType::Daily => "日摘要",
+        SummaryType::Weekly => "周摘要",
+        SummaryType::Monthly => "月摘要",
+        SummaryType::Quarterly => "季度摘要",
+        SummaryType::Custom => "自定义摘要",
+    };
+    
+    // 优先使用配置的日期范围，否则取日志中的首尾日期
+    let start = config
+        .start_date
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .or_else(|| dates.first().map(|date| date.to_string()))
+        .unwrap_or_default();
+    let end = config
+        .end_date
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .or_else(|| dates.last().map(|date| date.to_string()))
+        .unwrap_or_default();
+    
+    let mut output = format!("# {}\n\n", config.title);
+    output.push_str("> 离线摘要：未调用模型，按日期和标签统计生成\n\n");
+    output.push_str(&format!("- 摘要类型：{}\n", type_label));
+    output.push_str(&format!("- 时间范围：{} 至 {}\n", start, end));
+    output.push_str(&format!("- 记录天数：{} 天\n", dates.len()));
+    output.push_str(&format!("- 日志条数：{} 条\n\n", total_entries));
+    
+    let all_entries: Vec<&LogEntry> = logs.values().flatten().collect();
+    
+    output.push_str("## 标签统计\n\n");
+    output.push_str(&format_count_table("标签", &count_tags(&all_entries)));
+    
+    output.push_str("## 来源统计\n\n");
+    let mut source_counts: HashMap<&str, usize> = HashMap::new();
+    for entry in &all_entries {
+        *source_counts.entry(entry.source.as_str()).or_default() += 1;
+    }
+    output.push_str(&format_count_table("来源", &sort_counts(source_counts)));
+    
+    output.push_str("## 每日记录\n\n");
+    for date in dates {
+        let mut entries: Vec<&LogEntry> = logs[date].iter().collect();
+        entries.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        
+        output.push_str(&format!("### {}（{} 条）\n\n", date, entries.len()));
+        
+        let tag_summary = count_tags(&entries)
+            .iter()
+            .map(|(tag, count)| format!("{} ×{}", tag, count))
+            .collect::<Vec<_>>()
+            .join("、");
+        output.push_str(&format!("标签：{}\n\n", tag_summary));
+        
+        for entry in entries {
+            let time = entry
+                .created_at_local()
+                .map(|time| format!("{} ", time.format("%H:%M")))
+                .unwrap_or_default();
+            let content = entry.content.lines().collect::<Vec<_>>().join(" ");
+            let tags = entry
+                .tags
+                .iter()
+                .map(|tag| format!(" `#{}`", tag))
+                .collect::<String>();
+            let status = entry
+                .status
+                .map(|status| format!("[{}] ", status.label()))
+                .unwrap_or_default();
+            output.push_str(&format!("- {}{}{}{}\n", time, status, content, tags));
+        }
+        output.push('\n');
+    }
+    
+    output
+}
+
+/// 统计标签出现次数，没有标签的记录计入「未打标签」
+fn count_tags(entries: &[&LogEntry]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        if entry.tags.is_empty() {
+            *counts.entry("未打标签").or_default() += 1;
+        }
+        for tag in &entry.tags {
+            *counts.entry(tag.as_str()).or_default() += 1;
+        }
+    }
+    sort_counts(counts)
+}
+
+/// 按数量降序、名称升序排列统计结果，保证输出稳定
+fn sort_counts(counts: HashMap<&str, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// 将统计结果格式化为 Markdown 表格
+fn format_count_table(label: &str, counts: &[(String, usize)]) -> String {
+    let mut table = format!("| {} | 条数 |\n| --- | --- |\n", label);
+    for (name, count) in counts {
+        table.push_str(&format!("| {} | {} |\n", name, count));
+    }
+    table.push('\n');
+    table
+}
+
+/// 去掉生成摘要后自动记录的日志，避免之前的摘要记录再被写进新的摘要
+///
+/// 按时间段逐天读取的日志在读取时再去掉
+fn without_summary_entries(logs: SummaryLogs) -> SummaryLogs {
+    match logs {
+        SummaryLogs::Loaded(logs) => SummaryLogs::Loaded(without_summary_day_entries(logs)),
+        range => range,
+    }
+}
+
+/// 去掉每天日志中生成摘要后自动记录的日志，不再有日志的日期一并去掉
+fn without_summary_day_entries(
+    logs: HashMap<String, Vec<LogEntry>>,
+) -> HashMap<String, Vec<LogEntry>> {
+    logs.into_iter()
+        .filter_map(|(date, entries)| {
+            let entries: Vec<LogEntry> = entries
+                .into_iter()
+                .filter(|entry| entry.source != SUMMARY_SOURCE)
+                .collect();
+            (!entries.is_empty()).then_some((date, entries))
+        })
+        .collect()
+}
+
+/// 日志中最晚的日期
+fn last_log_date_of(logs: &HashMap<String, Vec<LogEntry>>) -> Option<NaiveDate> {
+    logs.keys()
+        .filter_map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .max()
+}
+
+/// 文件不存在时原样返回，否则依次尝试 `{文件名}-2.{扩展名}`、`{文件名}-3.{扩展名}`，直到找到不存在的文件
+fn unique_file_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// 将标题中不能用于文件名的字符替换为 `_`，去掉首尾的空白和 `.`，结果为空时返回 `summary`
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches(|c: char| c.is_whitespace() || c == '.');
+    
+    if sanitized.is_empty() {
+        "summary".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// 计算 FNV-1a 64 位哈希，各部分之间以非 UTF-8 字节分隔
+///
+/// 用作缓存键，结果不随编译器版本变化
+fn fnv1a_hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0xff)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// 将日志按日期排序后格式化为 Markdown 段落，每个日期一段
+///
+/// 当天有 Git 提交时追加「Commits」小节，已作为 `git-commit` 日志记录过的提交不再重复列出
+fn format_log_sections(
+    logs: &HashMap<String, Vec<LogEntry>>,
+    commits: &BTreeMap<String, Vec<GitCommit>>,
+    include_time: bool,
+) -> Vec<String> {
+    let mut dates: Vec<&String> = logs.keys().collect();
+    dates.sort();
+    
+    let mut days = DaySections::new(commits, include_time);
+    for date in dates {
+        days.push_day(date, &logs[date]);
+    }
+    days.finish().0
+}
+
+/// 按日期从早到晚逐天生成 Markdown 段落
+///
+/// 每天的日志追加后即格式化为文本，调用方不需要同时持有整个时间段的日志；
+/// 只有 Git 提交而没有日志的日期同样生成段落，按日期插入到相应位置
+struct DaySections<'a> {
+    /// 按日期分组的 Git 提交
+    commits: &'a BTreeMap<String, Vec<GitCommit>>,
+    /// 还没有生成段落的提交日期
+    pending_commit_dates: Peekable<btree_map::Keys<'a, String, Vec<GitCommit>>>,
+    /// 是否在日志前加上记录时间
+    include_time: bool,
+    /// 已生成的段落
+    sections: Vec<String>,
+    /// 高优先级日志的行
+    high_priority: Vec<String>,
+}
+
+impl<'a> DaySections<'a> {
+    fn new(commits: &'a BTreeMap<String, Vec<GitCommit>>, include_time: bool) -> Self {
+        Self {
+            commits,
+            pending_commit_dates: commits.keys().peekable(),
+            include_time,
+            sections: Vec::new(),
+            high_priority: Vec::new(),
+        }
+    }
+    
+    /// 追加一天的日志，日期 (YYYY-MM-DD) 需要从早到晚依次追加
+    fn push_day(&mut self, date: &str, entries: &[LogEntry]) {
+        while let Some(commit_date) = self.pending_commit_dates.next_if(|commit_date| commit_date.as_str() < date) {
+            self.sections.push(format_day_section(commit_date, &[], self.commits, self.include_time));
+        }
+        self.pending_commit_dates.next_if(|commit_date| commit_date.as_str() == date);
+        
+        self.high_priority.extend(
+            entries
+                .iter()
+                .filter(|entry| entry.is_high_priority())
+                .map(|entry| format!("- {}（{}）\n", entry.content, date)),
+        );
+        self.sections.push(format_day_section(date, entries, self.commits, self.include_time));
+    }
+    
+    /// 补上剩余只有 Git 提交的日期，返回全部段落和高优先级日志的汇总
+    fn finish(mut self) -> (Vec<String>, Option<String>) {
+        for commit_date in self.pending_commit_dates {
+            self.sections.push(format_day_section(commit_date, &[], self.commits, self.include_time));
+        }
+        
+        let high_priority = (!self.high_priority.is_empty())
+            .then(|| format_high_priority_lines(&self.high_priority));
+        (self.sections, high_priority)
+    }
+}
+
+/// 将一天的日志和当天未记录为日志的 Git 提交格式化为一个段落
+fn format_day_section(
+    date: &str,
+    entries: &[LogEntry],
+    commits: &BTreeMap<String, Vec<GitCommit>>,
+    include_time: bool,
+) -> String {
+    let mut section = format!("## {}\n", date);
+    for entry in entries {
+        section.push_str(&format!("- {}\n", format_entry_line(entry, include_time)));
+    }
+    
+    let day_commits: Vec<&GitCommit> = commits
+        .get(date)
+        .into_iter()
+        .flatten()
+        .filter(|commit| !is_commit_logged(commit, entries))
+        .collect();
+    if !day_commits.is_empty() {
+        section.push_str("### Commits\n");
+        for commit in day_commits {
+            let subject = commit.message.lines().next().unwrap_or("").trim();
+            section.push_str(&format!("- {} ({})\n", subject, short_commit_id(&commit.id)));
+        }
+    }
+    
+    section.push('\n');
+    section
+}
+
+/// 将日志按分组键格式化为 Markdown 段落，每个分组一段，组内按日期排列并带上日期
+///
+/// `keys` 返回日志所属的分组，可以有多个，没有分组的日志列在标题为 `ungrouped_title` 的段落中；
+/// Git 提交不属于任何分组，按日期单独列在最后
+fn format_grouped_sections<F>(
+    logs: &HashMap<String, Vec<LogEntry>>,
+    commits: &BTreeMap<String, Vec<GitCommit>>,
+    include_time: bool,
+    ungrouped_title: &str,
+    keys: F,
+) -> Vec<String>
+where
+    F: Fn(&LogEntry) -> Vec<String>,
+{
+    let mut dates: Vec<&String> = logs.keys().collect();
+    dates.sort();
+    
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+    for date in dates {
+        for entry in &logs[date] {
+            let line = format!("- {} {}\n", date, format_entry_line(entry, include_time));
+            // 同一条日志重复写同一个标签时只列一次
+            let mut entry_keys = keys(entry);
+            entry_keys.sort();
+            entry_keys.dedup();
+            if entry_keys.is_empty() {
+                ungrouped.push(line);
+                continue;
+            }
+            for key in entry_keys {
+                groups.entry(key).or_default().push(line.clone());
+            }
+        }
     }
     
-    /// 生成本地API摘要
-    async fn generate_with_local_api(&self, prompt: &str, logs: &str) -> Result<String, AppError> {
-        // 构建完整提示词
-        let full_prompt = format!("{}\n\n{}", prompt, logs);
+    let mut sections: Vec<String> = groups
+        .into_iter()
+        .map(|(key, lines)| format!("## {}\n{}\n", key, lines.concat()))
+        .collect();
+    if !ungrouped.is_empty() {
+        sections.push(format!("## {}\n{}\n", ungrouped_title, ungrouped.concat()));
+    }
+    
+    let commit_lines: Vec<String> = commits
+        .iter()
+        .flat_map(|(date, day_commits)| {
+            let entries = logs.get(date).map(Vec::as_slice).unwrap_or_default();
+            day_commits
+                .iter()
+                .filter(move |commit| !is_commit_logged(commit, entries))
+                .map(move |commit| {
+                    let subject = commit.message.lines().next().unwrap_or("").trim();
+                    format!("- {} {} ({})\n", date, subject, short_commit_id(&commit.id))
+                })
+        })
+        .collect();
+    if !commit_lines.is_empty() {
+        sections.push(format!("## Commits\n{}\n", commit_lines.concat()));
+    }
+    
+    sections
+}
+
+/// 汇总高优先级的日志，按日期排列，没有高优先级日志时返回 None
+fn format_high_priority_section(logs: &HashMap<String, Vec<LogEntry>>) -> Option<String> {
+    let mut dates: Vec<&String> = logs.keys().collect();
+    dates.sort();
+    
+    let lines: Vec<String> = dates
+        .into_iter()
+        .flat_map(|date| {
+            logs[date]
+                .iter()
+                .filter(|entry| entry.is_high_priority())
+                .map(move |entry| format!("- {}（{}）\n", entry.content, date))
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    
+    Some(format_high_priority_lines(&lines))
+}
+
+/// 高优先级日志汇总的段落
+fn format_high_priority_lines(lines: &[String]) -> String {
+    format!("## 重点事项（请在摘要中优先体现）\n{}\n", lines.concat())
+}
+
+/// 格式化提示词中的单条日志，带状态的记录加上状态前缀，高优先级的记录标注为重要
+///
+/// `include_time` 为 true 且日志有记录时间时，以 `HH:MM` 开头
+fn format_entry_line(entry: &LogEntry, include_time: bool) -> String {
+    let mut line = String::new();
+    if let Some(timestamp) = entry.timestamp.filter(|_| include_time) {
+        line.push_str(&format!("{} ", timestamp.format("%H:%M")));
+    }
+    if entry.is_high_priority() {
+        line.push_str("[重要] ");
+    }
+    if let Some(status) = entry.status {
+        line.push_str(&format!("[{}] ", status.label()));
+    }
+    line.push_str(&entry.content);
+    line
+}
+
+/// 判断提交是否已经以 `git-commit` 来源的日志记录过（按导入 ID、提交 ID 或提交标题匹配）
+fn is_commit_logged(commit: &GitCommit, entries: &[LogEntry]) -> bool {
+    let entry_id = git_commit_entry_id(&commit.id);
+    let short_id = short_commit_id(&commit.id);
+    let subject = commit.message.lines().next().unwrap_or("").trim();
+    
+    entries
+        .iter()
+        .filter(|entry| entry.source == GIT_COMMIT_SOURCE)
+        .any(|entry| {
+            entry.id == entry_id
+                || entry.content.contains(short_id)
+                || (!subject.is_empty() && entry.content.contains(subject))
+        })
+}
+
+/// 取提交 ID 的前 7 位
+fn short_commit_id(id: &str) -> &str {
+    id.get(..7).unwrap_or(id)
+}
+
+/// 将段落贪心合并为不超过 `max_chars` 个字符的批次
+///
+/// 单个段落超过限制时按行拆开，单行超过限制时按字符截断成多段
+fn split_into_batches(sections: &[String], max_chars: usize) -> Vec<String> {
+    let mut batches = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    
+    let mut push_piece = |piece: &str, batches: &mut Vec<String>| {
+        let piece_len = piece.chars().count();
+        if current_len + piece_len > max_chars && !current.is_empty() {
+            batches.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        current.push_str(piece);
+        current_len += piece_len;
+    };
+    
+    for section in sections {
+        if section.chars().count() <= max_chars {
+            push_piece(section, &mut batches);
+            continue;
+        }
         
-        // 调用已有的Ollama生成函数
-        self.generate_with_ollama(&full_prompt).await
+        for line in section.split_inclusive('\n') {
+            let chars: Vec<char> = line.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                push_piece(&piece.iter().collect::<String>(), &mut batches);
+            }
+        }
+    }
+    
+    if !current.is_empty() {
+        batches.push(current);
     }
-} 
\ No newline at end of file
+    
+    batches
+}
+
+/// 判断 HTTP 状态码是否值得重试（限流或服务端错误）
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// 判断请求错误是否为可重试的临时网络故障
+fn is_retryable_reqwest_error(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+}
+
+/// 计算第 attempt 次重试前的等待时间（指数退避，有上限）
+fn retry_delay(attempt: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt));
+    delay.min(RETRY_MAX_DELAY)
+}
+
+/// 渲染提示词模板
+///
+/// 替换 `{title}` 与 `{logs}` 占位符，模板中没有 `{logs}` 时将日志追加到末尾
+fn render_prompt(template: &str, title: &str, logs: &str) -> String {
+    let has_logs_placeholder = template.contains(LOGS_PLACEHOLDER);
+    let prompt = template.replace(TITLE_PLACEHOLDER, title);
+    
+    if has_logs_placeholder {
+        prompt.replace(LOGS_PLACEHOLDER, logs)
+    } else {
+        format!("{}\n\n{}", prompt, logs)
+    }
+}
+
+/// 流式响应的行缓冲
+///
+/// 网络数据块可能在一行中间甚至多字节字符中间断开，
+/// 因此按字节缓冲，只有遇到换行符时才解码出完整的一行
+#[derive(Debug, Default)]
+struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// 追加数据块，返回其中已完整的行
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+        
+        let mut lines = Vec::new();
+        while let Some(pos) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            lines.push(String::from_utf8_lossy(&line).trim_end().to_string());
+        }
+        
+        lines
+    }
+    
+    /// 取出流结束时剩余的不完整行
+    fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&self.pending).trim_end().to_string();
+        self.pending.clear();
+        
+        if line.is_empty() {
+            None
+        } else {
+            Some(line)
+        }
+    }
+}
+
+/// 检查 Anthropic API 响应状态，失败时转换为可读的错误信息
+///
+/// 响应内容在记录和返回前会遮盖 `api_key`
+async fn check_anthropic_status(
+    response: reqwest::Response,
+    api_key: &str,
+) -> Result<reqwest::Response, AppError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    
+    let text = redact_text(&response.text().await.unwrap_or_default(), &[api_key]);
+    log::error!("Anthropic API错误: 状态 {}, 响应: {}", status, text);
+    
+    let detail = serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .and_then(|json| json.get("error").map(extract_error_message))
+        .unwrap_or(text);
+    
+    let error_msg = match status.as_u16() {
+        401 => {
+            return Err(AppError::ApiAuthError(Message::AnthropicAuthFailed.to_string()))
+        }
+        429 => Message::AnthropicRateLimited.to_string(),
+        _ => Message::AnthropicFailed { status: &status, detail: &detail }.to_string(),
+    };
+    
+    Err(AppError::SummaryError(error_msg))
+}
+
+/// 读取 SSE 事件流响应，逐段回调增量文本
+///
+/// 部分服务端（或代理）会忽略 stream 参数直接返回完整 JSON，此时退化为非流式解析
+async fn read_event_stream<F>(
+    response: reqwest::Response,
+    progress_callback: &F,
+    cancel_flag: &AtomicBool,
+) -> Result<String, AppError>
+where
+    F: Fn(&str),
+{
+    let is_event_stream = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("text/event-stream"));
+    
+    if !is_event_stream {
+        log::info!("API未返回事件流，按非流式响应解析");
+        let text = response.text().await.map_err(AppError::ReqwestError)?;
+        let content = parse_non_stream_response(&text)?;
+        progress_callback(&content);
+        return Ok(content);
+    }
+    
+    // 处理响应
+    let mut result = String::new();
+    let mut lines = LineBuffer::default();
+    
+    // 使用流式处理
+    let mut stream = response.bytes_stream();
+    
+    while let Some(item) = stream.next().await {
+        // 取消时直接返回，丢弃响应流即可关闭 HTTP 连接
+        if cancel_flag.load(Ordering::Relaxed) {
+            return Err(AppError::Cancelled);
+        }
+        
+        // 网络中断保留为 ReqwestError，以便在尚未输出内容时重试
+        let bytes = item.map_err(|e| {
+            log::error!("读取响应流失败: {}", e);
+            AppError::ReqwestError(e)
+        })?;
+        
+        for line in lines.push(&bytes) {
+            if handle_sse_line(&line, &mut result, progress_callback)? {
+                return Ok(result);
+            }
+        }
+    }
+    
+    if let Some(line) = lines.finish() {
+        handle_sse_line(&line, &mut result, progress_callback)?;
+    }
+    
+    Ok(result)
+}
+
+/// 处理一行 SSE 数据，返回流是否已结束
+fn handle_sse_line<F>(
+    line: &str,
+    result: &mut String,
+    progress_callback: &F,
+) -> Result<bool, AppError>
+where
+    F: Fn(&str),
+{
+    let data = match line.strip_prefix("data:") {
+        Some(data) => data.trim_start(),
+        None => return Ok(false),
+    };
+    
+    // 检查流是否结束
+    if data == "[DONE]" {
+        return Ok(true);
+    }
+    
+    let json = match serde_json::from_str::<serde_json::Value>(data) {
+        Ok(json) => json,
+        Err(e) => {
+            log::debug!("解析JSON失败: {} - 数据: {}", e, data);
+            return Ok(false);
+        }
+    };
+    
+    if let Some(message) = extract_response_error(&json) {
+        return Err(AppError::SummaryError(Message::StreamReturnedError(&message).to_string()));
+    }
+    
+    if let Some(text) = extract_delta_content(&json) {
+        if !text.is_empty() {
+            result.push_str(text);
+            progress_callback(text);
+        }
+    }
+    
+    Ok(false)
+}
+
+/// 处理一行 Ollama 流式 JSON，返回流是否已结束
+fn handle_ollama_line<F>(
+    line: &str,
+    result: &mut String,
+    progress_callback: &F,
+) -> Result<bool, AppError>
+where
+    F: Fn(&str),
+{
+    if line.trim().is_empty() {
+        return Ok(false);
+    }
+    
+    let json: serde_json::Value = serde_json::from_str(line)?;
+    
+    if let Some(error) = json.get("error") {
+        return Err(AppError::SummaryError(
+            Message::OllamaReturnedError(&extract_error_message(error)).to_string()
+        ));
+    }
+    
+    // `/api/generate` 返回 response 字段，`/api/chat` 返回 message.content 字段
+    let text = json
+        .get("response")
+        .or_else(|| json.pointer("/message/content"))
+        .and_then(|value| value.as_str());
+    if let Some(text) = text {
+        if !text.is_empty() {
+            result.push_str(text);
+            progress_callback(text);
+        }
+    }
+    
+    Ok(json.get("done").and_then(|value| value.as_bool()).unwrap_or(false))
+}
+
+/// 从流式数据块中提取增量文本，兼容 OpenAI、百炼与 Anthropic 格式
+fn extract_delta_content(json: &serde_json::Value) -> Option<&str> {
+    json.pointer("/choices/0/delta/content")
+        .or_else(|| json.pointer("/choices/0/text"))
+        .or_else(|| json.pointer("/output/choices/0/text"))
+        .or_else(|| json.pointer("/delta/text"))
+        .and_then(|content| content.as_str())
+}
+
+/// 从完整响应中提取回复文本
+///
+/// 依次尝试 OpenAI chat (`choices[0].message.content`)、OpenAI completions 与 vLLM
+/// (`choices[0].text`)、百炼 (`output.text`、`output.choices[0].message.content`)、
+/// Anthropic (`content[0].text`) 以及 Ollama (`message.content`、`response`) 的格式
+fn extract_message_content(json: &serde_json::Value) -> Option<&str> {
+    const CONTENT_POINTERS: [&str; 7] = [
+        "/choices/0/message/content",
+        "/choices/0/text",
+        "/output/text",
+        "/output/choices/0/message/content",
+        "/content/0/text",
+        "/message/content",
+        "/response",
+    ];
+    
+    CONTENT_POINTERS
+        .iter()
+        .find_map(|pointer| json.pointer(pointer).and_then(|content| content.as_str()))
+}
+
+/// 提取响应中的错误信息，兼容 HTTP 状态为 200 但内容为错误的响应
+///
+/// 识别非空的 `error` 字段、vLLM 的 `{"object": "error"}` 以及百炼的 `{"code", "message"}` 格式
+fn extract_response_error(json: &serde_json::Value) -> Option<String> {
+    if let Some(error) = json.get("error").filter(|error| !error.is_null()) {
+        return Some(extract_error_message(error));
+    }
+    
+    let message = json.get("message").and_then(|message| message.as_str());
+    if json.get("object").and_then(|object| object.as_str()) == Some("error") {
+        return Some(message.map_or_else(|| Message::UnknownError.to_string(), str::to_string));
+    }
+    
+    let code = json
+        .get("code")
+        .and_then(|code| code.as_str())
+        .filter(|code| !code.is_empty());
+    match (code, message) {
+        (Some(code), Some(message)) if json.get("output").is_none() => {
+            Some(format!("{} ({})", message, code))
+        }
+        _ => None,
+    }
+}
+
+/// 截取响应内容的开头用于错误信息，便于排查无法识别的响应格式
+fn response_snippet(text: &str) -> String {
+    const MAX_SNIPPET_CHARS: usize = 300;
+    
+    let text = text.trim();
+    if text.chars().count() <= MAX_SNIPPET_CHARS {
+        return text.to_string();
+    }
+    let snippet: String = text.chars().take(MAX_SNIPPET_CHARS).collect();
+    format!("{}...", snippet)
+}
+
+/// 提取错误对象中的错误信息
+fn extract_error_message(error: &serde_json::Value) -> String {
+    error
+        .get("message")
+        .and_then(|message| message.as_str())
+        .or_else(|| error.as_str())
+        .map(|message| message.to_string())
+        .unwrap_or_else(|| error.to_string())
+}
+
+/// 解析未使用事件流返回的完整响应
+///
+/// 响应中包含错误信息时即使 HTTP 状态为 200 也视为失败，
+/// 无法识别的响应格式在错误信息中附带响应内容的开头
+fn parse_non_stream_response(text: &str) -> Result<String, AppError> {
+    let json: serde_json::Value = serde_json::from_str(text).map_err(|e| {
+        log::error!("解析JSON响应失败: {}", e);
+        AppError::SummaryError(Message::ResponseNotJson {
+            error: &e,
+            snippet: &response_snippet(text),
+        }.to_string())
+    })?;
+    
+    if let Some(message) = extract_response_error(&json) {
+        return Err(AppError::SummaryError(Message::ApiReturnedError(&message).to_string()));
+    }
+    
+    if let Some(content) = extract_message_content(&json) {
+        return Ok(content.to_string());
+    }
+    
+    Err(AppError::SummaryError(
+        Message::UnrecognizedResponse(&response_snippet(text)).to_string()
+    ))
+}
+
+/// 解析 Ollama `/api/tags` 响应，返回本地模型名称
+fn parse_ollama_models(text: &str) -> Result<Vec<String>, AppError> {
+    let tags: OllamaTagsResponse = serde_json::from_str(text).map_err(|e| {
+        AppError::SummaryError(Message::OllamaListUnreadable(&e).to_string())
+    })?;
+    Ok(tags.models.into_iter().map(|model| model.name).collect())
+}
+
+/// 判断本地模型名称是否与配置的模型一致，未指定标签时等同于 `latest`
+fn ollama_model_matches(name: &str, model: &str) -> bool {
+    name == model || (!model.contains(':') && name == format!("{}:latest", model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_manager::PRIORITY_HIGH;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    /// 使用临时目录保存日志和摘要的设置
+    fn test_settings(dir: &TempDir) -> Settings {
+        Settings {
+            log_storage_dir: dir.path().to_string_lossy().to_string(),
+            log_output_dir: dir.path().join("output").to_string_lossy().to_string(),
+            ..Settings::default()
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// 指定日期本地时间 `hour` 点创建的记录
+    fn entry_at(content: &str, source: &str, day: NaiveDate, hour: u32) -> LogEntry {
+        let created_at = Local
+            .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+            .unwrap();
+        LogEntry::new_with_datetime(content.to_string(), source.to_string(), Vec::new(), created_at)
+    }
+
+    fn config_for(period: &SummaryPeriod) -> SummaryConfig {
+        SummaryConfig {
+            summary_type: SummaryType::Custom,
+            start_date: Some(period.start_date),
+            end_date: Some(period.end_date),
+            title: "测试摘要".to_string(),
+            include_git: false,
+            git_repo_paths: Vec::new(),
+            format: SummaryFormat::Markdown,
+            group_by: GroupBy::Date,
+        }
+    }
+
+    #[test]
+    fn range_sections_match_loaded_sections() {
+        let dir = TempDir::new().unwrap();
+        let settings = test_settings(&dir);
+        let manager = LogManager::new(settings.clone());
+        let mut important = entry_at("发布版本", "manual", date(2024, 5, 3), 15);
+        important.priority = Some(PRIORITY_HIGH);
+        for entry in [
+            entry_at("修复登录问题", "manual", date(2024, 5, 1), 9),
+            entry_at("已生成摘要", SUMMARY_SOURCE, date(2024, 5, 2), 18),
+            entry_at("评审代码", "manual", date(2024, 5, 3), 10),
+            important,
+        ] {
+            manager.add_entry(entry).unwrap();
+        }
+
+        let period = SummaryPeriod::new(date(2024, 5, 1), date(2024, 5, 7)).unwrap();
+        let config = config_for(&period);
+        let generator = SummaryGenerator::new(settings);
+        let loaded = SummaryLogs::Loaded(
+            manager
+                .get_entries_in_date_range(&period.start_date, &period.end_date)
+                .unwrap(),
+        );
+
+        let (streamed, last_date) = generator
+            .summary_log_sections(&without_summary_entries(SummaryLogs::Range(period)), &config)
+            .unwrap();
+        let (expected, expected_last_date) = generator
+            .summary_log_sections(&without_summary_entries(loaded), &config)
+            .unwrap();
+
+        assert_eq!(streamed, expected);
+        assert_eq!(last_date, Some(date(2024, 5, 3)));
+        assert_eq!(expected_last_date, last_date);
+        assert!(streamed[0].contains("发布版本"));
+        assert!(streamed.iter().all(|section| !section.contains("已生成摘要")));
+    }
+
+    #[test]
+    fn date_range_for_each_summary_type() {
+        // 闰年二月的最后一天，周四
+        let today = date(2024, 2, 29);
+        let cases = [
+            (SummaryType::Daily, false, (today, today)),
+            (SummaryType::Weekly, false, (date(2024, 2, 26), date(2024, 3, 3))),
+            (SummaryType::Monthly, false, (date(2024, 2, 1), date(2024, 2, 29))),
+            (SummaryType::Quarterly, false, (date(2024, 1, 1), date(2024, 3, 31))),
+            (SummaryType::Custom, false, (today, today)),
+            (SummaryType::Daily, true, (today, today)),
+            (SummaryType::Weekly, true, (date(2024, 2, 22), today)),
+            (SummaryType::Monthly, true, (date(2024, 1, 30), today)),
+            (SummaryType::Quarterly, true, (date(2023, 12, 1), today)),
+        ];
+        for (summary_type, rolling, expected) in cases {
+            assert_eq!(
+                summary_type.date_range(today, rolling),
+                expected,
+                "{:?} rolling={}",
+                summary_type,
+                rolling
+            );
+        }
+
+        // 周日属于本周，第四季度到年末
+        assert_eq!(
+            SummaryType::Weekly.date_range(date(2024, 3, 3), false),
+            (date(2024, 2, 26), date(2024, 3, 3))
+        );
+        assert_eq!(
+            SummaryType::Quarterly.date_range(date(2024, 11, 15), false),
+            (date(2024, 10, 1), date(2024, 12, 31))
+        );
+    }
+
+    /// 在本地端口上启动只响应一次请求的 HTTP 服务，返回服务地址
+    fn serve_once(body: &'static str) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        address
+    }
+
+    const OLLAMA_TAGS: &str =
+        r#"{"models":[{"name":"llama3:latest","size":4661224676},{"name":"qwen2:7b","size":4431388192}]}"#;
+
+    #[tokio::test]
+    async fn missing_ollama_model_is_reported() {
+        assert_eq!(
+            parse_ollama_models(OLLAMA_TAGS).unwrap(),
+            ["llama3:latest", "qwen2:7b"]
+        );
+        assert!(ollama_model_matches("llama3:latest", "llama3"));
+        assert!(!ollama_model_matches("qwen2:7b", "qwen2"));
+        assert!(parse_ollama_models("not json").is_err());
+
+        let dir = TempDir::new().unwrap();
+        let generator_for = |model: &str| {
+            SummaryGenerator::new(Settings {
+                ollama_address: serve_once(OLLAMA_TAGS),
+                ollama_model: model.to_string(),
+                ..test_settings(&dir)
+            })
+        };
+
+        generator_for("llama3").check_ollama_model().await.unwrap();
+        generator_for("qwen2:7b").check_ollama_model().await.unwrap();
+
+        let error = generator_for("mistral")
+            .check_ollama_model()
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'mistral'"), "{}", error);
+        assert!(error.contains("ollama pull mistral"), "{}", error);
+    }
+
+    #[test]
+    fn provider_responses_are_parsed() {
+        let fixtures = [
+            (
+                "OpenAI",
+                r#"{"id":"chatcmpl-1","object":"chat.completion","choices":[{"index":0,"message":{"role":"assistant","content":"本周修复了登录问题"},"finish_reason":"stop"}],"error":null}"#,
+                "本周修复了登录问题",
+            ),
+            (
+                "OpenAI completions",
+                r#"{"id":"cmpl-1","object":"text_completion","choices":[{"index":0,"text":"补全输出"}]}"#,
+                "补全输出",
+            ),
+            (
+                "Anthropic",
+                r#"{"id":"msg_1","type":"message","role":"assistant","content":[{"type":"text","text":"Anthropic 输出"}],"stop_reason":"end_turn"}"#,
+                "Anthropic 输出",
+            ),
+            (
+                "Ollama generate",
+                r#"{"model":"llama3","created_at":"2024-06-01T10:00:00Z","response":"Ollama 输出","done":true}"#,
+                "Ollama 输出",
+            ),
+            (
+                "Ollama chat",
+                r#"{"model":"llama3","message":{"role":"assistant","content":"Ollama chat 输出"},"done":true}"#,
+                "Ollama chat 输出",
+            ),
+        ];
+        for (provider, body, expected) in fixtures {
+            assert_eq!(parse_non_stream_response(body).unwrap(), expected, "{}", provider);
+        }
+
+        // HTTP 状态为 200 但内容是错误
+        let errors = [
+            (
+                r#"{"error":{"message":"Incorrect API key provided","type":"invalid_request_error"}}"#,
+                "Incorrect API key provided",
+            ),
+            (
+                r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+                "Overloaded",
+            ),
+            (r#"{"error":"model 'llama3' not found"}"#, "model 'llama3' not found"),
+            (
+                r#"{"object":"error","message":"The model does not exist.","code":404}"#,
+                "The model does not exist.",
+            ),
+        ];
+        for (body, message) in errors {
+            let error = parse_non_stream_response(body).unwrap_err().to_string();
+            assert!(error.contains(message), "{}", error);
+        }
+
+        // 无法识别的响应在错误中附带响应内容
+        let error = parse_non_stream_response(r#"{"result":"unknown"}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains(r#""result":"unknown""#), "{}", error);
+        let error = parse_non_stream_response("<html>502 Bad Gateway</html>")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("502 Bad Gateway"), "{}", error);
+    }
+
+    #[test]
+    fn existing_summary_files_are_not_overwritten() {
+        let dir = TempDir::new().unwrap();
+        let output = dir.path().join("output");
+        let file_name = "weekly_summary_2024-06-01.md";
+        let read = |name: &str| fs::read_to_string(output.join(name)).unwrap();
+
+        // 默认添加数字后缀
+        let generator = SummaryGenerator::new(test_settings(&dir));
+        let first = generator.save_summary_file(file_name, "第一次").unwrap().unwrap();
+        let second = generator.save_summary_file(file_name, "第二次").unwrap().unwrap();
+        let third = generator.save_summary_file(file_name, "第三次").unwrap().unwrap();
+        assert_eq!(first, output.join(file_name));
+        assert_eq!(second, output.join("weekly_summary_2024-06-01-2.md"));
+        assert_eq!(third, output.join("weekly_summary_2024-06-01-3.md"));
+        assert_eq!(read(file_name), "第一次");
+        assert_eq!(read("weekly_summary_2024-06-01-2.md"), "第二次");
+
+        // 设置为 skip 时保留已有文件
+        let generator = SummaryGenerator::new(Settings {
+            summary_file_conflict: "skip".to_string(),
+            ..test_settings(&dir)
+        });
+        assert_eq!(generator.save_summary_file(file_name, "跳过").unwrap(), None);
+        assert_eq!(read(file_name), "第一次");
+        assert!(generator.save_summary_file("new.md", "新文件").unwrap().is_some());
+
+        let generator = SummaryGenerator::new(test_settings(&dir))
+            .with_file_conflict(SummaryFileConflict::Overwrite);
+        assert_eq!(
+            generator.save_summary_file(file_name, "覆盖").unwrap(),
+            Some(output.join(file_name))
+        );
+        assert_eq!(read(file_name), "覆盖");
+        assert!(!output.join("weekly_summary_2024-06-01-4.md").exists());
+
+        assert_eq!(SummaryFileConflict::from_name(" Overwrite "), Some(SummaryFileConflict::Overwrite));
+        assert_eq!(SummaryFileConflict::from_name("rename"), None);
+    }
+
+    #[tokio::test]
+    async fn unreachable_backend_falls_back_to_offline_summary() {
+        // 绑定后立即释放端口，连接时被拒绝
+        let closed_address = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        for api_provider in ["ollama", "openai", crate::settings::API_PROVIDER_ANTHROPIC] {
+            let dir = TempDir::new().unwrap();
+            let settings = Settings {
+                use_local_ollama: api_provider == "ollama",
+                ollama_address: closed_address.clone(),
+                api_provider: api_provider.to_string(),
+                llm_api_url: format!("{}/v1/chat/completions", closed_address),
+                llm_api_key: "test-key".to_string(),
+                llm_model: "test-model".to_string(),
+                summary_max_retries: 0,
+                ..test_settings(&dir)
+            };
+            let manager = LogManager::new(settings.clone());
+            manager
+                .add_entry(entry_at("修复登录问题", "manual", date(2024, 5, 2), 10))
+                .unwrap();
+
+            let period = SummaryPeriod::new(date(2024, 5, 1), date(2024, 5, 7)).unwrap();
+            let outcome = SummaryGenerator::new(settings)
+                .generate_summary_with_stream(SummaryLogs::Range(period), config_for(&period), |_| {})
+                .await
+                .unwrap();
+
+            assert_eq!(outcome.model, None, "{}", api_provider);
+            assert!(outcome.summary.contains("离线摘要"), "{}", outcome.summary);
+            assert!(outcome.summary.contains("修复登录问题"), "{}", outcome.summary);
+        }
+    }
+
+    #[test]
+    fn generation_options_change_the_cache_key() {
+        let dir = TempDir::new().unwrap();
+        let period = SummaryPeriod::new(date(2024, 5, 1), date(2024, 5, 7)).unwrap();
+        let config = config_for(&period);
+        let sections = vec!["修复登录问题".to_string()];
+        let cache_key = |settings: Settings| {
+            SummaryGenerator::new(settings).summary_cache_key(&sections, &config)
+        };
+
+        let base = cache_key(test_settings(&dir));
+        assert_eq!(cache_key(test_settings(&dir)), base);
+
+        let mut warmer = test_settings(&dir);
+        warmer.summary_temperature += 0.5;
+        assert_ne!(cache_key(warmer), base);
+
+        let mut longer = test_settings(&dir);
+        longer.summary_max_tokens += 100;
+        assert_ne!(cache_key(longer), base);
+
+        let mut with_options = test_settings(&dir);
+        with_options.ollama_options.insert("top_p".to_string(), serde_json::json!(0.9));
+        assert_ne!(cache_key(with_options), base);
+
+        // 温度变化后不会读到之前缓存的摘要
+        let generator = SummaryGenerator::new(test_settings(&dir));
+        generator.write_cached_summary(&base, "缓存的摘要");
+        assert_eq!(generator.read_cached_summary(&base).as_deref(), Some("缓存的摘要"));
+        let mut warmer = test_settings(&dir);
+        warmer.summary_temperature += 0.5;
+        let warmer = SummaryGenerator::new(warmer);
+        let warmer_key = warmer.summary_cache_key(&sections, &config);
+        assert_eq!(warmer.read_cached_summary(&warmer_key), None);
+    }
+}
@@ -1,5 +1,10 @@
 use crate::app_state::AppState;
+use crate::commands::add_clipboard_entry;
 use crate::errors::AppError;
+use crate::log_manager::{file_modified_time, LogEntry, LogManager, SortOrder};
+use crate::messages::{Language, Message};
+use crate::settings::{validate_shortcut, Settings};
+use chrono::{Local, NaiveDate};
 use tauri::{
     AppHandle, CustomMenuItem, GlobalShortcutManager, Manager, SystemTrayEvent, SystemTrayMenu,
     SystemTrayMenuItem,
@@ -10,28 +15,19 @@ pub fn setup_system_tray(app_handle: AppHandle, state: AppState) -> Result<(), A
     // 更新应用句柄
     state.set_app_handle(app_handle.clone());
 
-    // 注册快捷键
-    let settings = state.get_settings();
-    if !settings.shortcut.is_empty() {
-        let app_handle_clone = app_handle.clone();
-        app_handle
-            .global_shortcut_manager()
-            .register(&settings.shortcut, move || {
-                if let Some(window) = app_handle_clone.get_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            })
-            .map_err(|e| AppError::TauriError(e.into()))?;
-    }
+    // 注册快捷键，失败时只提示，不影响应用启动
+    apply_shortcut(&app_handle, &state.get_settings());
 
     // 设置系统托盘
-    let tray_menu = get_tray_menu();
+    let tray_menu = get_tray_menu(&state);
     app_handle
         .tray_handle()
         .set_menu(tray_menu)
         .map_err(|e| AppError::TauriError(e))?;
 
+    // 日期变化或命令行添加日志后刷新托盘菜单
+    start_tray_refresh_task(state.clone());
+
     // 如果是 macOS，设置图标显示模式
     #[cfg(target_os = "macos")]
     {
@@ -44,6 +40,107 @@ pub fn setup_system_tray(app_handle: AppHandle, state: AppState) -> Result<(), A
     Ok(())
 }
 
+/// 检查托盘菜单是否需要刷新的间隔
+const TRAY_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// 定期检查当天的日志，日期变化或日志文件被其他进程（如命令行）修改时重建托盘菜单
+///
+/// 应用内的修改已由命令刷新菜单，这里只负责应用之外的变化
+fn start_tray_refresh_task(state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = today_log_version(&state);
+        loop {
+            tokio::time::sleep(TRAY_REFRESH_INTERVAL).await;
+
+            let current = today_log_version(&state);
+            if current != last {
+                refresh_tray_menu(&state);
+                last = current;
+            }
+        }
+    });
+}
+
+/// 当天日期及当天日志文件的修改时间，任一变化都说明托盘菜单可能已经过时
+fn today_log_version(state: &AppState) -> (NaiveDate, String) {
+    let today = Local::now().date_naive();
+    let path = LogManager::new(state.get_settings()).get_log_file_path(&today);
+    (today, file_modified_time(&path))
+}
+
+/// 快捷键注册失败时向前端发送的事件，内容为提示信息
+pub const SHORTCUT_ERROR_EVENT: &str = "shortcut-registration-failed";
+
+/// 按设置注册全局快捷键
+///
+/// 先注销已注册的快捷键，只有启用快捷键且快捷键不为空时才重新注册。
+/// 快捷键无效或已被其他应用占用时记录警告并通过 `shortcut-registration-failed` 事件通知界面，
+/// 应用继续在没有快捷键的情况下运行
+pub fn apply_shortcut(app_handle: &AppHandle, settings: &Settings) {
+    if let Err(e) = app_handle.global_shortcut_manager().unregister_all() {
+        log::warn!("注销快捷键失败: {}", e);
+    }
+
+    if !settings.enable_shortcut || settings.shortcut.is_empty() {
+        return;
+    }
+
+    let app_handle_clone = app_handle.clone();
+    let result = app_handle
+        .global_shortcut_manager()
+        .register(&settings.shortcut, move || {
+            if let Some(window) = app_handle_clone.get_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        });
+
+    if let Err(e) = result {
+        let message = Message::ShortcutRegisterFailed {
+            shortcut: &settings.shortcut,
+            error: &e,
+        }
+        .to_string();
+        log::warn!("{}", message);
+        let _ = app_handle.emit_all(SHORTCUT_ERROR_EVENT, message);
+    }
+}
+
+/// 检查快捷键能否注册：格式可以识别，且没有被其他应用占用
+///
+/// 本应用当前已注册的快捷键视为可用
+pub fn check_shortcut(app_handle: &AppHandle, shortcut: &str) -> Result<(), String> {
+    validate_shortcut(shortcut)?;
+
+    let mut manager = app_handle.global_shortcut_manager();
+    if manager.is_registered(shortcut).unwrap_or(false) {
+        return Ok(());
+    }
+
+    // 尝试注册后立即注销，注册失败说明快捷键不可用
+    manager.register(shortcut, || {}).map_err(|e| {
+        Message::ShortcutUnavailable {
+            shortcut,
+            error: &e,
+        }
+        .to_string()
+    })?;
+    if let Err(e) = manager.unregister(shortcut) {
+        log::warn!("注销快捷键 {} 失败: {}", shortcut, e);
+    }
+
+    Ok(())
+}
+
+/// 托盘菜单中显示的最近记录数量
+const TRAY_RECENT_ENTRIES: usize = 5;
+
+/// 托盘菜单中记录内容的最大显示字符数
+const TRAY_ENTRY_PREVIEW_CHARS: usize = 24;
+
+/// 最近记录菜单项 ID 的前缀，后接记录 ID
+const RECENT_ENTRY_PREFIX: &str = "recent_entry:";
+
 /// 处理系统托盘事件
 pub fn handle_system_tray_event(app: &AppHandle, event: SystemTrayEvent) {
     match event {
@@ -54,6 +151,18 @@ pub fn handle_system_tray_event(app: &AppHandle, event: SystemTrayEvent) {
                     .get_window("main")
                     .map(|w| w.emit("navigate", "log-files"));
             }
+            "quick_add_clipboard" => {
+                // 结果通过系统通知告知，这里只记录日志
+                let state = app.state::<AppState>();
+                if let Err(e) = add_clipboard_entry(app, &state) {
+                    log::warn!("从剪贴板添加日志失败: {}", e);
+                }
+            }
+            "toggle_shortcut" => {
+                if let Err(e) = toggle_shortcut(app) {
+                    log::error!("切换快捷键失败: {}", e);
+                }
+            }
             "settings" => {
                 let _ = show_main_window(app);
                 let _ = app
@@ -66,7 +175,20 @@ pub fn handle_system_tray_event(app: &AppHandle, event: SystemTrayEvent) {
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                if let Some(entry_id) = id.strip_prefix(RECENT_ENTRY_PREFIX) {
+                    let _ = show_main_window(app);
+                    let _ = app.get_window("main").map(|w| {
+                        w.emit(
+                            "focus_entry",
+                            serde_json::json!({
+                                "id": entry_id,
+                                "date": Local::now().date_naive().format("%Y-%m-%d").to_string(),
+                            }),
+                        )
+                    });
+                }
+            }
         },
         SystemTrayEvent::LeftClick { .. } => {
             let _ = show_main_window(app);
@@ -76,20 +198,106 @@ pub fn handle_system_tray_event(app: &AppHandle, event: SystemTrayEvent) {
 }
 
 /// 创建系统托盘菜单
-pub fn get_tray_menu() -> SystemTrayMenu {
-    let add_log = CustomMenuItem::new("add_log".to_string(), "添加日志");
-    let settings = CustomMenuItem::new("settings".to_string(), "设置");
-    let open_main = CustomMenuItem::new("open_main".to_string(), "打开主窗口");
-    let quit = CustomMenuItem::new("quit".to_string(), "退出");
+///
+/// 顶部显示今日记录数量和最近几条记录，点击记录会打开主窗口并定位到该记录
+pub fn get_tray_menu(state: &AppState) -> SystemTrayMenu {
+    let settings = state.get_settings();
+    let language = Language::from_code(&settings.language);
+    let today = Local::now().date_naive();
+    let entries = LogManager::new(settings.clone())
+        .get_entries_for_date_sorted(&today, SortOrder::Desc)
+        .unwrap_or_else(|e| {
+            log::warn!("读取今日日志失败: {}", e);
+            Vec::new()
+        });
 
-    SystemTrayMenu::new()
+    let mut menu = SystemTrayMenu::new().add_item(
+        CustomMenuItem::new(
+            "today_count".to_string(),
+            Message::TrayTodayCount(entries.len()).text(language),
+        )
+        .disabled(),
+    );
+    for entry in entries.iter().take(TRAY_RECENT_ENTRIES) {
+        menu = menu.add_item(CustomMenuItem::new(
+            format!("{}{}", RECENT_ENTRY_PREFIX, entry.id),
+            entry_menu_title(entry),
+        ));
+    }
+
+    let add_log = CustomMenuItem::new("add_log".to_string(), Message::TrayAddLog.text(language));
+    let quick_add_clipboard = CustomMenuItem::new(
+        "quick_add_clipboard".to_string(),
+        Message::TrayAddFromClipboard.text(language),
+    );
+    let shortcut = Some(settings.shortcut.as_str()).filter(|shortcut| !shortcut.is_empty());
+    let toggle_title = Message::TrayEnableShortcut(shortcut).text(language);
+    let mut toggle_shortcut = CustomMenuItem::new("toggle_shortcut".to_string(), toggle_title);
+    // 未设置快捷键时无法启用
+    if settings.shortcut.is_empty() {
+        toggle_shortcut = toggle_shortcut.disabled();
+    }
+    if settings.enable_shortcut {
+        toggle_shortcut = toggle_shortcut.selected();
+    }
+
+    let settings =
+        CustomMenuItem::new("settings".to_string(), Message::TraySettings.text(language));
+    let open_main = CustomMenuItem::new(
+        "open_main".to_string(),
+        Message::TrayOpenMain.text(language),
+    );
+    let quit = CustomMenuItem::new("quit".to_string(), Message::TrayQuit.text(language));
+
+    menu.add_native_item(SystemTrayMenuItem::Separator)
         .add_item(add_log)
+        .add_item(quick_add_clipboard)
+        .add_item(toggle_shortcut)
         .add_item(settings)
         .add_item(open_main)
         .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(quit)
 }
 
+/// 根据最新的日志记录重建托盘菜单，在添加或删除记录后调用
+pub fn refresh_tray_menu(state: &AppState) {
+    let app_handle = match state.app_handle.lock() {
+        Ok(app_handle) => app_handle.clone(),
+        Err(_) => return,
+    };
+
+    if let Some(app_handle) = app_handle {
+        if let Err(e) = app_handle.tray_handle().set_menu(get_tray_menu(state)) {
+            log::warn!("更新托盘菜单失败: {}", e);
+        }
+    }
+}
+
+/// 切换是否启用全局快捷键，保存设置后重新注册快捷键并更新菜单的勾选状态
+fn toggle_shortcut(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut settings = state.get_settings();
+    settings.enable_shortcut = !settings.enable_shortcut;
+
+    state
+        .update_settings(settings.clone())
+        .map_err(|e| e.to_string())?;
+    apply_shortcut(app, &settings);
+    refresh_tray_menu(&state);
+
+    Ok(())
+}
+
+/// 托盘菜单中记录的标题，只取第一行并截断过长的内容
+fn entry_menu_title(entry: &LogEntry) -> String {
+    let first_line = entry.content.lines().next().unwrap_or("").trim();
+    let mut title: String = first_line.chars().take(TRAY_ENTRY_PREVIEW_CHARS).collect();
+    if first_line.chars().count() > TRAY_ENTRY_PREVIEW_CHARS {
+        title.push('…');
+    }
+    title
+}
+
 /// 显示主窗口
 fn show_main_window(app: &AppHandle) -> Result<(), String> {
     if let Some(window) = app.get_window("main") {
@@ -0,0 +1,201 @@
+use crate::app_state::AppState;
+use crate::errors::AppError;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{Manager, Monitor, PhysicalPosition, PhysicalSize, Window, WindowEvent};
+
+/// 窗口状态文件名，与设置文件放在同一目录
+const WINDOW_STATE_FILE_NAME: &str = "window_state.json";
+
+/// 窗口停止移动或缩放后延迟保存的时间，拖动过程中不反复写文件
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 需要记住位置和大小的窗口
+pub const TRACKED_WINDOWS: [&str; 2] = ["main", "quick_entry"];
+
+/// 窗口位置和大小，均为物理像素
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 已保存的窗口状态，按窗口标签索引，避免每次移动都重新读取文件
+static WINDOW_STATES: Mutex<Option<HashMap<String, WindowGeometry>>> = Mutex::new(None);
+
+/// 当前档案的窗口状态文件路径，非默认档案使用 `window_state.{档案}.json`
+fn window_state_path(profile: Option<&str>) -> PathBuf {
+    let file_name = match profile {
+        Some(profile) => format!("window_state.{}.json", profile),
+        None => WINDOW_STATE_FILE_NAME.to_string(),
+    };
+    Settings::get_settings_path(profile).with_file_name(file_name)
+}
+
+/// 读取窗口状态文件，文件不存在或损坏时返回空表
+fn load_window_states(path: &Path) -> HashMap<String, WindowGeometry> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return HashMap::new(),
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("窗口状态文件 {} 格式错误，已忽略: {}", path.display(), e);
+        HashMap::new()
+    })
+}
+
+fn write_window_states(
+    path: &Path,
+    states: &HashMap<String, WindowGeometry>,
+) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(states)?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// 恢复窗口的位置和大小，并在窗口停止移动、缩放后或关闭时保存
+pub fn track_window(window: &Window) {
+    let path = window_state_path(window.state::<AppState>().profile.as_deref());
+    restore_window_state(window, &path);
+
+    // 每次移动或缩放都更新序号，延迟保存时序号未变说明窗口已经停下
+    let generation = Arc::new(AtomicU64::new(0));
+    let tracked = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
+            let generation = generation.clone();
+            let window = tracked.clone();
+            let path = path.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(SAVE_DEBOUNCE).await;
+                if generation.load(Ordering::SeqCst) == current {
+                    save_window_state(&window, &path);
+                }
+            });
+        }
+        WindowEvent::CloseRequested { .. } => {
+            generation.fetch_add(1, Ordering::SeqCst);
+            save_window_state(&tracked, &path);
+        }
+        _ => {}
+    });
+}
+
+/// 已保存的窗口状态，锁失效时状态表仍然可用，直接取回
+fn window_states() -> MutexGuard<'static, Option<HashMap<String, WindowGeometry>>> {
+    WINDOW_STATES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 按保存的状态设置窗口位置和大小，保证窗口落在某个显示器内
+fn restore_window_state(window: &Window, path: &Path) {
+    let saved = window_states()
+        .get_or_insert_with(|| load_window_states(path))
+        .get(window.label())
+        .copied();
+    let Some(saved) = saved else {
+        return;
+    };
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let monitor = best_monitor(&saved, &monitors)
+        .cloned()
+        .or_else(|| window.primary_monitor().ok().flatten());
+    let geometry = match &monitor {
+        Some(monitor) => clamp_to_monitor(&saved, monitor),
+        None => saved,
+    };
+
+    if let Err(e) = window.set_size(PhysicalSize::new(geometry.width, geometry.height)) {
+        log::warn!("恢复窗口 {} 大小失败: {}", window.label(), e);
+    }
+    if let Err(e) = window.set_position(PhysicalPosition::new(geometry.x, geometry.y)) {
+        log::warn!("恢复窗口 {} 位置失败: {}", window.label(), e);
+    }
+}
+
+/// 保存窗口当前的位置和大小，最小化时的位置没有意义，不保存
+fn save_window_state(window: &Window, path: &Path) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    if size.width == 0 || size.height == 0 {
+        return;
+    }
+
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let mut states = window_states();
+    let states = states.get_or_insert_with(|| load_window_states(path));
+    if states.get(window.label()) == Some(&geometry) {
+        return;
+    }
+
+    states.insert(window.label().to_string(), geometry);
+    if let Err(e) = write_window_states(path, states) {
+        log::warn!("保存窗口状态失败: {}", e);
+    }
+}
+
+/// 找到与保存的窗口区域重叠最多的显示器，完全不重叠时返回 None
+fn best_monitor<'a>(geometry: &WindowGeometry, monitors: &'a [Monitor]) -> Option<&'a Monitor> {
+    monitors
+        .iter()
+        .map(|monitor| (monitor, overlap_area(geometry, monitor)))
+        .filter(|(_, area)| *area > 0)
+        .max_by_key(|(_, area)| *area)
+        .map(|(monitor, _)| monitor)
+}
+
+fn overlap_area(geometry: &WindowGeometry, monitor: &Monitor) -> i64 {
+    let position = monitor.position();
+    let size = monitor.size();
+
+    let left = i64::from(geometry.x).max(i64::from(position.x));
+    let top = i64::from(geometry.y).max(i64::from(position.y));
+    let right = (i64::from(geometry.x) + i64::from(geometry.width))
+        .min(i64::from(position.x) + i64::from(size.width));
+    let bottom = (i64::from(geometry.y) + i64::from(geometry.height))
+        .min(i64::from(position.y) + i64::from(size.height));
+
+    (right - left).max(0) * (bottom - top).max(0)
+}
+
+/// 缩小超出显示器的窗口，并把窗口移回显示器范围内
+fn clamp_to_monitor(geometry: &WindowGeometry, monitor: &Monitor) -> WindowGeometry {
+    let position = monitor.position();
+    let size = monitor.size();
+
+    let width = geometry.width.min(size.width);
+    let height = geometry.height.min(size.height);
+    let max_x = position.x + (size.width - width) as i32;
+    let max_y = position.y + (size.height - height) as i32;
+
+    WindowGeometry {
+        x: geometry.x.clamp(position.x, max_x),
+        y: geometry.y.clamp(position.y, max_y),
+        width,
+        height,
+    }
+}
@@ -2,6 +2,7 @@
  * 应用头部组件
  */
 import { createIcon } from './icons';
+import { errorMessage } from '../errors';
 
 class AppHeader extends HTMLElement {
   constructor() {
@@ -52,7 +53,7 @@ class AppHeader extends HTMLElement {
       console.log('快速添加日志窗口已打开');
     } catch (error) {
       console.error('调用 Tauri API 失败:', error);
-      alert(`打开添加日志窗口失败: ${error instanceof Error ? error.message : String(error)}`);
+      alert(`打开添加日志窗口失败: ${errorMessage(error)}`);
     }
   }
 
@@ -1,6 +1,8 @@
 /**
  * 日志条目输入表单组件
  */
+import { isCommandError } from '../errors';
+
 class LogEntryForm extends HTMLElement {
   private selectedTags: Set<string> = new Set();
 
@@ -84,22 +86,26 @@ class LogEntryForm extends HTMLElement {
       const { invoke } = window.__TAURI__;
       console.log('调用添加日志接口，内容:', content);
       
-      await invoke('add_log_entry', {
+      const entry = await invoke('add_log_entry', {
         content,
         source: 'manual',
         tags: Array.from(this.selectedTags),
       });
 
-      console.log('日志添加成功');
+      console.log('日志添加成功，ID:', entry.id);
       this.showSuccess('日志添加成功');
       this.resetForm();
       
       // 触发日志更新事件，通知文件列表组件刷新
-      const event = new CustomEvent('log-added');
+      const event = new CustomEvent('log-added', { detail: entry });
       this.dispatchEvent(event);
     } catch (error) {
       console.error('添加日志失败:', error);
-      this.showError('添加日志失败，请重试');
+      if (isCommandError(error) && error.code === 'duplicate_entry') {
+        this.showError('今天已经记录过相同的内容');
+      } else {
+        this.showError('添加日志失败，请重试');
+      }
     }
   }
 
@@ -130,7 +136,7 @@ class LogEntryForm extends HTMLElement {
       if (!contentTextarea) return;
       
       const commitMessages = (commits as any[])
-        .map(commit => `- ${commit.message}`)
+        .map(commit => `- ${commit.summary}`)
         .join('\n');
       
       // 如果文本区域已有内容，添加到现有内容
@@ -1,6 +1,8 @@
 /**
  * 日志文件管理面板组件
  */
+import { errorMessage, isCommandError } from '../errors';
+
 class LogFilesPanel extends HTMLElement {
   private logFiles: string[] = [];
   private currentFile: string | null = null;
@@ -39,7 +41,11 @@ class LogFilesPanel extends HTMLElement {
       }
     } catch (error) {
       console.error('加载日志文件失败:', error);
-      this.showError(`加载日志文件失败: ${error instanceof Error ? error.message : String(error)}`);
+      if (isCommandError(error) && error.code === 'storage_not_found') {
+        this.showError('日志目录不存在，请在设置中检查日志存储目录');
+      } else {
+        this.showError(`加载日志文件失败: ${errorMessage(error)}`);
+      }
     }
   }
 
@@ -61,7 +67,7 @@ class LogFilesPanel extends HTMLElement {
       this.updateSelectedFile();
     } catch (error) {
       console.error('加载日志条目失败:', error);
-      this.showError(`加载日志条目失败: ${error instanceof Error ? error.message : String(error)}`);
+      this.showError(`加载日志条目失败: ${errorMessage(error)}`);
     }
   }
 
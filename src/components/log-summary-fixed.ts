@@ -2,6 +2,27 @@
  * 修复后的日志摘要面板组件
  */
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { CommandError, isCommandError } from '../errors';
+
+/**
+ * 摘要生成事件的内容，request_id 用于区分同时进行的多个摘要任务
+ */
+interface SummaryEvent<T> {
+  request_id: string;
+  payload: T;
+}
+
+/**
+ * 摘要完成事件的内容，附带摘要的字数、字符数和保存的文件路径
+ */
+interface SummaryResult {
+  summary: string;
+  word_count: number;
+  char_count: number;
+  /** 同名摘要文件已存在且按设置跳过保存时为空 */
+  path: string | null;
+}
 
 class LogSummaryPanel extends HTMLElement {
   private startDate: string = '';
@@ -197,6 +218,40 @@ class LogSummaryPanel extends HTMLElement {
     return `${date.getFullYear()}年第${quarter}季度`;
   }
 
+  /**
+   * 监听指定请求的摘要完成、错误和取消事件
+   */
+  private async waitForSummary(requestId: string) {
+    const unlisteners: UnlistenFn[] = [];
+    let resolveSummary: (summary: SummaryResult) => void = () => {};
+    let rejectSummary: (error: string) => void = () => {};
+    const summary = new Promise<SummaryResult>((resolve, reject) => {
+      resolveSummary = resolve;
+      rejectSummary = reject;
+    });
+
+    unlisteners.push(await listen<SummaryEvent<SummaryResult>>('summary-generation-complete', (event) => {
+      if (event.payload.request_id === requestId) {
+        resolveSummary(event.payload.payload);
+      }
+    }));
+    unlisteners.push(await listen<SummaryEvent<CommandError>>('summary-generation-error', (event) => {
+      if (event.payload.request_id === requestId) {
+        rejectSummary(event.payload.payload?.message || '生成摘要失败');
+      }
+    }));
+    unlisteners.push(await listen<SummaryEvent<string>>('summary-generation-cancelled', (event) => {
+      if (event.payload.request_id === requestId) {
+        rejectSummary('已取消摘要生成');
+      }
+    }));
+
+    return {
+      summary,
+      dispose: () => unlisteners.forEach((unlisten) => unlisten()),
+    };
+  }
+
   /**
    * 格式化日期为 YYYY-MM-DD
    */
@@ -253,15 +308,24 @@ class LogSummaryPanel extends HTMLElement {
         params.endDate = this.endDate;
       }
       
-      console.log('调用generate_summary命令，参数:', JSON.stringify(params));
+      // 为本次生成指定请求ID，只处理属于本次请求的事件
+      const requestId = crypto.randomUUID();
+      params.requestId = requestId;
       
-      // 使用导入的invoke而不是window.__TAURI__
-      const response = await invoke('generate_summary', params);
+      console.log('调用generate_summary命令，参数:', JSON.stringify(params));
       
-      // 确保我们有一个字符串类型的摘要
-      const summary = (typeof response === 'string') ? response : '';
+      // 先注册事件监听再调用命令，避免错过生成结果
+      const result = await this.waitForSummary(requestId);
+      let summary: SummaryResult;
+      try {
+        // 命令立即返回请求ID，摘要内容通过事件推送
+        await invoke('generate_summary', params);
+        summary = await result.summary;
+      } finally {
+        result.dispose();
+      }
       
-      console.log('摘要生成成功，结果长度:', summary.length);
+      console.log('摘要生成成功，字数:', summary.word_count);
       
       // 显示摘要结果
       this.showSummaryResult(summary);
@@ -275,6 +339,9 @@ class LogSummaryPanel extends HTMLElement {
       if (typeof error === 'string') {
         errorMessage = error;
         console.error('错误信息(字符串):', error);
+      } else if (isCommandError(error)) {
+        errorMessage = error.message;
+        console.error('错误详情(CommandError):', error);
       } else if (error instanceof Error) {
         errorMessage = `${error.name}: ${error.message}`;
         console.error('错误详情(Error对象):', {
@@ -321,20 +388,30 @@ class LogSummaryPanel extends HTMLElement {
   /**
    * 显示摘要结果
    */
-  private showSummaryResult(summary: string) {
+  private showSummaryResult(result: SummaryResult) {
     if (!this.shadowRoot) return;
     
+    const summary = result.summary || '';
     const resultContainer = this.shadowRoot.getElementById('summary-result');
     if (resultContainer) {
       resultContainer.style.display = 'block';
       resultContainer.innerHTML = `
         <div class="result-header">
           <h3>${this.summaryTitle}</h3>
+          <span class="result-length">${result.word_count} 字 · ${result.char_count} 字符</span>
           <button id="copy-btn" class="copy-btn">复制</button>
         </div>
+        <div id="result-path" class="result-path"></div>
         <div class="result-content">${this.formatMarkdown(summary)}</div>
       `;
 
+      const resultPath = this.shadowRoot.getElementById('result-path');
+      if (resultPath) {
+        resultPath.textContent = result.path
+          ? `已保存到: ${result.path}`
+          : '同名摘要文件已存在，未保存本次摘要';
+      }
+
       // 绑定复制按钮
       const copyBtn = this.shadowRoot.getElementById('copy-btn');
       if (copyBtn) {
@@ -538,6 +615,20 @@ class LogSummaryPanel extends HTMLElement {
           color: var(--text-primary);
         }
 
+        .result-length {
+          margin-left: auto;
+          margin-right: 10px;
+          font-size: 12px;
+          color: var(--text-secondary);
+        }
+
+        .result-path {
+          margin-bottom: 10px;
+          font-size: 12px;
+          color: var(--text-secondary);
+          word-break: break-all;
+        }
+
         .copy-btn {
           padding: 5px 10px;
           font-size: 12px;
@@ -1,6 +1,8 @@
 /**
  * 日志摘要面板组件
  */
+import { CommandError, errorMessage } from '../errors';
+
 class LogSummaryPanel extends HTMLElement {
   private startDate: string = '';
   private endDate: string = '';
@@ -8,6 +10,7 @@ class LogSummaryPanel extends HTMLElement {
   private summaryType: number = 0; // 0: 周, 1: 月, 2: 季度, 3: 自定义
   private currentSummary: string = ''; // 存储当前累积的摘要内容
   private eventListenersActive: boolean = false; // 跟踪是否已注册事件监听器
+  private currentRequestId: string | null = null; // 当前摘要任务ID，用于取消
 
   constructor() {
     super();
@@ -37,7 +40,9 @@ class LogSummaryPanel extends HTMLElement {
       const { listen } = window.__TAURI__.event;
 
       // 监听摘要生成开始事件
-      listen('summary-generation-start', () => {
+      // 事件内容为 { request_id, payload }，只处理当前请求的事件
+      listen('summary-generation-start', (event: { payload: { request_id: string } }) => {
+        if (event.payload.request_id !== this.currentRequestId) return;
         console.log('摘要生成开始');
         this.setGeneratingState(true);
         // 重置当前摘要内容
@@ -56,14 +61,16 @@ class LogSummaryPanel extends HTMLElement {
       });
 
       // 监听处理中事件
-      listen('summary-generation-processing', (event: { payload: string }) => {
-        console.log('摘要生成处理中:', event.payload);
+      listen('summary-generation-processing', (event: { payload: { request_id: string; payload: string } }) => {
+        if (event.payload.request_id !== this.currentRequestId) return;
+        console.log('摘要生成处理中:', event.payload.payload);
         // 可以显示一些处理状态或总的进度，如果需要
       });
 
       // 监听分块内容事件
-      listen('summary-generation-chunk', (event: { payload: string }) => {
-        const chunk = event.payload;
+      listen('summary-generation-chunk', (event: { payload: { request_id: string; payload: string } }) => {
+        if (event.payload.request_id !== this.currentRequestId) return;
+        const chunk = event.payload.payload;
         if (!chunk) return;
 
         // 累积摘要内容
@@ -79,9 +86,10 @@ class LogSummaryPanel extends HTMLElement {
       });
 
       // 监听完成事件
-      listen('summary-generation-complete', (event: { payload: string }) => {
-        console.log('摘要生成完成');
-        const summary = event.payload || '';
+      listen('summary-generation-complete', (event: { payload: { request_id: string; payload: { summary: string; word_count: number } } }) => {
+        if (event.payload.request_id !== this.currentRequestId) return;
+        console.log('摘要生成完成，字数:', event.payload.payload.word_count);
+        const summary = event.payload.payload.summary || '';
         
         // 显示完整摘要结果
         this.showSummaryResult(summary);
@@ -89,9 +97,18 @@ class LogSummaryPanel extends HTMLElement {
       });
 
       // 监听错误事件
-      listen('summary-generation-error', (event: { payload: string }) => {
-        console.error('摘要生成错误:', event.payload);
-        this.showError(event.payload || '生成摘要失败，请重试');
+      listen('summary-generation-error', (event: { payload: { request_id: string; payload: CommandError } }) => {
+        if (event.payload.request_id !== this.currentRequestId) return;
+        console.error('摘要生成错误:', event.payload.payload);
+        this.showError(errorMessage(event.payload.payload, '生成摘要失败，请重试'));
+        this.setGeneratingState(false);
+      });
+
+      // 监听取消事件
+      listen('summary-generation-cancelled', (event: { payload: { request_id: string } }) => {
+        if (event.payload.request_id !== this.currentRequestId) return;
+        console.log('摘要生成已取消');
+        this.showSuccess('已取消摘要生成');
         this.setGeneratingState(false);
       });
 
@@ -114,6 +131,7 @@ class LogSummaryPanel extends HTMLElement {
       listen.drop('summary-generation-chunk');
       listen.drop('summary-generation-complete');
       listen.drop('summary-generation-error');
+      listen.drop('summary-generation-cancelled');
       
       this.eventListenersActive = false;
     } catch (error: unknown) {
@@ -171,6 +189,12 @@ class LogSummaryPanel extends HTMLElement {
       form.addEventListener('submit', this.handleSubmit.bind(this));
     }
 
+    // 取消生成
+    const cancelBtn = this.shadowRoot.getElementById('cancel-btn');
+    if (cancelBtn) {
+      cancelBtn.addEventListener('click', this.handleCancel.bind(this));
+    }
+
     // 摘要类型变更
     const radioButtons = this.shadowRoot.querySelectorAll('input[name="summary-type"]');
     radioButtons.forEach(radio => {
@@ -354,23 +378,41 @@ class LogSummaryPanel extends HTMLElement {
         params.endDate = this.endDate;
       }
       
+      // 为本次生成指定请求ID，事件中据此过滤其他摘要任务
+      this.currentRequestId = crypto.randomUUID();
+      params.requestId = this.currentRequestId;
+      
       console.log('调用generate_summary命令，参数:', JSON.stringify(params));
       
-      // 调用后端接口，但不等待结果（结果通过事件传递）
+      // 调用后端接口，命令立即返回请求ID，结果通过事件传递
       invoke('generate_summary', params).catch((error: unknown) => {
         console.error('调用摘要生成接口失败:', error);
-        this.showError(typeof error === 'string' ? error : '调用摘要生成接口失败，请重试');
+        this.showError(errorMessage(error, '调用摘要生成接口失败，请重试'));
         this.setGeneratingState(false);
       });
       
       // 注意：不再在这里设置非生成状态，将在收到完成事件时设置
     } catch (error: unknown) {
       console.error('生成摘要失败:', error);
-      this.showError(typeof error === 'string' ? error : '生成摘要失败，请重试');
+      this.showError(errorMessage(error, '生成摘要失败，请重试'));
       this.setGeneratingState(false);
     }
   }
 
+  /**
+   * 取消正在进行的摘要生成
+   */
+  private async handleCancel() {
+    try {
+      // @ts-ignore - Tauri API
+      const { invoke } = window.__TAURI__.core;
+      await invoke('cancel_summary', { requestId: this.currentRequestId });
+    } catch (error: unknown) {
+      console.error('取消摘要生成失败:', error);
+      this.showError(errorMessage(error, '取消摘要生成失败'));
+    }
+  }
+
   /**
    * 设置生成中状态
    */
@@ -378,8 +420,16 @@ class LogSummaryPanel extends HTMLElement {
     if (!this.shadowRoot) return;
 
     const generateBtn = this.shadowRoot.getElementById('generate-btn');
+    const cancelBtn = this.shadowRoot.getElementById('cancel-btn');
     const loadingIndicator = this.shadowRoot.getElementById('loading-indicator');
     
+    if (cancelBtn) {
+      cancelBtn.style.display = isGenerating ? 'flex' : 'none';
+    }
+    if (!isGenerating) {
+      this.currentRequestId = null;
+    }
+    
     if (generateBtn && loadingIndicator) {
       if (isGenerating) {
         generateBtn.setAttribute('disabled', 'true');
@@ -584,9 +634,19 @@ class LogSummaryPanel extends HTMLElement {
         .form-actions {
           display: flex;
           justify-content: flex-end;
+          gap: 10px;
           margin-top: 10px;
         }
 
+        .cancel-btn {
+          display: none;
+          background-color: #6c757d;
+        }
+
+        .cancel-btn:hover:not(:disabled) {
+          background-color: #5a6268;
+        }
+
         .loading-indicator {
           width: 20px;
           height: 20px;
@@ -717,6 +777,7 @@ class LogSummaryPanel extends HTMLElement {
         </div>
 
         <div class="form-actions">
+          <button type="button" id="cancel-btn" class="cancel-btn">取消</button>
           <button type="submit" id="generate-btn">
             生成摘要
             <div id="loading-indicator" class="loading-indicator"></div>
@@ -5,6 +5,38 @@ import ThemeManager, { ThemeMode, FontSize } from '../theme-manager';
 import { createIcon } from './icons';
 import { open } from '@tauri-apps/api/dialog';
 import { invoke } from '@tauri-apps/api/tauri';
+import { errorMessage, isCommandError } from '../errors';
+
+/**
+ * 摘要服务连通性检查结果
+ */
+interface BackendInfo {
+  backend: string;
+  url: string;
+  model: string;
+  latency_ms: number;
+}
+
+/**
+ * 配置诊断的单项检查结果
+ */
+interface DiagnosisCheck {
+  id: string;
+  name: string;
+  passed: boolean;
+  message: string;
+  suggestion?: string;
+  fix?: { kind: string; path: string };
+}
+
+/**
+ * 配置诊断报告
+ */
+interface DiagnosisReport {
+  settings_path: string;
+  checks: DiagnosisCheck[];
+  unrecognized_files: string[];
+}
 
 class SettingsPanel extends HTMLElement {
   private settings: any = null;
@@ -223,7 +255,7 @@ class SettingsPanel extends HTMLElement {
       submitButton.textContent = originalButtonText;
     } catch (error) {
       console.error('保存设置失败:', error);
-      this.showError(`保存设置失败: ${error}`);
+      this.showError(`保存设置失败: ${errorMessage(error)}`);
       
       // 恢复按钮状态
       const submitButton = this.shadowRoot.querySelector('button[type="submit"]') as HTMLButtonElement;
@@ -578,6 +610,24 @@ class SettingsPanel extends HTMLElement {
         .api-info strong {
           font-weight: 600;
         }
+
+        .diagnosis-list {
+          grid-column: 1 / -1;
+          list-style: none;
+          margin: 0;
+          padding: 0;
+          font-size: var(--font-size-sm);
+          line-height: 1.5;
+        }
+
+        .diagnosis-list li {
+          padding: 4px 0;
+        }
+
+        .diagnosis-list .suggestion {
+          color: var(--text-secondary);
+          margin-left: 1.5em;
+        }
       </style>
 
       <h2>设置</h2>
@@ -631,6 +681,11 @@ class SettingsPanel extends HTMLElement {
             <input type="text" id="git_author" name="git_author">
           </div>
 
+          <div class="form-group">
+            <label for="git_author_email">Git 作者邮箱</label>
+            <input type="email" id="git_author_email" name="git_author_email">
+          </div>
+
           <div class="form-group">
             <label for="shortcut">快捷键</label>
             <input type="text" id="shortcut" name="shortcut" placeholder="例如: Alt+Shift+L">
@@ -674,6 +729,16 @@ class SettingsPanel extends HTMLElement {
             <label for="llm_api_key">LLM API Key</label>
             <input type="password" id="llm_api_key" name="llm_api_key">
           </div>
+
+          <div class="form-group command-line">
+            <label>连接测试</label>
+            <div class="command-line-buttons">
+              <button type="button" id="test-backend-btn" class="secondary-btn">
+                <app-icon name="help" size="16"></app-icon>
+                测试摘要服务
+              </button>
+            </div>
+          </div>
            
           <div class="form-group api-info-container">
             <div class="api-info">
@@ -714,6 +779,28 @@ class SettingsPanel extends HTMLElement {
           </div>
         </div>
 
+        <div class="form-section">
+          <div class="section-title">配置诊断</div>
+
+          <div class="form-group command-line">
+            <label>诊断</label>
+            <div class="command-line-buttons">
+              <button type="button" id="run-diagnostics-btn" class="secondary-btn">
+                <app-icon name="help" size="16"></app-icon>
+                检查配置
+              </button>
+              <button type="button" id="apply-fixes-btn" class="secondary-btn" hidden>
+                <app-icon name="edit" size="16"></app-icon>
+                自动修复
+              </button>
+            </div>
+          </div>
+
+          <div class="form-group">
+            <ul id="diagnosis-list" class="diagnosis-list"></ul>
+          </div>
+        </div>
+
         <div class="form-actions">
           <button type="submit">保存设置</button>
         </div>
@@ -761,6 +848,21 @@ class SettingsPanel extends HTMLElement {
     this.updateThemeSelections();
 
     // Bind CLI registration/unregistration buttons
+    const testBackendBtn = this.shadowRoot.getElementById('test-backend-btn');
+    if (testBackendBtn) {
+      testBackendBtn.addEventListener('click', this.handleTestBackend.bind(this));
+    }
+
+    const runDiagnosticsBtn = this.shadowRoot.getElementById('run-diagnostics-btn');
+    if (runDiagnosticsBtn) {
+      runDiagnosticsBtn.addEventListener('click', () => this.handleDiagnostics('run_diagnostics'));
+    }
+
+    const applyFixesBtn = this.shadowRoot.getElementById('apply-fixes-btn');
+    if (applyFixesBtn) {
+      applyFixesBtn.addEventListener('click', () => this.handleDiagnostics('apply_diagnosis_fixes'));
+    }
+
     const registerCliBtn = this.shadowRoot.getElementById('register-cli-btn');
     if (registerCliBtn) {
       registerCliBtn.addEventListener('click', this.handleRegisterCli.bind(this));
@@ -772,6 +874,78 @@ class SettingsPanel extends HTMLElement {
     }
   }
 
+  /**
+   * 测试已保存设置中的摘要服务是否可用
+   */
+  private async handleTestBackend() {
+    const testBtn = this.shadowRoot?.getElementById('test-backend-btn') as HTMLButtonElement;
+    if (!testBtn) return;
+
+    const originalContent = testBtn.innerHTML;
+    testBtn.innerHTML = `<div class="loading-spinner"></div> 测试中...`;
+    testBtn.disabled = true;
+
+    try {
+      const info = await invoke<BackendInfo>('test_summary_backend');
+      const model = info.model || '未配置';
+      this.showSuccess(`摘要服务可用：${info.backend} / ${model}，耗时 ${info.latency_ms} 毫秒`);
+    } catch (error) {
+      console.error('测试摘要服务失败:', error);
+      this.showError(errorMessage(error, '测试摘要服务失败'));
+    } finally {
+      testBtn.innerHTML = originalContent;
+      testBtn.disabled = false;
+    }
+  }
+
+  /**
+   * 运行配置诊断或执行自动修复，并显示各项检查结果
+   */
+  private async handleDiagnostics(command: 'run_diagnostics' | 'apply_diagnosis_fixes') {
+    try {
+      const report = await invoke<DiagnosisReport>(command);
+      this.renderDiagnosis(report);
+      if (command === 'apply_diagnosis_fixes') {
+        this.showSuccess('已执行可以自动完成的修复');
+      }
+    } catch (error) {
+      console.error('配置诊断失败:', error);
+      this.showError(errorMessage(error, '配置诊断失败'));
+    }
+  }
+
+  /**
+   * 以清单的形式显示诊断结果，有可以自动修复的问题时显示修复按钮
+   */
+  private renderDiagnosis(report: DiagnosisReport) {
+    const list = this.shadowRoot?.getElementById('diagnosis-list');
+    const applyFixesBtn = this.shadowRoot?.getElementById('apply-fixes-btn') as HTMLButtonElement | null;
+    if (!list) return;
+
+    list.innerHTML = '';
+    for (const check of report.checks) {
+      const item = document.createElement('li');
+      item.textContent = `${check.passed ? '✅' : '❌'} ${check.name}：${check.message}`;
+      if (!check.passed && check.suggestion) {
+        const suggestion = document.createElement('div');
+        suggestion.className = 'suggestion';
+        suggestion.textContent = check.suggestion;
+        item.appendChild(suggestion);
+      }
+      list.appendChild(item);
+    }
+
+    if (report.unrecognized_files.length > 0) {
+      const item = document.createElement('li');
+      item.textContent = `⚠️ ${report.unrecognized_files.length} 个 JSON 文件不符合日志文件名格式，已被忽略`;
+      list.appendChild(item);
+    }
+
+    if (applyFixesBtn) {
+      applyFixesBtn.hidden = !report.checks.some((check) => check.fix);
+    }
+  }
+
   /**
    * 处理注册命令行
    */
@@ -791,15 +965,15 @@ class SettingsPanel extends HTMLElement {
         this.showSuccess('命令行工具已成功注册');
       } catch (error: any) {
         // 检查是否是需要显示命令的错误信息
-        if (typeof error === 'string' && error.includes('请在终端中手动执行')) {
-          this.showCommandDialog('注册命令行工具', error);
+        if (isCommandError(error) && error.code === 'manual_command_required') {
+          this.showCommandDialog('注册命令行工具', error.message, error.details ?? '');
         } else {
           throw error;
         }
       }
     } catch (error) {
       console.error('注册命令行失败:', error);
-      this.showError(`注册命令行失败: ${error}`);
+      this.showError(`注册命令行失败: ${errorMessage(error)}`);
     } finally {
       // 恢复按钮状态
       const registerBtn = this.shadowRoot?.getElementById('register-cli-btn') as HTMLButtonElement;
@@ -829,15 +1003,15 @@ class SettingsPanel extends HTMLElement {
         this.showSuccess('命令行工具已成功注销');
       } catch (error: any) {
         // 检查是否是需要显示命令的错误信息
-        if (typeof error === 'string' && error.includes('请在终端中手动执行')) {
-          this.showCommandDialog('注销命令行工具', error);
+        if (isCommandError(error) && error.code === 'manual_command_required') {
+          this.showCommandDialog('注销命令行工具', error.message, error.details ?? '');
         } else {
           throw error;
         }
       }
     } catch (error) {
       console.error('注销命令行失败:', error);
-      this.showError(`注销命令行失败: ${error}`);
+      this.showError(`注销命令行失败: ${errorMessage(error)}`);
     } finally {
       // 恢复按钮状态
       const unregisterBtn = this.shadowRoot?.getElementById('unregister-cli-btn') as HTMLButtonElement;
@@ -851,33 +1025,13 @@ class SettingsPanel extends HTMLElement {
   /**
    * 显示命令对话框
    */
-  private showCommandDialog(title: string, message: string) {
+  private showCommandDialog(title: string, message: string, command: string) {
     if (!this.shadowRoot) return;
 
     // 创建对话框元素
     const dialog = document.createElement('div');
     dialog.className = 'command-dialog';
 
-    // 提取命令 - 修复提取逻辑
-    // 查找命令模式：在 "：\n\n" 和 "\n\n" 之间的内容
-    let command = '';
-    const regex = /：\n\n(.*?)\n\n/s;
-    // 如果上面的模式没匹配到，尝试不同的格式 "：\n\n" 之后的所有内容直到结尾
-    const altRegex = /：\n\n([^]*?)(?:\n\n|$)/s;
-    
-    const match = message.match(regex) || message.match(altRegex);
-    if (match && match[1]) {
-      command = match[1].trim();
-    }
-    
-    // 如果仍然没有找到，提取消息中可能的sudo命令
-    if (!command) {
-      const sudoMatch = message.match(/sudo [^;]+/);
-      if (sudoMatch) {
-        command = sudoMatch[0];
-      }
-    }
-
     dialog.innerHTML = `
       <div class="command-dialog-content">
         <div class="command-dialog-header">
@@ -0,0 +1,49 @@
+/**
+ * 后端命令返回的结构化错误
+ */
+
+/**
+ * 错误代码，与后端 ErrorCode 对应
+ */
+export type ErrorCode =
+  | 'storage_not_found'
+  | 'permission_denied'
+  | 'io'
+  | 'invalid_data'
+  | 'git'
+  | 'network'
+  | 'api_auth_failed'
+  | 'invalid_date'
+  | 'settings'
+  | 'log_manager'
+  | 'summary'
+  | 'duplicate_entry'
+  | 'cancelled'
+  | 'invalid_input'
+  | 'manual_command_required'
+  | 'internal';
+
+export interface CommandError {
+  code: ErrorCode;
+  message: string;
+  details?: string;
+}
+
+/**
+ * 判断 invoke 抛出的错误是否为后端返回的结构化错误
+ */
+export function isCommandError(error: unknown): error is CommandError {
+  return typeof error === 'object' && error !== null
+    && typeof (error as CommandError).code === 'string'
+    && typeof (error as CommandError).message === 'string';
+}
+
+/**
+ * 取出可展示的错误信息，无法识别时返回 fallback
+ */
+export function errorMessage(error: unknown, fallback = '未知错误'): string {
+  if (isCommandError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  if (typeof error === 'string' && error) return error;
+  return fallback;
+}